@@ -4,13 +4,14 @@ use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{
     rand::{prelude::StdRng, RngCore, SeedableRng},
-    UniformRand,
+    UniformRand, Zero,
 };
 use generic_array::{typenum::U96, GenericArray};
 use serde::*;
 use serde_with::serde_as;
+use zeroize::Zeroize;
 
-use crate::{serialization, Error, Result};
+use crate::{serialization, Error, LockedBox, Result};
 
 // Normally, we would use a custom trait for this, but we can't because
 // the arkworks will not let us create a blanket implementation for G1Affine
@@ -86,12 +87,27 @@ impl<E: Pairing> std::fmt::Display for PublicKey<E> {
 }
 
 #[serde_as]
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "unsafe-debug", derive(Debug))]
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Keypair<E: Pairing> {
     #[serde_as(as = "serialization::SerdeAs")]
     pub decryption_key: E::ScalarField,
 }
 
+// Keep the decryption key out of logs
+#[cfg(not(feature = "unsafe-debug"))]
+impl<E: Pairing> std::fmt::Debug for Keypair<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Keypair(<redacted>)")
+    }
+}
+
+impl<E: Pairing> std::fmt::Display for Keypair<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Keypair(<redacted>)")
+    }
+}
+
 impl<E: Pairing> PartialOrd for Keypair<E> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -141,6 +157,22 @@ impl<E: Pairing> Keypair<E> {
         let mut rng = rand::thread_rng();
         Self::new(&mut rng)
     }
+
+    /// Same as [`Keypair::new`], but the keypair is kept in locked memory,
+    /// see [`crate::memlock`].
+    pub fn new_locked<R: RngCore>(rng: &mut R) -> LockedBox<Self> {
+        let mut keypair = LockedBox::new(Self {
+            decryption_key: E::ScalarField::zero(),
+        });
+        keypair.decryption_key = E::ScalarField::rand(rng);
+        keypair
+    }
+}
+
+impl<E: Pairing> Zeroize for Keypair<E> {
+    fn zeroize(&mut self) {
+        self.decryption_key.zeroize();
+    }
 }
 
 #[cfg(test)]
@@ -163,6 +195,27 @@ mod tests {
         assert!(keypair.is_err());
     }
 
+    #[cfg(not(feature = "unsafe-debug"))]
+    #[test]
+    fn test_keypair_is_redacted() {
+        let keypair = Keypair::<E>::new(&mut rand::thread_rng());
+        assert_eq!(format!("{keypair:?}"), "Keypair(<redacted>)");
+        assert_eq!(keypair.to_string(), "Keypair(<redacted>)");
+    }
+
+    #[test]
+    fn test_locked_keypair() {
+        let keypair = Keypair::<E>::new_locked(&mut rand::thread_rng());
+        assert!(!keypair.decryption_key.is_zero());
+        assert_eq!(
+            keypair.public_key(),
+            Keypair::<E> {
+                decryption_key: keypair.decryption_key
+            }
+            .public_key()
+        );
+    }
+
     #[test]
     fn test_keypair_sorting() {
         let mut keypairs = vec![];
@@ -1,9 +1,13 @@
 pub mod keypair;
+pub mod memlock;
+pub mod security;
 pub mod serialization;
 
 use std::{fmt, fmt::Formatter};
 
 pub use keypair::*;
+pub use memlock::LockedBox;
+pub use security::SecurityConfig;
 pub use serialization::*;
 
 #[derive(Debug)]
@@ -0,0 +1,145 @@
+//! Best-effort protection of secrets in memory, enabled by the `memlock` feature.
+//!
+//! Locked pages are kept out of swap, and on Linux they are also excluded from core dumps.
+//! Without the feature, or on platforms without `mlock`, locking is a no-op.
+//!
+//! Locks are per page and don't nest: unlocking a value also unlocks any other value that
+//! shares a page with it. Locking may also fail if it would exceed `RLIMIT_MEMLOCK`, in which
+//! case the value is left unprotected.
+
+use std::{
+    fmt,
+    mem::size_of_val,
+    ops::{Deref, DerefMut},
+};
+
+use zeroize::Zeroize;
+
+/// Lock the pages holding `value`. Returns whether the pages were locked.
+pub fn lock<T: ?Sized>(value: &T) -> bool {
+    sys::lock(value as *const T as *const u8, size_of_val(value))
+}
+
+/// Unlock the pages holding `value`.
+pub fn unlock<T: ?Sized>(value: &T) {
+    sys::unlock(value as *const T as *const u8, size_of_val(value))
+}
+
+/// A heap-allocated secret that is locked in memory, and zeroized before it is unlocked.
+///
+/// The value passes through the stack while it is moved into the box, so construct it as
+/// close to its use as possible.
+pub struct LockedBox<T: Zeroize> {
+    inner: Box<T>,
+    locked: bool,
+}
+
+impl<T: Zeroize> LockedBox<T> {
+    pub fn new(value: T) -> Self {
+        let inner = Box::new(value);
+        let locked = lock(inner.as_ref());
+        Self { inner, locked }
+    }
+
+    /// Whether the pages of the value are actually locked
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+}
+
+impl<T: Zeroize> Deref for LockedBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Zeroize> DerefMut for LockedBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+impl<T: Zeroize> Drop for LockedBox<T> {
+    fn drop(&mut self) {
+        self.inner.zeroize();
+        if self.locked {
+            unlock(self.inner.as_ref());
+        }
+    }
+}
+
+impl<T: Zeroize> fmt::Debug for LockedBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LockedBox(<redacted>)")
+    }
+}
+
+#[cfg(all(feature = "memlock", unix))]
+mod sys {
+    fn page_range(ptr: *const u8, len: usize) -> (*mut libc::c_void, usize) {
+        // SAFETY: sysconf has no preconditions
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let start = ptr as usize & !(page_size - 1);
+        let end = ptr as usize + len;
+        (start as *mut libc::c_void, end - start)
+    }
+
+    pub(super) fn lock(ptr: *const u8, len: usize) -> bool {
+        if len == 0 {
+            return false;
+        }
+        let (start, len) = page_range(ptr, len);
+        // SAFETY: the range covers the pages of a live allocation, and neither call
+        // changes the contents of memory
+        unsafe {
+            if libc::mlock(start, len) != 0 {
+                return false;
+            }
+            #[cfg(target_os = "linux")]
+            libc::madvise(start, len, libc::MADV_DONTDUMP);
+        }
+        true
+    }
+
+    pub(super) fn unlock(ptr: *const u8, len: usize) {
+        if len == 0 {
+            return;
+        }
+        let (start, len) = page_range(ptr, len);
+        // SAFETY: see `lock`
+        unsafe {
+            #[cfg(target_os = "linux")]
+            libc::madvise(start, len, libc::MADV_DODUMP);
+            libc::munlock(start, len);
+        }
+    }
+}
+
+#[cfg(not(all(feature = "memlock", unix)))]
+mod sys {
+    pub(super) fn lock(_ptr: *const u8, _len: usize) -> bool {
+        false
+    }
+
+    pub(super) fn unlock(_ptr: *const u8, _len: usize) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locked_box_holds_value() {
+        let mut secret = LockedBox::new([7u8; 32]);
+        assert_eq!(*secret, [7u8; 32]);
+        secret[0] = 0;
+        assert_eq!(secret[0], 0);
+        assert_eq!(format!("{secret:?}"), "LockedBox(<redacted>)");
+        // Locking is best-effort, e.g. it may exceed the limit of the test environment
+        if !cfg!(all(feature = "memlock", unix)) {
+            assert!(!secret.is_locked());
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Security levels of the checks of artifacts received from other parties.
+
+use serde::{Deserialize, Serialize};
+
+/// Which checks are run on artifacts received from other parties, and how.
+///
+/// The [`Default`] configuration is the hardened one. Deployments that want to trade
+/// safety for speed, e.g. benchmarks, must opt in with
+/// [`SecurityConfig::relaxed_for_benchmarks`] or by setting the fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityConfig {
+    /// Check that every point of a received artifact is in the prime order subgroup, on
+    /// top of the checks done when it was deserialized, e.g. for artifacts built in memory
+    /// by bindings
+    pub strict_subgroup_checks: bool,
+    /// Verify many artifacts with a single randomized check, and only fall back to checking
+    /// them one by one to find the invalid ones when it fails
+    pub batch_verification: bool,
+    /// Require transcripts to be signed by their dealer, with the signing key the validator
+    /// they are sent by announced. Transcripts posted on chain are already
+    /// authenticated by the chain, so this is only needed when they are gossiped
+    pub require_signed_transcripts: bool,
+    /// Maximum number of shares, i.e. of weighted validators, of a DKG
+    pub max_cohort_size: u32,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            strict_subgroup_checks: true,
+            batch_verification: false,
+            require_signed_transcripts: false,
+            max_cohort_size: 4096,
+        }
+    }
+}
+
+impl SecurityConfig {
+    /// Skips the redundant subgroup checks, batches verification and doesn't bound the
+    /// cohort size. Meant for benchmarks, not for deployments
+    pub fn relaxed_for_benchmarks() -> Self {
+        Self {
+            strict_subgroup_checks: false,
+            batch_verification: true,
+            require_signed_transcripts: false,
+            max_cohort_size: u32::MAX,
+        }
+    }
+}
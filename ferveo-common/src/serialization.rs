@@ -3,7 +3,8 @@
 //! Adapted from [o1-labs/proof-systems](https://raw.githubusercontent.com/o1-labs/proof-systems/31c76ceae3122f0ce09cded8260960ed5cbbe3d8/utils/src/serialization.rs).
 
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use serde::{self, Deserialize, Serialize};
+use bincode::Options;
+use serde::{self, de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::Bytes;
 
 //
@@ -88,6 +89,47 @@ where
     }
 }
 
+/// Same as [SerdeAs] for vectors, but rejects empty vectors when deserializing, e.g. for the
+/// commitments to the coefficients of a polynomial, whose first one is the public key.
+/// Simply add annotations like `#[serde_as(as = "serialization::NonEmptySerdeAs")]`
+pub struct NonEmptySerdeAs;
+
+impl<T> serde_with::SerializeAs<Vec<T>> for NonEmptySerdeAs
+where
+    T: CanonicalSerialize,
+{
+    fn serialize_as<S>(val: &Vec<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        <SerdeAs as serde_with::SerializeAs<Vec<T>>>::serialize_as(
+            val, serializer,
+        )
+    }
+}
+
+impl<'de, T> serde_with::DeserializeAs<'de, Vec<T>> for NonEmptySerdeAs
+where
+    T: CanonicalDeserialize,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let val: Vec<T> =
+            <SerdeAs as serde_with::DeserializeAs<Vec<T>>>::deserialize_as(
+                deserializer,
+            )?;
+        if val.is_empty() {
+            return Err(serde::de::Error::invalid_length(
+                0,
+                &"at least one element",
+            ));
+        }
+        Ok(val)
+    }
+}
+
 // TODO: Trait aliases are experimental
 // trait ByteSerializable = ToBytes + FromBytes;
 
@@ -106,8 +148,79 @@ impl<T: Serialize> ToBytes for T {
 }
 
 impl<T: for<'de> Deserialize<'de>> FromBytes for T {
+    /// Deserializes with the default [`DeserializationLimits::max_message_size`]
     fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
-        bincode::deserialize(bytes)
+        deserialize_limited(bytes, &DeserializationLimits::default())
+    }
+}
+
+/// Bounds on the messages received from untrusted peers, so that a crafted length prefix
+/// can't make a node allocate gigabytes.
+///
+/// The message size bounds the memory allocated while deserializing, since nothing is read
+/// past it. The other limits are checked on the contents of specific messages, once they are
+/// deserialized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeserializationLimits {
+    /// Maximum size of a serialized message, in bytes
+    pub max_message_size: u64,
+    /// Maximum size of the encrypted payload of a ciphertext, in bytes
+    pub max_ciphertext_payload: usize,
+    /// Maximum number of shares, and so of validators, in a PVSS transcript or aggregate
+    pub max_transcript_shares: usize,
+    /// Maximum number of transcripts aggregated at once
+    pub max_aggregate_transcripts: usize,
+}
+
+impl Default for DeserializationLimits {
+    fn default() -> Self {
+        Self {
+            max_message_size: 64 * 1024 * 1024,
+            max_ciphertext_payload: 16 * 1024 * 1024,
+            max_transcript_shares: 4096,
+            max_aggregate_transcripts: 4096,
+        }
+    }
+}
+
+/// Same as `bincode::deserialize`, but fails with [`bincode::ErrorKind::SizeLimit`] on
+/// messages larger than `limits.max_message_size`.
+pub fn deserialize_limited<T: DeserializeOwned>(
+    bytes: &[u8],
+    limits: &DeserializationLimits,
+) -> Result<T, bincode::Error> {
+    if bytes.len() as u64 > limits.max_message_size {
+        return Err(Box::new(bincode::ErrorKind::SizeLimit));
+    }
+    // The options of `bincode::deserialize`, with a limit
+    bincode::options()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(limits.max_message_size)
+        .deserialize(bytes)
+}
+
+#[cfg(feature = "serde-json")]
+pub trait ToJson {
+    fn to_json(&self) -> Result<String, serde_json::Error>;
+}
+
+#[cfg(feature = "serde-json")]
+pub trait FromJson: Sized {
+    fn from_json(json: &str) -> Result<Self, serde_json::Error>;
+}
+
+#[cfg(feature = "serde-json")]
+impl<T: Serialize> ToJson for T {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+#[cfg(feature = "serde-json")]
+impl<T: for<'de> Deserialize<'de>> FromJson for T {
+    fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
     }
 }
 
@@ -128,4 +241,53 @@ mod test {
         let test2 = Test::from_bytes(&bytes).unwrap();
         assert_eq!(test, test2);
     }
+
+    #[test]
+    fn test_non_empty_serde_as() {
+        #[serde_with::serde_as]
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Coeffs(#[serde_as(as = "NonEmptySerdeAs")] Vec<u64>);
+
+        let coeffs = Coeffs(vec![1, 2]);
+        let bytes = coeffs.to_bytes().unwrap();
+        assert_eq!(Coeffs::from_bytes(&bytes).unwrap(), coeffs);
+
+        let bytes = Coeffs(vec![]).to_bytes().unwrap();
+        assert!(Coeffs::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_limited() {
+        let limits = DeserializationLimits {
+            max_message_size: 64,
+            ..Default::default()
+        };
+        let bytes = vec![7u8; 32].to_bytes().unwrap();
+        assert_eq!(
+            deserialize_limited::<Vec<u8>>(&bytes, &limits).unwrap(),
+            vec![7u8; 32]
+        );
+
+        let bytes = vec![7u8; 64].to_bytes().unwrap();
+        assert!(matches!(
+            *deserialize_limited::<Vec<u8>>(&bytes, &limits).unwrap_err(),
+            bincode::ErrorKind::SizeLimit
+        ));
+
+        // A length prefix claiming more than the limit, in a message within the limit
+        let mut bytes = u64::MAX.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&[7u8; 8]);
+        assert!(deserialize_limited::<Vec<Vec<u8>>>(&bytes, &limits).is_err());
+        assert!(Vec::<Vec<u8>>::from_bytes(&bytes).is_err());
+    }
+
+    #[cfg(feature = "serde-json")]
+    #[test]
+    fn test_serde_json() {
+        let test = Test { a: 1, b: 2 };
+        let json = test.to_json().unwrap();
+        assert_eq!(json, r#"{"a":1,"b":2}"#);
+        let test2 = Test::from_json(&json).unwrap();
+        assert_eq!(test, test2);
+    }
 }
@@ -25,12 +25,20 @@ class FerveoPublicKey:
 
 @final
 class Validator:
-    def __init__(self, address: str, public_key: FerveoPublicKey): ...
+    def __init__(
+        self,
+        address: str,
+        public_key: FerveoPublicKey,
+        share_index: int,
+        weight: int = 1,
+    ): ...
 
     address: str
 
     public_key: FerveoPublicKey
 
+    weight: int
+
 @final
 class Transcript:
     @staticmethod
@@ -44,6 +52,12 @@ class DkgPublicKey:
     def __bytes__(self) -> bytes: ...
     @staticmethod
     def serialized_size() -> int: ...
+    def verify_derivation(
+        self,
+        aggregate: AggregatedTranscript,
+        security_threshold: int,
+        shares_num: int,
+    ) -> bool: ...
 
 @final
 class ValidatorMessage:
@@ -105,6 +119,10 @@ class DecryptionSharePrecomputed:
 class AggregatedTranscript:
     def __init__(self, messages: Sequence[ValidatorMessage]): ...
     def verify(self, shares_num: int, messages: Sequence[ValidatorMessage]) -> bool: ...
+    public_key: DkgPublicKey
+    def to_chunks(self, max_chunk_size: int) -> Sequence[bytes]: ...
+    @staticmethod
+    def from_chunks(chunks: Sequence[bytes]) -> AggregatedTranscript: ...
     def create_decryption_share_simple(
         self,
         dkg: Dkg,
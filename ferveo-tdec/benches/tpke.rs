@@ -61,7 +61,7 @@ impl SetupFast {
 
         let pub_contexts = contexts[0].clone().public_decryption_contexts;
         let prepared_key_shares =
-            prepare_combine_fast(&pub_contexts, &decryption_shares);
+            prepare_combine_fast(&pub_contexts, &decryption_shares).unwrap();
 
         let shared_secret = share_combine_fast_unchecked(
             &decryption_shares,
@@ -310,7 +310,9 @@ pub fn bench_share_combine(c: &mut Criterion) {
                 .collect();
 
             move || {
-                black_box(share_combine_precomputed::<E>(&decryption_shares));
+                black_box(
+                    share_combine_precomputed::<E>(&decryption_shares).unwrap(),
+                );
             }
         };
 
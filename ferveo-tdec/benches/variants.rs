@@ -0,0 +1,47 @@
+//! Prints the crossover between the simple and precomputed variants, as a markdown table.
+
+use ark_bls12_381::Bls12_381;
+use ferveo_tdec::{analyze_variants, VariantAnalysis};
+use itertools::iproduct;
+use rand::prelude::StdRng;
+use rand_core::SeedableRng;
+
+const NUM_SHARES_CASES: [usize; 5] = [4, 8, 16, 32, 64];
+const OFFLINE_RATE_CASES: [f64; 4] = [0.0, 0.01, 0.05, 0.1];
+const SAMPLES: usize = 10;
+
+type E = Bls12_381;
+
+fn print_row(analysis: &VariantAnalysis) {
+    println!(
+        "|{}|{}|{}|{:?}|{:?}|{:.4}|{:?}|",
+        analysis.shares_num,
+        analysis.threshold,
+        analysis.offline_rate,
+        analysis.simple.expected_latency,
+        analysis.precomputed.expected_latency,
+        analysis.precomputed.success_probability,
+        analysis.recommended,
+    );
+}
+
+fn main() {
+    let rng = &mut StdRng::seed_from_u64(0);
+
+    println!("|shares_num|threshold|offline_rate|simple|precomputed|precomputed_success|recommended|");
+    println!("|---|---|---|---|---|---|---|");
+    for (shares_num, offline_rate) in
+        iproduct!(NUM_SHARES_CASES, OFFLINE_RATE_CASES)
+    {
+        let threshold = shares_num * 2 / 3;
+        let analysis = analyze_variants::<E>(
+            shares_num,
+            threshold,
+            offline_rate,
+            SAMPLES,
+            rng,
+        )
+        .unwrap();
+        print_row(&analysis);
+    }
+}
@@ -0,0 +1,124 @@
+//! Schema-versioned AAD.
+//!
+//! Downstream policy enforcement reads the decryption conditions from the AAD of a
+//! ciphertext. If validators created decryption shares for AAD that doesn't follow a known
+//! schema, e.g. empty AAD, the conditions could be bypassed.
+//!
+//! Layout: `magic || version || len(conditions) || conditions`
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    protocol::{ProtocolVersion, V1},
+    Error, Result,
+};
+
+/// AAD parsed under a known schema version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionedAad<'a> {
+    pub version: u8,
+    pub conditions: &'a [u8],
+}
+
+impl<'a> VersionedAad<'a> {
+    /// Encode `conditions` under the current AAD schema version.
+    pub fn encode(conditions: &[u8]) -> Vec<u8> {
+        let mut aad = V1::AAD_SCHEMA_MAGIC.to_vec();
+        aad.push(V1::AAD_SCHEMA_VERSION);
+        aad.extend_from_slice(&(conditions.len() as u32).to_be_bytes());
+        aad.extend_from_slice(conditions);
+        aad
+    }
+
+    pub fn parse(aad: &'a [u8]) -> Result<Self> {
+        let rest = aad
+            .strip_prefix(V1::AAD_SCHEMA_MAGIC)
+            .ok_or(Error::MissingAadSchemaVersion)?;
+        let (version, rest) =
+            rest.split_first().ok_or(Error::MissingAadSchemaVersion)?;
+        if *version != V1::AAD_SCHEMA_VERSION {
+            return Err(Error::UnsupportedAadSchemaVersion(*version));
+        }
+        if rest.len() < 4 {
+            return Err(Error::MalformedAad);
+        }
+        let (len, conditions) = rest.split_at(4);
+        let len = u32::from_be_bytes(len.try_into().unwrap()) as usize;
+        if conditions.len() != len {
+            return Err(Error::MalformedAad);
+        }
+        if conditions.is_empty() {
+            return Err(Error::EmptyAadConditions);
+        }
+        Ok(Self {
+            version: *version,
+            conditions,
+        })
+    }
+}
+
+/// Whether validators refuse to create decryption shares for AAD that doesn't parse
+/// under a known schema version.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum AadPolicy {
+    /// Any AAD is accepted
+    #[default]
+    Unchecked,
+    /// The AAD must be a [`VersionedAad`] with non-empty conditions
+    RequireSchema,
+}
+
+impl AadPolicy {
+    pub fn check(&self, aad: &[u8]) -> Result<()> {
+        match self {
+            AadPolicy::Unchecked => Ok(()),
+            AadPolicy::RequireSchema => VersionedAad::parse(aad).map(|_| ()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versioned_aad() {
+        let aad = VersionedAad::encode(b"my-conditions");
+        let parsed = VersionedAad::parse(&aad).unwrap();
+        assert_eq!(parsed.version, V1::AAD_SCHEMA_VERSION);
+        assert_eq!(parsed.conditions, b"my-conditions");
+        assert!(AadPolicy::RequireSchema.check(&aad).is_ok());
+
+        let reject = |aad: &[u8]| AadPolicy::RequireSchema.check(aad);
+        assert!(matches!(reject(b""), Err(Error::MissingAadSchemaVersion)));
+        assert!(matches!(
+            reject(b"my-aad"),
+            Err(Error::MissingAadSchemaVersion)
+        ));
+        assert!(matches!(
+            reject(&VersionedAad::encode(b"")),
+            Err(Error::EmptyAadConditions)
+        ));
+
+        let mut unknown_version = aad.clone();
+        unknown_version[V1::AAD_SCHEMA_MAGIC.len()] = 0xff;
+        assert!(matches!(
+            reject(&unknown_version),
+            Err(Error::UnsupportedAadSchemaVersion(0xff))
+        ));
+
+        // Trailing bytes are not covered by the schema
+        let mut trailing = aad.clone();
+        trailing.push(0);
+        assert!(matches!(reject(&trailing), Err(Error::MalformedAad)));
+        assert!(matches!(
+            reject(&aad[..aad.len() - 1]),
+            Err(Error::MalformedAad)
+        ));
+
+        // Any AAD goes without a policy
+        assert!(AadPolicy::Unchecked.check(b"").is_ok());
+    }
+}
@@ -0,0 +1,354 @@
+use std::ops::Mul;
+
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::{Field, One, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use ferveo_common::{serialization, ToBytes};
+use itertools::izip;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{
+    combine::{dedup_by_share_index, sort_by_share_index},
+    prepare_combine_simple,
+    protocol::{ProtocolVersion, V1},
+    sha256, CiphertextHeader, DecryptionShareSimple, Error,
+    PublicDecryptionContextSimple, Result, SharedSecret,
+};
+
+/// The public data of a cohort member, as fixed by the aggregated transcript.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CohortMember<E: Pairing> {
+    #[serde_as(as = "serialization::SerdeAs")]
+    pub domain_point: E::ScalarField,
+    /// `Y_i`, the share of the validator in the aggregated transcript
+    #[serde_as(as = "serialization::SerdeAs")]
+    pub share_aggregate: E::G2Affine,
+    /// `ek_i`, the public encryption key of the validator
+    #[serde_as(as = "serialization::SerdeAs")]
+    pub validator_public_key: E::G2Affine,
+}
+
+/// Everything a verifier of a [`ShareAggregationProof`] needs to know about the cohort.
+///
+/// A contract or light client only needs to pin the [`CohortCommitment::digest`].
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "CohortMember<E>: Serialize",
+    deserialize = "CohortMember<E>: DeserializeOwned"
+))]
+pub struct CohortCommitment<E: Pairing> {
+    pub threshold: usize,
+    #[serde_as(as = "serialization::SerdeAs")]
+    pub h: E::G2Affine,
+    /// Cohort members, indexed by share index
+    pub members: Vec<CohortMember<E>>,
+}
+
+impl<E: Pairing> CohortCommitment<E> {
+    /// The cohort of the validators of `public_contexts`, which share the same `h`. Returns
+    /// [`Error::EmptyCohort`] if there are none.
+    pub fn from_public_contexts(
+        threshold: usize,
+        public_contexts: &[PublicDecryptionContextSimple<E>],
+    ) -> Result<Self> {
+        let h = public_contexts.first().ok_or(Error::EmptyCohort)?.h;
+        let members = public_contexts
+            .iter()
+            .map(|context| CohortMember {
+                domain_point: context.domain,
+                share_aggregate: context.blinded_key_share.blinded_key_share,
+                validator_public_key: context
+                    .validator_public_key
+                    .into_affine(),
+            })
+            .collect();
+        Ok(Self {
+            threshold,
+            h,
+            members,
+        })
+    }
+
+    pub fn digest(&self) -> Result<[u8; 32]> {
+        let mut preimage = V1::COHORT_COMMITMENT_DOMAIN.to_vec();
+        preimage.extend_from_slice(&self.to_bytes()?);
+        Ok(sha256(&preimage))
+    }
+}
+
+/// A decryption share included in a [`ShareAggregationProof`].
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregatedShareEntry<E: Pairing> {
+    pub share_index: usize,
+    /// `D_i = e(C_i, Y_i)`
+    #[serde_as(as = "serialization::SerdeAs")]
+    pub decryption_share: E::TargetField,
+    /// `C_i = dk_i^{-1} * U`
+    #[serde_as(as = "serialization::SerdeAs")]
+    pub checksum: E::G1Affine,
+}
+
+/// Published by a combiner alongside a shared secret, to prove that the shared secret was
+/// combined from at least `threshold` valid decryption shares of the cohort.
+///
+/// Verification only requires the [`CohortCommitment`] and the ciphertext header, and
+/// checks all shares at once with two multi-pairings.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "AggregatedShareEntry<E>: Serialize",
+    deserialize = "AggregatedShareEntry<E>: DeserializeOwned"
+))]
+pub struct ShareAggregationProof<E: Pairing> {
+    #[serde_as(as = "serialization::SerdeAs")]
+    pub shared_secret: E::TargetField,
+    /// Included shares, ordered by share index
+    pub shares: Vec<AggregatedShareEntry<E>>,
+}
+
+impl<E: Pairing> ShareAggregationProof<E> {
+    /// Combine the given `(share_index, share)` pairs and prove the result. The quorum of the
+    /// ciphertext is only checked by [`ShareAggregationProof::verify`].
+    pub fn new(
+        shares: &[(usize, DecryptionShareSimple<E>)],
+        cohort: &CohortCommitment<E>,
+    ) -> Result<Self> {
+        let shares = dedup_by_share_index(
+            shares,
+            Some(cohort.members.len()),
+            |(share_index, _)| *share_index,
+        )?;
+        let domain_points = shares
+            .iter()
+            .map(|(share_index, _)| cohort.members[*share_index].domain_point)
+            .collect::<Vec<_>>();
+        let shares = shares
+            .into_iter()
+            .map(|(share_index, share)| AggregatedShareEntry {
+                share_index: *share_index,
+                decryption_share: share.decryption_share,
+                checksum: share.validator_checksum.checksum,
+            })
+            .collect::<Vec<_>>();
+        let shared_secret = Self::combine(&shares, &domain_points);
+        Ok(Self {
+            shared_secret,
+            shares,
+        })
+    }
+
+    pub fn shared_secret(&self) -> SharedSecret<E> {
+        SharedSecret(self.shared_secret)
+    }
+
+    /// Check that the shared secret was combined from at least `threshold` valid shares
+    /// of the cohort, for the given ciphertext.
+    pub fn verify(
+        &self,
+        cohort: &CohortCommitment<E>,
+        ciphertext_header: &CiphertextHeader<E>,
+    ) -> Result<bool> {
+        let shares = sort_by_share_index(
+            &self.shares,
+            Some(cohort.members.len()),
+            |entry| entry.share_index,
+        )?;
+        let quorum = ciphertext_header.quorum.unwrap_or(0) as usize;
+        if shares.len() < cohort.threshold.max(quorum) {
+            return Ok(false);
+        }
+        let members = shares
+            .iter()
+            .map(|entry| &cohort.members[entry.share_index])
+            .collect::<Vec<_>>();
+
+        // Random linear combination of the per-share checks, with coefficients
+        // derived from everything that is being checked
+        let alphas = self.challenges(cohort, ciphertext_header)?;
+        let alpha_checksums = izip!(&shares, &alphas)
+            .map(|(entry, alpha)| entry.checksum.mul(alpha))
+            .collect::<Vec<_>>();
+        let alpha_checksums = E::G1::normalize_batch(&alpha_checksums);
+
+        // ∏ e([α_i] C_i, ek_i) == e([∑ α_i] U, H)
+        let alpha_sum = alphas.iter().sum::<E::ScalarField>();
+        let mut pairings_a = alpha_checksums.clone();
+        pairings_a.push((-ciphertext_header.commitment.mul(alpha_sum)).into());
+        let mut pairings_b = members
+            .iter()
+            .map(|member| member.validator_public_key)
+            .collect::<Vec<_>>();
+        pairings_b.push(cohort.h);
+        if !E::multi_pairing(pairings_a, pairings_b).0.is_one() {
+            return Ok(false);
+        }
+
+        // ∏ D_i^{α_i} == ∏ e([α_i] C_i, Y_i)
+        let alpha_shares = izip!(&shares, &alphas).fold(
+            E::TargetField::one(),
+            |acc, (entry, alpha)| {
+                acc * entry.decryption_share.pow(alpha.into_bigint())
+            },
+        );
+        let share_aggregates = members
+            .iter()
+            .map(|member| member.share_aggregate)
+            .collect::<Vec<_>>();
+        if E::multi_pairing(alpha_checksums, share_aggregates).0 != alpha_shares
+        {
+            return Ok(false);
+        }
+
+        // The shared secret is the combination of the shares
+        let domain_points = members
+            .iter()
+            .map(|member| member.domain_point)
+            .collect::<Vec<_>>();
+        let shares = shares.into_iter().cloned().collect::<Vec<_>>();
+        Ok(Self::combine(&shares, &domain_points) == self.shared_secret)
+    }
+
+    fn combine(
+        shares: &[AggregatedShareEntry<E>],
+        domain_points: &[E::ScalarField],
+    ) -> E::TargetField {
+        let lagrange_coeffs = prepare_combine_simple::<E>(domain_points);
+        izip!(shares, &lagrange_coeffs).fold(
+            E::TargetField::one(),
+            |acc, (entry, lambda)| {
+                acc * entry.decryption_share.pow(lambda.into_bigint())
+            },
+        )
+    }
+
+    /// α_i = H(tag || H(cohort) || U || proof || i)
+    fn challenges(
+        &self,
+        cohort: &CohortCommitment<E>,
+        ciphertext_header: &CiphertextHeader<E>,
+    ) -> Result<Vec<E::ScalarField>> {
+        let mut seed = V1::SHARE_AGGREGATION_PROOF_DOMAIN.to_vec();
+        seed.extend_from_slice(&cohort.digest()?);
+        ciphertext_header
+            .commitment
+            .serialize_compressed(&mut seed)?;
+        seed.extend_from_slice(&self.to_bytes()?);
+        let seed = sha256(&seed);
+        Ok((0..self.shares.len() as u64)
+            .map(|i| {
+                let mut preimage = seed.to_vec();
+                preimage.extend_from_slice(&i.to_be_bytes());
+                E::ScalarField::from_be_bytes_mod_order(&sha256(&preimage))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_std::test_rng;
+
+    use crate::*;
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[test]
+    fn share_aggregation_proof() {
+        let rng = &mut test_rng();
+        let (threshold, shares_num) = (3, 5);
+        let (pubkey, _, contexts) =
+            test_common::setup_simple::<E>(threshold, shares_num, rng);
+        let aad: &[u8] = "my-aad".as_bytes();
+        let ciphertext = encrypt::<E>(
+            SecretBox::new("my-msg".as_bytes().to_vec()),
+            aad,
+            &pubkey,
+            rng,
+        )
+        .unwrap();
+        let header = ciphertext.header().unwrap();
+        let cohort = CohortCommitment::from_public_contexts(
+            threshold,
+            &contexts[0].public_decryption_contexts,
+        )
+        .unwrap();
+        assert!(matches!(
+            CohortCommitment::<E>::from_public_contexts(threshold, &[]),
+            Err(Error::EmptyCohort)
+        ));
+
+        // Any subset of at least `threshold` shares, in any order
+        let shares = [4, 1, 2]
+            .into_iter()
+            .map(|i| (i, contexts[i].create_share(&header, aad).unwrap()))
+            .collect::<Vec<_>>();
+        let proof = ShareAggregationProof::new(&shares, &cohort).unwrap();
+        assert!(proof.verify(&cohort, &header).unwrap());
+        let plaintext = decrypt_with_shared_secret(
+            &ciphertext,
+            aad,
+            &proof.shared_secret(),
+            &contexts[0].setup_params.g_inv,
+        )
+        .unwrap();
+        assert_eq!(plaintext.as_bytes(), "my-msg".as_bytes());
+
+        // A share relayed twice is only combined once, but a decrypter can't send two
+        // different shares
+        let mut duplicated = shares.clone();
+        duplicated.push(shares[0].clone());
+        assert_eq!(
+            ShareAggregationProof::new(&duplicated, &cohort).unwrap(),
+            proof
+        );
+        let mut conflicting = shares.clone();
+        conflicting.push((4, shares[1].1.clone()));
+        assert!(matches!(
+            ShareAggregationProof::new(&conflicting, &cohort),
+            Err(Error::ConflictingDecryptionShares(4))
+        ));
+
+        // Not enough shares
+        let mut insufficient = proof.clone();
+        insufficient.shares.pop();
+        assert!(!insufficient.verify(&cohort, &header).unwrap());
+
+        // A share that doesn't match its checksum
+        let mut bad_share = proof.clone();
+        bad_share.shares[0].decryption_share =
+            bad_share.shares[1].decryption_share;
+        assert!(!bad_share.verify(&cohort, &header).unwrap());
+
+        // A share attributed to another validator
+        let mut bad_index = proof.clone();
+        bad_index.shares[0].share_index = 0;
+        assert!(!bad_index.verify(&cohort, &header).unwrap());
+
+        // A shared secret that isn't the combination of the shares
+        let mut bad_secret = proof.clone();
+        bad_secret.shared_secret = bad_secret.shares[0].decryption_share;
+        assert!(!bad_secret.verify(&cohort, &header).unwrap());
+
+        // Another ciphertext
+        let other = encrypt::<E>(
+            SecretBox::new("my-msg".as_bytes().to_vec()),
+            aad,
+            &pubkey,
+            rng,
+        )
+        .unwrap();
+        assert!(!proof.verify(&cohort, &other.header().unwrap()).unwrap());
+
+        // Duplicate shares
+        let mut duplicate = proof;
+        duplicate.shares.push(duplicate.shares[0].clone());
+        assert!(matches!(
+            duplicate.verify(&cohort, &header),
+            Err(Error::DuplicateDecryptionShare(1))
+        ));
+    }
+}
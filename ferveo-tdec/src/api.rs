@@ -7,17 +7,40 @@ pub type G2Affine = <E as ark_ec::pairing::Pairing>::G2Affine;
 pub type Fr = ark_bls12_381::Fr;
 pub type PrivateKey = ark_bls12_381::G2Affine;
 pub type Result<T> = crate::Result<T>;
+#[cfg(feature = "tdec-simple")]
 pub type PrivateDecryptionContextSimple =
     crate::PrivateDecryptionContextSimple<E>;
+#[cfg(feature = "tdec-simple")]
 pub type DecryptionSharePrecomputed = crate::DecryptionSharePrecomputed<E>;
+#[cfg(feature = "tdec-simple")]
 pub type DecryptionShareSimple = crate::DecryptionShareSimple<E>;
+#[cfg(feature = "tdec-simple")]
+pub use crate::ShareRequestContext;
+#[cfg(feature = "tdec-simple")]
+pub type CohortCommitment = crate::CohortCommitment<E>;
+#[cfg(feature = "tdec-simple")]
+pub type ShareAggregationProof = crate::ShareAggregationProof<E>;
 pub type Ciphertext = crate::Ciphertext<E>;
 
 pub type CiphertextHeader = crate::CiphertextHeader<E>;
+pub type DecryptionRequest = crate::DecryptionRequest<E>;
+pub type CoverTrafficBatch = crate::CoverTrafficBatch<E>;
+pub type CiphertextBuilder = crate::CiphertextBuilder<E>;
+pub type CiphertextEnvelope = crate::CiphertextEnvelope<E>;
 pub type TargetField = <E as ark_ec::pairing::Pairing>::TargetField;
+pub type LagrangeCache<S = crate::InMemoryStore> = crate::LagrangeCache<E, S>;
+pub type PreparedKeyCache<S = crate::InMemoryStore> =
+    crate::PreparedKeyCache<E, S>;
 
 pub use crate::{
     decrypt_symmetric, decrypt_with_shared_secret, encrypt,
-    prepare_combine_simple, share_combine_precomputed, share_combine_simple,
-    SecretBox, SharedSecret,
+    prepare_combine_simple, AadPolicy, CacheKey, CacheStore, Compression,
+    DecryptionShareCommitment, DecryptionShareOpening, InMemoryStore,
+    SecretBox, SecretBuffer, ShareReleaseRound, SharedSecret, SuiteId,
+    SuiteRegistry, VerificationReport, VersionedAad,
+};
+#[cfg(feature = "tdec-simple")]
+pub use crate::{
+    share_combine_precomputed, share_combine_precomputed_with_quorum,
+    share_combine_simple,
 };
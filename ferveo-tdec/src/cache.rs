@@ -0,0 +1,223 @@
+use std::{collections::HashMap, marker::PhantomData, sync::RwLock};
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{
+    prepare_combine_simple,
+    protocol::{ProtocolVersion, V1},
+    sha256, Result,
+};
+
+pub type CacheKey = [u8; 32];
+
+/// A key-value store for expensive decryption precomputations.
+///
+/// Values are opaque bytes, so that the store may be backed by a remote cache (e.g. Redis)
+/// shared between many decryption workers. Implementations may evict entries at any time.
+pub trait CacheStore: Send + Sync {
+    fn get(&self, key: &CacheKey) -> Option<Vec<u8>>;
+
+    fn insert(&self, key: CacheKey, value: Vec<u8>);
+}
+
+/// Local, in-process cache store. Used by default.
+#[derive(Debug, Default)]
+pub struct InMemoryStore(RwLock<HashMap<CacheKey, Vec<u8>>>);
+
+impl InMemoryStore {
+    pub fn len(&self) -> usize {
+        self.0.read().expect("Cache lock is poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl CacheStore for InMemoryStore {
+    fn get(&self, key: &CacheKey) -> Option<Vec<u8>> {
+        self.0
+            .read()
+            .expect("Cache lock is poisoned")
+            .get(key)
+            .cloned()
+    }
+
+    fn insert(&self, key: CacheKey, value: Vec<u8>) {
+        self.0
+            .write()
+            .expect("Cache lock is poisoned")
+            .insert(key, value);
+    }
+}
+
+fn cache_key<T: CanonicalSerialize>(tag: &[u8], item: &T) -> Result<CacheKey> {
+    let mut preimage = tag.to_vec();
+    item.serialize_compressed(&mut preimage)?;
+    Ok(sha256(&preimage))
+}
+
+/// Fetches a value from the store, or computes and stores it on a miss.
+/// Entries that fail to deserialize are treated as a miss and overwritten.
+fn get_or_compute<S, T>(
+    store: &S,
+    key: CacheKey,
+    compute: impl FnOnce() -> T,
+) -> Result<T>
+where
+    S: CacheStore,
+    T: CanonicalSerialize + CanonicalDeserialize,
+{
+    if let Some(bytes) = store.get(&key) {
+        if let Ok(value) = T::deserialize_compressed(&bytes[..]) {
+            return Ok(value);
+        }
+    }
+    let value = compute();
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes)?;
+    store.insert(key, bytes);
+    Ok(value)
+}
+
+/// Caches the Lagrange coefficients at zero for a given set of domain points.
+#[derive(Debug)]
+pub struct LagrangeCache<E: Pairing, S: CacheStore = InMemoryStore> {
+    store: S,
+    phantom: PhantomData<E>,
+}
+
+impl<E: Pairing, S: CacheStore + Default> Default for LagrangeCache<E, S> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+impl<E: Pairing, S: CacheStore> LagrangeCache<E, S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Lagrange coefficients at zero for `domain_points`, in the same order.
+    pub fn coefficients_at_zero(
+        &self,
+        domain_points: &[E::ScalarField],
+    ) -> Result<Vec<E::ScalarField>> {
+        let key = cache_key(V1::LAGRANGE_CACHE_DOMAIN, &domain_points)?;
+        get_or_compute(&self.store, key, || {
+            prepare_combine_simple::<E>(domain_points)
+        })
+    }
+}
+
+/// Caches prepared (Miller loop ready) forms of validator G2 keys.
+#[derive(Debug)]
+pub struct PreparedKeyCache<E: Pairing, S: CacheStore = InMemoryStore> {
+    store: S,
+    phantom: PhantomData<E>,
+}
+
+impl<E: Pairing, S: CacheStore + Default> Default for PreparedKeyCache<E, S> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+impl<E: Pairing, S: CacheStore> PreparedKeyCache<E, S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn prepared(&self, key: &E::G2Affine) -> Result<E::G2Prepared> {
+        let cache_key = cache_key(V1::PREPARED_KEY_CACHE_DOMAIN, key)?;
+        get_or_compute(&self.store, cache_key, || E::G2Prepared::from(*key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::{pairing::Pairing, AffineRepr};
+    use ark_poly::EvaluationDomain;
+    use ark_std::UniformRand;
+
+    use super::cache_key;
+    use crate::{
+        protocol::{ProtocolVersion, V1},
+        *,
+    };
+
+    type E = ark_bls12_381::Bls12_381;
+    type ScalarField = <E as ark_ec::pairing::Pairing>::ScalarField;
+
+    #[test]
+    fn lagrange_cache_hit_and_miss() {
+        let domain =
+            ark_poly::GeneralEvaluationDomain::<ScalarField>::new(8).unwrap();
+        let domain_points = domain.elements().take(5).collect::<Vec<_>>();
+
+        let cache = LagrangeCache::<E>::default();
+        let expected = prepare_combine_simple::<E>(&domain_points);
+        assert_eq!(
+            cache.coefficients_at_zero(&domain_points).unwrap(),
+            expected
+        );
+        assert_eq!(cache.store().len(), 1);
+
+        // Served from the cache
+        assert_eq!(
+            cache.coefficients_at_zero(&domain_points).unwrap(),
+            expected
+        );
+        assert_eq!(cache.store().len(), 1);
+
+        // A different subset of shares is a different entry
+        let other = cache.coefficients_at_zero(&domain_points[1..]).unwrap();
+        assert_eq!(other, prepare_combine_simple::<E>(&domain_points[1..]));
+        assert_eq!(cache.store().len(), 2);
+    }
+
+    #[test]
+    fn corrupted_entries_are_recomputed() {
+        let rng = &mut ark_std::test_rng();
+        let domain_points =
+            (0..4).map(|_| ScalarField::rand(rng)).collect::<Vec<_>>();
+        let cache = LagrangeCache::<E>::default();
+        let expected = cache.coefficients_at_zero(&domain_points).unwrap();
+
+        let key = cache_key(V1::LAGRANGE_CACHE_DOMAIN, &domain_points).unwrap();
+        cache.store().insert(key, vec![0xff; 3]);
+        assert_eq!(
+            cache.coefficients_at_zero(&domain_points).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn prepared_key_cache() {
+        let rng = &mut ark_std::test_rng();
+        let cache = PreparedKeyCache::<E>::default();
+        let key = ark_bls12_381::G2Affine::rand(rng);
+        let g = ark_bls12_381::G1Affine::generator();
+
+        let prepared = cache.prepared(&key).unwrap();
+        let cached = cache.prepared(&key).unwrap();
+        assert_eq!(cache.store().len(), 1);
+        assert_eq!(E::pairing(g, prepared), E::pairing(g, key));
+        assert_eq!(E::pairing(g, cached), E::pairing(g, key));
+    }
+}
@@ -4,35 +4,117 @@ use ark_ec::{pairing::Pairing, AffineRepr};
 use ark_ff::{One, UniformRand};
 use ark_serialize::CanonicalSerialize;
 use chacha20poly1305::{
-    aead::{generic_array::GenericArray, Aead, KeyInit, Payload},
+    aead::{generic_array::GenericArray, Aead, AeadInPlace, KeyInit, Payload},
     ChaCha20Poly1305,
 };
-use ferveo_common::serialization;
+use ferveo_common::{
+    deserialize_limited, memlock, serialization, DeserializationLimits,
+    LockedBox,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sha2::{digest::Digest, Sha256};
-use zeroize::ZeroizeOnDrop;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use crate::{htp_bls12381_g2, Error, Result, SecretBox, SharedSecret};
+use crate::{
+    htp_bls12381_g2,
+    protocol::{ProtocolVersion, V1},
+    Error, Result, SecretBox, SecretBuffer, SharedSecret, SuiteId,
+};
 
-#[serde_as]
+/// Ciphertexts are encoded with a version tag followed by their suite, see
+/// [`SuiteId::from_artifact_bytes`], and [`Ciphertext::from_legacy_bytes`] for the encoding
+/// without them
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    bound(serialize = "", deserialize = ""),
+    into = "VersionedCiphertext<E>",
+    from = "VersionedCiphertext<E>"
+)]
 pub struct Ciphertext<E: Pairing> {
+    pub suite: SuiteId,
+
     // U
-    #[serde_as(as = "serialization::SerdeAs")]
     pub commitment: E::G1Affine,
 
     // W
-    #[serde_as(as = "serialization::SerdeAs")]
     pub auth_tag: E::G2Affine,
 
     // V
-    #[serde(with = "serde_bytes")]
     pub ciphertext: Vec<u8>,
+
+    /// Number of decryption shares required to decrypt, if higher than the DKG threshold.
+    /// Bound to the authentication tag, so it can't be lowered.
+    pub quorum: Option<u32>,
+}
+
+/// The encoding of a [`Ciphertext`]. Every version is a variant, so that fields can be added
+/// to ciphertexts without changing how the ciphertexts encoded before decode
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+enum VersionedCiphertext<E: Pairing> {
+    V1 {
+        // Must stay the first field, see `SuiteId::from_artifact_bytes`
+        suite: SuiteId,
+        #[serde_as(as = "serialization::SerdeAs")]
+        commitment: E::G1Affine,
+        #[serde_as(as = "serialization::SerdeAs")]
+        auth_tag: E::G2Affine,
+        #[serde(with = "serde_bytes")]
+        ciphertext: Vec<u8>,
+        quorum: Option<u32>,
+    },
+}
+
+impl<E: Pairing> From<Ciphertext<E>> for VersionedCiphertext<E> {
+    fn from(ciphertext: Ciphertext<E>) -> Self {
+        VersionedCiphertext::V1 {
+            suite: ciphertext.suite,
+            commitment: ciphertext.commitment,
+            auth_tag: ciphertext.auth_tag,
+            ciphertext: ciphertext.ciphertext,
+            quorum: ciphertext.quorum,
+        }
+    }
+}
+
+impl<E: Pairing> From<VersionedCiphertext<E>> for Ciphertext<E> {
+    fn from(ciphertext: VersionedCiphertext<E>) -> Self {
+        match ciphertext {
+            VersionedCiphertext::V1 {
+                suite,
+                commitment,
+                auth_tag,
+                ciphertext,
+                quorum,
+            } => Self {
+                suite,
+                commitment,
+                auth_tag,
+                ciphertext,
+                quorum,
+            },
+        }
+    }
+}
+
+/// The encoding of a [`Ciphertext`] before suites and quorums, without a version tag
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = ""))]
+struct LegacyCiphertext<E: Pairing> {
+    #[serde_as(as = "serialization::SerdeAs")]
+    commitment: E::G1Affine,
+    #[serde_as(as = "serialization::SerdeAs")]
+    auth_tag: E::G2Affine,
+    #[serde(with = "serde_bytes")]
+    ciphertext: Vec<u8>,
 }
 
 impl<E: Pairing> Ciphertext<E> {
     pub fn check(&self, aad: &[u8], g_inv: &E::G1Prepared) -> Result<bool> {
+        self.suite.check::<E>()?;
         self.header()?.check(aad, g_inv)
     }
 
@@ -42,37 +124,156 @@ impl<E: Pairing> Ciphertext<E> {
 
     pub fn header(&self) -> Result<CiphertextHeader<E>> {
         Ok(CiphertextHeader {
+            suite: self.suite,
             commitment: self.commitment,
             auth_tag: self.auth_tag,
             ciphertext_hash: self.ciphertext_hash(),
+            quorum: self.quorum,
         })
     }
     pub fn payload(&self) -> Vec<u8> {
         self.ciphertext.clone()
     }
+
+    /// Decode a ciphertext from its bincode encoding before suites and quorums, which had no
+    /// version tag. The ciphertext was created with the curve `E`, and has no quorum
+    pub fn from_legacy_bytes(bytes: &[u8]) -> Result<Self> {
+        let legacy: LegacyCiphertext<E> = bincode::deserialize(bytes)?;
+        Ok(Self {
+            suite: SuiteId::of::<E>(),
+            commitment: legacy.commitment,
+            auth_tag: legacy.auth_tag,
+            ciphertext: legacy.ciphertext,
+            quorum: None,
+        })
+    }
+
+    /// Deserialize a ciphertext received from an untrusted peer, within `limits`
+    pub fn from_bytes_limited(
+        bytes: &[u8],
+        limits: &DeserializationLimits,
+    ) -> Result<Self> {
+        let ciphertext: Self =
+            deserialize_limited(bytes, limits).map_err(|err| match *err {
+                bincode::ErrorKind::SizeLimit => {
+                    Error::MessageTooLarge(limits.max_message_size)
+                }
+                _ => Error::BincodeError(err),
+            })?;
+        if ciphertext.ciphertext.len() > limits.max_ciphertext_payload {
+            return Err(Error::CiphertextPayloadTooLarge(
+                ciphertext.ciphertext.len(),
+                limits.max_ciphertext_payload,
+            ));
+        }
+        Ok(ciphertext)
+    }
 }
 
-#[serde_as]
+/// Headers are encoded like [`Ciphertext`]s, see [`CiphertextHeader::from_legacy_bytes`] for
+/// the encoding without a version tag
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(
+    bound(serialize = "", deserialize = ""),
+    into = "VersionedCiphertextHeader<E>",
+    from = "VersionedCiphertextHeader<E>"
+)]
 pub struct CiphertextHeader<E: Pairing> {
-    #[serde_as(as = "serialization::SerdeAs")]
+    pub suite: SuiteId,
     pub commitment: E::G1Affine,
-    #[serde_as(as = "serialization::SerdeAs")]
     pub auth_tag: E::G2Affine,
     pub ciphertext_hash: [u8; 32],
+    pub quorum: Option<u32>,
+}
+
+/// The encoding of a [`CiphertextHeader`], see [`VersionedCiphertext`]
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+enum VersionedCiphertextHeader<E: Pairing> {
+    V1 {
+        // Must stay the first field, see `SuiteId::from_artifact_bytes`
+        suite: SuiteId,
+        #[serde_as(as = "serialization::SerdeAs")]
+        commitment: E::G1Affine,
+        #[serde_as(as = "serialization::SerdeAs")]
+        auth_tag: E::G2Affine,
+        ciphertext_hash: [u8; 32],
+        quorum: Option<u32>,
+    },
+}
+
+impl<E: Pairing> From<CiphertextHeader<E>> for VersionedCiphertextHeader<E> {
+    fn from(header: CiphertextHeader<E>) -> Self {
+        VersionedCiphertextHeader::V1 {
+            suite: header.suite,
+            commitment: header.commitment,
+            auth_tag: header.auth_tag,
+            ciphertext_hash: header.ciphertext_hash,
+            quorum: header.quorum,
+        }
+    }
+}
+
+impl<E: Pairing> From<VersionedCiphertextHeader<E>> for CiphertextHeader<E> {
+    fn from(header: VersionedCiphertextHeader<E>) -> Self {
+        match header {
+            VersionedCiphertextHeader::V1 {
+                suite,
+                commitment,
+                auth_tag,
+                ciphertext_hash,
+                quorum,
+            } => Self {
+                suite,
+                commitment,
+                auth_tag,
+                ciphertext_hash,
+                quorum,
+            },
+        }
+    }
+}
+
+/// The encoding of a [`CiphertextHeader`] before suites and quorums, without a version tag
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = ""))]
+struct LegacyCiphertextHeader<E: Pairing> {
+    #[serde_as(as = "serialization::SerdeAs")]
+    commitment: E::G1Affine,
+    #[serde_as(as = "serialization::SerdeAs")]
+    auth_tag: E::G2Affine,
+    ciphertext_hash: [u8; 32],
 }
 
 impl<E: Pairing> CiphertextHeader<E> {
+    /// Decode a header from its bincode encoding before suites and quorums, see
+    /// [`Ciphertext::from_legacy_bytes`]
+    pub fn from_legacy_bytes(bytes: &[u8]) -> Result<Self> {
+        let legacy: LegacyCiphertextHeader<E> = bincode::deserialize(bytes)?;
+        Ok(Self {
+            suite: SuiteId::of::<E>(),
+            commitment: legacy.commitment,
+            auth_tag: legacy.auth_tag,
+            ciphertext_hash: legacy.ciphertext_hash,
+            quorum: None,
+        })
+    }
+
     pub fn check(&self, aad: &[u8], g_inv: &E::G1Prepared) -> Result<bool> {
         // Implements a variant of the check in section 4.4.2 of the Ferveo paper:
         // 'TPKE.CheckCiphertextValidity(U,W,aad)'
         // See: https://eprint.iacr.org/2022/898.pdf
         // See: https://nikkolasg.github.io/ferveo/tpke.html#to-validate-ciphertext-for-ind-cca2-security
 
+        self.suite.check::<E>()?;
+
         // H_G2(U, sym_ctxt_digest, aad)
         let hash_g2 = E::G2Prepared::from(construct_tag_hash::<E>(
             self.commitment,
             &self.ciphertext_hash,
+            self.quorum,
             aad,
         )?);
 
@@ -97,6 +298,21 @@ pub fn encrypt<E: Pairing>(
     aad: &[u8],
     pubkey: &E::G1Affine,
     rng: &mut impl rand::Rng,
+) -> Result<Ciphertext<E>> {
+    encrypt_with_quorum(message, aad, pubkey, None, rng)
+}
+
+/// Same as [`encrypt`], but the ciphertext requires `quorum` decryption shares to be
+/// combined, e.g. for a policy that is stricter than the DKG threshold.
+///
+/// The quorum is enforced by the combiner, not by the threshold scheme: any DKG threshold
+/// of shares still determines the shared secret.
+pub fn encrypt_with_quorum<E: Pairing>(
+    message: SecretBox<Vec<u8>>,
+    aad: &[u8],
+    pubkey: &E::G1Affine,
+    quorum: Option<u32>,
+    rng: &mut impl rand::Rng,
 ) -> Result<Ciphertext<E>> {
     // r
     let rand_element = E::ScalarField::rand(rng);
@@ -120,20 +336,20 @@ pub fn encrypt<E: Pairing>(
     };
     let ciphertext = shared_secret_to_chacha(&shared_secret)?
         .encrypt(&nonce.0, payload)
-        .map_err(Error::SymmetricEncryptionError)?
-        .to_vec();
-    let ciphertext_hash = sha256(&ciphertext);
+        .map_err(Error::SymmetricEncryptionError)?;
 
     // w
-    let auth_tag = construct_tag_hash::<E>(commitment, &ciphertext_hash, aad)?
-        .mul(rand_element)
-        .into();
+    let mut tag_hasher = TagHasher::<E>::with_quorum(commitment, quorum);
+    tag_hasher.update(&ciphertext);
+    let auth_tag = tag_hasher.finalize(aad)?.mul(rand_element).into();
 
     // TODO: Consider adding aad to the Ciphertext struct
     Ok(Ciphertext::<E> {
+        suite: SuiteId::of::<E>(),
         commitment,
         ciphertext,
         auth_tag,
+        quorum,
     })
 }
 
@@ -142,7 +358,7 @@ pub fn decrypt_symmetric<E: Pairing>(
     aad: &[u8],
     private_key: &E::G2Affine,
     g_inv: &E::G1Prepared,
-) -> Result<Vec<u8>> {
+) -> Result<SecretBuffer> {
     ciphertext.check(aad, g_inv)?;
     let shared_secret = E::pairing(
         E::G1Prepared::from(ciphertext.commitment),
@@ -153,36 +369,37 @@ pub fn decrypt_symmetric<E: Pairing>(
     decrypt_with_shared_secret_unchecked(ciphertext, aad, &shared_secret)
 }
 
+/// Only called on checked ciphertexts, whose payload is intact, so failing to decrypt is
+/// down to the shared secret
 fn decrypt_with_shared_secret_unchecked<E: Pairing>(
     ciphertext: &Ciphertext<E>,
     aad: &[u8],
     shared_secret: &SharedSecret<E>,
-) -> Result<Vec<u8>> {
+) -> Result<SecretBuffer> {
     let nonce = Nonce::from_commitment::<E>(ciphertext.commitment)?;
-    let ctxt = ciphertext.ciphertext.to_vec();
-    let payload = Payload {
-        msg: ctxt.as_ref(),
-        aad,
-    };
-    let plaintext = shared_secret_to_chacha(shared_secret)?
-        .decrypt(&nonce.0, payload)
-        .map_err(|_| Error::CiphertextVerificationFailed)?
-        .to_vec();
+    // Decrypt in place, so that the plaintext never leaves the secret buffer
+    let mut plaintext = SecretBuffer::new(ciphertext.ciphertext.clone());
+    shared_secret_to_chacha(shared_secret)?
+        .decrypt_in_place(&nonce.0, aad, plaintext.as_mut_vec())
+        .map_err(|_| Error::InvalidSharedSecret)?;
 
     Ok(plaintext)
 }
 
+/// Fails with [`Error::CiphertextVerificationFailed`] if the ciphertext or the AAD was
+/// corrupted, and with [`Error::InvalidSharedSecret`] if the shared secret is wrong, e.g.
+/// because it was combined from invalid decryption shares.
 pub fn decrypt_with_shared_secret<E: Pairing>(
     ciphertext: &Ciphertext<E>,
     aad: &[u8],
     shared_secret: &SharedSecret<E>,
     g_inv: &E::G1Prepared,
-) -> Result<Vec<u8>> {
+) -> Result<SecretBuffer> {
     ciphertext.check(aad, g_inv)?;
     decrypt_with_shared_secret_unchecked(ciphertext, aad, shared_secret)
 }
 
-fn sha256(input: &[u8]) -> [u8; 32] {
+pub(crate) fn sha256(input: &[u8]) -> [u8; 32] {
     let mut hasher = Sha256::new();
     hasher.update(input);
     let result = hasher.finalize();
@@ -192,13 +409,17 @@ fn sha256(input: &[u8]) -> [u8; 32] {
 pub fn shared_secret_to_chacha<E: Pairing>(
     shared_secret: &SharedSecret<E>,
 ) -> Result<ChaCha20Poly1305> {
-    let mut prf_key = SecretBox::new(Vec::new());
+    let mut prf_key =
+        SecretBox::new(vec![0u8; shared_secret.0.compressed_size()]);
+    memlock::lock(prf_key.as_secret().as_slice());
     shared_secret
         .0
-        .serialize_compressed(prf_key.as_mut_secret())?;
-    Ok(ChaCha20Poly1305::new(GenericArray::from_slice(&sha256(
-        prf_key.as_secret(),
-    ))))
+        .serialize_compressed(prf_key.as_mut_secret().as_mut_slice())?;
+    let key = LockedBox::new(sha256(prf_key.as_secret()));
+    let cipher = ChaCha20Poly1305::new(GenericArray::from_slice(key.as_ref()));
+    prf_key.as_mut_secret().zeroize();
+    memlock::unlock(prf_key.as_secret().as_slice());
+    Ok(cipher)
 }
 
 /// Wrapper around the Nonce implementation from the `chacha20poly1305` crate.
@@ -215,12 +436,12 @@ impl Nonce {
         commitment.serialize_compressed(&mut commitment_bytes)?;
         let commitment_hash = sha256(&commitment_bytes);
         Ok(Nonce(*chacha20poly1305::Nonce::from_slice(
-            &commitment_hash[..12],
+            &commitment_hash[..V1::NONCE_SIZE],
         )))
     }
 }
 
-fn hash_to_g2<T: ark_serialize::CanonicalDeserialize>(
+pub fn hash_to_g2<T: ark_serialize::CanonicalDeserialize>(
     message: &[u8],
 ) -> Result<T> {
     let point = htp_bls12381_g2(message);
@@ -231,20 +452,85 @@ fn hash_to_g2<T: ark_serialize::CanonicalDeserialize>(
 
 fn construct_tag_hash<E: Pairing>(
     commitment: E::G1Affine,
-    ciphertext_hash: &[u8],
+    ciphertext_hash: &[u8; 32],
+    quorum: Option<u32>,
     aad: &[u8],
 ) -> Result<E::G2Affine> {
-    let mut hash_input = Vec::<u8>::new();
+    // The quorum is folded into the payload digest, which has a fixed size, so that it
+    // can't be moved into the AAD of a ciphertext without a quorum.
+    let ciphertext_hash = match quorum {
+        Some(quorum) => {
+            let mut hasher = Sha256::new();
+            hasher.update(V1::QUORUM_BINDING_DOMAIN);
+            hasher.update(quorum.to_be_bytes());
+            hasher.update(ciphertext_hash);
+            hasher.finalize().into()
+        }
+        None => *ciphertext_hash,
+    };
+    // Only the digest of the payload is hashed to the curve, so the input stays small
+    // regardless of the payload size.
+    let mut hash_input = Vec::<u8>::with_capacity(
+        commitment.compressed_size() + ciphertext_hash.len() + aad.len(),
+    );
     commitment.serialize_compressed(&mut hash_input)?;
-    hash_input.extend_from_slice(ciphertext_hash);
+    hash_input.extend_from_slice(&ciphertext_hash);
     hash_input.extend_from_slice(aad);
     hash_to_g2(&hash_input)
 }
 
-#[cfg(test)]
+/// Incrementally computes the ciphertext tag hash `H_G2(U, sha256(V), aad)`.
+///
+/// The payload `V` may be fed in chunks, so that large payloads never have to be
+/// buffered in order to produce or check the authentication tag.
+#[derive(Clone, Debug)]
+pub struct TagHasher<E: Pairing> {
+    commitment: E::G1Affine,
+    quorum: Option<u32>,
+    payload_hasher: Sha256,
+}
+
+impl<E: Pairing> TagHasher<E> {
+    pub fn new(commitment: E::G1Affine) -> Self {
+        Self::with_quorum(commitment, None)
+    }
+
+    /// For a ciphertext with the given quorum, see [`encrypt_with_quorum`].
+    pub fn with_quorum(commitment: E::G1Affine, quorum: Option<u32>) -> Self {
+        Self {
+            commitment,
+            quorum,
+            payload_hasher: Sha256::new(),
+        }
+    }
+
+    /// Feed the next chunk of the symmetric ciphertext.
+    pub fn update(&mut self, payload_chunk: &[u8]) {
+        self.payload_hasher.update(payload_chunk);
+    }
+
+    /// Digest of the payload fed so far, as stored in the `CiphertextHeader`.
+    pub fn payload_hash(&self) -> [u8; 32] {
+        self.payload_hasher.clone().finalize().into()
+    }
+
+    pub fn finalize(self, aad: &[u8]) -> Result<E::G2Affine> {
+        construct_tag_hash::<E>(
+            self.commitment,
+            &self.payload_hash(),
+            self.quorum,
+            aad,
+        )
+    }
+}
+
+#[cfg(all(test, feature = "tdec-fast"))]
 mod tests {
+    use ark_ec::pairing::Pairing;
+    use ark_ff::Field;
     use ark_std::test_rng;
 
+    use super::construct_tag_hash;
     use crate::{test_common::*, *};
 
     type E = ark_bls12_381::Bls12_381;
@@ -268,11 +554,33 @@ mod tests {
         let plaintext =
             decrypt_symmetric(&ciphertext, aad, &privkey, g_inv).unwrap();
 
-        assert_eq!(msg, plaintext);
+        assert_eq!(msg, plaintext.as_bytes());
 
         let bad: &[u8] = "bad-aad".as_bytes();
 
         assert!(decrypt_symmetric(&ciphertext, bad, &privkey, g_inv).is_err());
+
+        // Failures are blamed on either the shared secret or the payload
+        let shared_secret =
+            SharedSecret(E::pairing(ciphertext.commitment, privkey).0);
+        assert!(decrypt_with_shared_secret(
+            &ciphertext,
+            aad,
+            &shared_secret,
+            g_inv
+        )
+        .is_ok());
+        let wrong_secret = SharedSecret(shared_secret.0.square());
+        assert!(matches!(
+            decrypt_with_shared_secret(&ciphertext, aad, &wrong_secret, g_inv),
+            Err(Error::InvalidSharedSecret)
+        ));
+        let mut corrupted = ciphertext;
+        corrupted.ciphertext[0] ^= 1;
+        assert!(matches!(
+            decrypt_with_shared_secret(&corrupted, aad, &shared_secret, g_inv),
+            Err(Error::CiphertextVerificationFailed)
+        ));
     }
 
     #[test]
@@ -298,4 +606,114 @@ mod tests {
         let aad = "bad aad".as_bytes();
         assert!(ciphertext.check(aad, &g_inv).is_err());
     }
+
+    #[test]
+    fn ciphertext_from_bytes_limited() {
+        use ark_ec::AffineRepr;
+        use ferveo_common::{DeserializationLimits, ToBytes};
+
+        let rng = &mut test_rng();
+        let pubkey = ark_bls12_381::G1Affine::generator();
+        let ciphertext =
+            encrypt::<E>(SecretBox::new(vec![7u8; 1000]), b"aad", &pubkey, rng)
+                .unwrap();
+        let bytes = ciphertext.to_bytes().unwrap();
+
+        let limits = DeserializationLimits::default();
+        assert_eq!(
+            Ciphertext::<E>::from_bytes_limited(&bytes, &limits).unwrap(),
+            ciphertext
+        );
+
+        let limits = DeserializationLimits {
+            max_ciphertext_payload: 1000,
+            ..Default::default()
+        };
+        assert!(matches!(
+            Ciphertext::<E>::from_bytes_limited(&bytes, &limits),
+            Err(Error::CiphertextPayloadTooLarge(actual, 1000))
+                if actual == ciphertext.ciphertext.len()
+        ));
+
+        let limits = DeserializationLimits {
+            max_message_size: 512,
+            ..Default::default()
+        };
+        assert!(matches!(
+            Ciphertext::<E>::from_bytes_limited(&bytes, &limits),
+            Err(Error::MessageTooLarge(512))
+        ));
+    }
+
+    /// Test that ciphertexts encoded before suites and quorums decode with the legacy
+    /// decoders only
+    #[test]
+    fn legacy_ciphertext_encoding() {
+        use ferveo_common::{FromBytes, ToBytes};
+
+        let rng = &mut test_rng();
+        let aad: &[u8] = "my-aad".as_bytes();
+        let (pubkey, _, contexts) = setup_fast::<E>(3, 4, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let ciphertext =
+            encrypt::<E>(SecretBox::new(vec![7u8; 100]), aad, &pubkey, rng)
+                .unwrap();
+        let header = ciphertext.header().unwrap();
+
+        // The encodings before suites and quorums, of the points and the payload alone
+        let commitment =
+            ferveo_common::to_bytes(&ciphertext.commitment).unwrap();
+        let auth_tag = ferveo_common::to_bytes(&ciphertext.auth_tag).unwrap();
+        let legacy = bincode::serialize(&(
+            &commitment,
+            &auth_tag,
+            &ciphertext.ciphertext,
+        ))
+        .unwrap();
+        let decoded = Ciphertext::<E>::from_legacy_bytes(&legacy).unwrap();
+        assert_eq!(decoded, ciphertext);
+        assert!(decoded.check(aad, g_inv).is_ok());
+        assert!(Ciphertext::<E>::from_bytes(&legacy).is_err());
+        let bytes = ciphertext.to_bytes().unwrap();
+        assert_eq!(Ciphertext::<E>::from_bytes(&bytes).unwrap(), ciphertext);
+        assert!(Ciphertext::<E>::from_legacy_bytes(&bytes).is_err());
+
+        let legacy = bincode::serialize(&(
+            &commitment,
+            &auth_tag,
+            header.ciphertext_hash,
+        ))
+        .unwrap();
+        assert_eq!(
+            CiphertextHeader::<E>::from_legacy_bytes(&legacy).unwrap(),
+            header
+        );
+        assert!(CiphertextHeader::<E>::from_bytes(&legacy).is_err());
+    }
+
+    #[test]
+    fn tag_hasher_accepts_chunks() {
+        let rng = &mut test_rng();
+        let msg = vec![7u8; 10_000];
+        let aad: &[u8] = "my-aad".as_bytes();
+        let (pubkey, _, _) = setup_fast::<E>(3, 4, rng);
+        let ciphertext =
+            encrypt::<E>(SecretBox::new(msg), aad, &pubkey, rng).unwrap();
+
+        let mut tag_hasher = TagHasher::<E>::new(ciphertext.commitment);
+        for chunk in ciphertext.ciphertext.chunks(1000) {
+            tag_hasher.update(chunk);
+        }
+        assert_eq!(tag_hasher.payload_hash(), ciphertext.ciphertext_hash());
+        assert_eq!(
+            tag_hasher.finalize(aad).unwrap(),
+            construct_tag_hash::<E>(
+                ciphertext.commitment,
+                &ciphertext.ciphertext_hash(),
+                None,
+                aad
+            )
+            .unwrap()
+        );
+    }
 }
@@ -1,34 +1,133 @@
 #![allow(non_snake_case)]
 
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+use std::collections::{btree_map::Entry, BTreeMap};
+use std::fmt;
+#[cfg(feature = "tdec-fast")]
 use std::ops::Mul;
 
-use ark_ec::{pairing::Pairing, CurveGroup};
-use ark_ff::{Field, One, PrimeField, Zero};
+use ark_ec::pairing::Pairing;
+#[cfg(feature = "tdec-fast")]
+use ark_ec::CurveGroup;
+#[cfg(feature = "tdec-simple")]
+use ark_ff::{Field, PrimeField};
+use ark_ff::{One, Zero};
 use ferveo_common::serialization;
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
 use itertools::izip;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+#[cfg(feature = "tdec-fast")]
 use subproductdomain::SubproductDomain;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[serde_as]
+#[cfg_attr(feature = "unsafe-debug", derive(Debug))]
 #[derive(
-    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop,
+    Clone, PartialEq, Eq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop,
 )]
 pub struct SharedSecret<E: Pairing>(
     #[serde_as(as = "serialization::SerdeAs")] pub(crate) E::TargetField,
 );
 
+#[cfg(not(feature = "unsafe-debug"))]
+impl<E: Pairing> fmt::Debug for SharedSecret<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SharedSecret(<redacted>)")
+    }
+}
+
+impl<E: Pairing> fmt::Display for SharedSecret<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SharedSecret(<redacted>)")
+    }
+}
+
+#[cfg(feature = "tdec-fast")]
 use crate::{
     verify_decryption_shares_fast, Ciphertext, DecryptionShareFast,
-    DecryptionSharePrecomputed, DecryptionShareSimple, Error,
-    PublicDecryptionContextFast, Result,
+    PublicDecryptionContextFast,
 };
+#[cfg(feature = "tdec-simple")]
+use crate::{DecryptionSharePrecomputed, DecryptionShareSimple};
+use crate::{Error, Result};
 
+/// Orders decryption shares by the share index they carry, so that the result of combining
+/// doesn't depend on the order in which the shares were received.
+/// Fails on duplicate share indices, and on indices outside of `0..shares_num` if given.
+#[cfg(feature = "tdec-simple")]
+pub(crate) fn sort_by_share_index<S>(
+    shares: &[S],
+    shares_num: Option<usize>,
+    share_index: impl Fn(&S) -> usize,
+) -> Result<Vec<&S>> {
+    let mut sorted = BTreeMap::new();
+    for share in shares {
+        let index = share_index(share);
+        if shares_num.map_or(false, |n| index >= n) {
+            return Err(Error::UnknownDecryptionShareIndex(index));
+        }
+        if sorted.insert(index, share).is_some() {
+            return Err(Error::DuplicateDecryptionShare(index));
+        }
+    }
+    Ok(sorted.into_values().collect())
+}
+
+/// Same as [`sort_by_share_index`], but drops repeated copies of a share, e.g. a share that
+/// was relayed more than once. Fails if a decrypter sent conflicting shares, since only one
+/// of them can be valid.
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+pub(crate) fn dedup_by_share_index<S: PartialEq>(
+    shares: &[S],
+    shares_num: Option<usize>,
+    share_index: impl Fn(&S) -> usize,
+) -> Result<Vec<&S>> {
+    let mut sorted = BTreeMap::new();
+    for share in shares {
+        let index = share_index(share);
+        if shares_num.map_or(false, |n| index >= n) {
+            return Err(Error::UnknownDecryptionShareIndex(index));
+        }
+        match sorted.entry(index) {
+            Entry::Vacant(entry) => {
+                entry.insert(share);
+            }
+            Entry::Occupied(entry) => {
+                if *entry.get() != share {
+                    return Err(Error::ConflictingDecryptionShares(index));
+                }
+            }
+        }
+    }
+    Ok(sorted.into_values().collect())
+}
+
+/// Checks that `share_count` distinct decryption shares meet `quorum`, the quorum of the
+/// ciphertext they were created for.
+///
+/// The quorum must come from the ciphertext, e.g. [`crate::CiphertextHeader::quorum`], and
+/// never from the shares, whose senders could lower it.
+pub fn check_quorum(quorum: Option<u32>, share_count: usize) -> Result<()> {
+    match quorum {
+        Some(quorum) if share_count < quorum as usize => {
+            Err(Error::InsufficientQuorum(quorum, share_count))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Prepares the blinded key shares of the decrypters, in the order of their share indices.
+#[cfg(feature = "tdec-fast")]
 pub fn prepare_combine_fast<E: Pairing>(
     public_decryption_contexts: &[PublicDecryptionContextFast<E>],
     shares: &[DecryptionShareFast<E>],
-) -> Vec<E::G2Prepared> {
+) -> Result<Vec<E::G2Prepared>> {
+    let shares = dedup_by_share_index(
+        shares,
+        Some(public_decryption_contexts.len()),
+        |d_i| d_i.decrypter_index,
+    )?;
     let mut domain = vec![]; // omega_i, vector of domain points
     let mut n_0 = E::ScalarField::one();
     for d_i in shares.iter() {
@@ -43,7 +142,7 @@ pub fn prepare_combine_fast<E: Pairing>(
     ark_ff::batch_inversion_and_mul(&mut lagrange, &n_0); // n_0 * L_i
 
     // L_i * [b]Z_i
-    izip!(shares.iter(), lagrange.iter())
+    Ok(izip!(shares.iter(), lagrange.iter())
         .map(|(d_i, lambda)| {
             let decrypter = &public_decryption_contexts[d_i.decrypter_index];
             let blinded_key_share =
@@ -53,7 +152,7 @@ pub fn prepare_combine_fast<E: Pairing>(
                 blinded_key_share.mul(*lambda).into_affine(),
             )
         })
-        .collect::<Vec<_>>()
+        .collect::<Vec<_>>())
 }
 
 // TODO: Combine `tpke::prepare_combine_simple` and `tpke::share_combine_simple` into
@@ -85,10 +184,16 @@ pub fn lagrange_basis_at<E: Pairing>(
 }
 
 // TODO: Hide this from external users. Currently blocked by usage in benchmarks.
+/// Expects `prepared_key_shares` as returned by `prepare_combine_fast`, i.e. in the order
+/// of share indices. Shares are sorted accordingly, but not checked for duplicates.
+#[cfg(feature = "tdec-fast")]
 pub fn share_combine_fast_unchecked<E: Pairing>(
     shares: &[DecryptionShareFast<E>],
     prepared_key_shares: &[E::G2Prepared],
 ) -> SharedSecret<E> {
+    let mut shares = shares.iter().collect::<Vec<_>>();
+    shares.sort_by_key(|d_i| d_i.decrypter_index);
+
     let mut pairing_a = vec![];
     let mut pairing_b = vec![];
 
@@ -109,26 +214,40 @@ pub fn share_combine_fast_unchecked<E: Pairing>(
     SharedSecret(shared_secret)
 }
 
+#[cfg(feature = "tdec-fast")]
 pub fn share_combine_fast<E: Pairing>(
     pub_contexts: &[PublicDecryptionContextFast<E>],
     ciphertext: &Ciphertext<E>,
     decryption_shares: &[DecryptionShareFast<E>],
     prepared_key_shares: &[E::G2Prepared],
 ) -> Result<SharedSecret<E>> {
+    let decryption_shares = dedup_by_share_index(
+        decryption_shares,
+        Some(pub_contexts.len()),
+        |d_i| d_i.decrypter_index,
+    )?
+    .into_iter()
+    .cloned()
+    .collect::<Vec<_>>();
+    check_quorum(ciphertext.quorum, decryption_shares.len())?;
     let is_valid_shares = verify_decryption_shares_fast(
         pub_contexts,
         ciphertext,
-        decryption_shares,
+        &decryption_shares,
     );
     if !is_valid_shares {
         return Err(Error::DecryptionShareVerificationFailed);
     }
     Ok(share_combine_fast_unchecked(
-        decryption_shares,
+        &decryption_shares,
         prepared_key_shares,
     ))
 }
 
+/// Simple decryption shares don't carry a share index, so `lagrange_coeffs` must be aligned
+/// with `decryption_shares`, as returned by `prepare_combine_simple` for their domain points.
+/// The quorum of the ciphertext isn't checked, see [`check_quorum`].
+#[cfg(feature = "tdec-simple")]
 pub fn share_combine_simple<E: Pairing>(
     decryption_shares: &[DecryptionShareSimple<E>],
     lagrange_coeffs: &[E::ScalarField],
@@ -143,17 +262,34 @@ pub fn share_combine_simple<E: Pairing>(
     SharedSecret(shared_secret)
 }
 
+/// The quorum of the ciphertext isn't checked, see
+/// [`share_combine_precomputed_with_quorum`].
+#[cfg(feature = "tdec-simple")]
 pub fn share_combine_precomputed<E: Pairing>(
     shares: &[DecryptionSharePrecomputed<E>],
-) -> SharedSecret<E> {
+) -> Result<SharedSecret<E>> {
+    let shares = dedup_by_share_index(shares, None, |c_i| c_i.decrypter_index)?;
     // s = ∏ C_{λ_i}, where λ_i is the Lagrange coefficient for i
     let shared_secret = shares
         .iter()
         .fold(E::TargetField::one(), |acc, c_i| acc * c_i.decryption_share);
-    SharedSecret(shared_secret)
+    Ok(SharedSecret(shared_secret))
+}
+
+/// Same as [`share_combine_precomputed`], but also checks that the shares meet `quorum`, the
+/// quorum of the ciphertext they were created for, see [`check_quorum`].
+#[cfg(feature = "tdec-simple")]
+pub fn share_combine_precomputed_with_quorum<E: Pairing>(
+    shares: &[DecryptionSharePrecomputed<E>],
+    quorum: Option<u32>,
+) -> Result<SharedSecret<E>> {
+    let share_count =
+        dedup_by_share_index(shares, None, |c_i| c_i.decrypter_index)?.len();
+    check_quorum(quorum, share_count)?;
+    share_combine_precomputed(shares)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "tdec-fast"))]
 mod tests {
     type ScalarField =
         <ark_bls12_381::Bls12_381 as ark_ec::pairing::Pairing>::ScalarField;
@@ -1,13 +1,28 @@
+#[cfg(feature = "tdec-fast")]
 use std::ops::Mul;
 
-use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ec::pairing::Pairing;
+#[cfg(feature = "tdec-fast")]
+use ark_ec::CurveGroup;
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+use ferveo_common::SecurityConfig;
+#[cfg(feature = "tdec-fast")]
+use rand_core::RngCore;
 
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+use crate::Ciphertext;
+#[cfg(feature = "tdec-fast")]
+use crate::{find_invalid_decryption_shares_fast, DecryptionShareFast};
+#[cfg(feature = "tdec-simple")]
 use crate::{
-    prepare_combine_simple, BlindedKeyShare, Ciphertext, CiphertextHeader,
-    DecryptionShareFast, DecryptionSharePrecomputed, DecryptionShareSimple,
-    PrivateKeyShare, PublicKeyShare, Result,
+    find_invalid_decryption_shares_simple, prepare_combine_simple,
+    CiphertextHeader, DecryptionRequest, DecryptionSharePrecomputed,
+    DecryptionShareSimple, ShareRequestContext,
 };
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+use crate::{BlindedKeyShare, PrivateKeyShare, PublicKeyShare, Result};
 
+#[cfg(feature = "tdec-fast")]
 #[derive(Clone, Debug)]
 pub struct PublicDecryptionContextFast<E: Pairing> {
     pub domain: E::ScalarField,
@@ -18,6 +33,7 @@ pub struct PublicDecryptionContextFast<E: Pairing> {
     pub h_inv: E::G2Prepared,
 }
 
+#[cfg(feature = "tdec-simple")]
 #[derive(Clone, Debug)]
 pub struct PublicDecryptionContextSimple<E: Pairing> {
     pub domain: E::ScalarField,
@@ -27,7 +43,8 @@ pub struct PublicDecryptionContextSimple<E: Pairing> {
     pub validator_public_key: E::G2,
 }
 
-#[derive(Clone, Debug)]
+#[cfg_attr(feature = "unsafe-debug", derive(Debug))]
+#[derive(Clone)]
 pub struct SetupParams<E: Pairing> {
     pub b: E::ScalarField, // Validator private key
     pub b_inv: E::ScalarField,
@@ -37,14 +54,30 @@ pub struct SetupParams<E: Pairing> {
     pub h: E::G2Affine,
 }
 
+#[cfg(not(feature = "unsafe-debug"))]
+impl<E: Pairing> std::fmt::Debug for SetupParams<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SetupParams")
+            .field("b", &"<redacted>")
+            .field("b_inv", &"<redacted>")
+            .field("g", &self.g)
+            .field("h", &self.h)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "tdec-fast")]
 #[derive(Clone, Debug)]
 pub struct PrivateDecryptionContextFast<E: Pairing> {
     pub index: usize,
     pub setup_params: SetupParams<E>,
     pub private_key_share: PrivateKeyShare<E>,
     pub public_decryption_contexts: Vec<PublicDecryptionContextFast<E>>,
+    /// How the decryption shares of the other decrypters are checked
+    pub security: SecurityConfig,
 }
 
+#[cfg(feature = "tdec-fast")]
 impl<E: Pairing> PrivateDecryptionContextFast<E> {
     pub fn create_share(
         &self,
@@ -63,16 +96,37 @@ impl<E: Pairing> PrivateDecryptionContextFast<E> {
             decryption_share,
         })
     }
+
+    /// The positions of the invalid shares of `decryption_shares`, see
+    /// [`find_invalid_decryption_shares_fast`]
+    pub fn find_invalid_shares<R: RngCore>(
+        &self,
+        ciphertext: &Ciphertext<E>,
+        decryption_shares: &[DecryptionShareFast<E>],
+        rng: &mut R,
+    ) -> Result<Vec<usize>> {
+        find_invalid_decryption_shares_fast(
+            &self.public_decryption_contexts,
+            ciphertext,
+            decryption_shares,
+            &self.security,
+            rng,
+        )
+    }
 }
 
+#[cfg(feature = "tdec-simple")]
 #[derive(Clone, Debug)]
 pub struct PrivateDecryptionContextSimple<E: Pairing> {
     pub index: usize,
     pub setup_params: SetupParams<E>,
     pub private_key_share: PrivateKeyShare<E>,
     pub public_decryption_contexts: Vec<PublicDecryptionContextSimple<E>>,
+    /// How the decryption shares of the other decrypters are checked
+    pub security: SecurityConfig,
 }
 
+#[cfg(feature = "tdec-simple")]
 impl<E: Pairing> PrivateDecryptionContextSimple<E> {
     pub fn create_share(
         &self,
@@ -88,6 +142,53 @@ impl<E: Pairing> PrivateDecryptionContextSimple<E> {
         )
     }
 
+    /// The positions of the invalid shares of `decryption_shares`, see
+    /// [`find_invalid_decryption_shares_simple`]
+    pub fn find_invalid_shares(
+        &self,
+        ciphertext: &Ciphertext<E>,
+        decryption_shares: &[(usize, DecryptionShareSimple<E>)],
+    ) -> Result<Vec<usize>> {
+        find_invalid_decryption_shares_simple(
+            &self.public_decryption_contexts,
+            ciphertext,
+            decryption_shares,
+            &self.security,
+        )
+    }
+
+    pub fn create_share_for_request(
+        &self,
+        ciphertext_header: &CiphertextHeader<E>,
+        aad: &[u8],
+        request: &ShareRequestContext,
+    ) -> Result<DecryptionShareSimple<E>> {
+        DecryptionShareSimple::create_for_request(
+            &self.setup_params.b,
+            &self.private_key_share,
+            ciphertext_header,
+            aad,
+            &self.setup_params.g_inv,
+            request,
+        )
+    }
+
+    /// Creates a decryption share for every request in the batch.
+    ///
+    /// Every request is processed, even if some of them fail, so that the response doesn't
+    /// reveal which requests in a cover traffic batch were real.
+    pub fn create_share_batch(
+        &self,
+        requests: &[DecryptionRequest<E>],
+    ) -> Vec<Result<DecryptionShareSimple<E>>> {
+        requests
+            .iter()
+            .map(|request| {
+                self.create_share(&request.ciphertext.header()?, &request.aad)
+            })
+            .collect()
+    }
+
     pub fn create_share_precomputed(
         &self,
         ciphertext_header: &CiphertextHeader<E>,
@@ -0,0 +1,169 @@
+use ark_ec::pairing::Pairing;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    encrypt,
+    protocol::{ProtocolVersion, V1},
+    Ciphertext, Result, SecretBox,
+};
+
+/// A ciphertext and the associated data it was encrypted with, as sent to validators.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "Ciphertext<E>: Serialize",
+    deserialize = "Ciphertext<E>: Deserialize<'de>"
+))]
+pub struct DecryptionRequest<E: Pairing> {
+    pub ciphertext: Ciphertext<E>,
+    #[serde(with = "serde_bytes")]
+    pub aad: Vec<u8>,
+}
+
+/// A batch of decryption requests where real requests are hidden among dummy ones.
+///
+/// Dummy requests are valid "canary" ciphertexts of random plaintexts under the same public
+/// key, with the same payload sizes and associated data as the real requests, so validators
+/// and network observers can't tell them apart. Only the client knows which is which.
+#[derive(Clone, Debug)]
+pub struct CoverTrafficBatch<E: Pairing> {
+    requests: Vec<DecryptionRequest<E>>,
+    /// Position of each real request in `requests`, in the original order
+    real_positions: Vec<usize>,
+}
+
+impl<E: Pairing> CoverTrafficBatch<E> {
+    /// Mixes `dummies_per_request` dummy requests for each real request, in random order.
+    pub fn new(
+        real_requests: Vec<DecryptionRequest<E>>,
+        dummies_per_request: usize,
+        pubkey: &E::G1Affine,
+        rng: &mut impl Rng,
+    ) -> Result<Self> {
+        let real_count = real_requests.len();
+        let mut tagged =
+            Vec::with_capacity(real_count * (dummies_per_request + 1));
+        for _ in 0..real_count * dummies_per_request {
+            // Mimic a random real request, so that the size distribution is preserved
+            let template = real_requests
+                .choose(rng)
+                .expect("There is at least one real request");
+            tagged.push((None, Self::make_dummy(template, pubkey, rng)?));
+        }
+        for (i, real) in real_requests.into_iter().enumerate() {
+            tagged.push((Some(i), real));
+        }
+        tagged.shuffle(rng);
+
+        let mut real_positions = vec![0; real_count];
+        for (position, (real_index, _)) in tagged.iter().enumerate() {
+            if let Some(i) = real_index {
+                real_positions[*i] = position;
+            }
+        }
+        let requests = tagged.into_iter().map(|(_, request)| request).collect();
+        Ok(Self {
+            requests,
+            real_positions,
+        })
+    }
+
+    fn make_dummy(
+        template: &DecryptionRequest<E>,
+        pubkey: &E::G1Affine,
+        rng: &mut impl Rng,
+    ) -> Result<DecryptionRequest<E>> {
+        let plaintext_len = template
+            .ciphertext
+            .ciphertext
+            .len()
+            .saturating_sub(V1::AEAD_TAG_SIZE);
+        let mut plaintext = vec![0u8; plaintext_len];
+        rng.fill_bytes(&mut plaintext);
+        let ciphertext = encrypt::<E>(
+            SecretBox::new(plaintext),
+            &template.aad,
+            pubkey,
+            rng,
+        )?;
+        Ok(DecryptionRequest {
+            ciphertext,
+            aad: template.aad.clone(),
+        })
+    }
+
+    /// All requests to be sent out, real and dummy.
+    pub fn requests(&self) -> &[DecryptionRequest<E>] {
+        &self.requests
+    }
+
+    pub fn is_dummy(&self, position: usize) -> bool {
+        !self.real_positions.contains(&position)
+    }
+
+    /// Given the responses to `requests()`, in the same order, returns the responses to the
+    /// real requests in their original order and discards the rest.
+    pub fn real_responses<T>(&self, responses: Vec<T>) -> Vec<T> {
+        let mut responses = responses.into_iter().map(Some).collect::<Vec<_>>();
+        self.real_positions
+            .iter()
+            .filter_map(|position| responses.get_mut(*position)?.take())
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "tdec-simple"))]
+mod tests {
+    use ark_std::test_rng;
+
+    use crate::{test_common::*, *};
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[test]
+    fn cover_traffic_batch() {
+        let rng = &mut test_rng();
+        let (pubkey, _, contexts) = setup_simple::<E>(3, 4, rng);
+        let real_requests: Vec<_> = ["first", "second"]
+            .iter()
+            .map(|msg| {
+                let aad = format!("{msg}-aad").into_bytes();
+                DecryptionRequest {
+                    ciphertext: encrypt::<E>(
+                        SecretBox::new(msg.as_bytes().to_vec()),
+                        &aad,
+                        &pubkey,
+                        rng,
+                    )
+                    .unwrap(),
+                    aad,
+                }
+            })
+            .collect();
+
+        let batch =
+            CoverTrafficBatch::new(real_requests.clone(), 3, &pubkey, rng)
+                .unwrap();
+        assert_eq!(batch.requests().len(), 8);
+        assert_eq!((0..8).filter(|i| batch.is_dummy(*i)).count(), 6,);
+
+        // Validators handle every request the same way
+        let responses = contexts[0].create_share_batch(batch.requests());
+        assert!(responses.iter().all(|share| share.is_ok()));
+
+        let real_ciphertexts = batch.real_responses(
+            batch
+                .requests()
+                .iter()
+                .map(|r| r.ciphertext.clone())
+                .collect(),
+        );
+        assert_eq!(
+            real_ciphertexts,
+            real_requests
+                .into_iter()
+                .map(|r| r.ciphertext)
+                .collect::<Vec<_>>()
+        );
+    }
+}
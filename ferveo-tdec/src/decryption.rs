@@ -1,33 +1,165 @@
-use std::ops::Mul;
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+use std::{collections::BTreeSet, ops::Mul};
 
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
 use ark_ec::{pairing::Pairing, CurveGroup};
-use ark_ff::{Field, One, Zero};
-use ferveo_common::serialization;
-use itertools::{izip, zip_eq};
+#[cfg(feature = "tdec-simple")]
+use ark_ff::Field;
+#[cfg(feature = "tdec-fast")]
+use ark_ff::{One, Zero};
+#[cfg(feature = "tdec-simple")]
+use ark_serialize::CanonicalSerialize;
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+use ark_serialize::Valid;
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+use ferveo_common::{serialization, SecurityConfig};
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+use itertools::izip;
+#[cfg(feature = "tdec-fast")]
+use itertools::zip_eq;
+#[cfg(feature = "tdec-fast")]
 use rand_core::RngCore;
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+#[cfg(feature = "tdec-simple")]
+use serde::de::DeserializeOwned;
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
 use serde_with::serde_as;
 
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+use crate::Ciphertext;
+#[cfg(feature = "tdec-fast")]
+use crate::{generate_random, PublicDecryptionContextFast};
+#[cfg(feature = "tdec-simple")]
 use crate::{
-    generate_random, Ciphertext, CiphertextHeader, PrivateKeyShare,
-    PublicDecryptionContextFast, PublicDecryptionContextSimple, Result,
+    hash_to_g2,
+    protocol::{ProtocolVersion, V1},
+    CiphertextHeader, PrivateKeyShare, PublicDecryptionContextSimple,
+    VerificationReport,
 };
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+use crate::{Error, Result};
 
+/// Identifies the request that a decryption share was produced for.
+#[cfg(feature = "tdec-simple")]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareRequestContext {
+    #[serde(with = "serde_bytes")]
+    pub requester: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    pub nonce: Vec<u8>,
+}
+
+#[cfg(feature = "tdec-simple")]
+impl ShareRequestContext {
+    pub fn new(requester: &[u8], nonce: &[u8]) -> Self {
+        Self {
+            requester: requester.to_vec(),
+            nonce: nonce.to_vec(),
+        }
+    }
+
+    /// H(tag || U || len(requester) || requester || len(nonce) || nonce)
+    fn hash_to_g2<E: Pairing>(
+        &self,
+        commitment: &E::G1Affine,
+    ) -> Result<E::G2Affine> {
+        let mut hash_input = V1::SHARE_REQUEST_BINDING_DOMAIN.to_vec();
+        commitment.serialize_compressed(&mut hash_input)?;
+        for field in [&self.requester, &self.nonce] {
+            hash_input.extend_from_slice(&(field.len() as u64).to_be_bytes());
+            hash_input.extend_from_slice(field);
+        }
+        hash_to_g2(&hash_input)
+    }
+}
+
+#[cfg(feature = "tdec-fast")]
 #[serde_as]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DecryptionShareFast<E: Pairing> {
     pub decrypter_index: usize,
     #[serde_as(as = "serialization::SerdeAs")]
     pub decryption_share: E::G1Affine,
 }
 
-#[serde_as]
+/// Checksums are encoded with a version tag, see [`DecryptionShareSimple::from_legacy_bytes`]
+/// and [`DecryptionSharePrecomputed::from_legacy_bytes`] for the shares encoded without one
+#[cfg(feature = "tdec-simple")]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(
+    bound(serialize = "", deserialize = ""),
+    into = "VersionedShareChecksum<E>",
+    from = "VersionedShareChecksum<E>"
+)]
 pub struct ValidatorShareChecksum<E: Pairing> {
-    #[serde_as(as = "serialization::SerdeAs")]
     pub checksum: E::G1Affine,
+    /// `dk_i^{-1} * H(request)`, binding the share to the request it was produced for
+    pub request_binding: Option<E::G2Affine>,
+}
+
+/// The encoding of a [`ValidatorShareChecksum`]. Every version is a variant, so that fields
+/// can be added to checksums without changing how the checksums encoded before decode
+#[cfg(feature = "tdec-simple")]
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+enum VersionedShareChecksum<E: Pairing> {
+    V1 {
+        #[serde_as(as = "serialization::SerdeAs")]
+        checksum: E::G1Affine,
+        #[serde_as(as = "Option<serialization::SerdeAs>")]
+        request_binding: Option<E::G2Affine>,
+    },
 }
 
+#[cfg(feature = "tdec-simple")]
+impl<E: Pairing> From<ValidatorShareChecksum<E>> for VersionedShareChecksum<E> {
+    fn from(checksum: ValidatorShareChecksum<E>) -> Self {
+        VersionedShareChecksum::V1 {
+            checksum: checksum.checksum,
+            request_binding: checksum.request_binding,
+        }
+    }
+}
+
+#[cfg(feature = "tdec-simple")]
+impl<E: Pairing> From<VersionedShareChecksum<E>> for ValidatorShareChecksum<E> {
+    fn from(checksum: VersionedShareChecksum<E>) -> Self {
+        match checksum {
+            VersionedShareChecksum::V1 {
+                checksum,
+                request_binding,
+            } => Self {
+                checksum,
+                request_binding,
+            },
+        }
+    }
+}
+
+/// The encoding of a [`ValidatorShareChecksum`] before request bindings, without a version
+/// tag
+#[cfg(feature = "tdec-simple")]
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = ""))]
+struct LegacyShareChecksum<E: Pairing> {
+    #[serde_as(as = "serialization::SerdeAs")]
+    checksum: E::G1Affine,
+}
+
+#[cfg(feature = "tdec-simple")]
+impl<E: Pairing> From<LegacyShareChecksum<E>> for ValidatorShareChecksum<E> {
+    fn from(legacy: LegacyShareChecksum<E>) -> Self {
+        Self {
+            checksum: legacy.checksum,
+            request_binding: None,
+        }
+    }
+}
+
+#[cfg(feature = "tdec-simple")]
 impl<E: Pairing> ValidatorShareChecksum<E> {
     pub fn new(
         validator_decryption_key: &E::ScalarField,
@@ -45,7 +177,50 @@ impl<E: Pairing> ValidatorShareChecksum<E> {
                     .expect("Inverse of this key doesn't exist"),
             )
             .into_affine();
-        Ok(Self { checksum })
+        Ok(Self {
+            checksum,
+            request_binding: None,
+        })
+    }
+
+    /// Same as [`ValidatorShareChecksum::new`], but also binds the checksum to the given
+    /// request, so that it can't be presented as service to a different requester.
+    pub fn new_for_request(
+        validator_decryption_key: &E::ScalarField,
+        ciphertext_header: &CiphertextHeader<E>,
+        request: &ShareRequestContext,
+    ) -> Result<Self> {
+        let mut checksum =
+            Self::new(validator_decryption_key, ciphertext_header)?;
+        // R_i = dk_i^{-1} * H(request)
+        let request_binding = request
+            .hash_to_g2::<E>(&ciphertext_header.commitment)?
+            .mul(
+                validator_decryption_key
+                    .inverse()
+                    .expect("Inverse of this key doesn't exist"),
+            )
+            .into_affine();
+        checksum.request_binding = Some(request_binding);
+        Ok(checksum)
+    }
+
+    /// Check that the checksum was produced for the given request.
+    /// Must be used together with [`ValidatorShareChecksum::verify`], which ties the checksum
+    /// to the validator.
+    pub fn verify_request(
+        &self,
+        ciphertext: &Ciphertext<E>,
+        request: &ShareRequestContext,
+    ) -> Result<bool> {
+        let request_binding = match self.request_binding {
+            Some(request_binding) => request_binding,
+            None => return Ok(false),
+        };
+        // e(C_i, H(request)) == e(U, R_i)
+        let request_hash = request.hash_to_g2::<E>(&ciphertext.commitment)?;
+        Ok(E::pairing(self.checksum, request_hash)
+            == E::pairing(ciphertext.commitment, request_binding))
     }
 
     pub fn verify(
@@ -55,6 +230,25 @@ impl<E: Pairing> ValidatorShareChecksum<E> {
         validator_public_key: &E::G2Affine,
         h: &E::G2,
         ciphertext: &Ciphertext<E>,
+    ) -> bool {
+        self.verify_with_prepared_key(
+            decryption_share,
+            share_aggregate,
+            &E::G2Prepared::from(*validator_public_key),
+            h,
+            ciphertext,
+        )
+    }
+
+    /// Same as `verify`, with a validator public key that was prepared in advance,
+    /// e.g. once per DKG session.
+    pub fn verify_with_prepared_key(
+        &self,
+        decryption_share: &E::TargetField,
+        share_aggregate: &E::G2Affine,
+        validator_public_key: &E::G2Prepared,
+        h: &E::G2,
+        ciphertext: &Ciphertext<E>,
     ) -> bool {
         // See https://github.com/nucypher/ferveo/issues/42#issuecomment-1398953777
         // D_i == e(C_i, Y_i)
@@ -64,7 +258,7 @@ impl<E: Pairing> ValidatorShareChecksum<E> {
 
         // TODO: use multipairing here (h_inv)
         // e(C_i, ek_i) == e(U, H)
-        if E::pairing(self.checksum, *validator_public_key)
+        if E::pairing(self.checksum, validator_public_key.clone())
             != E::pairing(ciphertext.commitment, *h)
         {
             return false;
@@ -72,8 +266,30 @@ impl<E: Pairing> ValidatorShareChecksum<E> {
 
         true
     }
+
+    /// Same as [`ValidatorShareChecksum::verify`], but runs every check and reports which
+    /// of them failed.
+    pub fn verify_report(
+        &self,
+        decryption_share: &E::TargetField,
+        share_aggregate: &E::G2Affine,
+        validator_public_key: &E::G2Affine,
+        h: &E::G2,
+        ciphertext: &Ciphertext<E>,
+    ) -> VerificationReport {
+        let mut report = VerificationReport::new();
+        report.check("decryption_share_matches_checksum", || {
+            *decryption_share == E::pairing(self.checksum, *share_aggregate).0
+        });
+        report.check("checksum_matches_validator_key", || {
+            E::pairing(self.checksum, *validator_public_key)
+                == E::pairing(ciphertext.commitment, *h)
+        });
+        report
+    }
 }
 
+#[cfg(feature = "tdec-simple")]
 #[serde_as]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(bound(
@@ -86,7 +302,30 @@ pub struct DecryptionShareSimple<E: Pairing> {
     pub validator_checksum: ValidatorShareChecksum<E>,
 }
 
+/// The encoding of a [`DecryptionShareSimple`] before request bindings
+#[cfg(feature = "tdec-simple")]
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = ""))]
+struct LegacyDecryptionShareSimple<E: Pairing> {
+    #[serde_as(as = "serialization::SerdeAs")]
+    decryption_share: E::TargetField,
+    validator_checksum: LegacyShareChecksum<E>,
+}
+
+#[cfg(feature = "tdec-simple")]
 impl<E: Pairing> DecryptionShareSimple<E> {
+    /// Decode a decryption share from its bincode encoding before request bindings, which
+    /// had no version tag. The share isn't bound to any request
+    pub fn from_legacy_bytes(bytes: &[u8]) -> Result<Self> {
+        let legacy: LegacyDecryptionShareSimple<E> =
+            bincode::deserialize(bytes)?;
+        Ok(Self {
+            decryption_share: legacy.decryption_share,
+            validator_checksum: legacy.validator_checksum.into(),
+        })
+    }
+
     /// Create a decryption share from the given parameters.
     /// This function checks that the ciphertext is valid.
     pub fn create(
@@ -128,6 +367,48 @@ impl<E: Pairing> DecryptionShareSimple<E> {
             validator_checksum,
         })
     }
+
+    /// Same as [`DecryptionShareSimple::create`], but the share is bound to the given request.
+    pub fn create_for_request(
+        validator_decryption_key: &E::ScalarField,
+        private_key_share: &PrivateKeyShare<E>,
+        ciphertext_header: &CiphertextHeader<E>,
+        aad: &[u8],
+        g_inv: &E::G1Prepared,
+        request: &ShareRequestContext,
+    ) -> Result<Self> {
+        let mut share = Self::create(
+            validator_decryption_key,
+            private_key_share,
+            ciphertext_header,
+            aad,
+            g_inv,
+        )?;
+        share.validator_checksum = ValidatorShareChecksum::new_for_request(
+            validator_decryption_key,
+            ciphertext_header,
+            request,
+        )?;
+        Ok(share)
+    }
+
+    /// Verify that the decryption share is valid and was produced for the given request.
+    pub fn verify_for_request(
+        &self,
+        share_aggregate: &E::G2Affine,
+        validator_public_key: &E::G2Affine,
+        h: &E::G2,
+        ciphertext: &Ciphertext<E>,
+        request: &ShareRequestContext,
+    ) -> Result<bool> {
+        Ok(
+            self.verify(share_aggregate, validator_public_key, h, ciphertext)
+                && self
+                    .validator_checksum
+                    .verify_request(ciphertext, request)?,
+        )
+    }
+
     /// Verify that the decryption share is valid.
     pub fn verify(
         &self,
@@ -144,8 +425,43 @@ impl<E: Pairing> DecryptionShareSimple<E> {
             ciphertext,
         )
     }
+
+    /// Same as `verify`, with a validator public key that was prepared in advance.
+    pub fn verify_with_prepared_key(
+        &self,
+        share_aggregate: &E::G2Affine,
+        validator_public_key: &E::G2Prepared,
+        h: &E::G2,
+        ciphertext: &Ciphertext<E>,
+    ) -> bool {
+        self.validator_checksum.verify_with_prepared_key(
+            &self.decryption_share,
+            share_aggregate,
+            validator_public_key,
+            h,
+            ciphertext,
+        )
+    }
+
+    /// Same as `verify`, but reports which checks failed.
+    pub fn verify_report(
+        &self,
+        share_aggregate: &E::G2Affine,
+        validator_public_key: &E::G2Affine,
+        h: &E::G2,
+        ciphertext: &Ciphertext<E>,
+    ) -> VerificationReport {
+        self.validator_checksum.verify_report(
+            &self.decryption_share,
+            share_aggregate,
+            validator_public_key,
+            h,
+            ciphertext,
+        )
+    }
 }
 
+#[cfg(feature = "tdec-simple")]
 #[serde_as]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(bound(
@@ -159,7 +475,32 @@ pub struct DecryptionSharePrecomputed<E: Pairing> {
     pub validator_checksum: ValidatorShareChecksum<E>,
 }
 
+/// The encoding of a [`DecryptionSharePrecomputed`] before request bindings
+#[cfg(feature = "tdec-simple")]
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = ""))]
+struct LegacyDecryptionSharePrecomputed<E: Pairing> {
+    decrypter_index: usize,
+    #[serde_as(as = "serialization::SerdeAs")]
+    decryption_share: E::TargetField,
+    validator_checksum: LegacyShareChecksum<E>,
+}
+
+#[cfg(feature = "tdec-simple")]
 impl<E: Pairing> DecryptionSharePrecomputed<E> {
+    /// Decode a decryption share from its bincode encoding before request bindings, which
+    /// had no version tag. The share isn't bound to any request
+    pub fn from_legacy_bytes(bytes: &[u8]) -> Result<Self> {
+        let legacy: LegacyDecryptionSharePrecomputed<E> =
+            bincode::deserialize(bytes)?;
+        Ok(Self {
+            decrypter_index: legacy.decrypter_index,
+            decryption_share: legacy.decryption_share,
+            validator_checksum: legacy.validator_checksum.into(),
+        })
+    }
+
     pub fn new(
         validator_index: usize,
         validator_decryption_key: &E::ScalarField,
@@ -224,9 +565,27 @@ impl<E: Pairing> DecryptionSharePrecomputed<E> {
             ciphertext,
         )
     }
+
+    /// Same as `verify`, with a validator public key that was prepared in advance.
+    pub fn verify_with_prepared_key(
+        &self,
+        share_aggregate: &E::G2Affine,
+        validator_public_key: &E::G2Prepared,
+        h: &E::G2,
+        ciphertext: &Ciphertext<E>,
+    ) -> bool {
+        self.validator_checksum.verify_with_prepared_key(
+            &self.decryption_share,
+            share_aggregate,
+            validator_public_key,
+            h,
+            ciphertext,
+        )
+    }
 }
 
 // TODO: Remove this code? Currently only used in benchmarks. Move to benchmark suite?
+#[cfg(feature = "tdec-fast")]
 pub fn batch_verify_decryption_shares<R: RngCore, E: Pairing>(
     pub_contexts: &[PublicDecryptionContextFast<E>],
     ciphertexts: &[Ciphertext<E>],
@@ -293,6 +652,7 @@ pub fn batch_verify_decryption_shares<R: RngCore, E: Pairing>(
     E::multi_pairing(pairings_a, pairings_b).0 == E::TargetField::one()
 }
 
+#[cfg(feature = "tdec-fast")]
 pub fn verify_decryption_shares_fast<E: Pairing>(
     pub_contexts: &[PublicDecryptionContextFast<E>],
     ciphertext: &Ciphertext<E>,
@@ -331,6 +691,7 @@ pub fn verify_decryption_shares_fast<E: Pairing>(
     true
 }
 
+#[cfg(feature = "tdec-simple")]
 pub fn verify_decryption_shares_simple<E: Pairing>(
     pub_contexts: &Vec<PublicDecryptionContextSimple<E>>,
     ciphertext: &Ciphertext<E>,
@@ -356,7 +717,128 @@ pub fn verify_decryption_shares_simple<E: Pairing>(
     true
 }
 
-#[cfg(test)]
+/// The positions in `decryption_shares` of the shares that fail
+/// [`verify_decryption_shares_fast`], checked as `security` sets.
+///
+/// If verification is batched, the shares are checked at once with
+/// [`batch_verify_decryption_shares`] first, and one by one only if the batch fails.
+#[cfg(feature = "tdec-fast")]
+pub fn find_invalid_decryption_shares_fast<R: RngCore, E: Pairing>(
+    pub_contexts: &[PublicDecryptionContextFast<E>],
+    ciphertext: &Ciphertext<E>,
+    decryption_shares: &[DecryptionShareFast<E>],
+    security: &SecurityConfig,
+    rng: &mut R,
+) -> Result<Vec<usize>> {
+    check_cohort_size(
+        decryption_shares.iter().map(|share| share.decrypter_index),
+        security,
+    )?;
+    // Malformed shares would fail every batch they are in, so they are set aside first
+    let (well_formed, malformed): (Vec<_>, Vec<_>) = decryption_shares
+        .iter()
+        .enumerate()
+        .partition(|(_, share)| {
+            share.decrypter_index < pub_contexts.len()
+                && (!security.strict_subgroup_checks
+                    || share.decryption_share.check().is_ok())
+        });
+    let mut invalid = malformed
+        .into_iter()
+        .map(|(position, _)| position)
+        .collect::<Vec<_>>();
+    if security.batch_verification {
+        let batch = well_formed
+            .iter()
+            .map(|(_, share)| (*share).clone())
+            .collect::<Vec<_>>();
+        if batch_verify_decryption_shares(
+            pub_contexts,
+            std::slice::from_ref(ciphertext),
+            &[batch],
+            rng,
+        ) {
+            return Ok(invalid);
+        }
+    }
+    invalid.extend(
+        well_formed
+            .into_iter()
+            .filter(|(_, share)| {
+                !verify_decryption_shares_fast(
+                    pub_contexts,
+                    ciphertext,
+                    std::slice::from_ref(*share),
+                )
+            })
+            .map(|(position, _)| position),
+    );
+    invalid.sort_unstable();
+    Ok(invalid)
+}
+
+/// The positions in `decryption_shares` of the shares that fail
+/// [`verify_decryption_shares_simple`], checked as `security` sets. Simple decryption shares
+/// are always checked one by one.
+///
+/// Simple decryption shares don't carry a share index, so they are given as
+/// `(share_index, share)` pairs, and each share is checked against the public context of
+/// its share index, in any order.
+#[cfg(feature = "tdec-simple")]
+pub fn find_invalid_decryption_shares_simple<E: Pairing>(
+    pub_contexts: &[PublicDecryptionContextSimple<E>],
+    ciphertext: &Ciphertext<E>,
+    decryption_shares: &[(usize, DecryptionShareSimple<E>)],
+    security: &SecurityConfig,
+) -> Result<Vec<usize>> {
+    check_cohort_size(
+        decryption_shares
+            .iter()
+            .map(|(share_index, _)| *share_index),
+        security,
+    )?;
+    Ok(decryption_shares
+        .iter()
+        .enumerate()
+        .filter(|(_, (share_index, share))| {
+            let pub_context = match pub_contexts.get(*share_index) {
+                Some(pub_context) => pub_context,
+                None => return true,
+            };
+            let checksum = &share.validator_checksum;
+            if security.strict_subgroup_checks
+                && (checksum.checksum.check().is_err()
+                    || checksum.request_binding.check().is_err())
+            {
+                return true;
+            }
+            !share.verify(
+                &pub_context.blinded_key_share.blinded_key_share,
+                &pub_context.validator_public_key.into_affine(),
+                &pub_context.h.into(),
+                ciphertext,
+            )
+        })
+        .map(|(position, _)| position)
+        .collect())
+}
+
+/// Checks that the shares come from at most `max_cohort_size` decrypters. Repeated shares of
+/// a decrypter only count once.
+#[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+fn check_cohort_size(
+    share_indices: impl IntoIterator<Item = usize>,
+    security: &SecurityConfig,
+) -> Result<()> {
+    let cohort_size = share_indices.into_iter().collect::<BTreeSet<_>>().len();
+    if cohort_size > security.max_cohort_size as usize {
+        Err(Error::CohortTooLarge(cohort_size, security.max_cohort_size))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "tdec-fast"))]
 mod tests {
     use ark_ec::AffineRepr;
     use ferveo_common::{FromBytes, ToBytes};
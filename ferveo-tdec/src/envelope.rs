@@ -0,0 +1,418 @@
+use ark_ec::pairing::Pairing;
+use miniz_oxide::{
+    deflate::compress_to_vec, inflate::decompress_to_vec_with_limit,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::{
+    decrypt_with_shared_secret, encrypt_with_quorum,
+    protocol::{ProtocolVersion, V1},
+    Ciphertext, Error, Result, SecretBox, SecretBuffer, SharedSecret,
+};
+
+/// Default upper bound on the size of a decompressed plaintext
+pub const MAX_DECOMPRESSED_SIZE: usize = 16 * 1024 * 1024;
+
+const DEFLATE_LEVEL: u8 = 6;
+#[cfg(feature = "zstd")]
+const ZSTD_LEVEL: i32 = 3;
+
+/// Compression of the plaintext before encryption.
+///
+/// The algorithm is stored both in the envelope, so that a decrypter can tell upfront
+/// whether it supports it, and in the authenticated plaintext.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub enum Compression {
+    #[default]
+    None,
+    Deflate,
+    /// Requires the `zstd` feature
+    Zstd,
+}
+
+impl Compression {
+    fn flag(&self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Deflate => 1,
+            Compression::Zstd => 2,
+        }
+    }
+
+    fn from_flag(flag: u8) -> Result<Self> {
+        match flag {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Deflate),
+            2 => Ok(Compression::Zstd),
+            _ => Err(Error::MalformedEnvelope),
+        }
+    }
+
+    /// Whether this build can compress and decompress with this algorithm
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Compression::None | Compression::Deflate => true,
+            Compression::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+
+    /// Compress `message`, or `None` if it is left uncompressed, so that it isn't copied
+    fn compress(&self, message: &[u8]) -> Result<Option<Zeroizing<Vec<u8>>>> {
+        match self {
+            Compression::None => Ok(None),
+            Compression::Deflate => Ok(Some(Zeroizing::new(compress_to_vec(
+                message,
+                DEFLATE_LEVEL,
+            )))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::bulk::compress(message, ZSTD_LEVEL)
+                .map(|compressed| Some(Zeroizing::new(compressed)))
+                .map_err(|_| Error::UnsupportedCompression(self.flag())),
+            #[cfg(not(feature = "zstd"))]
+            Compression::Zstd => {
+                Err(Error::UnsupportedCompression(self.flag()))
+            }
+        }
+    }
+
+    /// Decompress `body`, failing if the result would exceed `max_size` bytes
+    fn decompress(&self, body: &[u8], max_size: usize) -> Result<Vec<u8>> {
+        match self {
+            Compression::None if body.len() > max_size => {
+                Err(Error::PlaintextTooLarge(body.len(), max_size))
+            }
+            Compression::None => Ok(body.to_vec()),
+            Compression::Deflate => {
+                decompress_to_vec_with_limit(body, max_size)
+                    .map_err(|_| Error::DecompressionFailed)
+            }
+            // The output grows as the frame is decoded, instead of being allocated upfront
+            // from the size the frame declares
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => {
+                use std::io::Read;
+
+                let mut plaintext = Zeroizing::new(Vec::new());
+                zstd::stream::read::Decoder::new(body)
+                    .and_then(|decoder| {
+                        decoder
+                            .take(max_size as u64 + 1)
+                            .read_to_end(&mut plaintext)
+                    })
+                    .map_err(|_| Error::DecompressionFailed)?;
+                if plaintext.len() > max_size {
+                    return Err(Error::DecompressionFailed);
+                }
+                Ok(std::mem::take(&mut *plaintext))
+            }
+            #[cfg(not(feature = "zstd"))]
+            Compression::Zstd => {
+                Err(Error::UnsupportedCompression(self.flag()))
+            }
+        }
+    }
+}
+
+/// A ciphertext together with the metadata required to decrypt it.
+///
+/// The plaintext is framed as `flags || len(label) || label || body`, so that the label and
+/// the compression flag are authenticated along with the message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "Ciphertext<E>: Serialize",
+    deserialize = "Ciphertext<E>: DeserializeOwned"
+))]
+pub struct CiphertextEnvelope<E: Pairing> {
+    pub version: u8,
+    /// Policy label, e.g. the name of the conditions the ciphertext is encrypted under
+    #[serde(with = "serde_bytes")]
+    pub label: Vec<u8>,
+    /// The AAD the ciphertext is bound to
+    #[serde(with = "serde_bytes")]
+    pub aad: Vec<u8>,
+    pub compression: Compression,
+    pub ciphertext: Ciphertext<E>,
+}
+
+impl<E: Pairing> CiphertextEnvelope<E> {
+    pub fn decrypt_with_shared_secret(
+        &self,
+        shared_secret: &SharedSecret<E>,
+        g_inv: &E::G1Prepared,
+    ) -> Result<SecretBuffer> {
+        self.decrypt_with_shared_secret_limited(
+            shared_secret,
+            g_inv,
+            MAX_DECOMPRESSED_SIZE,
+        )
+    }
+
+    /// Same as [`CiphertextEnvelope::decrypt_with_shared_secret`], but with a custom bound
+    /// on the size of the decompressed plaintext.
+    pub fn decrypt_with_shared_secret_limited(
+        &self,
+        shared_secret: &SharedSecret<E>,
+        g_inv: &E::G1Prepared,
+        max_size: usize,
+    ) -> Result<SecretBuffer> {
+        if self.version != V1::ENVELOPE_VERSION {
+            return Err(Error::UnsupportedEnvelopeVersion(self.version));
+        }
+        // Fail before decrypting if this build can't decompress the plaintext
+        if !self.compression.is_supported() {
+            return Err(Error::UnsupportedCompression(self.compression.flag()));
+        }
+        let plaintext = decrypt_with_shared_secret(
+            &self.ciphertext,
+            &self.aad,
+            shared_secret,
+            g_inv,
+        )?;
+        let (flags, rest) = plaintext
+            .as_bytes()
+            .split_first()
+            .ok_or(Error::MalformedEnvelope)?;
+        if rest.len() < 4 {
+            return Err(Error::MalformedEnvelope);
+        }
+        let (label_len, rest) = rest.split_at(4);
+        let label_len =
+            u32::from_be_bytes(label_len.try_into().unwrap()) as usize;
+        if rest.len() < label_len {
+            return Err(Error::MalformedEnvelope);
+        }
+        let (label, body) = rest.split_at(label_len);
+        if label != self.label {
+            return Err(Error::EnvelopeLabelMismatch);
+        }
+        if Compression::from_flag(*flags)? != self.compression {
+            return Err(Error::MalformedEnvelope);
+        }
+        self.compression
+            .decompress(body, max_size)
+            .map(SecretBuffer::new)
+    }
+}
+
+/// Builds a [`CiphertextEnvelope`], checking the invariants that the [`crate::encrypt`] function
+/// leaves to the caller.
+#[derive(Clone, Debug)]
+pub struct CiphertextBuilder<E: Pairing> {
+    pubkey: E::G1Affine,
+    aad: Vec<u8>,
+    label: Vec<u8>,
+    max_size: Option<usize>,
+    compression: Compression,
+    quorum: Option<u32>,
+}
+
+impl<E: Pairing> CiphertextBuilder<E> {
+    pub fn new(pubkey: E::G1Affine) -> Self {
+        Self {
+            pubkey,
+            aad: Vec::new(),
+            label: Vec::new(),
+            max_size: None,
+            compression: Compression::None,
+            quorum: None,
+        }
+    }
+
+    pub fn aad(mut self, aad: &[u8]) -> Self {
+        self.aad = aad.to_vec();
+        self
+    }
+
+    pub fn label(mut self, label: &[u8]) -> Self {
+        self.label = label.to_vec();
+        self
+    }
+
+    /// Reject messages larger than `max_size` bytes, before compression.
+    /// Defaults to [`MAX_DECOMPRESSED_SIZE`].
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Compress the message with deflate, or not at all
+    pub fn compress(self, compress: bool) -> Self {
+        self.compression(if compress {
+            Compression::Deflate
+        } else {
+            Compression::None
+        })
+    }
+
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Require `quorum` decryption shares to decrypt, see [`encrypt_with_quorum`]
+    pub fn quorum(mut self, quorum: u32) -> Self {
+        self.quorum = Some(quorum);
+        self
+    }
+
+    pub fn encrypt(
+        self,
+        message: SecretBox<Vec<u8>>,
+        rng: &mut impl rand::Rng,
+    ) -> Result<CiphertextEnvelope<E>> {
+        let message = message.as_secret();
+        let max_size = self.max_size.unwrap_or(MAX_DECOMPRESSED_SIZE);
+        if message.len() > max_size {
+            return Err(Error::PlaintextTooLarge(message.len(), max_size));
+        }
+
+        let compressed = self.compression.compress(message)?;
+        let body = compressed.as_ref().map_or(message, |body| body.as_slice());
+        // Sized upfront, so that no copy of the plaintext is left behind by a reallocation
+        let mut framed = SecretBox::new(Vec::with_capacity(
+            1 + 4 + self.label.len() + body.len(),
+        ));
+        let plaintext = framed.as_mut_secret();
+        plaintext.push(self.compression.flag());
+        plaintext.extend_from_slice(&(self.label.len() as u32).to_be_bytes());
+        plaintext.extend_from_slice(&self.label);
+        plaintext.extend_from_slice(body);
+
+        let ciphertext = encrypt_with_quorum(
+            framed,
+            &self.aad,
+            &self.pubkey,
+            self.quorum,
+            rng,
+        )?;
+        Ok(CiphertextEnvelope {
+            version: V1::ENVELOPE_VERSION,
+            label: self.label,
+            aad: self.aad,
+            compression: self.compression,
+            ciphertext,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "tdec-simple"))]
+mod tests {
+    use ark_ec::pairing::Pairing;
+    use ark_std::test_rng;
+
+    use crate::{test_common::*, *};
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[test]
+    fn envelope_round_trip() {
+        let rng = &mut test_rng();
+        let (pubkey, privkey, contexts) = setup_simple::<E>(3, 5, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let decrypt = |envelope: &CiphertextEnvelope<E>| {
+            let shared_secret = SharedSecret(
+                E::pairing(envelope.ciphertext.commitment, privkey).0,
+            );
+            envelope.decrypt_with_shared_secret(&shared_secret, g_inv)
+        };
+        let msg = "my-msg".repeat(100).into_bytes();
+
+        let supported =
+            [Compression::None, Compression::Deflate, Compression::Zstd]
+                .into_iter()
+                .filter(Compression::is_supported);
+        for compression in supported {
+            let envelope = CiphertextBuilder::<E>::new(pubkey)
+                .aad(b"my-aad")
+                .label(b"my-label")
+                .compression(compression)
+                .encrypt(SecretBox::new(msg.clone()), rng)
+                .unwrap();
+            assert_eq!(envelope.label, b"my-label");
+            assert_eq!(decrypt(&envelope).unwrap().as_bytes(), msg);
+            assert_eq!(
+                bincode::deserialize::<CiphertextEnvelope<E>>(
+                    &bincode::serialize(&envelope).unwrap()
+                )
+                .unwrap(),
+                envelope
+            );
+
+            // The public compression flag must match the authenticated one
+            let mut reflagged = envelope.clone();
+            reflagged.compression = match compression {
+                Compression::None => Compression::Deflate,
+                _ => Compression::None,
+            };
+            assert!(matches!(
+                decrypt(&reflagged),
+                Err(Error::MalformedEnvelope)
+            ));
+
+            // The public label must match the authenticated one
+            let mut relabeled = envelope.clone();
+            relabeled.label = b"other-label".to_vec();
+            assert!(matches!(
+                decrypt(&relabeled),
+                Err(Error::EnvelopeLabelMismatch)
+            ));
+
+            let mut wrong_aad = envelope;
+            wrong_aad.aad = b"other-aad".to_vec();
+            assert!(decrypt(&wrong_aad).is_err());
+        }
+
+        // Compression shrinks repetitive messages
+        let compressed = CiphertextBuilder::<E>::new(pubkey)
+            .compress(true)
+            .encrypt(SecretBox::new(msg.clone()), rng)
+            .unwrap();
+        assert!(compressed.ciphertext.ciphertext.len() < msg.len());
+
+        // Decompression is bounded
+        let shared_secret = SharedSecret(
+            E::pairing(compressed.ciphertext.commitment, privkey).0,
+        );
+        assert!(matches!(
+            compressed.decrypt_with_shared_secret_limited(
+                &shared_secret,
+                g_inv,
+                msg.len() - 1
+            ),
+            Err(Error::DecompressionFailed)
+        ));
+        if Compression::Zstd.is_supported() {
+            let zstd = CiphertextBuilder::<E>::new(pubkey)
+                .compression(Compression::Zstd)
+                .encrypt(SecretBox::new(msg.clone()), rng)
+                .unwrap();
+            let shared_secret =
+                SharedSecret(E::pairing(zstd.ciphertext.commitment, privkey).0);
+            assert!(matches!(
+                zstd.decrypt_with_shared_secret_limited(
+                    &shared_secret,
+                    g_inv,
+                    msg.len() - 1
+                ),
+                Err(Error::DecompressionFailed)
+            ));
+        } else {
+            let mut zstd = compressed;
+            zstd.compression = Compression::Zstd;
+            assert!(matches!(
+                decrypt(&zstd),
+                Err(Error::UnsupportedCompression(2))
+            ));
+        }
+
+        assert!(matches!(
+            CiphertextBuilder::<E>::new(pubkey)
+                .max_size(msg.len() - 1)
+                .encrypt(SecretBox::new(msg.clone()), rng),
+            Err(Error::PlaintextTooLarge(len, max)) if len == msg.len() && max == msg.len() - 1
+        ));
+    }
+}
@@ -10,6 +10,8 @@ use miracl_core::{
     hmac,
 };
 
+use crate::protocol::{ProtocolVersion, V1};
+
 fn ceil(a: usize, b: usize) -> usize {
     (a - 1) / b + 1
 }
@@ -49,7 +51,7 @@ fn hash_to_field2_bls12381(
 }
 
 pub fn htp_bls12381_g2(msg: &[u8]) -> ark_bls12_381::G2Affine {
-    let dst = "QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_".as_bytes();
+    let dst = V1::HASH_TO_G2_DST;
     let u = hash_to_field2_bls12381(hmac::MC_SHA2, ecp::HASH_TYPE, dst, msg, 2);
     let mut P = ECP2::map2point(&u[0]);
     let P1 = ECP2::map2point(&u[1]);
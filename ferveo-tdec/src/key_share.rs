@@ -1,4 +1,4 @@
-use std::ops::Mul;
+use std::{fmt, ops::Mul};
 
 use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
 use ark_ff::One;
@@ -58,11 +58,26 @@ impl<E: Pairing> BlindedKeyShare<E> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ZeroizeOnDrop)]
+#[cfg_attr(feature = "unsafe-debug", derive(Debug))]
+#[derive(Clone, PartialEq, Eq, ZeroizeOnDrop)]
 pub struct PrivateKeyShare<E: Pairing> {
     pub private_key_share: E::G2Affine,
 }
 
+// Secret-bearing types print as redacted, unless the `unsafe-debug` feature is enabled
+#[cfg(not(feature = "unsafe-debug"))]
+impl<E: Pairing> fmt::Debug for PrivateKeyShare<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PrivateKeyShare(<redacted>)")
+    }
+}
+
+impl<E: Pairing> fmt::Display for PrivateKeyShare<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PrivateKeyShare(<redacted>)")
+    }
+}
+
 impl<E: Pairing> PrivateKeyShare<E> {
     pub fn blind(&self, b: E::ScalarField) -> BlindedKeyShare<E> {
         let blinding_key = E::G2Affine::generator().mul(b).into_affine();
@@ -1,12 +1,26 @@
 #![warn(rust_2018_idioms)]
 
+pub mod aad;
+#[cfg(feature = "tdec-simple")]
+pub mod aggregation_proof;
+pub mod cache;
 pub mod ciphertext;
 pub mod combine;
 pub mod context;
+pub mod cover_traffic;
 pub mod decryption;
+pub mod envelope;
 pub mod hash_to_curve;
 pub mod key_share;
+#[cfg(feature = "tdec-simple")]
+pub mod mpc;
+pub mod protocol;
+pub mod report;
 pub mod secret_box;
+pub mod share_commitment;
+pub mod suite;
+#[cfg(feature = "variant-analysis")]
+pub mod variant_analysis;
 
 // TODO: Only show the public API, tpke::api
 // use ciphertext::*;
@@ -17,24 +31,42 @@ pub mod secret_box;
 // use key_share::*;
 // use refresh::*;
 
+pub use aad::*;
+#[cfg(feature = "tdec-simple")]
+pub use aggregation_proof::*;
+pub use cache::*;
 pub use ciphertext::*;
 pub use combine::*;
 pub use context::*;
+pub use cover_traffic::*;
 pub use decryption::*;
+pub use envelope::*;
 pub use hash_to_curve::*;
 pub use key_share::*;
+#[cfg(feature = "tdec-simple")]
+pub use mpc::*;
+pub use report::*;
 pub use secret_box::*;
+pub use share_commitment::*;
+pub use suite::*;
+#[cfg(feature = "variant-analysis")]
+pub use variant_analysis::*;
 
 #[cfg(feature = "api")]
 pub mod api;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    /// Ciphertext verification failed
+    /// Ciphertext verification failed, i.e. the payload, header or AAD was corrupted
     /// Refers to the check 4.4.2 in the paper: https://eprint.iacr.org/2022/898.pdf
     #[error("Ciphertext verification failed")]
     CiphertextVerificationFailed,
 
+    /// A verified ciphertext doesn't decrypt with the shared secret, i.e. the shared secret
+    /// was combined from invalid decryption shares
+    #[error("Shared secret doesn't decrypt the ciphertext")]
+    InvalidSharedSecret,
+
     /// Decryption share verification failed
     /// Refers to the check 4.4.4 in the paper: https://eprint.iacr.org/2022/898.pdf
     #[error("Decryption share verification failed")]
@@ -44,6 +76,116 @@ pub enum Error {
     #[error("Symmetric encryption failed")]
     SymmetricEncryptionError(chacha20poly1305::aead::Error),
 
+    /// Decryption share commitments can't be added after the reveal phase has started
+    #[error("Decryption share commitment phase is closed")]
+    ShareCommitmentPhaseClosed,
+
+    /// Decryption share commitment for this share index was already added
+    #[error("Duplicate decryption share commitment for share index: {0}")]
+    DuplicateShareCommitment(usize),
+
+    /// Decryption shares can't be revealed before a quorum of commitments exists
+    #[error("Not enough decryption share commitments to reveal: {0} < {1}")]
+    ShareRevealBeforeQuorum(usize, usize),
+
+    /// Revealed decryption share doesn't have a matching commitment
+    #[error("Unknown decryption share commitment for share index: {0}")]
+    UnknownShareCommitment(usize),
+
+    /// Revealed decryption share doesn't match its commitment
+    #[error(
+        "Decryption share doesn't match its commitment for share index: {0}"
+    )]
+    ShareCommitmentMismatch(usize),
+
+    /// More than one decryption share was given for the same share index
+    #[error("Duplicate decryption share for share index: {0}")]
+    DuplicateDecryptionShare(usize),
+
+    /// A decrypter sent different decryption shares for the same ciphertext
+    #[error("Conflicting decryption shares for share index: {0}")]
+    ConflictingDecryptionShares(usize),
+
+    /// Not enough decryption shares to meet the quorum of the ciphertext
+    #[error("Not enough decryption shares for the quorum: {1} < {0}")]
+    InsufficientQuorum(u32, usize),
+
+    /// Decryption shares must be combined with one Lagrange coefficient each
+    #[error("Expected {0} decryption shares, got {1}")]
+    LagrangeCoefficientCountMismatch(usize, usize),
+
+    /// Decryption share refers to a share index that is not a part of the decryption context
+    #[error("Unknown decryption share index: {0}")]
+    UnknownDecryptionShareIndex(usize),
+
+    /// Artifact was created with a different cipher suite than the one expected
+    #[error("Cipher suite mismatch: expected {0}, got {1}")]
+    SuiteMismatch(SuiteId, SuiteId),
+
+    /// No handler is registered for the cipher suite of an artifact
+    #[error("Unknown cipher suite: {0}")]
+    UnknownSuite(SuiteId),
+
+    /// Artifact is too short to contain a cipher suite identifier
+    #[error("Artifact is too short to contain a cipher suite: {0} bytes")]
+    ArtifactTooShort(usize),
+
+    /// AAD doesn't start with a schema version
+    #[error("AAD is missing a schema version")]
+    MissingAadSchemaVersion,
+
+    /// AAD was encoded under a schema version that is not supported
+    #[error("Unsupported AAD schema version: {0}")]
+    UnsupportedAadSchemaVersion(u8),
+
+    /// AAD doesn't parse under its schema version
+    #[error("Malformed AAD")]
+    MalformedAad,
+
+    /// AAD doesn't carry any conditions
+    #[error("AAD has no conditions")]
+    EmptyAadConditions,
+
+    /// Message is larger than the ciphertext builder allows
+    #[error("Plaintext is too large: {0} > {1} bytes")]
+    PlaintextTooLarge(usize, usize),
+
+    /// Ciphertext envelope was created with an unsupported format version
+    #[error("Unsupported ciphertext envelope version: {0}")]
+    UnsupportedEnvelopeVersion(u8),
+
+    /// Decrypted plaintext isn't framed as expected
+    #[error("Malformed ciphertext envelope")]
+    MalformedEnvelope,
+
+    /// The label of the envelope doesn't match the label it was encrypted with
+    #[error("Ciphertext envelope label mismatch")]
+    EnvelopeLabelMismatch,
+
+    /// Compressed plaintext is invalid or too large
+    #[error("Plaintext decompression failed")]
+    DecompressionFailed,
+
+    /// Plaintext is compressed with an algorithm this build doesn't support
+    #[error("Unsupported plaintext compression: {0}")]
+    UnsupportedCompression(u8),
+
+    /// Serialized message is larger than the deserialization limit
+    #[error("Message is too large: more than {0} bytes")]
+    MessageTooLarge(u64),
+
+    /// Ciphertext payload is larger than the deserialization limit
+    #[error("Ciphertext payload is too large: {0} > {1} bytes")]
+    CiphertextPayloadTooLarge(usize, usize),
+
+    /// More decryption shares than the security configuration allows
+    #[error("Too many decrypters for the security configuration: {0} > {1}")]
+    CohortTooLarge(usize, u32),
+
+    /// A cohort must have at least one member
+    #[error("Cohort has no members")]
+    EmptyCohort,
+
     #[error(transparent)]
     BincodeError(#[from] bincode::Error),
 
@@ -56,22 +198,37 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// Factory functions for testing
 #[cfg(any(test, feature = "test-common"))]
 pub mod test_common {
+    #[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
     use std::{ops::Mul, usize};
 
     pub use ark_bls12_381::Bls12_381 as EllipticCurve;
+    #[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
     use ark_ec::{pairing::Pairing, AffineRepr};
+    #[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+    use ark_ff::Field;
+    #[cfg(feature = "tdec-fast")]
+    use ark_ff::One;
     pub use ark_ff::UniformRand;
-    use ark_ff::{Field, One, Zero};
+    #[cfg(feature = "tdec-simple")]
+    use ark_ff::Zero;
+    #[cfg(feature = "tdec-simple")]
+    use ark_poly::Polynomial;
+    #[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
     use ark_poly::{
         univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain,
-        Polynomial,
     };
+    #[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
+    use ferveo_common::SecurityConfig;
+    #[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
     use itertools::izip;
+    #[cfg(feature = "tdec-fast")]
     use rand_core::RngCore;
+    #[cfg(any(feature = "tdec-fast", feature = "tdec-simple"))]
     use subproductdomain::fast_multiexp;
 
     pub use super::*;
 
+    #[cfg(feature = "tdec-fast")]
     pub fn setup_fast<E: Pairing>(
         threshold: usize,
         shares_num: usize,
@@ -156,6 +313,7 @@ pub mod test_common {
                 },
                 private_key_share,
                 public_decryption_contexts: vec![],
+                security: SecurityConfig::default(),
             });
             public_contexts.push(PublicDecryptionContextFast::<E> {
                 domain: *domain,
@@ -174,6 +332,7 @@ pub mod test_common {
         (pubkey.into(), privkey.into(), private_contexts)
     }
 
+    #[cfg(feature = "tdec-simple")]
     pub fn setup_simple<E: Pairing>(
         threshold: usize,
         shares_num: usize,
@@ -244,6 +403,7 @@ pub mod test_common {
                 },
                 private_key_share,
                 public_decryption_contexts: vec![],
+                security: SecurityConfig::default(),
             });
             public_contexts.push(PublicDecryptionContextSimple::<E> {
                 domain: *domain,
@@ -262,6 +422,7 @@ pub mod test_common {
         (pubkey.into(), privkey.into(), private_contexts)
     }
 
+    #[cfg(feature = "tdec-simple")]
     pub fn setup_precomputed<E: Pairing>(
         shares_num: usize,
         rng: &mut impl rand::Rng,
@@ -274,6 +435,7 @@ pub mod test_common {
         setup_simple::<E>(shares_num, shares_num, rng)
     }
 
+    #[cfg(feature = "tdec-simple")]
     pub fn make_shared_secret<E: Pairing>(
         pub_contexts: &[PublicDecryptionContextSimple<E>],
         decryption_shares: &[DecryptionShareSimple<E>],
@@ -284,13 +446,14 @@ pub mod test_common {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "tdec-fast", feature = "tdec-simple"))]
 mod tests {
     use std::ops::Mul;
 
     use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+    use ark_ff::One;
     use ark_std::{test_rng, UniformRand};
-    use ferveo_common::{FromBytes, ToBytes};
+    use ferveo_common::{FromBytes, SecurityConfig, ToBytes};
 
     use crate::test_common::{make_shared_secret, setup_simple, *};
 
@@ -329,7 +492,7 @@ mod tests {
         let plaintext =
             decrypt_with_shared_secret(ciphertext, aad, shared_secret, g_inv)
                 .unwrap();
-        assert_eq!(plaintext, msg);
+        assert_eq!(plaintext.as_bytes(), msg);
 
         // Malformed the ciphertext
         let mut ciphertext = ciphertext.clone();
@@ -419,7 +582,8 @@ mod tests {
         let prepared_blinded_key_shares = prepare_combine_fast(
             &contexts[0].public_decryption_contexts,
             &decryption_shares,
-        );
+        )
+        .unwrap();
 
         let shared_secret = share_combine_fast(
             &contexts[0].public_decryption_contexts,
@@ -438,6 +602,96 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tdec_combine_is_independent_of_share_order() {
+        let rng = &mut test_rng();
+        let shares_num = 8;
+        let threshold = shares_num * 2 / 3;
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        // Fast variant
+        let (pubkey, _, contexts) = setup_fast::<E>(threshold, shares_num, rng);
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+        let ciphertext = encrypt::<E>(
+            SecretBox::new("my-msg".as_bytes().to_vec()),
+            aad,
+            &pubkey,
+            rng,
+        )
+        .unwrap();
+        let mut decryption_shares: Vec<_> = contexts
+            .iter()
+            .map(|c| c.create_share(&ciphertext, aad).unwrap())
+            .collect();
+        let combine = |shares: &[DecryptionShareFast<E>]| {
+            let prepared = prepare_combine_fast(pub_contexts, shares)?;
+            share_combine_fast(pub_contexts, &ciphertext, shares, &prepared)
+        };
+        let expected = combine(&decryption_shares).unwrap();
+        decryption_shares.reverse();
+        assert_eq!(combine(&decryption_shares).unwrap(), expected);
+
+        // A share relayed twice is only combined once
+        let mut duplicated = decryption_shares.clone();
+        duplicated.push(decryption_shares[0].clone());
+        assert_eq!(combine(&duplicated).unwrap(), expected);
+        // But a decrypter can't send two different shares
+        let mut conflicting = decryption_shares.clone();
+        conflicting.push(DecryptionShareFast {
+            decryption_share: decryption_shares[1].decryption_share,
+            ..decryption_shares[0].clone()
+        });
+        assert!(matches!(
+            combine(&conflicting),
+            Err(Error::ConflictingDecryptionShares(i)) if i == shares_num - 1
+        ));
+        let mut unknown = decryption_shares.clone();
+        unknown[0].decrypter_index = shares_num;
+        assert!(matches!(
+            combine(&unknown),
+            Err(Error::UnknownDecryptionShareIndex(i)) if i == shares_num
+        ));
+
+        // Precomputed variant
+        let (pubkey, _, contexts) = setup_precomputed::<E>(shares_num, rng);
+        let ciphertext = encrypt::<E>(
+            SecretBox::new("my-msg".as_bytes().to_vec()),
+            aad,
+            &pubkey,
+            rng,
+        )
+        .unwrap();
+        let mut decryption_shares: Vec<_> = contexts
+            .iter()
+            .map(|c| {
+                c.create_share_precomputed(&ciphertext.header().unwrap(), aad)
+                    .unwrap()
+            })
+            .collect();
+        let expected =
+            share_combine_precomputed::<E>(&decryption_shares).unwrap();
+        decryption_shares.reverse();
+        assert_eq!(
+            share_combine_precomputed::<E>(&decryption_shares).unwrap(),
+            expected
+        );
+        let mut duplicated = decryption_shares.clone();
+        duplicated.push(decryption_shares[0].clone());
+        assert_eq!(
+            share_combine_precomputed::<E>(&duplicated).unwrap(),
+            expected
+        );
+        let mut conflicting = decryption_shares.clone();
+        conflicting.push(DecryptionSharePrecomputed {
+            decryption_share: decryption_shares[1].decryption_share,
+            ..decryption_shares[0].clone()
+        });
+        assert!(matches!(
+            share_combine_precomputed::<E>(&conflicting),
+            Err(Error::ConflictingDecryptionShares(i)) if i == shares_num - 1
+        ));
+    }
+
     #[test]
     fn tdec_simple_variant_e2e() {
         let mut rng = &mut test_rng();
@@ -512,7 +766,8 @@ mod tests {
             })
             .collect();
 
-        let shared_secret = share_combine_precomputed::<E>(&decryption_shares);
+        let shared_secret =
+            share_combine_precomputed::<E>(&decryption_shares).unwrap();
 
         test_ciphertext_validation_fails(
             &msg,
@@ -527,7 +782,7 @@ mod tests {
 
         let not_enough_shares = &decryption_shares[0..shares_num - 1];
         let bad_shared_secret =
-            share_combine_precomputed::<E>(not_enough_shares);
+            share_combine_precomputed::<E>(not_enough_shares).unwrap();
         assert!(decrypt_with_shared_secret(
             &ciphertext,
             aad,
@@ -600,5 +855,385 @@ mod tests {
             &pub_contexts[0].h.into_group(),
             &ciphertext,
         ));
+
+        // Reports tell the two failures apart
+        let report = |share: &DecryptionShareSimple<E>| {
+            share.verify_report(
+                &pub_contexts[0].blinded_key_share.blinded_key_share,
+                &pub_contexts[0].validator_public_key.into_affine(),
+                &pub_contexts[0].h.into_group(),
+                &ciphertext,
+            )
+        };
+        assert!(report(&decryption_shares[0]).is_valid());
+        let failures = |share| {
+            report(share)
+                .failures()
+                .map(|outcome| outcome.check)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            failures(&has_bad_checksum),
+            [
+                "decryption_share_matches_checksum",
+                "checksum_matches_validator_key"
+            ]
+        );
+        assert_eq!(
+            failures(&has_bad_share),
+            ["decryption_share_matches_checksum"]
+        );
+    }
+
+    #[test]
+    fn tdec_find_invalid_shares_with_security_config() {
+        let rng = &mut test_rng();
+        let shares_num = 16;
+        let threshold = shares_num * 2 / 3;
+        let msg = "my-msg".as_bytes().to_vec();
+        let aad: &[u8] = "my-aad".as_bytes();
+        let off_curve = ark_bls12_381::G1Affine::new_unchecked(
+            ark_bls12_381::Fq::one(),
+            ark_bls12_381::Fq::one(),
+        );
+        let relaxed = SecurityConfig::relaxed_for_benchmarks();
+
+        // Fast variant
+        let (pubkey, _, mut contexts) =
+            setup_fast::<E>(threshold, shares_num, rng);
+        let ciphertext =
+            encrypt::<E>(SecretBox::new(msg.clone()), aad, &pubkey, rng)
+                .unwrap();
+        let mut decryption_shares = contexts
+            .iter()
+            .map(|c| c.create_share(&ciphertext, aad).unwrap())
+            .collect::<Vec<_>>();
+        for security in [SecurityConfig::default(), relaxed] {
+            contexts[0].security = security;
+            assert!(contexts[0]
+                .find_invalid_shares(&ciphertext, &decryption_shares, rng)
+                .unwrap()
+                .is_empty());
+        }
+        decryption_shares[2].decryption_share = decryption_shares[2]
+            .decryption_share
+            .mul(ScalarField::rand(rng))
+            .into_affine();
+        decryption_shares[5].decrypter_index = shares_num + 1;
+        assert_eq!(
+            contexts[0]
+                .find_invalid_shares(&ciphertext, &decryption_shares, rng)
+                .unwrap(),
+            [2, 5]
+        );
+        contexts[0].security = SecurityConfig::default();
+        decryption_shares[7].decryption_share = off_curve;
+        assert_eq!(
+            contexts[0]
+                .find_invalid_shares(&ciphertext, &decryption_shares, rng)
+                .unwrap(),
+            [2, 5, 7]
+        );
+        // Repeated shares don't count towards the cohort size
+        let mut repeated = decryption_shares.clone();
+        repeated.extend(decryption_shares.clone());
+        contexts[0].security.max_cohort_size = shares_num as u32;
+        assert!(contexts[0]
+            .find_invalid_shares(&ciphertext, &repeated, rng)
+            .is_ok());
+        contexts[0].security.max_cohort_size = threshold as u32;
+        assert!(matches!(
+            contexts[0].find_invalid_shares(
+                &ciphertext,
+                &decryption_shares,
+                rng
+            ),
+            Err(Error::CohortTooLarge(16, _))
+        ));
+
+        // Simple variant
+        let (pubkey, _, mut contexts) =
+            setup_simple::<E>(threshold, shares_num, rng);
+        let ciphertext =
+            encrypt::<E>(SecretBox::new(msg), aad, &pubkey, rng).unwrap();
+        let mut decryption_shares = contexts
+            .iter()
+            .enumerate()
+            .map(|(share_index, c)| {
+                let share =
+                    c.create_share(&ciphertext.header().unwrap(), aad).unwrap();
+                (share_index, share)
+            })
+            .collect::<Vec<_>>();
+        assert!(contexts[0]
+            .find_invalid_shares(&ciphertext, &decryption_shares)
+            .unwrap()
+            .is_empty());
+        // Shares are checked against the context of their share index, in any order
+        let mut reordered = decryption_shares.clone();
+        reordered.reverse();
+        reordered.remove(1);
+        assert!(contexts[0]
+            .find_invalid_shares(&ciphertext, &reordered)
+            .unwrap()
+            .is_empty());
+        decryption_shares[3].1.decryption_share = decryption_shares[3]
+            .1
+            .decryption_share
+            .mul(TargetField::rand(rng));
+        decryption_shares[4].1.validator_checksum.checksum = off_curve;
+        decryption_shares[6].0 = 7;
+        assert_eq!(
+            contexts[0]
+                .find_invalid_shares(&ciphertext, &decryption_shares)
+                .unwrap(),
+            [3, 4, 6]
+        );
+        contexts[0].security.max_cohort_size = threshold as u32;
+        assert!(matches!(
+            contexts[0].find_invalid_shares(&ciphertext, &decryption_shares),
+            // Two of the shares claim the same share index
+            Err(Error::CohortTooLarge(15, _))
+        ));
+    }
+
+    #[test]
+    fn tdec_simple_variant_share_bound_to_request() {
+        let rng = &mut test_rng();
+        let (pubkey, _, contexts) = setup_simple::<E>(3, 4, rng);
+        let aad: &[u8] = "my-aad".as_bytes();
+        let ciphertext = encrypt::<E>(
+            SecretBox::new("my-msg".as_bytes().to_vec()),
+            aad,
+            &pubkey,
+            rng,
+        )
+        .unwrap();
+        let header = ciphertext.header().unwrap();
+        let pub_context = &contexts[0].public_decryption_contexts[0];
+        let request = ShareRequestContext::new(b"alice", b"nonce-1");
+
+        let share = contexts[0]
+            .create_share_for_request(&header, aad, &request)
+            .unwrap();
+        let verify_for =
+            |share: &DecryptionShareSimple<E>,
+             request: &ShareRequestContext| {
+                share
+                    .verify_for_request(
+                        &pub_context.blinded_key_share.blinded_key_share,
+                        &pub_context.validator_public_key.into_affine(),
+                        &pub_context.h.into_group(),
+                        &ciphertext,
+                        request,
+                    )
+                    .unwrap()
+            };
+        assert!(verify_for(&share, &request));
+
+        // Can't be presented as service to another requester, or for another request
+        assert!(!verify_for(
+            &share,
+            &ShareRequestContext::new(b"bob", b"nonce-1")
+        ));
+        assert!(!verify_for(
+            &share,
+            &ShareRequestContext::new(b"alice", b"nonce-2")
+        ));
+
+        // Shares that aren't bound to any request are rejected
+        let unbound_share = contexts[0].create_share(&header, aad).unwrap();
+        assert!(!verify_for(&unbound_share, &request));
+
+        // Binding doesn't affect the decryption share itself
+        assert_eq!(share.decryption_share, unbound_share.decryption_share);
+    }
+
+    /// Test that shares encoded before request bindings decode with the legacy decoders only
+    #[test]
+    fn tdec_simple_legacy_share_encoding() {
+        let rng = &mut test_rng();
+        let (pubkey, _, contexts) = setup_simple::<E>(3, 4, rng);
+        let aad: &[u8] = "my-aad".as_bytes();
+        let ciphertext = encrypt::<E>(
+            SecretBox::new("my-msg".as_bytes().to_vec()),
+            aad,
+            &pubkey,
+            rng,
+        )
+        .unwrap();
+        let header = ciphertext.header().unwrap();
+
+        // The encoding of the shares before request bindings, where the checksum was the
+        // checksum point alone
+        let share = contexts[0].create_share(&header, aad).unwrap();
+        let legacy = bincode::serialize(&(
+            ferveo_common::to_bytes(&share.decryption_share).unwrap(),
+            ferveo_common::to_bytes(&share.validator_checksum.checksum)
+                .unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(
+            DecryptionShareSimple::<E>::from_legacy_bytes(&legacy).unwrap(),
+            share
+        );
+        assert!(DecryptionShareSimple::<E>::from_bytes(&legacy).is_err());
+        let bytes = share.to_bytes().unwrap();
+        assert_eq!(
+            DecryptionShareSimple::<E>::from_bytes(&bytes).unwrap(),
+            share
+        );
+        assert!(DecryptionShareSimple::<E>::from_legacy_bytes(&bytes).is_err());
+
+        let share = contexts[0].create_share_precomputed(&header, aad).unwrap();
+        let legacy = bincode::serialize(&(
+            share.decrypter_index,
+            ferveo_common::to_bytes(&share.decryption_share).unwrap(),
+            ferveo_common::to_bytes(&share.validator_checksum.checksum)
+                .unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(
+            DecryptionSharePrecomputed::<E>::from_legacy_bytes(&legacy)
+                .unwrap(),
+            share
+        );
+        assert!(DecryptionSharePrecomputed::<E>::from_bytes(&legacy).is_err());
+    }
+
+    #[test]
+    fn tdec_quorum_above_dkg_threshold() {
+        let rng = &mut test_rng();
+        let (threshold, shares_num, quorum) = (3, 5, 4);
+        let aad: &[u8] = "my-aad".as_bytes();
+        let msg = "my-msg".as_bytes().to_vec();
+
+        // Simple variant
+        let (pubkey, _, contexts) =
+            setup_simple::<E>(threshold, shares_num, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let ciphertext = encrypt_with_quorum::<E>(
+            SecretBox::new(msg.clone()),
+            aad,
+            &pubkey,
+            Some(quorum),
+            rng,
+        )
+        .unwrap();
+        let header = ciphertext.header().unwrap();
+        assert!(header.check(aad, g_inv).unwrap());
+
+        // The quorum can't be lowered or removed
+        for lowered in [None, Some(threshold as u32)] {
+            let mut lowered_header = header.clone();
+            lowered_header.quorum = lowered;
+            assert!(matches!(
+                lowered_header.check(aad, g_inv),
+                Err(Error::CiphertextVerificationFailed)
+            ));
+        }
+
+        let shares = (0..shares_num)
+            .map(|i| (i, contexts[i].create_share(&header, aad).unwrap()))
+            .collect::<Vec<_>>();
+        let pub_context = &contexts[0].public_decryption_contexts[0];
+        assert!(shares[0].1.verify(
+            &pub_context.blinded_key_share.blinded_key_share,
+            &pub_context.validator_public_key.into_affine(),
+            &pub_context.h.into_group(),
+            &ciphertext,
+        ));
+
+        // A DKG threshold of shares isn't enough
+        let cohort = CohortCommitment::from_public_contexts(
+            threshold,
+            &contexts[0].public_decryption_contexts,
+        )
+        .unwrap();
+        let below_quorum =
+            ShareAggregationProof::new(&shares[..threshold], &cohort).unwrap();
+        assert!(!below_quorum.verify(&cohort, &header).unwrap());
+        let proof =
+            ShareAggregationProof::new(&shares[..quorum as usize], &cohort)
+                .unwrap();
+        assert!(proof.verify(&cohort, &header).unwrap());
+        let plaintext = decrypt_with_shared_secret(
+            &ciphertext,
+            aad,
+            &proof.shared_secret(),
+            g_inv,
+        )
+        .unwrap();
+        assert_eq!(plaintext.as_bytes(), msg);
+        let mut insufficient = proof;
+        insufficient.shares.pop();
+        assert!(!insufficient.verify(&cohort, &header).unwrap());
+
+        // Fast variant
+        let (pubkey, _, contexts) = setup_fast::<E>(threshold, shares_num, rng);
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+        let ciphertext = encrypt_with_quorum::<E>(
+            SecretBox::new(msg.clone()),
+            aad,
+            &pubkey,
+            Some(quorum),
+            rng,
+        )
+        .unwrap();
+        let shares = contexts
+            .iter()
+            .map(|c| c.create_share(&ciphertext, aad).unwrap())
+            .collect::<Vec<_>>();
+        let combine = |shares: &[DecryptionShareFast<E>]| {
+            let prepared = prepare_combine_fast(pub_contexts, shares)?;
+            share_combine_fast(pub_contexts, &ciphertext, shares, &prepared)
+        };
+        assert!(matches!(
+            combine(&shares[..threshold]),
+            Err(Error::InsufficientQuorum(4, 3))
+        ));
+        let shared_secret = combine(&shares[..quorum as usize]).unwrap();
+        assert_eq!(
+            decrypt_with_shared_secret(&ciphertext, aad, &shared_secret, g_inv)
+                .unwrap()
+                .as_bytes(),
+            msg
+        );
+        // Repeated shares only count once
+        let mut repeated = shares[..threshold].to_vec();
+        repeated.push(shares[0].clone());
+        assert!(matches!(
+            combine(&repeated),
+            Err(Error::InsufficientQuorum(4, 3))
+        ));
+
+        // Precomputed variant, which already requires every share
+        let (pubkey, _, contexts) = setup_precomputed::<E>(shares_num, rng);
+        let ciphertext = encrypt_with_quorum::<E>(
+            SecretBox::new(msg),
+            aad,
+            &pubkey,
+            Some(quorum),
+            rng,
+        )
+        .unwrap();
+        let header = ciphertext.header().unwrap();
+        let shares = contexts
+            .iter()
+            .map(|c| c.create_share_precomputed(&header, aad).unwrap())
+            .collect::<Vec<_>>();
+        assert!(share_combine_precomputed_with_quorum::<E>(
+            &shares,
+            header.quorum
+        )
+        .is_ok());
+        // The quorum is taken from the ciphertext, not from the shares
+        assert!(matches!(
+            share_combine_precomputed_with_quorum::<E>(
+                &shares,
+                Some(shares_num as u32 + 1)
+            ),
+            Err(Error::InsufficientQuorum(6, 5))
+        ));
     }
 }
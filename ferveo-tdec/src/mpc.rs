@@ -0,0 +1,276 @@
+//! Extension points for combining decryption shares inside a multi-party computation.
+//!
+//! Combining decryption shares yields the shared secret in `GT`, which lets whoever combines
+//! the shares decrypt the ciphertext. When the secret is only an intermediate value, e.g. to
+//! derive a re-encryption key, an MPC protocol can compute on it instead, so that no single
+//! party learns it.
+//!
+//! Each decrypter turns their decryption share into a [`CombineContribution`], the share
+//! raised to its Lagrange coefficient, whose product is the shared secret. Contributions are
+//! handed to a [`ShareCombiner`], e.g. as private inputs of an MPC protocol.
+//! [`LocalCombiner`] multiplies them in the clear, like [`crate::share_combine_simple`].
+
+use std::fmt;
+
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, One, PrimeField};
+use ferveo_common::serialization;
+use itertools::izip;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{
+    dedup_by_share_index, sort_by_share_index, DecryptionSharePrecomputed,
+    DecryptionShareSimple, Error, Result, SharedSecret,
+};
+
+/// A decryption share raised to its Lagrange coefficient, `C_i^{λ_i}`.
+///
+/// The product of the contributions of a quorum is the shared secret, so contributions must
+/// be kept from any party that shouldn't learn it.
+#[serde_as]
+#[derive(
+    Clone, PartialEq, Eq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop,
+)]
+pub struct CombineContribution<E: Pairing>(
+    #[serde_as(as = "serialization::SerdeAs")] E::TargetField,
+);
+
+impl<E: Pairing> CombineContribution<E> {
+    /// `lagrange_coeff` is the coefficient of the share in the quorum it is combined in,
+    /// as returned by [`crate::prepare_combine_simple`].
+    pub fn from_simple(
+        share: &DecryptionShareSimple<E>,
+        lagrange_coeff: &E::ScalarField,
+    ) -> Self {
+        Self(share.decryption_share.pow(lagrange_coeff.into_bigint()))
+    }
+
+    /// Precomputed shares are already raised to their Lagrange coefficient.
+    pub fn from_precomputed(share: &DecryptionSharePrecomputed<E>) -> Self {
+        Self(share.decryption_share)
+    }
+
+    pub fn value(&self) -> &E::TargetField {
+        &self.0
+    }
+}
+
+impl<E: Pairing> fmt::Debug for CombineContribution<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CombineContribution(<redacted>)")
+    }
+}
+
+/// The combine stage of threshold decryption.
+///
+/// A combiner only keeps the shared secret from a party that never holds a quorum of
+/// decryption shares or contributions. [`combine_simple_with`] and
+/// [`combine_precomputed_with`] compute every contribution in one place, so their caller
+/// could combine the shares itself. To keep the secret from every single party, each
+/// decrypter must compute its own contribution and hand it to the combiner, e.g. as its
+/// private input to the MPC protocol.
+pub trait ShareCombiner<E: Pairing> {
+    type Output;
+
+    /// Combine the contributions of a quorum of decrypters.
+    fn combine(
+        &mut self,
+        contributions: Vec<CombineContribution<E>>,
+    ) -> Result<Self::Output>;
+}
+
+/// Combines contributions in the clear, into the shared secret.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LocalCombiner;
+
+impl<E: Pairing> ShareCombiner<E> for LocalCombiner {
+    type Output = SharedSecret<E>;
+
+    fn combine(
+        &mut self,
+        contributions: Vec<CombineContribution<E>>,
+    ) -> Result<SharedSecret<E>> {
+        Ok(SharedSecret(
+            contributions
+                .iter()
+                .fold(E::TargetField::one(), |acc, contribution| {
+                    acc * contribution.0
+                }),
+        ))
+    }
+}
+
+/// Same as [`crate::share_combine_simple`], but with a custom combine stage.
+///
+/// Simple decryption shares don't carry a share index, so `share_indices` and
+/// `lagrange_coeffs` must be aligned with `decryption_shares`. Fails on duplicate share
+/// indices, since a share combined twice yields a wrong secret.
+pub fn combine_simple_with<E: Pairing, C: ShareCombiner<E>>(
+    decryption_shares: &[DecryptionShareSimple<E>],
+    share_indices: &[usize],
+    lagrange_coeffs: &[E::ScalarField],
+    combiner: &mut C,
+) -> Result<C::Output> {
+    for count in [share_indices.len(), lagrange_coeffs.len()] {
+        if count != decryption_shares.len() {
+            return Err(Error::LagrangeCoefficientCountMismatch(
+                count,
+                decryption_shares.len(),
+            ));
+        }
+    }
+    let shares = izip!(share_indices, decryption_shares, lagrange_coeffs)
+        .collect::<Vec<_>>();
+    let shares =
+        sort_by_share_index(&shares, None, |(share_index, ..)| **share_index)?;
+    let contributions = shares
+        .into_iter()
+        .map(|(_, share, lagrange_coeff)| {
+            CombineContribution::from_simple(share, lagrange_coeff)
+        })
+        .collect();
+    combiner.combine(contributions)
+}
+
+/// Same as [`crate::share_combine_precomputed`], but with a custom combine stage.
+pub fn combine_precomputed_with<E: Pairing, C: ShareCombiner<E>>(
+    decryption_shares: &[DecryptionSharePrecomputed<E>],
+    combiner: &mut C,
+) -> Result<C::Output> {
+    let shares = dedup_by_share_index(decryption_shares, None, |c_i| {
+        c_i.decrypter_index
+    })?;
+    let contributions = shares
+        .into_iter()
+        .map(CombineContribution::from_precomputed)
+        .collect();
+    combiner.combine(contributions)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_std::test_rng;
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::{
+        encrypt, prepare_combine_simple, share_combine_precomputed,
+        share_combine_simple, test_common::*, SecretBox,
+    };
+
+    type E = ark_bls12_381::Bls12_381;
+
+    /// Stands in for an MPC protocol that outputs a key derived from the shared secret
+    struct DerivedKeyCombiner {
+        seen: usize,
+    }
+
+    impl ShareCombiner<E> for DerivedKeyCombiner {
+        type Output = [u8; 32];
+
+        fn combine(
+            &mut self,
+            contributions: Vec<CombineContribution<E>>,
+        ) -> Result<[u8; 32]> {
+            self.seen += contributions.len();
+            let secret = LocalCombiner.combine(contributions)?;
+            Ok(Sha256::digest(secret.0.to_string()).into())
+        }
+    }
+
+    #[test]
+    fn combine_with_custom_combiner() {
+        let rng = &mut test_rng();
+        let (threshold, shares_num) = (3, 5);
+        let aad: &[u8] = b"my-aad";
+        let (pubkey, _, contexts) =
+            setup_simple::<E>(threshold, shares_num, rng);
+        let ciphertext =
+            encrypt::<E>(SecretBox::new(b"my-msg".to_vec()), aad, &pubkey, rng)
+                .unwrap();
+        let header = ciphertext.header().unwrap();
+
+        let quorum = &contexts[..threshold];
+        let shares = quorum
+            .iter()
+            .map(|c| c.create_share(&header, aad).unwrap())
+            .collect::<Vec<_>>();
+        let domain = quorum
+            .iter()
+            .map(|c| c.public_decryption_contexts[c.index].domain)
+            .collect::<Vec<_>>();
+        let lagrange_coeffs = prepare_combine_simple::<E>(&domain);
+        let share_indices = quorum.iter().map(|c| c.index).collect::<Vec<_>>();
+
+        let expected = share_combine_simple::<E>(&shares, &lagrange_coeffs);
+        assert_eq!(
+            combine_simple_with(
+                &shares,
+                &share_indices,
+                &lagrange_coeffs,
+                &mut LocalCombiner
+            )
+            .unwrap(),
+            expected
+        );
+
+        let mut combiner = DerivedKeyCombiner { seen: 0 };
+        let key = combine_simple_with(
+            &shares,
+            &share_indices,
+            &lagrange_coeffs,
+            &mut combiner,
+        )
+        .unwrap();
+        assert_eq!(
+            key,
+            <[u8; 32]>::from(Sha256::digest(expected.0.to_string()))
+        );
+        assert_eq!(combiner.seen, threshold);
+        assert!(matches!(
+            combine_simple_with(
+                &shares[1..],
+                &share_indices[1..],
+                &lagrange_coeffs,
+                &mut combiner
+            ),
+            Err(Error::LagrangeCoefficientCountMismatch(..))
+        ));
+
+        // A share can't be combined twice
+        let mut repeated = shares.clone();
+        repeated[1] = shares[0].clone();
+        let mut repeated_indices = share_indices.clone();
+        repeated_indices[1] = share_indices[0];
+        assert!(matches!(
+            combine_simple_with(
+                &repeated,
+                &repeated_indices,
+                &lagrange_coeffs,
+                &mut combiner
+            ),
+            Err(Error::DuplicateDecryptionShare(i)) if i == share_indices[0]
+        ));
+
+        // Precomputed variant
+        let (pubkey, _, contexts) = setup_precomputed::<E>(shares_num, rng);
+        let ciphertext =
+            encrypt::<E>(SecretBox::new(b"my-msg".to_vec()), aad, &pubkey, rng)
+                .unwrap();
+        let header = ciphertext.header().unwrap();
+        let shares = contexts
+            .iter()
+            .map(|c| c.create_share_precomputed(&header, aad).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            combine_precomputed_with(&shares, &mut LocalCombiner).unwrap(),
+            share_combine_precomputed::<E>(&shares).unwrap()
+        );
+        assert_eq!(
+            format!("{:?}", CombineContribution::from_precomputed(&shares[0])),
+            "CombineContribution(<redacted>)"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Protocol constants.
+//!
+//! Every constant that affects the wire format or the outputs of the protocol lives here,
+//! grouped by protocol version, so that a new version can be supported alongside the
+//! current one during a network upgrade.
+
+/// The constants that define a version of the protocol.
+pub trait ProtocolVersion {
+    const VERSION: u8;
+
+    /// Domain separation tag of the cipher suite identifiers
+    const SUITE_ID_DOMAIN: &'static [u8];
+    /// Domain separation tag of the BLS12-381 G2 hash-to-curve
+    const HASH_TO_G2_DST: &'static [u8];
+    /// Domain separation tag binding decryption shares to a request
+    const SHARE_REQUEST_BINDING_DOMAIN: &'static [u8];
+    /// Domain separation tag of decryption share commitments
+    const SHARE_COMMITMENT_DOMAIN: &'static [u8];
+    /// Domain separation tag of the Lagrange coefficients cache keys
+    const LAGRANGE_CACHE_DOMAIN: &'static [u8];
+    /// Domain separation tag of the prepared key cache keys
+    const PREPARED_KEY_CACHE_DOMAIN: &'static [u8];
+    /// Domain separation tag of validator set commitments
+    const VALIDATOR_SET_DOMAIN: &'static [u8];
+    /// Domain separation tag of the base of the PVSS proof of knowledge
+    const POK_BASE_DOMAIN: &'static [u8];
+    /// Domain separation tag of cohort commitments
+    const COHORT_COMMITMENT_DOMAIN: &'static [u8];
+    /// Domain separation tag of the challenges of share aggregation proofs
+    const SHARE_AGGREGATION_PROOF_DOMAIN: &'static [u8];
+    /// Associated data of sealed decryption context bundles
+    const RECOVERY_BUNDLE_DOMAIN: &'static [u8];
+    /// Domain separation tag of the challenges of dealer signatures over dealer commitments
+    const DEALER_COMMITMENT_SIGNATURE_DOMAIN: &'static [u8];
+    /// Domain separation tag of the hashes that order validators by share index
+    const SHARE_INDEX_DOMAIN: &'static [u8];
+    /// Domain separation tag of aggregate hashes in epoch registries
+    const AGGREGATE_HASH_DOMAIN: &'static [u8];
+    /// Domain separation tag binding the quorum of a ciphertext to its authentication tag
+    const QUORUM_BINDING_DOMAIN: &'static [u8];
+    /// Domain separation tag of the extensions of transcripts and aggregates
+    const EXTENSIONS_HASH_DOMAIN: &'static [u8];
+    /// Domain separation tag of the DRBG seeds of deterministic transcripts
+    const DETERMINISTIC_DEALING_DOMAIN: &'static [u8];
+    /// Domain separation tag of the challenges of dealer signatures over transcripts
+    const TRANSCRIPT_SIGNATURE_DOMAIN: &'static [u8];
+    /// Domain separation tag of the keys of pairwise channels between validators
+    const SECURE_CHANNEL_DOMAIN: &'static [u8];
+    /// Domain separation tag of the inner nodes of Merkle trees
+    const MERKLE_NODE_DOMAIN: &'static [u8];
+    /// Domain separation tag of the leaves of committee membership trees
+    const COMMITTEE_MEMBER_DOMAIN: &'static [u8];
+    /// Domain separation tag of the challenges of proofs of correct share encryption
+    const SHARE_PROOF_DOMAIN: &'static [u8];
+    /// Domain separation tag of the leaves of encrypted share trees
+    const SHARE_LEAF_DOMAIN: &'static [u8];
+    /// Domain separation tag of the challenges of proofs of share re-encryption
+    const SHARE_REENCRYPTION_DOMAIN: &'static [u8];
+    /// Domain separation tag of transcript fingerprints
+    const TRANSCRIPT_FINGERPRINT_DOMAIN: &'static [u8];
+    /// Domain separation tag of aggregate fingerprints
+    const AGGREGATE_FINGERPRINT_DOMAIN: &'static [u8];
+    /// Domain separation tag of DKG public key fingerprints
+    const PUBLIC_KEY_FINGERPRINT_DOMAIN: &'static [u8];
+    /// Domain separation tag of the challenges of complaints about dealers
+    const COMPLAINT_DOMAIN: &'static [u8];
+    /// Domain separation tag of the challenges of sender signatures over DKG messages
+    const MESSAGE_SIGNATURE_DOMAIN: &'static [u8];
+    /// Domain separation tag of the challenges of proofs that a validator holds their share
+    const SHARE_OWNERSHIP_DOMAIN: &'static [u8];
+    /// Domain separation tag of the binding of validator sets to non-default PVSS generators
+    const PVSS_PARAMS_DOMAIN: &'static [u8];
+
+    /// Prefix of schema-versioned AAD
+    const AAD_SCHEMA_MAGIC: &'static [u8];
+    /// Schema version of newly encoded AAD
+    const AAD_SCHEMA_VERSION: u8;
+
+    /// Version of the ciphertext envelope format
+    const ENVELOPE_VERSION: u8;
+    /// Prefix of versioned PVSS transcripts, ahead of their version. Transcripts encoded
+    /// before the version start with the length of their coefficients instead
+    const TRANSCRIPT_MAGIC: [u8; 4];
+    /// Version of the PVSS transcript format, of transcripts and aggregates
+    const TRANSCRIPT_VERSION: u8;
+
+    /// Size of the symmetric encryption nonce, derived from the ciphertext commitment
+    const NONCE_SIZE: usize;
+    /// Size of the authentication tag appended to the symmetric ciphertext
+    const AEAD_TAG_SIZE: usize;
+}
+
+/// The first, and current, version of the protocol
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct V1;
+
+impl ProtocolVersion for V1 {
+    const VERSION: u8 = 1;
+
+    const SUITE_ID_DOMAIN: &'static [u8] = b"FERVEO_CIPHER_SUITE";
+    const HASH_TO_G2_DST: &'static [u8] =
+        b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+    const SHARE_REQUEST_BINDING_DOMAIN: &'static [u8] =
+        b"FERVEO_SHARE_REQUEST_BINDING_V1";
+    const SHARE_COMMITMENT_DOMAIN: &'static [u8] =
+        b"FERVEO_DECRYPTION_SHARE_COMMITMENT";
+    const LAGRANGE_CACHE_DOMAIN: &'static [u8] = b"FERVEO_LAGRANGE_COEFFS";
+    const PREPARED_KEY_CACHE_DOMAIN: &'static [u8] = b"FERVEO_PREPARED_G2_KEY";
+    const VALIDATOR_SET_DOMAIN: &'static [u8] = b"FERVEO_VALIDATOR_SET_V1";
+    const POK_BASE_DOMAIN: &'static [u8] = b"FERVEO_PVSS_POK_BASE_V1";
+    const COHORT_COMMITMENT_DOMAIN: &'static [u8] = b"FERVEO_COHORT_V1";
+    const SHARE_AGGREGATION_PROOF_DOMAIN: &'static [u8] =
+        b"FERVEO_SHARE_AGGREGATION_PROOF_V1";
+    const RECOVERY_BUNDLE_DOMAIN: &'static [u8] = b"FERVEO_RECOVERY_BUNDLE_V1";
+    const DEALER_COMMITMENT_SIGNATURE_DOMAIN: &'static [u8] =
+        b"FERVEO_DEALER_COMMITMENT_SIGNATURE_V1";
+    const SHARE_INDEX_DOMAIN: &'static [u8] = b"FERVEO_SHARE_INDEX_V1";
+    const AGGREGATE_HASH_DOMAIN: &'static [u8] = b"FERVEO_AGGREGATE_V1";
+    const QUORUM_BINDING_DOMAIN: &'static [u8] = b"FERVEO_CIPHERTEXT_QUORUM_V1";
+    const EXTENSIONS_HASH_DOMAIN: &'static [u8] = b"FERVEO_EXTENSIONS_V1";
+    const DETERMINISTIC_DEALING_DOMAIN: &'static [u8] =
+        b"FERVEO_DETERMINISTIC_DEALING_V1";
+    const TRANSCRIPT_SIGNATURE_DOMAIN: &'static [u8] =
+        b"FERVEO_TRANSCRIPT_SIGNATURE_V1";
+    const SECURE_CHANNEL_DOMAIN: &'static [u8] = b"FERVEO_SECURE_CHANNEL_V1";
+    const MERKLE_NODE_DOMAIN: &'static [u8] = b"FERVEO_MERKLE_NODE_V1";
+    const COMMITTEE_MEMBER_DOMAIN: &'static [u8] =
+        b"FERVEO_COMMITTEE_MEMBER_V1";
+    const SHARE_PROOF_DOMAIN: &'static [u8] = b"FERVEO_SHARE_PROOF_V1";
+    const SHARE_LEAF_DOMAIN: &'static [u8] = b"FERVEO_SHARE_LEAF_V1";
+    const SHARE_REENCRYPTION_DOMAIN: &'static [u8] =
+        b"FERVEO_SHARE_REENCRYPTION_V1";
+    const TRANSCRIPT_FINGERPRINT_DOMAIN: &'static [u8] =
+        b"FERVEO_TRANSCRIPT_FINGERPRINT_V1";
+    const AGGREGATE_FINGERPRINT_DOMAIN: &'static [u8] =
+        b"FERVEO_AGGREGATE_FINGERPRINT_V1";
+    const PUBLIC_KEY_FINGERPRINT_DOMAIN: &'static [u8] =
+        b"FERVEO_PUBLIC_KEY_FINGERPRINT_V1";
+    const COMPLAINT_DOMAIN: &'static [u8] = b"FERVEO_COMPLAINT_V1";
+    const MESSAGE_SIGNATURE_DOMAIN: &'static [u8] =
+        b"FERVEO_MESSAGE_SIGNATURE_V1";
+    const SHARE_OWNERSHIP_DOMAIN: &'static [u8] = b"FERVEO_SHARE_OWNERSHIP_V1";
+    const PVSS_PARAMS_DOMAIN: &'static [u8] = b"FERVEO_PVSS_PARAMS_V1";
+
+    const AAD_SCHEMA_MAGIC: &'static [u8] = b"FAAD";
+    const AAD_SCHEMA_VERSION: u8 = 1;
+
+    const ENVELOPE_VERSION: u8 = 1;
+    const TRANSCRIPT_MAGIC: [u8; 4] = *b"FPVS";
+    const TRANSCRIPT_VERSION: u8 = 1;
+
+    const NONCE_SIZE: usize = 12;
+    const AEAD_TAG_SIZE: usize = 16;
+}
+
+/// The protocol version used to produce new artifacts
+pub type Current = V1;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn domain_tags_are_distinct() {
+        let tags = [
+            V1::SUITE_ID_DOMAIN,
+            V1::HASH_TO_G2_DST,
+            V1::SHARE_REQUEST_BINDING_DOMAIN,
+            V1::SHARE_COMMITMENT_DOMAIN,
+            V1::LAGRANGE_CACHE_DOMAIN,
+            V1::PREPARED_KEY_CACHE_DOMAIN,
+            V1::VALIDATOR_SET_DOMAIN,
+            V1::POK_BASE_DOMAIN,
+            V1::COHORT_COMMITMENT_DOMAIN,
+            V1::SHARE_AGGREGATION_PROOF_DOMAIN,
+            V1::RECOVERY_BUNDLE_DOMAIN,
+            V1::DEALER_COMMITMENT_SIGNATURE_DOMAIN,
+            V1::SHARE_INDEX_DOMAIN,
+            V1::AGGREGATE_HASH_DOMAIN,
+            V1::QUORUM_BINDING_DOMAIN,
+            V1::EXTENSIONS_HASH_DOMAIN,
+            V1::DETERMINISTIC_DEALING_DOMAIN,
+            V1::TRANSCRIPT_SIGNATURE_DOMAIN,
+            V1::SECURE_CHANNEL_DOMAIN,
+            V1::MERKLE_NODE_DOMAIN,
+            V1::COMMITTEE_MEMBER_DOMAIN,
+            V1::SHARE_PROOF_DOMAIN,
+            V1::SHARE_LEAF_DOMAIN,
+            V1::SHARE_REENCRYPTION_DOMAIN,
+            V1::TRANSCRIPT_FINGERPRINT_DOMAIN,
+            V1::AGGREGATE_FINGERPRINT_DOMAIN,
+            V1::PUBLIC_KEY_FINGERPRINT_DOMAIN,
+            V1::COMPLAINT_DOMAIN,
+            V1::MESSAGE_SIGNATURE_DOMAIN,
+            V1::SHARE_OWNERSHIP_DOMAIN,
+            V1::PVSS_PARAMS_DOMAIN,
+        ];
+        assert_eq!(tags.iter().collect::<HashSet<_>>().len(), tags.len());
+    }
+}
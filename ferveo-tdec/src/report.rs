@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// The outcome of a single check in a [`VerificationReport`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CheckOutcome {
+    /// Stable, machine-readable name of the check, e.g. `"proof_of_knowledge"`
+    pub check: &'static str,
+    pub passed: bool,
+    /// Indices of the items that failed the check, for checks run per share or validator
+    pub failed_indices: Vec<usize>,
+    /// Not measured on targets without a clock, e.g. WASM
+    pub elapsed: Option<Duration>,
+}
+
+/// Which checks a verification ran, and which of them failed.
+///
+/// Unlike the boolean verification methods, every check is run to completion, so that a
+/// report lists all failures rather than the first one.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct VerificationReport {
+    pub checks: Vec<CheckOutcome>,
+}
+
+impl VerificationReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run and record a check, returning whether it passed
+    pub fn check(
+        &mut self,
+        check: &'static str,
+        f: impl FnOnce() -> bool,
+    ) -> bool {
+        let timer = Timer::start();
+        let passed = f();
+        self.checks.push(CheckOutcome {
+            check,
+            passed,
+            failed_indices: vec![],
+            elapsed: timer.elapsed(),
+        });
+        passed
+    }
+
+    /// Run and record a check over `count` items, returning whether all of them passed
+    pub fn check_each(
+        &mut self,
+        check: &'static str,
+        count: usize,
+        mut f: impl FnMut(usize) -> bool,
+    ) -> bool {
+        let timer = Timer::start();
+        let failed_indices = (0..count).filter(|&i| !f(i)).collect::<Vec<_>>();
+        let passed = failed_indices.is_empty();
+        self.checks.push(CheckOutcome {
+            check,
+            passed,
+            failed_indices,
+            elapsed: timer.elapsed(),
+        });
+        passed
+    }
+
+    /// Same as [`VerificationReport::check_each`], but runs the check on the items in
+    /// parallel. Failed indices are still reported in order.
+    #[cfg(feature = "parallel")]
+    pub fn check_each_par(
+        &mut self,
+        check: &'static str,
+        count: usize,
+        f: impl Fn(usize) -> bool + Sync,
+    ) -> bool {
+        use rayon::prelude::*;
+
+        let timer = Timer::start();
+        let failed_indices = (0..count)
+            .into_par_iter()
+            .filter(|&i| !f(i))
+            .collect::<Vec<_>>();
+        let passed = failed_indices.is_empty();
+        self.checks.push(CheckOutcome {
+            check,
+            passed,
+            failed_indices,
+            elapsed: timer.elapsed(),
+        });
+        passed
+    }
+
+    /// Record a check that couldn't be run, e.g. because its inputs are malformed
+    pub fn fail(&mut self, check: &'static str) {
+        self.checks.push(CheckOutcome {
+            check,
+            passed: false,
+            failed_indices: vec![],
+            elapsed: None,
+        });
+    }
+
+    pub fn extend(&mut self, other: VerificationReport) {
+        self.checks.extend(other.checks);
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.checks.iter().all(|outcome| outcome.passed)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &CheckOutcome> {
+        self.checks.iter().filter(|outcome| !outcome.passed)
+    }
+
+    pub fn get(&self, check: &str) -> Option<&CheckOutcome> {
+        self.checks.iter().find(|outcome| outcome.check == check)
+    }
+}
+
+struct Timer {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+}
+
+impl Timer {
+    fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        #[cfg(not(target_arch = "wasm32"))]
+        return Some(self.start.elapsed());
+        #[cfg(target_arch = "wasm32")]
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_lists_every_failure() {
+        let mut report = VerificationReport::new();
+        assert!(report.check("first", || true));
+        assert!(!report.check_each("second", 4, |i| i % 2 == 0));
+        report.fail("third");
+
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.failures().map(|o| o.check).collect::<Vec<_>>(),
+            ["second", "third"]
+        );
+        assert_eq!(report.get("second").unwrap().failed_indices, [1, 3]);
+        #[cfg(feature = "parallel")]
+        {
+            assert!(!report.check_each_par("fourth", 100, |i| i % 7 != 0));
+            assert_eq!(
+                report.get("fourth").unwrap().failed_indices,
+                (0..100).step_by(7).collect::<Vec<_>>()
+            );
+        }
+        assert!(report.get("first").unwrap().elapsed.is_some());
+        assert!(bincode::serialize(&report).is_ok());
+    }
+}
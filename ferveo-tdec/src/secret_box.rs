@@ -81,3 +81,77 @@ where
         write!(f, "SecretBox<REDACTED>")
     }
 }
+
+/// A decrypted plaintext.
+///
+/// The contents are zeroized on drop, and are only reachable through `as_bytes()`,
+/// or by explicitly taking them out of the buffer with `into_bytes()`.
+#[derive(Clone, PartialEq)]
+pub struct SecretBuffer(SecretBox<Vec<u8>>);
+
+impl SecretBuffer {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(SecretBox::new(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_secret()
+    }
+
+    pub(crate) fn as_mut_vec(&mut self) -> &mut Vec<u8> {
+        self.0.as_mut_secret()
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_bytes().is_empty()
+    }
+
+    /// Moves the plaintext out of the buffer.
+    /// The returned bytes are not zeroized on drop anymore, so use sparingly.
+    pub fn into_bytes(mut self) -> Vec<u8> {
+        core::mem::take(self.0.as_mut_secret())
+    }
+}
+
+impl fmt::Debug for SecretBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBuffer<REDACTED>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_buffer_into_bytes() {
+        let buffer = SecretBuffer::new(b"secret".to_vec());
+        assert_eq!(buffer.as_bytes(), b"secret");
+        assert_eq!(format!("{buffer:?}"), "SecretBuffer<REDACTED>");
+        assert_eq!(buffer.into_bytes(), b"secret".to_vec());
+    }
+
+    #[cfg(not(feature = "unsafe-debug"))]
+    #[test]
+    fn secrets_are_redacted() {
+        use ark_ec::AffineRepr;
+
+        use crate::{PrivateKeyShare, SharedSecret};
+
+        type E = ark_bls12_381::Bls12_381;
+
+        let share = PrivateKeyShare::<E> {
+            private_key_share:
+                <E as ark_ec::pairing::Pairing>::G2Affine::generator(),
+        };
+        assert_eq!(format!("{share:?}"), "PrivateKeyShare(<redacted>)");
+        assert_eq!(share.to_string(), "PrivateKeyShare(<redacted>)");
+
+        let shared_secret = SharedSecret::<E>(Default::default());
+        assert_eq!(format!("{shared_secret:?}"), "SharedSecret(<redacted>)");
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+
+use ferveo_common::ToBytes;
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    protocol::{ProtocolVersion, V1},
+    sha256, Error, Result,
+};
+
+/// A binding and hiding commitment to a decryption share.
+///
+/// Validators publish this commitment before revealing their decryption share, so that
+/// their share can't be chosen adaptively after seeing the shares of others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecryptionShareCommitment {
+    pub digest: [u8; 32],
+}
+
+/// The data required to open a [`DecryptionShareCommitment`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecryptionShareOpening<S> {
+    pub share: S,
+    pub salt: [u8; 32],
+}
+
+impl DecryptionShareCommitment {
+    /// Commit to a decryption share using a fresh random salt.
+    /// Returns the commitment to publish and the opening to keep until the reveal phase.
+    pub fn commit<S: Serialize + Clone>(
+        share: &S,
+        rng: &mut impl RngCore,
+    ) -> Result<(Self, DecryptionShareOpening<S>)> {
+        let mut salt = [0u8; 32];
+        rng.fill_bytes(&mut salt);
+        let commitment = Self::from_parts(share, &salt)?;
+        let opening = DecryptionShareOpening {
+            share: share.clone(),
+            salt,
+        };
+        Ok((commitment, opening))
+    }
+
+    fn from_parts<S: Serialize>(share: &S, salt: &[u8; 32]) -> Result<Self> {
+        // H(tag || salt || share)
+        let mut preimage = V1::SHARE_COMMITMENT_DOMAIN.to_vec();
+        preimage.extend_from_slice(salt);
+        preimage.extend_from_slice(&share.to_bytes()?);
+        Ok(Self {
+            digest: sha256(&preimage),
+        })
+    }
+
+    /// Check that the opening matches this commitment.
+    pub fn verify<S: Serialize>(
+        &self,
+        opening: &DecryptionShareOpening<S>,
+    ) -> Result<bool> {
+        Ok(Self::from_parts(&opening.share, &opening.salt)? == *self)
+    }
+}
+
+/// Collects decryption shares in two phases: first commitments, then reveals.
+///
+/// Reveals are only accepted once at least `threshold` commitments have been collected.
+/// After that point, the commitment set is frozen, so no validator can commit to a share
+/// after having seen the revealed shares of others.
+#[derive(Clone, Debug)]
+pub struct ShareReleaseRound<S> {
+    threshold: usize,
+    commitments: BTreeMap<usize, DecryptionShareCommitment>,
+    revealed: BTreeMap<usize, S>,
+}
+
+impl<S: Serialize + Clone> ShareReleaseRound<S> {
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            threshold,
+            commitments: BTreeMap::new(),
+            revealed: BTreeMap::new(),
+        }
+    }
+
+    /// Whether enough commitments have been collected to start revealing shares.
+    pub fn is_reveal_phase(&self) -> bool {
+        self.commitments.len() >= self.threshold
+    }
+
+    /// Record a commitment published by the validator with the given share index.
+    pub fn add_commitment(
+        &mut self,
+        share_index: usize,
+        commitment: DecryptionShareCommitment,
+    ) -> Result<()> {
+        if self.is_reveal_phase() {
+            return Err(Error::ShareCommitmentPhaseClosed);
+        }
+        if self.commitments.contains_key(&share_index) {
+            return Err(Error::DuplicateShareCommitment(share_index));
+        }
+        self.commitments.insert(share_index, commitment);
+        Ok(())
+    }
+
+    /// Record the revealed decryption share of the validator with the given share index.
+    pub fn reveal(
+        &mut self,
+        share_index: usize,
+        opening: &DecryptionShareOpening<S>,
+    ) -> Result<()> {
+        if !self.is_reveal_phase() {
+            return Err(Error::ShareRevealBeforeQuorum(
+                self.commitments.len(),
+                self.threshold,
+            ));
+        }
+        let commitment = self
+            .commitments
+            .get(&share_index)
+            .ok_or(Error::UnknownShareCommitment(share_index))?;
+        if !commitment.verify(opening)? {
+            return Err(Error::ShareCommitmentMismatch(share_index));
+        }
+        self.revealed.insert(share_index, opening.share.clone());
+        Ok(())
+    }
+
+    /// Revealed shares, ordered by share index.
+    pub fn revealed_shares(&self) -> Vec<S> {
+        self.revealed.values().cloned().collect()
+    }
+
+    /// Share indices of validators that committed but haven't revealed yet.
+    pub fn pending_reveals(&self) -> Vec<usize> {
+        self.commitments
+            .keys()
+            .filter(|index| !self.revealed.contains_key(index))
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "tdec-simple"))]
+mod tests {
+    use ark_std::test_rng;
+
+    use crate::*;
+
+    type E = ark_bls12_381::Bls12_381;
+
+    fn make_shares(
+        threshold: usize,
+        shares_num: usize,
+    ) -> Vec<DecryptionShareSimple<E>> {
+        let rng = &mut test_rng();
+        let (pubkey, _, contexts) =
+            test_common::setup_simple::<E>(threshold, shares_num, rng);
+        let aad: &[u8] = "my-aad".as_bytes();
+        let ciphertext = encrypt::<E>(
+            SecretBox::new("my-msg".as_bytes().to_vec()),
+            aad,
+            &pubkey,
+            rng,
+        )
+        .unwrap();
+        let header = ciphertext.header().unwrap();
+        contexts
+            .iter()
+            .map(|c| c.create_share(&header, aad).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn commit_then_reveal() {
+        let rng = &mut test_rng();
+        let (threshold, shares_num) = (3, 4);
+        let shares = make_shares(threshold, shares_num);
+        let (commitments, openings): (Vec<_>, Vec<_>) = shares
+            .iter()
+            .map(|s| DecryptionShareCommitment::commit(s, rng).unwrap())
+            .unzip();
+
+        let mut round = ShareReleaseRound::new(threshold);
+
+        // Nothing can be revealed before a quorum of commitments exists
+        round.add_commitment(0, commitments[0]).unwrap();
+        assert!(matches!(
+            round.reveal(0, &openings[0]),
+            Err(Error::ShareRevealBeforeQuorum(1, 3))
+        ));
+        assert!(matches!(
+            round.add_commitment(0, commitments[0]),
+            Err(Error::DuplicateShareCommitment(0))
+        ));
+        round.add_commitment(1, commitments[1]).unwrap();
+        round.add_commitment(2, commitments[2]).unwrap();
+        assert!(round.is_reveal_phase());
+
+        // Late commitments are rejected
+        assert!(matches!(
+            round.add_commitment(3, commitments[3]),
+            Err(Error::ShareCommitmentPhaseClosed)
+        ));
+        assert!(matches!(
+            round.reveal(3, &openings[3]),
+            Err(Error::UnknownShareCommitment(3))
+        ));
+
+        // A share that doesn't match the commitment is rejected
+        assert!(matches!(
+            round.reveal(0, &openings[1]),
+            Err(Error::ShareCommitmentMismatch(0))
+        ));
+
+        for (i, opening) in openings.iter().enumerate().take(threshold) {
+            round.reveal(i, opening).unwrap();
+        }
+        assert!(round.pending_reveals().is_empty());
+        assert_eq!(round.revealed_shares(), shares[..threshold].to_vec());
+    }
+}
@@ -0,0 +1,166 @@
+use std::{collections::HashMap, fmt};
+
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_serialize::CanonicalSerialize;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    protocol::{ProtocolVersion, V1},
+    sha256, Error, Result,
+};
+
+/// Identifies the pairing-friendly curve that a ciphertext was created with.
+///
+/// The identifier follows the version tag of serialized ciphertexts and ciphertext headers,
+/// so that artifacts can be routed, or rejected, before deserializing any curve points.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+pub struct SuiteId(pub u32);
+
+impl SuiteId {
+    /// Derives the suite identifier from the generators of the pairing groups, so that
+    /// every curve gets a distinct identifier without having to maintain a list of curves.
+    pub fn of<E: Pairing>() -> Self {
+        let mut preimage = V1::SUITE_ID_DOMAIN.to_vec();
+        E::G1Affine::generator()
+            .serialize_compressed(&mut preimage)
+            .expect("Generator is serializable");
+        E::G2Affine::generator()
+            .serialize_compressed(&mut preimage)
+            .expect("Generator is serializable");
+        let digest = sha256(&preimage);
+        Self(u32::from_be_bytes(
+            digest[..4].try_into().expect("Digest is at least 4 bytes"),
+        ))
+    }
+
+    /// Reads the suite identifier of a serialized ciphertext or ciphertext header, which
+    /// follows their version tag.
+    pub fn from_artifact_bytes(bytes: &[u8]) -> Result<Self> {
+        let suite = bytes
+            .get(4..8)
+            .ok_or(Error::ArtifactTooShort(bytes.len()))?;
+        Ok(Self(u32::from_le_bytes(
+            suite.try_into().expect("Suite is 4 bytes"),
+        )))
+    }
+
+    /// Fails if this identifier doesn't match the curve `E`.
+    pub fn check<E: Pairing>(&self) -> Result<()> {
+        let expected = Self::of::<E>();
+        if *self != expected {
+            return Err(Error::SuiteMismatch(expected, *self));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SuiteId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#010x}", self.0)
+    }
+}
+
+/// Maps the cipher suites supported by a deployment to a handler, e.g. a ritual, so that
+/// serialized artifacts can be routed to the handler for their curve.
+#[derive(Clone, Debug)]
+pub struct SuiteRegistry<T> {
+    handlers: HashMap<SuiteId, T>,
+}
+
+impl<T> Default for SuiteRegistry<T> {
+    fn default() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SuiteRegistry<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the handler for the curve `E`, replacing any previous one.
+    pub fn register<E: Pairing>(&mut self, handler: T) -> SuiteId {
+        let suite = SuiteId::of::<E>();
+        self.handlers.insert(suite, handler);
+        suite
+    }
+
+    pub fn get(&self, suite: &SuiteId) -> Result<&T> {
+        self.handlers.get(suite).ok_or(Error::UnknownSuite(*suite))
+    }
+
+    /// Find the handler for a serialized ciphertext or ciphertext header.
+    pub fn route(&self, artifact_bytes: &[u8]) -> Result<&T> {
+        self.get(&SuiteId::from_artifact_bytes(artifact_bytes)?)
+    }
+}
+
+#[cfg(all(test, feature = "tdec-fast"))]
+mod tests {
+    use ark_std::test_rng;
+    use ferveo_common::{FromBytes, ToBytes};
+
+    use crate::{test_common::*, *};
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[test]
+    fn suite_ids_are_distinct() {
+        assert_eq!(SuiteId::of::<E>(), SuiteId::of::<E>());
+        assert_ne!(SuiteId::of::<E>(), SuiteId::of::<ark_bn254::Bn254>());
+    }
+
+    #[test]
+    fn route_and_reject_mismatched_ciphertexts() {
+        let rng = &mut test_rng();
+        let aad: &[u8] = "my-aad".as_bytes();
+        let (pubkey, _, contexts) = setup_fast::<E>(3, 4, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let ciphertext = encrypt::<E>(
+            SecretBox::new("my-msg".as_bytes().to_vec()),
+            aad,
+            &pubkey,
+            rng,
+        )
+        .unwrap();
+        assert_eq!(ciphertext.suite, SuiteId::of::<E>());
+
+        let mut registry = SuiteRegistry::new();
+        registry.register::<E>("bls12-381 ritual");
+        let bytes = ciphertext.to_bytes().unwrap();
+        assert_eq!(registry.route(&bytes).unwrap(), &"bls12-381 ritual");
+        let header_bytes = ciphertext.header().unwrap().to_bytes().unwrap();
+        assert_eq!(registry.route(&header_bytes).unwrap(), &"bls12-381 ritual");
+
+        // An artifact from another curve is rejected early, with a clear error
+        let bn254 = SuiteId::of::<ark_bn254::Bn254>();
+        let mut foreign = bytes;
+        foreign[4..8].copy_from_slice(&bn254.0.to_le_bytes());
+        assert!(matches!(
+            registry.route(&foreign),
+            Err(Error::UnknownSuite(suite)) if suite == bn254
+        ));
+        let foreign = Ciphertext::<E>::from_bytes(&foreign).unwrap();
+        assert!(matches!(
+            foreign.check(aad, g_inv),
+            Err(Error::SuiteMismatch(_, suite)) if suite == bn254
+        ));
+        assert!(matches!(
+            registry.route(&[0u8; 2]),
+            Err(Error::ArtifactTooShort(2))
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+//! Helps choosing between the simple and precomputed decryption variants.
+//!
+//! The precomputed variant is cheaper to combine, but requires a decryption share from every
+//! validator, while the simple variant only requires `threshold` shares. Which one is better
+//! depends on the parameters, the hardware, and on how often validators are offline.
+
+use std::time::{Duration, Instant};
+
+use ark_ec::pairing::Pairing;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    encrypt, share_combine_precomputed, test_common, Result, SecretBox,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecryptionVariant {
+    Simple,
+    Precomputed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VariantMeasurement {
+    /// Time for a single validator to create its decryption share
+    pub share_creation: Duration,
+    /// Time for the client to combine the decryption shares
+    pub combine: Duration,
+    /// Probability that enough validators are online to decrypt
+    pub success_probability: f64,
+    /// Expected time to decrypt, including falling back to the simple variant on failure
+    pub expected_latency: Duration,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VariantAnalysis {
+    pub shares_num: usize,
+    pub threshold: usize,
+    pub offline_rate: f64,
+    pub simple: VariantMeasurement,
+    pub precomputed: VariantMeasurement,
+    pub recommended: DecryptionVariant,
+}
+
+/// Probability that at least `threshold` out of `shares_num` validators are online,
+/// assuming that every validator is offline independently with probability `offline_rate`.
+pub fn quorum_probability(
+    shares_num: usize,
+    threshold: usize,
+    offline_rate: f64,
+) -> f64 {
+    let online_rate = 1.0 - offline_rate;
+    // online[k] is the probability of exactly k validators being online
+    let mut online = vec![0.0; shares_num + 1];
+    online[0] = 1.0;
+    for n in 1..=shares_num {
+        for k in (1..=n).rev() {
+            online[k] = online[k] * offline_rate + online[k - 1] * online_rate;
+        }
+        online[0] *= offline_rate;
+    }
+    online[threshold.min(shares_num + 1)..].iter().sum()
+}
+
+fn average<F: FnMut() -> Result<()>>(
+    samples: usize,
+    mut f: F,
+) -> Result<Duration> {
+    let start = Instant::now();
+    for _ in 0..samples {
+        f()?;
+    }
+    Ok(start.elapsed() / samples.max(1) as u32)
+}
+
+/// Measures both decryption variants for the given parameters and recommends one.
+///
+/// Every measurement is an average over `samples` runs. Note that the measurements use a
+/// trusted dealer setup, which produces the same key material shape as a DKG.
+pub fn analyze_variants<E: Pairing>(
+    shares_num: usize,
+    threshold: usize,
+    offline_rate: f64,
+    samples: usize,
+    rng: &mut impl rand::Rng,
+) -> Result<VariantAnalysis> {
+    let msg = SecretBox::new(b"variant-analysis".to_vec());
+    let aad: &[u8] = b"variant-analysis-aad";
+
+    // Simple variant
+    let (pubkey, _, contexts) =
+        test_common::setup_simple::<E>(threshold, shares_num, rng);
+    let header = encrypt::<E>(msg.clone(), aad, &pubkey, rng)?.header()?;
+    let share_creation = average(samples, || {
+        contexts[0].create_share(&header, aad).map(|_| ())
+    })?;
+    let shares = contexts[..threshold]
+        .iter()
+        .map(|c| c.create_share(&header, aad))
+        .collect::<Result<Vec<_>>>()?;
+    let pub_contexts = &contexts[0].public_decryption_contexts[..threshold];
+    let combine = average(samples, || {
+        test_common::make_shared_secret(pub_contexts, &shares);
+        Ok(())
+    })?;
+    let success_probability =
+        quorum_probability(shares_num, threshold, offline_rate);
+    let simple = VariantMeasurement {
+        share_creation,
+        combine,
+        success_probability,
+        expected_latency: share_creation + combine,
+    };
+
+    // Precomputed variant
+    let (pubkey, _, contexts) =
+        test_common::setup_precomputed::<E>(shares_num, rng);
+    let header = encrypt::<E>(msg, aad, &pubkey, rng)?.header()?;
+    let share_creation = average(samples, || {
+        contexts[0]
+            .create_share_precomputed(&header, aad)
+            .map(|_| ())
+    })?;
+    let shares = contexts
+        .iter()
+        .map(|c| c.create_share_precomputed(&header, aad))
+        .collect::<Result<Vec<_>>>()?;
+    let combine =
+        average(samples, || share_combine_precomputed(&shares).map(|_| ()))?;
+    let success_probability =
+        quorum_probability(shares_num, shares_num, offline_rate);
+    let fallback = simple.expected_latency.mul_f64(1.0 - success_probability);
+    let precomputed = VariantMeasurement {
+        share_creation,
+        combine,
+        success_probability,
+        expected_latency: share_creation + combine + fallback,
+    };
+
+    let recommended = if precomputed.expected_latency < simple.expected_latency
+    {
+        DecryptionVariant::Precomputed
+    } else {
+        DecryptionVariant::Simple
+    };
+    Ok(VariantAnalysis {
+        shares_num,
+        threshold,
+        offline_rate,
+        simple,
+        precomputed,
+        recommended,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_std::test_rng;
+
+    use super::*;
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[test]
+    fn quorum_probability_bounds() {
+        assert_eq!(quorum_probability(4, 3, 0.0), 1.0);
+        assert_eq!(quorum_probability(4, 3, 1.0), 0.0);
+        assert_eq!(quorum_probability(4, 0, 0.5), 1.0);
+        // All of 2 validators online
+        assert!((quorum_probability(2, 2, 0.5) - 0.25).abs() < 1e-12);
+        // At least 1 of 2 validators online
+        assert!((quorum_probability(2, 1, 0.5) - 0.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn analyze_variants_recommends_simple_when_validators_are_offline() {
+        let rng = &mut test_rng();
+        let analysis = analyze_variants::<E>(4, 3, 1.0, 1, rng).unwrap();
+        assert_eq!(analysis.precomputed.success_probability, 0.0);
+        assert_eq!(analysis.recommended, DecryptionVariant::Simple);
+    }
+}
@@ -29,10 +29,13 @@ fn gen_validators(
     keypairs: &[ferveo_common::Keypair<EllipticCurve>],
 ) -> Vec<Validator<EllipticCurve>> {
     (0..keypairs.len())
-        .map(|i| Validator {
-            address: gen_address(i),
-            public_key: keypairs[i].public_key(),
-            share_index: i as u32,
+        .map(|i| {
+            Validator::new(
+                gen_address(i).to_string(),
+                keypairs[i].public_key(),
+                i as u32,
+            )
+            .unwrap()
         })
         .collect()
 }
@@ -55,15 +58,14 @@ fn setup_dkg(
 fn setup(
     shares_num: u32,
     rng: &mut StdRng,
-) -> (PubliclyVerifiableDkg<Bls12_381>, Message<Bls12_381>) {
+) -> (PubliclyVerifiableDkg<Bls12_381>, Vec<Message<Bls12_381>>) {
     let mut transcripts = vec![];
     for i in 0..shares_num {
         let mut dkg = setup_dkg(i as usize, shares_num);
         transcripts.push(dkg.share(rng).expect("Test failed"));
     }
     let dkg = setup_dkg(0, shares_num);
-    let transcript = transcripts[0].clone();
-    (dkg, transcript)
+    (dkg, transcripts)
 }
 
 pub fn bench_verify_full(c: &mut Criterion) {
@@ -73,27 +75,47 @@ pub fn bench_verify_full(c: &mut Criterion) {
     let rng = &mut StdRng::seed_from_u64(0);
 
     for shares_num in NUM_SHARES_CASES {
-        let (dkg, transcript) = setup(shares_num as u32, rng);
-        let transcript = &transcript;
+        let (dkg, messages) = setup(shares_num as u32, rng);
+        let transcript = &messages[0];
+        let transcripts = messages
+            .iter()
+            .map(|message| match message {
+                Message::Deal(ss) => ss.clone(),
+                _ => panic!("Expected Deal"),
+            })
+            .collect::<Vec<_>>();
 
         let pvss_verify_optimistic = {
             move || {
                 if let Message::Deal(ss) = transcript {
-                    black_box(ss.verify_optimistic());
+                    black_box(ss.verify_optimistic(&gen_address(0)));
                 } else {
                     panic!("Expected Deal");
                 }
             }
         };
+        let dkg = &dkg;
         let pvss_verify_full = {
             move || {
                 if let Message::Deal(ss) = transcript {
-                    black_box(ss.verify_full(&dkg));
+                    black_box(ss.verify_full(dkg));
                 } else {
                     panic!("Expected Deal");
                 }
             }
         };
+        let pvss_verify_full_all = {
+            let transcripts = &transcripts;
+            move || {
+                for ss in transcripts {
+                    black_box(ss.verify_full(dkg));
+                }
+            }
+        };
+        let pvss_verify_full_batch = {
+            let transcripts = &transcripts;
+            move || black_box(verify_full_batch(transcripts, dkg))
+        };
 
         group.bench_function(
             BenchmarkId::new("pvss_verify_optimistic", shares_num),
@@ -103,6 +125,14 @@ pub fn bench_verify_full(c: &mut Criterion) {
             BenchmarkId::new("pvss_verify_full", shares_num),
             |b| b.iter(|| pvss_verify_full()),
         );
+        group.bench_function(
+            BenchmarkId::new("pvss_verify_full_all", shares_num),
+            |b| b.iter(|| pvss_verify_full_all()),
+        );
+        group.bench_function(
+            BenchmarkId::new("pvss_verify_full_batch", shares_num),
+            |b| b.iter(|| pvss_verify_full_batch()),
+        );
     }
 }
 
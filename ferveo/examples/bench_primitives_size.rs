@@ -63,10 +63,13 @@ fn gen_validators(
     keypairs: &[ferveo_common::Keypair<EllipticCurve>],
 ) -> Vec<Validator<EllipticCurve>> {
     (0..keypairs.len())
-        .map(|i| Validator {
-            address: gen_address(i),
-            public_key: keypairs[i].public_key(),
-            share_index: i as u32,
+        .map(|i| {
+            Validator::new(
+                gen_address(i).to_string(),
+                keypairs[i].public_key(),
+                i as u32,
+            )
+            .unwrap()
         })
         .collect()
 }
@@ -0,0 +1,417 @@
+//! Encoding of transcripts and aggregates for Ethereum contracts, e.g. contracts that
+//! coordinate rituals on-chain.
+//!
+//! Points are encoded as the BLS12-381 precompiles of EIP-2537 take them: a coordinate is
+//! 64 bytes, i.e. 16 zero bytes followed by its 48 big-endian bytes, a G1 point is `x || y`,
+//! a G2 point is `x.c0 || x.c1 || y.c0 || y.c1`, and the point at infinity is all zeros.
+//! Points decode only if they are in the prime order subgroup of their group.
+//!
+//! A transcript, aggregated or not, is encoded as `abi.encode(transcript)` of
+//!
+//! ```solidity
+//! struct Transcript {
+//!     uint8 version;
+//!     bytes32 validatorSetHash;
+//!     uint32 ritualId;
+//!     uint64 domainSize;
+//!     uint256 domainGenerator;
+//!     bytes sigma;        // a G2 point
+//!     bytes coefficients; // G1 points, e.g. the input of the G1 MSM precompile
+//!     bytes shares;       // G2 points
+//!     bytes extensions;   // uint16 type || uint32 length || data, by ascending type
+//! }
+//! ```
+//!
+//! so that it decodes with `abi.decode(data, (Transcript))`. Decoding only accepts the
+//! canonical encoding, in which dynamic fields are laid out in order and without gaps.
+
+use ark_bls12_381::{Bls12_381, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, Valid};
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+
+use crate::{
+    DomainDescriptor, Error, Extensions, PubliclyVerifiableSS, Result,
+    ShareEncryptions,
+};
+
+/// The size of an ABI word
+const WORD_SIZE: usize = 32;
+/// The size of an EIP-2537 encoding of a base field element
+const FQ_SIZE: usize = 64;
+/// The size of the big-endian bytes of a base field element
+const FQ_BYTES: usize = 48;
+/// The size of an EIP-2537 encoding of a G1 point
+pub const ABI_G1_SIZE: usize = 2 * FQ_SIZE;
+/// The size of an EIP-2537 encoding of a G2 point
+pub const ABI_G2_SIZE: usize = 4 * FQ_SIZE;
+/// The number of words in the head of an encoded transcript: five static fields and the
+/// offsets of four dynamic ones
+const TRANSCRIPT_HEAD_SIZE: usize = 9 * WORD_SIZE;
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidAbiEncoding(reason.to_string())
+}
+
+fn fq_to_abi(fq: &Fq, bytes: &mut [u8]) {
+    bytes[FQ_SIZE - FQ_BYTES..]
+        .copy_from_slice(&fq.into_bigint().to_bytes_be());
+}
+
+fn fq_from_abi(bytes: &[u8]) -> Result<Fq> {
+    let (padding, value) = bytes.split_at(FQ_SIZE - FQ_BYTES);
+    if padding.iter().any(|&byte| byte != 0) {
+        return Err(invalid("non-zero padding of a field element"));
+    }
+    let mut value = value.to_vec();
+    value.reverse();
+    // Rejects non-canonical encodings
+    Fq::deserialize_compressed(value.as_slice())
+        .map_err(|_| invalid("field element out of range"))
+}
+
+pub fn g1_to_abi_bytes(point: &G1Affine) -> [u8; ABI_G1_SIZE] {
+    let mut bytes = [0u8; ABI_G1_SIZE];
+    if let Some((x, y)) = point.xy() {
+        fq_to_abi(x, &mut bytes[..FQ_SIZE]);
+        fq_to_abi(y, &mut bytes[FQ_SIZE..]);
+    }
+    bytes
+}
+
+pub fn g1_from_abi_bytes(bytes: &[u8]) -> Result<G1Affine> {
+    if bytes.len() != ABI_G1_SIZE {
+        return Err(Error::InvalidByteLength(ABI_G1_SIZE, bytes.len()));
+    }
+    if bytes.iter().all(|&byte| byte == 0) {
+        return Ok(G1Affine::zero());
+    }
+    let point = G1Affine::new_unchecked(
+        fq_from_abi(&bytes[..FQ_SIZE])?,
+        fq_from_abi(&bytes[FQ_SIZE..])?,
+    );
+    point
+        .check()
+        .map_err(|_| invalid("G1 point not in the prime order subgroup"))?;
+    Ok(point)
+}
+
+pub fn g2_to_abi_bytes(point: &G2Affine) -> [u8; ABI_G2_SIZE] {
+    let mut bytes = [0u8; ABI_G2_SIZE];
+    if let Some((x, y)) = point.xy() {
+        for (i, fq) in [x.c0, x.c1, y.c0, y.c1].iter().enumerate() {
+            fq_to_abi(fq, &mut bytes[i * FQ_SIZE..(i + 1) * FQ_SIZE]);
+        }
+    }
+    bytes
+}
+
+pub fn g2_from_abi_bytes(bytes: &[u8]) -> Result<G2Affine> {
+    if bytes.len() != ABI_G2_SIZE {
+        return Err(Error::InvalidByteLength(ABI_G2_SIZE, bytes.len()));
+    }
+    if bytes.iter().all(|&byte| byte == 0) {
+        return Ok(G2Affine::zero());
+    }
+    let fq = |i: usize| fq_from_abi(&bytes[i * FQ_SIZE..(i + 1) * FQ_SIZE]);
+    let point = G2Affine::new_unchecked(
+        Fq2::new(fq(0)?, fq(1)?),
+        Fq2::new(fq(2)?, fq(3)?),
+    );
+    point
+        .check()
+        .map_err(|_| invalid("G2 point not in the prime order subgroup"))?;
+    Ok(point)
+}
+
+fn uint_word(value: u64) -> [u8; WORD_SIZE] {
+    let mut word = [0u8; WORD_SIZE];
+    word[WORD_SIZE - 8..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Read a word holding an unsigned integer of at most 64 bits
+fn read_uint(word: &[u8]) -> Result<u64> {
+    let (high, low) = word.split_at(WORD_SIZE - 8);
+    if high.iter().any(|&byte| byte != 0) {
+        return Err(invalid("integer out of range"));
+    }
+    Ok(u64::from_be_bytes(low.try_into().unwrap()))
+}
+
+fn padded_len(len: usize) -> usize {
+    (len + WORD_SIZE - 1) / WORD_SIZE * WORD_SIZE
+}
+
+/// The tail of a `bytes` field: its length, then its data padded to a whole number of words
+fn encode_bytes(data: &[u8]) -> Vec<u8> {
+    let mut tail = uint_word(data.len() as u64).to_vec();
+    tail.extend_from_slice(data);
+    tail.resize(WORD_SIZE + padded_len(data.len()), 0);
+    tail
+}
+
+/// Read the `bytes` field at `offset` of `tuple`, returning its data and the offset
+/// following its tail
+fn decode_bytes(tuple: &[u8], offset: usize) -> Result<(&[u8], usize)> {
+    let truncated = || invalid("truncated bytes field");
+    let len_word = tuple
+        .get(offset..offset + WORD_SIZE)
+        .ok_or_else(truncated)?;
+    let len = read_uint(len_word)?;
+    if len > tuple.len() as u64 {
+        return Err(truncated());
+    }
+    let start = offset + WORD_SIZE;
+    let end = start + padded_len(len as usize);
+    let tail = tuple.get(start..end).ok_or_else(truncated)?;
+    let (data, padding) = tail.split_at(len as usize);
+    if padding.iter().any(|&byte| byte != 0) {
+        return Err(invalid("non-zero padding of a bytes field"));
+    }
+    Ok((data, end))
+}
+
+fn encode_extensions(extensions: &Extensions) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (extension_type, data) in extensions.iter() {
+        bytes.extend_from_slice(&extension_type.to_be_bytes());
+        bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(data);
+    }
+    bytes
+}
+
+fn decode_extensions(mut bytes: &[u8]) -> Result<Extensions> {
+    let mut extensions = Extensions::new();
+    let mut previous = None;
+    while !bytes.is_empty() {
+        if bytes.len() < 6 {
+            return Err(invalid("truncated extension"));
+        }
+        let extension_type = u16::from_be_bytes([bytes[0], bytes[1]]);
+        let len = u32::from_be_bytes(bytes[2..6].try_into().unwrap()) as usize;
+        if previous.map_or(false, |previous| extension_type <= previous) {
+            return Err(invalid("extensions not in ascending order"));
+        }
+        let data = bytes
+            .get(6..6 + len)
+            .ok_or_else(|| invalid("truncated extension"))?;
+        extensions.insert(extension_type, data.to_vec())?;
+        previous = Some(extension_type);
+        bytes = &bytes[6 + len..];
+    }
+    Ok(extensions)
+}
+
+impl<T> PubliclyVerifiableSS<Bls12_381, T> {
+    /// Encode the transcript as the `Transcript` struct of Ethereum contracts, see
+    /// [`crate::abi`]
+    pub fn to_abi_bytes(&self) -> Vec<u8> {
+        let sigma = g2_to_abi_bytes(&self.sigma).to_vec();
+        let coefficients = self
+            .coeffs
+            .iter()
+            .flat_map(g1_to_abi_bytes)
+            .collect::<Vec<_>>();
+        let shares = self
+            .shares
+            .iter()
+            .flat_map(|share| g2_to_abi_bytes(share.as_affine()))
+            .collect::<Vec<_>>();
+        let extensions = encode_extensions(&self.extensions);
+
+        let mut head = Vec::with_capacity(TRANSCRIPT_HEAD_SIZE);
+        head.extend_from_slice(&uint_word(self.version as u64));
+        head.extend_from_slice(&self.validator_set_hash);
+        head.extend_from_slice(&uint_word(self.tau as u64));
+        head.extend_from_slice(&uint_word(self.domain.size() as u64));
+        head.extend_from_slice(
+            &self.domain.group_gen().into_bigint().to_bytes_be(),
+        );
+        let mut tail = Vec::new();
+        for data in [sigma, coefficients, shares, extensions] {
+            head.extend_from_slice(&uint_word(
+                (TRANSCRIPT_HEAD_SIZE + tail.len()) as u64,
+            ));
+            tail.extend(encode_bytes(&data));
+        }
+        // The struct is dynamic, so it is encoded behind its offset
+        [uint_word(WORD_SIZE as u64).to_vec(), head, tail].concat()
+    }
+
+    /// Decode a transcript encoded by [`PubliclyVerifiableSS::to_abi_bytes`], e.g. read
+    /// from a contract. Transcripts of other format versions are rejected
+    pub fn from_abi_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < WORD_SIZE + TRANSCRIPT_HEAD_SIZE {
+            return Err(Error::InvalidByteLength(
+                WORD_SIZE + TRANSCRIPT_HEAD_SIZE,
+                bytes.len(),
+            ));
+        }
+        let (offset, tuple) = bytes.split_at(WORD_SIZE);
+        if read_uint(offset)? != WORD_SIZE as u64 {
+            return Err(invalid("non-canonical offset of the transcript"));
+        }
+        let word = |i: usize| &tuple[i * WORD_SIZE..(i + 1) * WORD_SIZE];
+
+        let version = read_uint(word(0))?;
+        if version != V1::TRANSCRIPT_VERSION as u64 {
+            return Err(Error::UnsupportedTranscriptVersion(
+                version.min(u8::MAX as u64) as u8,
+            ));
+        }
+        let validator_set_hash = word(1).try_into().unwrap();
+        let tau = u32::try_from(read_uint(word(2))?)
+            .map_err(|_| invalid("ritual id out of range"))?;
+        let domain_size = read_uint(word(3))?;
+        let mut group_gen = word(4).to_vec();
+        group_gen.reverse();
+        let group_gen = Fr::deserialize_compressed(group_gen.as_slice())
+            .map_err(|_| invalid("domain generator out of range"))?;
+        let domain = DomainDescriptor::from_parts(domain_size, group_gen)?;
+
+        let mut offset = TRANSCRIPT_HEAD_SIZE;
+        let mut fields = Vec::with_capacity(4);
+        for i in 5..9 {
+            if read_uint(word(i))? != offset as u64 {
+                return Err(invalid("non-canonical offset of a bytes field"));
+            }
+            let (data, next) = decode_bytes(tuple, offset)?;
+            fields.push(data);
+            offset = next;
+        }
+        if offset != tuple.len() {
+            return Err(invalid("trailing bytes"));
+        }
+        let (sigma, coefficients, shares, extensions) =
+            (fields[0], fields[1], fields[2], fields[3]);
+        if coefficients.len() % ABI_G1_SIZE != 0
+            || shares.len() % ABI_G2_SIZE != 0
+        {
+            return Err(invalid("partial point"));
+        }
+        Ok(Self {
+            version: version as u8,
+            coeffs: coefficients
+                .chunks(ABI_G1_SIZE)
+                .map(g1_from_abi_bytes)
+                .collect::<Result<_>>()?,
+            shares: shares
+                .chunks(ABI_G2_SIZE)
+                .map(|share| {
+                    g2_from_abi_bytes(share).and_then(ShareEncryptions::new)
+                })
+                .collect::<Result<_>>()?,
+            sigma: g2_from_abi_bytes(sigma)?,
+            validator_set_hash,
+            tau,
+            domain,
+            extensions: decode_extensions(extensions)?,
+            phantom: Default::default(),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_abi {
+    use super::*;
+    use crate::{test_common::*, AggregatedPvss};
+
+    #[test]
+    fn points_use_eip2537_encodings() {
+        // The generators, as in the test vectors of EIP-2537
+        let g1 = g1_to_abi_bytes(&G1Affine::generator());
+        assert_eq!(
+            hex::encode(g1),
+            "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+             0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1"
+        );
+        let g2 = g2_to_abi_bytes(&G2Affine::generator());
+        assert_eq!(
+            hex::encode(&g2[..FQ_SIZE]),
+            "00000000000000000000000000000000024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"
+        );
+        assert_eq!(g1_from_abi_bytes(&g1).unwrap(), G1Affine::generator());
+        assert_eq!(g2_from_abi_bytes(&g2).unwrap(), G2Affine::generator());
+        assert_eq!(
+            g1_from_abi_bytes(&[0u8; ABI_G1_SIZE]).unwrap(),
+            G1Affine::zero()
+        );
+
+        // Points off the curve, with non-canonical coordinates or padding are rejected
+        let mut off_curve = g1;
+        off_curve[ABI_G1_SIZE - 1] ^= 1;
+        assert!(g1_from_abi_bytes(&off_curve).is_err());
+        let mut padded = g1;
+        padded[0] = 1;
+        assert!(matches!(
+            g1_from_abi_bytes(&padded),
+            Err(Error::InvalidAbiEncoding(_))
+        ));
+        let mut out_of_range = g2;
+        out_of_range[FQ_SIZE - FQ_BYTES..FQ_SIZE].fill(0xff);
+        assert!(g2_from_abi_bytes(&out_of_range).is_err());
+        assert!(g2_from_abi_bytes(&g2[1..]).is_err());
+        let doubled: G1Affine =
+            (G1Affine::generator() + G1Affine::generator()).into();
+        assert_eq!(
+            g1_from_abi_bytes(&g1_to_abi_bytes(&doubled)).unwrap(),
+            doubled
+        );
+    }
+
+    #[test]
+    fn transcripts_round_trip_through_abi() {
+        let (dkg, _) = setup_dealt_dkg();
+        let pvss = dkg.vss.values().next().unwrap().clone();
+        let bytes = pvss.to_abi_bytes();
+        assert_eq!(bytes.len() % WORD_SIZE, 0);
+        assert_eq!(PubliclyVerifiableSS::from_abi_bytes(&bytes).unwrap(), pvss);
+
+        // Static fields are laid out at fixed offsets, e.g. the ritual id in the third word
+        let tuple = &bytes[WORD_SIZE..];
+        assert_eq!(
+            read_uint(&tuple[2 * WORD_SIZE..3 * WORD_SIZE]).unwrap(),
+            TAU as u64
+        );
+        let sigma_offset =
+            read_uint(&tuple[5 * WORD_SIZE..6 * WORD_SIZE]).unwrap() as usize;
+        assert_eq!(
+            &tuple[sigma_offset + WORD_SIZE
+                ..sigma_offset + WORD_SIZE + ABI_G2_SIZE],
+            &g2_to_abi_bytes(&pvss.sigma)
+        );
+
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let mut aggregate = crate::pvss::aggregate(&pvss_list).unwrap();
+        aggregate
+            .extensions
+            .insert(u16::MAX, vec![1, 2, 3])
+            .unwrap();
+        let bytes = aggregate.to_abi_bytes();
+        assert_eq!(AggregatedPvss::from_abi_bytes(&bytes).unwrap(), aggregate);
+
+        // Only the canonical encoding is accepted
+        assert!(
+            AggregatedPvss::<E>::from_abi_bytes(&bytes[..bytes.len() - 1])
+                .is_err()
+        );
+        let mut trailing = bytes.clone();
+        trailing.extend_from_slice(&[0u8; WORD_SIZE]);
+        assert!(matches!(
+            AggregatedPvss::<E>::from_abi_bytes(&trailing),
+            Err(Error::InvalidAbiEncoding(_))
+        ));
+        let mut other_version = bytes.clone();
+        other_version[2 * WORD_SIZE - 1] = 2;
+        assert!(matches!(
+            AggregatedPvss::<E>::from_abi_bytes(&other_version),
+            Err(Error::UnsupportedTranscriptVersion(2))
+        ));
+        let mut other_domain = bytes;
+        other_domain[5 * WORD_SIZE - 1] ^= 1;
+        assert!(AggregatedPvss::<E>::from_abi_bytes(&other_domain).is_err());
+    }
+}
@@ -1,18 +1,40 @@
+#[cfg(all(feature = "dkg", feature = "tdec-simple"))]
+use std::collections::BTreeMap;
 use std::{fmt, io};
 
+use ark_ec::AffineRepr;
+#[cfg(feature = "dkg")]
+use ark_ec::CurveGroup;
+#[cfg(feature = "dkg")]
 use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::UniformRand;
 use bincode;
 use ferveo_common::serialization;
+#[cfg(feature = "dkg")]
+pub use ferveo_tdec::api::SecretBuffer;
 pub use ferveo_tdec::api::{
-    prepare_combine_simple, share_combine_precomputed, share_combine_simple,
-    Fr, G1Affine, G1Prepared, G2Affine, SecretBox, E,
+    prepare_combine_simple, AadPolicy, CacheKey, CacheStore, CiphertextBuilder,
+    CiphertextEnvelope, Compression, DecryptionShareCommitment,
+    DecryptionShareOpening, Fr, G1Affine, G1Prepared, G2Affine, InMemoryStore,
+    LagrangeCache, PreparedKeyCache, SecretBox, ShareReleaseRound, SuiteId,
+    SuiteRegistry, VerificationReport, VersionedAad, E,
+};
+#[cfg(feature = "tdec-simple")]
+pub use ferveo_tdec::api::{CohortCommitment, ShareAggregationProof};
+// Combining shares recovers the shared secret, which a verification-only build never holds
+#[cfg(all(feature = "dkg", feature = "tdec-simple"))]
+pub use ferveo_tdec::api::{
+    share_combine_precomputed, share_combine_precomputed_with_quorum,
+    share_combine_simple,
 };
 use generic_array::{
     typenum::{Unsigned, U48},
     GenericArray,
 };
+#[cfg(feature = "dkg")]
+use itertools::izip;
+#[cfg(feature = "dkg")]
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -20,20 +42,37 @@ use serde_with::serde_as;
 pub type PublicKey = ferveo_common::PublicKey<E>;
 pub type Keypair = ferveo_common::Keypair<E>;
 pub type Validator = crate::Validator<E>;
+#[cfg(feature = "dkg")]
 pub type Transcript = PubliclyVerifiableSS<E>;
 
+#[cfg(feature = "dkg")]
 pub type ValidatorMessage = (Validator, Transcript);
+#[cfg(feature = "dkg")]
+pub type DecryptionContextBundle = crate::DecryptionContextBundle<E>;
+#[cfg(feature = "dkg")]
+pub type DealerCommitment = crate::DealerCommitment<E>;
+#[cfg(feature = "dkg")]
+pub type EpochEntry = crate::EpochEntry<E>;
+#[cfg(feature = "dkg")]
+pub type EpochRegistry = crate::EpochRegistry<E>;
+
+pub use ferveo_common::DeserializationLimits;
 
 #[cfg(feature = "bindings-python")]
 use crate::bindings_python;
 #[cfg(feature = "bindings-wasm")]
 use crate::bindings_wasm;
-pub use crate::EthereumAddress;
+#[cfg(feature = "dkg")]
+pub use crate::DomainPolicy;
+#[cfg(feature = "dkg")]
 use crate::{
-    do_verify_aggregation, Error, Message, PVSSMap, PubliclyVerifiableParams,
-    PubliclyVerifiableSS, Result,
+    do_verify_aggregation, Message, PVSSMap, PreparedEncryptionKeys,
+    PubliclyVerifiableParams, PubliclyVerifiableSS,
 };
+pub use crate::{AggregateChunk, ChunkReassembler, EthereumAddress};
+use crate::{Error, Result};
 
+#[cfg(feature = "tdec-simple")]
 pub type DecryptionSharePrecomputed =
     ferveo_tdec::api::DecryptionSharePrecomputed;
 
@@ -63,11 +102,12 @@ pub fn encrypt(
     Ok(Ciphertext(ciphertext))
 }
 
+#[cfg(feature = "dkg")]
 pub fn decrypt_with_shared_secret(
     ciphertext: &Ciphertext,
     aad: &[u8],
     shared_secret: &SharedSecret,
-) -> Result<Vec<u8>> {
+) -> Result<SecretBuffer> {
     let dkg_public_params = DkgPublicParameters::default();
     ferveo_tdec::api::decrypt_with_shared_secret(
         &ciphertext.0,
@@ -78,9 +118,26 @@ pub fn decrypt_with_shared_secret(
     .map_err(Error::from)
 }
 
+#[cfg(feature = "dkg")]
+pub fn decrypt_envelope_with_shared_secret(
+    envelope: &CiphertextEnvelope,
+    shared_secret: &SharedSecret,
+) -> Result<SecretBuffer> {
+    let dkg_public_params = DkgPublicParameters::default();
+    envelope
+        .decrypt_with_shared_secret(&shared_secret.0, &dkg_public_params.g1_inv)
+        .map_err(Error::from)
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq)]
 pub struct Ciphertext(ferveo_tdec::api::Ciphertext);
 
+impl From<&CiphertextEnvelope> for Ciphertext {
+    fn from(envelope: &CiphertextEnvelope) -> Self {
+        Self(envelope.ciphertext.clone())
+    }
+}
+
 impl Ciphertext {
     pub fn header(&self) -> Result<CiphertextHeader> {
         Ok(CiphertextHeader(self.0.header()?))
@@ -89,12 +146,33 @@ impl Ciphertext {
     pub fn payload(&self) -> Vec<u8> {
         self.0.payload()
     }
+
+    /// Number of decryption shares required to decrypt, if higher than the DKG threshold
+    pub fn quorum(&self) -> Option<u32> {
+        self.0.quorum
+    }
+
+    /// Deserialize a ciphertext received from an untrusted peer, within `limits`
+    pub fn from_bytes_limited(
+        bytes: &[u8],
+        limits: &DeserializationLimits,
+    ) -> Result<Self> {
+        Ok(Self(ferveo_tdec::api::Ciphertext::from_bytes_limited(
+            bytes, limits,
+        )?))
+    }
 }
 
 #[serde_as]
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CiphertextHeader(ferveo_tdec::api::CiphertextHeader);
 
+impl CiphertextHeader {
+    pub fn quorum(&self) -> Option<u32> {
+        self.0.quorum
+    }
+}
+
 /// The ferveo variant to use for the decryption share derivation.
 #[derive(
     PartialEq, Eq, Debug, Serialize, Deserialize, Copy, Clone, PartialOrd,
@@ -171,6 +249,41 @@ impl DkgPublicKey {
         U48::to_usize()
     }
 
+    /// The EIP-2537 encoding of this key, e.g. for the pairing precompile, see [`crate::abi`]
+    #[cfg(feature = "verify")]
+    pub fn to_abi_bytes(&self) -> [u8; crate::ABI_G1_SIZE] {
+        crate::g1_to_abi_bytes(&self.0)
+    }
+
+    #[cfg(feature = "verify")]
+    pub fn from_abi_bytes(bytes: &[u8]) -> Result<DkgPublicKey> {
+        crate::g1_from_abi_bytes(bytes).map(DkgPublicKey)
+    }
+
+    /// Fingerprint of this key, see [`crate::Fingerprint`]
+    pub fn fingerprint(&self) -> Result<crate::Fingerprint> {
+        crate::public_key_fingerprint::<E>(&self.0)
+    }
+
+    /// Start building a ciphertext envelope encrypted to this key.
+    pub fn ciphertext_builder(&self) -> CiphertextBuilder {
+        CiphertextBuilder::new(self.0)
+    }
+
+    /// Check that this key is the one derived from the given transcript aggregate,
+    /// and that the aggregate was produced for a DKG with the given parameters.
+    #[cfg(feature = "dkg")]
+    pub fn verify_derivation(
+        &self,
+        aggregate: &AggregatedTranscript,
+        security_threshold: u32,
+        shares_num: u32,
+    ) -> bool {
+        aggregate.0.coeffs.len() == security_threshold as usize
+            && aggregate.0.shares.len() == shares_num as usize
+            && aggregate.public_key() == *self
+    }
+
     /// Generate a random DKG public key.
     /// Use this for testing only.
     pub fn random() -> Self {
@@ -196,9 +309,11 @@ impl FieldPoint {
     }
 }
 
+#[cfg(feature = "dkg")]
 #[derive(Clone)]
 pub struct Dkg(crate::PubliclyVerifiableDkg<E>);
 
+#[cfg(feature = "dkg")]
 impl Dkg {
     pub fn new(
         tau: u32,
@@ -217,10 +332,56 @@ impl Dkg {
         Ok(Self(dkg))
     }
 
+    /// Same as [`Dkg::new`], but with share indices assigned canonically from the addresses of
+    /// the validators. See [`crate::assign_share_indices`].
+    pub fn with_canonical_share_indices(
+        tau: u32,
+        shares_num: u32,
+        security_threshold: u32,
+        validators: &[Validator],
+        me: &EthereumAddress,
+    ) -> Result<Self> {
+        let dkg_params =
+            crate::DkgParams::new(tau, security_threshold, shares_num)?;
+        let dkg =
+            crate::PubliclyVerifiableDkg::<E>::with_canonical_share_indices(
+                validators,
+                &dkg_params,
+                me,
+            )?;
+        Ok(Self(dkg))
+    }
+
+    /// Set which AAD this validator creates decryption shares for.
+    pub fn with_aad_policy(mut self, aad_policy: AadPolicy) -> Self {
+        self.0.aad_policy = aad_policy;
+        self
+    }
+
+    /// Deal the shares over the domain `domain_policy` chooses for their number, see
+    /// [`crate::PubliclyVerifiableDkg::with_domain_policy`]
+    pub fn with_domain_policy(
+        self,
+        domain_policy: DomainPolicy,
+    ) -> Result<Self> {
+        self.0.with_domain_policy(domain_policy).map(Self)
+    }
+
     pub fn public_key(&self) -> DkgPublicKey {
         DkgPublicKey(self.0.public_key())
     }
 
+    /// The domain point of the share `share_index`, stable across releases, see
+    /// [`crate::PubliclyVerifiableDkg::domain_point`]
+    pub fn domain_point(&self, share_index: u32) -> Result<FieldPoint> {
+        self.0.domain_point(share_index).map(FieldPoint)
+    }
+
+    /// The domain points of every share, in share index order
+    pub fn domain_points(&self) -> Vec<FieldPoint> {
+        self.0.domain_points().into_iter().map(FieldPoint).collect()
+    }
+
     pub fn generate_transcript<R: RngCore>(
         &mut self,
         rng: &mut R,
@@ -261,8 +422,18 @@ impl Dkg {
             g1_inv: self.0.pvss_params.g_inv(),
         }
     }
+
+    /// Serialize the state of this DKG, to [`Dkg::restore`] it after a restart
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.0.to_bytes()
+    }
+
+    pub fn restore(bytes: &[u8]) -> Result<Self> {
+        crate::PubliclyVerifiableDkg::<E>::restore(bytes).map(Self)
+    }
 }
 
+#[cfg(feature = "dkg")]
 fn make_pvss_map(messages: &[ValidatorMessage]) -> PVSSMap<E> {
     let mut pvss_map: PVSSMap<E> = PVSSMap::new();
     messages.iter().for_each(|(validator, transcript)| {
@@ -271,11 +442,29 @@ fn make_pvss_map(messages: &[ValidatorMessage]) -> PVSSMap<E> {
     pvss_map
 }
 
+#[cfg(feature = "dkg")]
+fn check_transcript_count(
+    messages: &[ValidatorMessage],
+    limits: &DeserializationLimits,
+) -> Result<()> {
+    if messages.len() > limits.max_aggregate_transcripts {
+        return Err(Error::TooManyTranscripts(
+            messages.len(),
+            limits.max_aggregate_transcripts,
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "dkg")]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AggregatedTranscript(PubliclyVerifiableSS<E, crate::Aggregated>);
 
+#[cfg(feature = "dkg")]
 impl AggregatedTranscript {
+    /// Aggregate at most [`DeserializationLimits::max_aggregate_transcripts`] transcripts
     pub fn new(messages: &[ValidatorMessage]) -> Result<Self> {
+        check_transcript_count(messages, &DeserializationLimits::default())?;
         let pvss_list = messages
             .iter()
             .map(|(_, t)| t)
@@ -284,16 +473,72 @@ impl AggregatedTranscript {
         Ok(AggregatedTranscript(crate::pvss::aggregate(&pvss_list)?))
     }
 
+    /// The aggregate of a ritual bootstrapped by a trusted dealer, see
+    /// [`crate::trusted_dealer`]
+    pub fn from_trusted_dealing(dealing: &crate::TrustedDealing<E>) -> Self {
+        AggregatedTranscript(dealing.aggregate.clone())
+    }
+
+    /// Verify the aggregate of `messages`, for a ritual of `shares_num` shares dealt with the
+    /// default PVSS params over the domain of the default [`DomainPolicy`]. See
+    /// [`AggregatedTranscript::verify_for`] to verify the aggregate of a [`Dkg`] with the
+    /// params and domain of the DKG
     pub fn verify(
         &self,
         shares_num: u32,
         messages: &[ValidatorMessage],
     ) -> Result<bool> {
-        let pvss_params = PubliclyVerifiableParams::<E>::default();
-        let domain = GeneralEvaluationDomain::<Fr>::new(shares_num as usize)
-            .expect("Unable to construct an evaluation domain");
+        let domain =
+            DomainPolicy::default().domain::<Fr>(shares_num as usize)?;
+        self.0.domain.check(&domain)?;
+        self.verify_with(
+            messages,
+            &PubliclyVerifiableParams::<E>::default(),
+            &domain,
+        )
+    }
+
+    /// Same as [`AggregatedTranscript::verify`], for the aggregate of the transcripts of
+    /// `dkg`, dealt with its PVSS params over its domain
+    pub fn verify_for(
+        &self,
+        dkg: &Dkg,
+        messages: &[ValidatorMessage],
+    ) -> Result<bool> {
+        self.0.domain.check(&dkg.0.domain)?;
+        self.verify_with(messages, &dkg.0.pvss_params, &dkg.0.domain)
+    }
 
-        let is_valid_optimistic = self.0.verify_optimistic();
+    fn verify_with(
+        &self,
+        messages: &[ValidatorMessage],
+        pvss_params: &PubliclyVerifiableParams<E>,
+        domain: &GeneralEvaluationDomain<Fr>,
+    ) -> Result<bool> {
+        check_transcript_count(messages, &DeserializationLimits::default())?;
+
+        // Transcripts dealt for another validator set can't be aggregated
+        if messages.iter().any(|(_, transcript)| {
+            transcript.validator_set_hash != self.0.validator_set_hash
+        }) {
+            return Err(Error::ValidatorSetMismatch);
+        }
+        // Every transcript must deal a polynomial of the degree of the aggregate
+        for (_, transcript) in messages {
+            transcript.check_degree(self.0.coeffs.len() as u32)?;
+            if transcript.tau != self.0.tau {
+                return Err(Error::RitualMismatch(self.0.tau, transcript.tau));
+            }
+        }
+        // Every dealer must prove knowledge of their secret. The aggregate proof is checked
+        // to be the sum of these proofs in `do_verify_aggregation`
+        let is_valid_optimistic =
+            messages.iter().all(|(validator, transcript)| {
+                transcript.verify_optimistic_with_params(
+                    &validator.address,
+                    pvss_params,
+                )
+            });
         if !is_valid_optimistic {
             return Err(Error::InvalidTranscriptAggregate);
         }
@@ -309,20 +554,198 @@ impl AggregatedTranscript {
         let is_valid = do_verify_aggregation(
             &self.0.coeffs,
             &self.0.shares,
-            &pvss_params,
+            &self.0.sigma,
+            pvss_params,
             &validators,
-            &domain,
+            &PreparedEncryptionKeys::new(&validators),
+            domain,
             &pvss_map,
         )?;
         Ok(is_valid)
     }
 
+    /// The DKG public key derived from this aggregate.
+    pub fn public_key(&self) -> DkgPublicKey {
+        DkgPublicKey(self.0.public_key().0)
+    }
+
+    /// Evaluate the aggregate commitment polynomial at `x`.
+    /// At the domain point of a validator, this is the public key share of that validator.
+    pub fn commitment_at(&self, x: &Fr) -> G1Affine {
+        self.0.commitment_at(x).into_affine()
+    }
+
+    /// The public data of the cohort of `dkg` that a [`ShareAggregationProof`] is verified
+    /// against.
+    pub fn cohort_commitment(&self, dkg: &Dkg) -> Result<CohortCommitment> {
+        let shares_num = self.0.shares.len();
+        let owners = dkg.0.share_owners();
+        if owners.len() != shares_num
+            || owners
+                .iter()
+                .enumerate()
+                .any(|(i, validator)| !validator.holds_share(i as u32))
+        {
+            return Err(Error::InsufficientValidators(
+                shares_num as u32,
+                owners.len() as u32,
+            ));
+        }
+        let members = izip!(&owners, &self.0.shares, self.0.domain_points())
+            .map(|(validator, share_aggregate, domain_point)| {
+                ferveo_tdec::CohortMember {
+                    domain_point,
+                    share_aggregate: share_aggregate.into_affine(),
+                    validator_public_key: validator.public_key.encryption_key,
+                }
+            })
+            .collect();
+        Ok(CohortCommitment {
+            threshold: self.0.coeffs.len(),
+            h: dkg.0.pvss_params.h.into_affine(),
+            members,
+        })
+    }
+
+    /// Split the serialized aggregate into chunks small enough for gossip messages.
+    pub fn to_chunks(
+        &self,
+        max_chunk_size: usize,
+    ) -> Result<Vec<AggregateChunk>> {
+        Ok(AggregateChunk::split(
+            &bincode::serialize(self)?,
+            max_chunk_size,
+        ))
+    }
+
+    /// Reassemble an aggregate from its chunks, in any order, within the default
+    /// [`DeserializationLimits`].
+    pub fn from_chunks(chunks: Vec<AggregateChunk>) -> Result<Self> {
+        let bytes = AggregateChunk::reassemble(chunks)?;
+        Self::from_bytes_limited(&bytes, &DeserializationLimits::default())
+    }
+
+    /// The entry recording this aggregate as finalized in `epoch`
+    pub fn epoch_entry(
+        &self,
+        epoch: u32,
+        predecessor: Option<u32>,
+    ) -> Result<EpochEntry> {
+        EpochEntry::new(epoch, &self.0, predecessor)
+    }
+
+    /// Check that this aggregate was finalized in `epoch` of the lineage of `registry`
+    pub fn verify_epoch(
+        &self,
+        registry: &EpochRegistry,
+        epoch: u32,
+    ) -> Result<()> {
+        registry.verify_aggregate(epoch, &self.0)
+    }
+
+    /// Deserialize an aggregate received from an untrusted peer, within `limits`
+    pub fn from_bytes_limited(
+        bytes: &[u8],
+        limits: &DeserializationLimits,
+    ) -> Result<Self> {
+        Ok(Self(PubliclyVerifiableSS::from_bytes_limited(
+            bytes, limits,
+        )?))
+    }
+
+    /// Verify the aggregate against the commitments of its dealers, published by an
+    /// aggregator service, instead of the transcripts themselves.
+    pub fn verify_with_commitments(
+        &self,
+        dkg: &Dkg,
+        commitments: &[DealerCommitment],
+    ) -> Result<bool> {
+        self.0.verify_with_commitments(&dkg.0, commitments)
+    }
+
+    /// Export everything this validator needs to serve decryptions for the ritual,
+    /// e.g. to stand up a disaster recovery environment.
+    pub fn export_decryption_context(
+        &self,
+        dkg: &Dkg,
+    ) -> Result<DecryptionContextBundle> {
+        DecryptionContextBundle::new(&dkg.0, &self.0)
+    }
+
     pub fn create_decryption_share_precomputed(
         &self,
         dkg: &Dkg,
         ciphertext_header: &CiphertextHeader,
         aad: &[u8],
         validator_keypair: &Keypair,
+    ) -> Result<DecryptionSharePrecomputed> {
+        self.create_decryption_share_precomputed_at(
+            dkg,
+            ciphertext_header,
+            aad,
+            validator_keypair,
+            dkg.0.me.share_index,
+        )
+    }
+
+    /// Same as [`AggregatedTranscript::create_decryption_share_precomputed`], but creates a
+    /// decryption share for each share of this validator.
+    pub fn create_decryption_shares_precomputed(
+        &self,
+        dkg: &Dkg,
+        ciphertext_header: &CiphertextHeader,
+        aad: &[u8],
+        validator_keypair: &Keypair,
+    ) -> Result<Vec<DecryptionSharePrecomputed>> {
+        dkg.0
+            .me
+            .share_indices()?
+            .map(|share_index| {
+                self.create_decryption_share_precomputed_at(
+                    dkg,
+                    ciphertext_header,
+                    aad,
+                    validator_keypair,
+                    share_index,
+                )
+            })
+            .collect()
+    }
+
+    /// Same as [`AggregatedTranscript::create_decryption_share_precomputed`], for a DKG of
+    /// any security threshold: the decryption share is combined with the shares
+    /// `participants`, which must be exactly `security_threshold` shares including the first
+    /// share of this validator
+    pub fn create_decryption_share_precomputed_among(
+        &self,
+        dkg: &Dkg,
+        ciphertext_header: &CiphertextHeader,
+        aad: &[u8],
+        validator_keypair: &Keypair,
+        participants: &[u32],
+    ) -> Result<DecryptionSharePrecomputed> {
+        dkg.0.aad_policy.check(aad)?;
+        let participants = participants
+            .iter()
+            .map(|&share_index| share_index as usize)
+            .collect::<Vec<_>>();
+        self.0.make_decryption_share_precomputed_among(
+            &ciphertext_header.0,
+            aad,
+            &validator_keypair.decryption_key,
+            dkg.0.me.share_index as usize,
+            &participants,
+            &dkg.0.pvss_params.g_inv(),
+        )
+    }
+
+    fn create_decryption_share_precomputed_at(
+        &self,
+        dkg: &Dkg,
+        ciphertext_header: &CiphertextHeader,
+        aad: &[u8],
+        validator_keypair: &Keypair,
+        share_index: u32,
     ) -> Result<DecryptionSharePrecomputed> {
         if dkg.0.dkg_params.shares_num()
             != dkg.0.dkg_params.security_threshold()
@@ -332,22 +755,19 @@ impl AggregatedTranscript {
                 dkg.0.dkg_params.security_threshold(),
             ));
         }
-        let domain_points: Vec<_> = dkg
-            .0
-            .domain
-            .elements()
-            .take(dkg.0.dkg_params.shares_num() as usize)
-            .collect();
+        dkg.0.aad_policy.check(aad)?;
         self.0.make_decryption_share_simple_precomputed(
             &ciphertext_header.0,
             aad,
             &validator_keypair.decryption_key,
-            dkg.0.me.share_index as usize,
-            &domain_points,
+            share_index as usize,
             &dkg.0.pvss_params.g_inv(),
         )
     }
 
+    /// Creates the decryption share of the first share of this validator. Validators with a
+    /// weight above one should use
+    /// [`AggregatedTranscript::create_decryption_shares_simple`] instead.
     pub fn create_decryption_share_simple(
         &self,
         dkg: &Dkg,
@@ -355,25 +775,70 @@ impl AggregatedTranscript {
         aad: &[u8],
         validator_keypair: &Keypair,
     ) -> Result<DecryptionShareSimple> {
+        self.create_decryption_share_simple_at(
+            dkg,
+            ciphertext_header,
+            aad,
+            validator_keypair,
+            dkg.0.me.share_index,
+        )
+    }
+
+    /// Same as [`AggregatedTranscript::create_decryption_share_simple`], but creates a
+    /// decryption share for each share of this validator.
+    pub fn create_decryption_shares_simple(
+        &self,
+        dkg: &Dkg,
+        ciphertext_header: &CiphertextHeader,
+        aad: &[u8],
+        validator_keypair: &Keypair,
+    ) -> Result<Vec<DecryptionShareSimple>> {
+        dkg.0
+            .me
+            .share_indices()?
+            .map(|share_index| {
+                self.create_decryption_share_simple_at(
+                    dkg,
+                    ciphertext_header,
+                    aad,
+                    validator_keypair,
+                    share_index,
+                )
+            })
+            .collect()
+    }
+
+    fn create_decryption_share_simple_at(
+        &self,
+        dkg: &Dkg,
+        ciphertext_header: &CiphertextHeader,
+        aad: &[u8],
+        validator_keypair: &Keypair,
+        share_index: u32,
+    ) -> Result<DecryptionShareSimple> {
+        dkg.0.aad_policy.check(aad)?;
         let share = self.0.make_decryption_share_simple(
             &ciphertext_header.0,
             aad,
             &validator_keypair.decryption_key,
-            dkg.0.me.share_index as usize,
+            share_index as usize,
             &dkg.0.pvss_params.g_inv(),
         )?;
-        let domain_point = dkg.0.domain.element(dkg.0.me.share_index as usize);
+        let domain_point = self.0.domain().element(share_index as usize);
         Ok(DecryptionShareSimple {
             share,
+            share_index,
             domain_point,
         })
     }
 }
 
+#[cfg(feature = "tdec-simple")]
 #[serde_as]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DecryptionShareSimple {
     share: ferveo_tdec::api::DecryptionShareSimple,
+    share_index: u32,
     #[serde_as(as = "serialization::SerdeAs")]
     domain_point: Fr,
 }
@@ -388,7 +853,7 @@ pub struct DkgPublicParameters {
 impl Default for DkgPublicParameters {
     fn default() -> Self {
         DkgPublicParameters {
-            g1_inv: PubliclyVerifiableParams::<E>::default().g_inv(),
+            g1_inv: G1Prepared::from(-G1Affine::generator()),
         }
     }
 }
@@ -403,24 +868,122 @@ impl DkgPublicParameters {
     }
 }
 
-pub fn combine_shares_simple(shares: &[DecryptionShareSimple]) -> SharedSecret {
+#[cfg(feature = "tdec-simple")]
+impl DecryptionShareSimple {
+    pub fn share_index(&self) -> u32 {
+        self.share_index
+    }
+}
+
+/// Orders shares by their share index, so that combining doesn't depend on the order
+/// in which the shares were received. Repeated copies of a share are dropped, but
+/// conflicting shares for the same share index are rejected.
+#[cfg(all(feature = "dkg", feature = "tdec-simple"))]
+fn sort_by_share_index(
+    shares: &[DecryptionShareSimple],
+) -> Result<Vec<&DecryptionShareSimple>> {
+    let mut sorted = BTreeMap::new();
+    for share in shares {
+        match sorted.insert(share.share_index, share) {
+            Some(previous) if previous != share => {
+                return Err(Error::ConflictingDecryptionShares(
+                    share.share_index,
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(sorted.into_values().collect())
+}
+
+/// The quorum of the ciphertext isn't checked, see [`combine_shares_simple_with_quorum`].
+#[cfg(all(feature = "dkg", feature = "tdec-simple"))]
+pub fn combine_shares_simple(
+    shares: &[DecryptionShareSimple],
+) -> Result<SharedSecret> {
+    let shares = sort_by_share_index(shares)?;
     // Pick domain points that are corresponding to the shares we have.
     let domain_points: Vec<_> = shares.iter().map(|s| s.domain_point).collect();
     let lagrange_coefficients = prepare_combine_simple::<E>(&domain_points);
 
-    let shares: Vec<_> = shares.iter().cloned().map(|s| s.share).collect();
+    let shares: Vec<_> = shares.into_iter().map(|s| s.share.clone()).collect();
     let shared_secret =
         share_combine_simple(&shares, &lagrange_coefficients[..]);
-    SharedSecret(shared_secret)
+    Ok(SharedSecret(shared_secret))
+}
+
+/// Same as [`combine_shares_simple`], but the Lagrange coefficients are looked up in,
+/// or stored to, the given cache.
+#[cfg(all(feature = "dkg", feature = "tdec-simple"))]
+pub fn combine_shares_simple_with_cache<S: CacheStore>(
+    shares: &[DecryptionShareSimple],
+    cache: &LagrangeCache<S>,
+) -> Result<SharedSecret> {
+    let shares = sort_by_share_index(shares)?;
+    let domain_points: Vec<_> = shares.iter().map(|s| s.domain_point).collect();
+    let lagrange_coefficients = cache.coefficients_at_zero(&domain_points)?;
+
+    let shares: Vec<_> = shares.into_iter().map(|s| s.share.clone()).collect();
+    let shared_secret =
+        share_combine_simple(&shares, &lagrange_coefficients[..]);
+    Ok(SharedSecret(shared_secret))
+}
+
+/// Same as [`combine_shares_simple`], but also checks that the shares meet `quorum`, the
+/// quorum of the ciphertext they were created for, e.g. [`Ciphertext::quorum`].
+#[cfg(all(feature = "dkg", feature = "tdec-simple"))]
+pub fn combine_shares_simple_with_quorum(
+    shares: &[DecryptionShareSimple],
+    quorum: Option<u32>,
+) -> Result<SharedSecret> {
+    ferveo_tdec::check_quorum(quorum, sort_by_share_index(shares)?.len())?;
+    combine_shares_simple(shares)
+}
+
+/// Same as [`AggregatedTranscript::create_decryption_share_simple`], but from an exported
+/// decryption context.
+#[cfg(all(feature = "dkg", feature = "tdec-simple"))]
+pub fn create_decryption_share_simple_from_context(
+    context: &DecryptionContextBundle,
+    ciphertext_header: &CiphertextHeader,
+    aad: &[u8],
+    validator_keypair: &Keypair,
+) -> Result<DecryptionShareSimple> {
+    let share = context.create_decryption_share_simple(
+        &ciphertext_header.0,
+        aad,
+        validator_keypair,
+    )?;
+    Ok(DecryptionShareSimple {
+        share,
+        share_index: context.me.share_index,
+        domain_point: context.domain_point(),
+    })
+}
+
+/// Same as [`combine_shares_simple`], but also proves that the shared secret was combined
+/// from valid shares of the cohort, for verifiers that don't see the shares themselves.
+#[cfg(all(feature = "dkg", feature = "tdec-simple"))]
+pub fn combine_shares_simple_with_proof(
+    shares: &[DecryptionShareSimple],
+    cohort: &CohortCommitment,
+) -> Result<(SharedSecret, ShareAggregationProof)> {
+    let shares = shares
+        .iter()
+        .map(|s| (s.share_index as usize, s.share.clone()))
+        .collect::<Vec<_>>();
+    let proof = ShareAggregationProof::new(&shares, cohort)?;
+    Ok((SharedSecret(proof.shared_secret()), proof))
 }
 
+#[cfg(feature = "dkg")]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SharedSecret(pub ferveo_tdec::api::SharedSecret<E>);
 
-#[cfg(test)]
+#[cfg(all(test, feature = "dkg"))]
 mod test_ferveo_api {
+    use ark_std::Zero;
     use ferveo_tdec::SecretBox;
-    use itertools::izip;
     use rand::{prelude::StdRng, SeedableRng};
     use test_case::test_case;
 
@@ -442,6 +1005,9 @@ mod test_ferveo_api {
                 address: gen_address(i),
                 public_key: keypair.public_key(),
                 share_index: i as u32,
+                weight: 1,
+                metadata: Default::default(),
+                signing_key: None,
             })
             .collect::<Vec<_>>();
 
@@ -472,6 +1038,10 @@ mod test_ferveo_api {
         let deserialized = DkgPublicKey::from_bytes(&serialized).unwrap();
         assert_eq!(serialized.len(), 48_usize);
         assert_eq!(dkg_pk, deserialized);
+
+        let abi_bytes = dkg_pk.to_abi_bytes();
+        assert_eq!(DkgPublicKey::from_abi_bytes(&abi_bytes).unwrap(), dkg_pk);
+        assert!(DkgPublicKey::from_abi_bytes(&serialized).is_err());
     }
 
     #[test_case(4; "number of shares (validators) is a power of 2")]
@@ -533,21 +1103,23 @@ mod test_ferveo_api {
         // Now, the decryption share can be used to decrypt the ciphertext
         // This part is part of the client API
 
-        let shared_secret = share_combine_precomputed(&decryption_shares);
+        let shared_secret =
+            share_combine_precomputed(&decryption_shares).unwrap();
         let plaintext = decrypt_with_shared_secret(
             &ciphertext,
             AAD,
             &SharedSecret(shared_secret),
         )
         .unwrap();
-        assert_eq!(plaintext, MSG);
+        assert_eq!(plaintext.as_bytes(), MSG);
 
         // Since we're using a precomputed variant, we need all the shares to be able to decrypt
         // So if we remove one share, we should not be able to decrypt
         let decryption_shares =
             decryption_shares[..shares_num as usize - 1].to_vec();
 
-        let shared_secret = share_combine_precomputed(&decryption_shares);
+        let shared_secret =
+            share_combine_precomputed(&decryption_shares).unwrap();
         let result = decrypt_with_shared_secret(
             &ciphertext,
             AAD,
@@ -556,6 +1128,93 @@ mod test_ferveo_api {
         assert!(result.is_err());
     }
 
+    #[test_case(4, 1; "one of four")]
+    #[test_case(7, 3; "three of seven")]
+    #[test_case(5, 5; "five of five")]
+    fn test_server_api_tdec_precomputed_among(
+        shares_num: u32,
+        security_threshold: u32,
+    ) {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let (messages, validators, validator_keypairs) =
+            make_test_inputs(rng, TAU, security_threshold, shares_num);
+        let mut dkg = Dkg::new(
+            TAU,
+            shares_num,
+            security_threshold,
+            &validators,
+            &validators[0],
+        )
+        .unwrap();
+        let aggregate = dkg.aggregate_transcripts(&messages).unwrap();
+        let ciphertext =
+            encrypt(SecretBox::new(MSG.to_vec()), AAD, &dkg.public_key())
+                .unwrap();
+
+        // Any `security_threshold` validators can decrypt together, e.g. the last ones
+        let participants =
+            (shares_num - security_threshold..shares_num).collect::<Vec<_>>();
+        let decryption_shares: Vec<_> = participants
+            .iter()
+            .map(|&share_index| {
+                let validator = &validators[share_index as usize];
+                let mut dkg = Dkg::new(
+                    TAU,
+                    shares_num,
+                    security_threshold,
+                    &validators,
+                    validator,
+                )
+                .unwrap();
+                dkg.aggregate_transcripts(&messages)
+                    .unwrap()
+                    .create_decryption_share_precomputed_among(
+                        &dkg,
+                        &ciphertext.header().unwrap(),
+                        AAD,
+                        &validator_keypairs[share_index as usize],
+                        &participants,
+                    )
+                    .unwrap()
+            })
+            .collect();
+        let shared_secret =
+            share_combine_precomputed(&decryption_shares).unwrap();
+        let plaintext = decrypt_with_shared_secret(
+            &ciphertext,
+            AAD,
+            &SharedSecret(shared_secret),
+        )
+        .unwrap();
+        assert_eq!(plaintext.as_bytes(), MSG);
+
+        // The participants must be as many as the security threshold, and include the sender
+        let header = ciphertext.header().unwrap();
+        let too_many = (0..=security_threshold).collect::<Vec<_>>();
+        if security_threshold < shares_num {
+            assert!(matches!(
+                aggregate.create_decryption_share_precomputed_among(
+                    &dkg,
+                    &header,
+                    AAD,
+                    &validator_keypairs[0],
+                    &too_many,
+                ),
+                Err(Error::InvalidDkgParametersForPrecomputedVariant(..))
+            ));
+            assert!(matches!(
+                aggregate.create_decryption_share_precomputed_among(
+                    &dkg,
+                    &header,
+                    AAD,
+                    &validator_keypairs[0],
+                    &participants,
+                ),
+                Err(Error::InvalidShareIndex(0))
+            ));
+        }
+    }
+
     #[test_case(4; "number of shares (validators) is a power of 2")]
     #[test_case(7; "number of shares (validators) is not a power of 2")]
     fn test_server_api_tdec_simple(shares_num: u32) {
@@ -582,6 +1241,23 @@ mod test_ferveo_api {
 
         // At this point, any given validator should be able to provide a DKG public key
         let public_key = dkg.public_key();
+        assert_eq!(pvss_aggregated.public_key(), public_key);
+        assert_eq!(pvss_aggregated.commitment_at(&Fr::zero()), public_key.0);
+        assert!(public_key.verify_derivation(
+            &pvss_aggregated,
+            security_threshold,
+            shares_num
+        ));
+        assert!(!public_key.verify_derivation(
+            &pvss_aggregated,
+            security_threshold + 1,
+            shares_num
+        ));
+        assert!(!DkgPublicKey::random().verify_derivation(
+            &pvss_aggregated,
+            security_threshold,
+            shares_num
+        ));
 
         // In the meantime, the client creates a ciphertext and decryption request
         let ciphertext =
@@ -619,23 +1295,344 @@ mod test_ferveo_api {
         let decryption_shares =
             decryption_shares[..security_threshold as usize].to_vec();
 
-        let shared_secret = combine_shares_simple(&decryption_shares);
+        let shared_secret = combine_shares_simple(&decryption_shares).unwrap();
         let plaintext =
             decrypt_with_shared_secret(&ciphertext, AAD, &shared_secret)
                 .unwrap();
-        assert_eq!(plaintext, MSG);
+        assert_eq!(plaintext.as_bytes(), MSG);
+
+        // Ciphertexts may also be built as envelopes, with a label, compression, and a
+        // quorum of decryption shares
+        let envelope = public_key
+            .ciphertext_builder()
+            .aad(AAD)
+            .label(b"my-label")
+            .compress(true)
+            .quorum(security_threshold)
+            .encrypt(SecretBox::new(MSG.to_vec()), rng)
+            .unwrap();
+        assert_eq!(
+            Ciphertext::from(&envelope).quorum(),
+            Some(security_threshold)
+        );
+        let envelope_shares: Vec<_> = izip!(&validators, &validator_keypairs)
+            .take(security_threshold as usize)
+            .map(|(validator, validator_keypair)| {
+                let mut dkg = Dkg::new(
+                    TAU,
+                    shares_num,
+                    security_threshold,
+                    &validators,
+                    validator,
+                )
+                .unwrap();
+                let aggregate = dkg.aggregate_transcripts(&messages).unwrap();
+                aggregate
+                    .create_decryption_share_simple(
+                        &dkg,
+                        &Ciphertext::from(&envelope).header().unwrap(),
+                        &envelope.aad,
+                        validator_keypair,
+                    )
+                    .unwrap()
+            })
+            .collect();
+        let quorum = Ciphertext::from(&envelope).quorum();
+        assert!(matches!(
+            combine_shares_simple_with_quorum(&envelope_shares[1..], quorum),
+            Err(Error::ThresholdEncryptionError(
+                ferveo_tdec::Error::InsufficientQuorum(..)
+            ))
+        ));
+        let envelope_shared_secret =
+            combine_shares_simple_with_quorum(&envelope_shares, quorum)
+                .unwrap();
+        let plaintext = decrypt_envelope_with_shared_secret(
+            &envelope,
+            &envelope_shared_secret,
+        )
+        .unwrap();
+        assert_eq!(plaintext.as_bytes(), MSG);
+
+        // Shares may be combined in any order, and repeated shares are only combined once
+        let mut reordered_shares = decryption_shares.clone();
+        reordered_shares.reverse();
+        reordered_shares.push(decryption_shares[0].clone());
+        assert_eq!(
+            combine_shares_simple(&reordered_shares).unwrap(),
+            shared_secret
+        );
+        // But a validator can't send conflicting shares
+        let mut conflicting_shares = decryption_shares.clone();
+        conflicting_shares.push(DecryptionShareSimple {
+            share_index: 0,
+            ..decryption_shares[1].clone()
+        });
+        assert!(matches!(
+            combine_shares_simple(&conflicting_shares),
+            Err(Error::ConflictingDecryptionShares(0))
+        ));
+
+        // Lagrange coefficients may also be shared between workers using a cache
+        let cache = LagrangeCache::<InMemoryStore>::default();
+        for _ in 0..2 {
+            let cached_shared_secret =
+                combine_shares_simple_with_cache(&decryption_shares, &cache)
+                    .unwrap();
+            assert_eq!(cached_shared_secret, shared_secret);
+        }
+        assert_eq!(cache.store().len(), 1);
+
+        // A combiner may prove the shared secret to verifiers that only know the cohort
+        let cohort = pvss_aggregated.cohort_commitment(&dkg).unwrap();
+        let (proven_shared_secret, proof) =
+            combine_shares_simple_with_proof(&decryption_shares, &cohort)
+                .unwrap();
+        assert_eq!(proven_shared_secret, shared_secret);
+        assert!(proof
+            .verify(&cohort, &ciphertext.header().unwrap().0)
+            .unwrap());
 
         // Let's say that we've only received `security_threshold - 1` shares
         // In this case, we should not be able to decrypt
         let decryption_shares =
             decryption_shares[..security_threshold as usize - 1].to_vec();
 
-        let shared_secret = combine_shares_simple(&decryption_shares);
+        let shared_secret = combine_shares_simple(&decryption_shares).unwrap();
         let result =
             decrypt_with_shared_secret(&ciphertext, AAD, &shared_secret);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn canonical_share_indices() {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let (security_threshold, shares_num) = (3, 4);
+        let (_, validators, _) =
+            make_test_inputs(rng, TAU, security_threshold, shares_num);
+
+        // Every validator derives the same indices, whatever the order and the given indices
+        // of the validator set
+        let mut shuffled = validators.clone();
+        shuffled.reverse();
+        shuffled
+            .iter_mut()
+            .for_each(|validator| validator.share_index = 0);
+        let dkgs = validators
+            .iter()
+            .map(|validator| {
+                Dkg::with_canonical_share_indices(
+                    TAU,
+                    shares_num,
+                    security_threshold,
+                    &shuffled,
+                    &validator.address,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        for dkg in &dkgs {
+            assert_eq!(dkg.0.validators, dkgs[0].0.validators);
+        }
+
+        // And transcripts dealt over the canonical indices aggregate
+        let messages = dkgs
+            .iter()
+            .map(|dkg| {
+                let mut dkg = dkg.clone();
+                (dkg.0.me.clone(), dkg.generate_transcript(rng).unwrap())
+            })
+            .collect::<Vec<_>>();
+        let aggregate =
+            dkgs[0].clone().aggregate_transcripts(&messages).unwrap();
+        assert!(aggregate.verify(shares_num, &messages).unwrap());
+
+        // A validator outside of the validator set can't join
+        assert!(matches!(
+            Dkg::with_canonical_share_indices(
+                TAU,
+                shares_num,
+                security_threshold,
+                &shuffled,
+                &gen_address(shares_num as usize),
+            ),
+            Err(Error::DealerNotInValidatorSet(_))
+        ));
+    }
+
+    #[test]
+    fn compact_domain_policy() {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let (security_threshold, shares_num) = (4, 6);
+        let (_, validators, _) =
+            make_test_inputs(rng, TAU, security_threshold, shares_num);
+        let dkgs = validators
+            .iter()
+            .map(|validator| {
+                Dkg::new(
+                    TAU,
+                    shares_num,
+                    security_threshold,
+                    &validators,
+                    validator,
+                )
+                .unwrap()
+                .with_domain_policy(DomainPolicy::Compact)
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let messages = dkgs
+            .iter()
+            .map(|dkg| {
+                let mut dkg = dkg.clone();
+                (dkg.0.me.clone(), dkg.generate_transcript(rng).unwrap())
+            })
+            .collect::<Vec<_>>();
+        let aggregate =
+            dkgs[0].clone().aggregate_transcripts(&messages).unwrap();
+
+        // The aggregate verifies over the domain of the DKG only
+        assert!(aggregate.verify_for(&dkgs[0], &messages).unwrap());
+        assert!(matches!(
+            aggregate.verify(shares_num, &messages),
+            Err(Error::EvaluationDomainMismatch)
+        ));
+    }
+
+    #[test]
+    fn weighted_validators_hold_several_shares() {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let (security_threshold, shares_num) = (3, 4);
+
+        // The first validator holds two shares
+        let validator_keypairs = gen_keypairs(3);
+        let validators = crate::assign_share_indices(
+            &validator_keypairs
+                .iter()
+                .zip([2, 1, 1])
+                .enumerate()
+                .map(|(i, (keypair, weight))| Validator {
+                    address: gen_address(i),
+                    public_key: keypair.public_key(),
+                    share_index: 0,
+                    weight,
+                    metadata: Default::default(),
+                    signing_key: None,
+                })
+                .collect::<Vec<_>>(),
+        );
+        let dkgs = validators
+            .iter()
+            .map(|validator| {
+                Dkg::new(
+                    TAU,
+                    shares_num,
+                    security_threshold,
+                    &validators,
+                    validator,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let messages = izip!(&validators, &dkgs)
+            .map(|(sender, dkg)| {
+                let mut dkg = dkg.clone();
+                (sender.clone(), dkg.generate_transcript(rng).unwrap())
+            })
+            .collect::<Vec<_>>();
+        for (_, transcript) in &messages {
+            assert_eq!(transcript.shares.len(), shares_num as usize);
+        }
+
+        let aggregate =
+            dkgs[0].clone().aggregate_transcripts(&messages).unwrap();
+        assert!(aggregate.verify(shares_num, &messages).unwrap());
+        let ciphertext =
+            encrypt(SecretBox::new(MSG.to_vec()), AAD, &aggregate.public_key())
+                .unwrap();
+        let header = ciphertext.header().unwrap();
+        let shares_of = |i: usize| {
+            aggregate
+                .create_decryption_shares_simple(
+                    &dkgs[i],
+                    &header,
+                    AAD,
+                    validator_keypairs
+                        .iter()
+                        .find(|keypair| {
+                            keypair.public_key() == dkgs[i].0.me.public_key
+                        })
+                        .unwrap(),
+                )
+                .unwrap()
+        };
+        let heavy = validators.iter().position(|v| v.weight == 2).unwrap();
+        let light = (heavy + 1) % validators.len();
+        assert_eq!(shares_of(heavy).len(), 2);
+
+        // The shares of the heavy validator alone are not enough
+        let shared_secret = combine_shares_simple(&shares_of(heavy)).unwrap();
+        assert!(decrypt_with_shared_secret(&ciphertext, AAD, &shared_secret)
+            .is_err());
+
+        // But together with another validator, they meet the threshold
+        let mut decryption_shares = shares_of(heavy);
+        decryption_shares.extend(shares_of(light));
+        let shared_secret = combine_shares_simple(&decryption_shares).unwrap();
+        let plaintext =
+            decrypt_with_shared_secret(&ciphertext, AAD, &shared_secret)
+                .unwrap();
+        assert_eq!(plaintext.as_bytes(), MSG);
+
+        let cohort = aggregate.cohort_commitment(&dkgs[0]).unwrap();
+        let (_, proof) =
+            combine_shares_simple_with_proof(&decryption_shares, &cohort)
+                .unwrap();
+        assert!(proof.verify(&cohort, &header.0).unwrap());
+    }
+
+    #[test]
+    fn decryption_shares_require_versioned_aad() {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let (messages, validators, validator_keypairs) =
+            make_test_inputs(rng, TAU, SECURITY_THRESHOLD, SHARES_NUM);
+        let mut dkg = Dkg::new(
+            TAU,
+            SHARES_NUM,
+            SECURITY_THRESHOLD,
+            &validators,
+            &validators[0],
+        )
+        .unwrap()
+        .with_aad_policy(AadPolicy::RequireSchema);
+        let aggregate = dkg.aggregate_transcripts(&messages).unwrap();
+        let create_share = |aad: &[u8]| {
+            let ciphertext =
+                encrypt(SecretBox::new(MSG.to_vec()), aad, &dkg.public_key())
+                    .unwrap();
+            aggregate.create_decryption_share_simple(
+                &dkg,
+                &ciphertext.header().unwrap(),
+                aad,
+                &validator_keypairs[0],
+            )
+        };
+
+        assert!(create_share(&VersionedAad::encode(b"my-conditions")).is_ok());
+        assert!(matches!(
+            create_share(b""),
+            Err(Error::ThresholdEncryptionError(
+                ferveo_tdec::Error::MissingAadSchemaVersion
+            ))
+        ));
+        assert!(matches!(
+            create_share(&VersionedAad::encode(b"")),
+            Err(Error::ThresholdEncryptionError(
+                ferveo_tdec::Error::EmptyAadConditions
+            ))
+        ));
+    }
+
     // Note that the server and client code are using the same underlying
     // implementation for aggregation and aggregate verification.
     // Here, we focus on testing user-facing APIs for server and client users.
@@ -740,4 +1737,76 @@ mod test_ferveo_api {
         let result = bad_aggregate.verify(SHARES_NUM, messages);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn aggregate_chunks_round_trip() {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let (messages, _, _) =
+            make_test_inputs(rng, TAU, SECURITY_THRESHOLD, SHARES_NUM);
+        let aggregate = AggregatedTranscript::new(&messages).unwrap();
+
+        let mut chunks = aggregate.to_chunks(128).unwrap();
+        assert!(chunks.len() > 1);
+        chunks.reverse();
+        let reassembled = AggregatedTranscript::from_chunks(chunks).unwrap();
+        assert_eq!(reassembled, aggregate);
+        assert!(reassembled.verify(SHARES_NUM, &messages).unwrap());
+    }
+
+    #[test]
+    fn deserialization_limits() {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let (messages, _, _) =
+            make_test_inputs(rng, TAU, SECURITY_THRESHOLD, SHARES_NUM);
+        let aggregate = AggregatedTranscript::new(&messages).unwrap();
+        let bytes = bincode::serialize(&aggregate).unwrap();
+        let transcript_bytes = bincode::serialize(&messages[0].1).unwrap();
+
+        let limits = DeserializationLimits::default();
+        assert_eq!(
+            AggregatedTranscript::from_bytes_limited(&bytes, &limits).unwrap(),
+            aggregate
+        );
+        assert_eq!(
+            Transcript::from_bytes_limited(&transcript_bytes, &limits).unwrap(),
+            messages[0].1
+        );
+
+        let limits = DeserializationLimits {
+            max_transcript_shares: SHARES_NUM as usize - 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            AggregatedTranscript::from_bytes_limited(&bytes, &limits),
+            Err(Error::TooManyTranscriptShares(actual, _))
+                if actual == SHARES_NUM as usize
+        ));
+        assert!(matches!(
+            Transcript::from_bytes_limited(&transcript_bytes, &limits),
+            Err(Error::TooManyTranscriptShares(..))
+        ));
+
+        let limits = DeserializationLimits {
+            max_message_size: bytes.len() as u64 - 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            AggregatedTranscript::from_bytes_limited(&bytes, &limits),
+            Err(Error::MessageTooLarge(_))
+        ));
+
+        let too_many =
+            vec![
+                messages[0].clone();
+                DeserializationLimits::default().max_aggregate_transcripts + 1
+            ];
+        assert!(matches!(
+            AggregatedTranscript::new(&too_many),
+            Err(Error::TooManyTranscripts(..))
+        ));
+        assert!(matches!(
+            aggregate.verify(SHARES_NUM, &too_many),
+            Err(Error::TooManyTranscripts(..))
+        ));
+    }
 }
@@ -58,6 +58,16 @@ impl From<FerveoPythonError> for PyErr {
                 Error::DuplicateDealer(dealer) => {
                     DuplicateDealer::new_err(dealer.to_string())
                 }
+                Error::ConflictingTranscript(dealer) => {
+                    DuplicateDealer::new_err(format!(
+                        "conflicting transcripts from {dealer}"
+                    ))
+                }
+                Error::KnownInvalidTranscript(dealer) => {
+                    InvalidPvssTranscript::new_err(format!(
+                        "already rejected transcript of {dealer}"
+                    ))
+                }
                 Error::InvalidPvssTranscript => {
                     InvalidPvssTranscript::new_err("")
                 }
@@ -90,6 +100,9 @@ impl From<FerveoPythonError> for PyErr {
                         "expected: {expected}, actual: {actual}"
                     ))
                 }
+                Error::InvalidAbiEncoding(reason) => {
+                    SerializationError::new_err(reason)
+                }
                 Error::InvalidVariant(variant) => {
                     InvalidVariant::new_err(variant.to_string())
                 },
@@ -98,11 +111,25 @@ impl From<FerveoPythonError> for PyErr {
                         "num_shares: {num_shares}, security_threshold: {security_threshold}"
                     ))
                 },
+                Error::DkgParamsViolations(violations) => {
+                    InvalidDkgParameters::new_err(
+                        violations
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join("; "),
+                    )
+                },
                 Error::InvalidShareIndex(index) => {
                     InvalidShareIndex::new_err(format!(
                         "{index}"
                     ))
                 },
+                Error::NotShareHolder(index) => {
+                    InvalidShareIndex::new_err(format!(
+                        "not the holder of share {index}"
+                    ))
+                },
                 Error::InvalidDkgParametersForPrecomputedVariant(num_shares, security_threshold) => {
                     InvalidDkgParameters::new_err(format!(
                         "num_shares: {num_shares}, security_threshold: {security_threshold}"
@@ -113,9 +140,175 @@ impl From<FerveoPythonError> for PyErr {
                         "{index}"
                     ))
                 },
+                Error::ConflictingDecryptionShares(index) => {
+                    InvalidShareIndex::new_err(format!(
+                        "{index}"
+                    ))
+                },
                 Error::NoTranscriptsToAggregate => {
                     NoTranscriptsToAggregate::new_err("")
                 },
+                Error::TranscriptSourceError(msg) => {
+                    TranscriptSourceError::new_err(msg.to_string())
+                },
+                Error::ValidatorSetMismatch => {
+                    ValidatorSetMismatch::new_err("")
+                },
+                Error::InvalidAggregateChunk(index, total) => {
+                    InvalidAggregateChunk::new_err(format!(
+                        "index: {index}, total: {total}"
+                    ))
+                },
+                Error::AggregateChunkMismatch => {
+                    AggregateChunkMismatch::new_err("")
+                },
+                Error::IncompleteAggregate(expected, actual) => {
+                    IncompleteAggregate::new_err(format!(
+                        "expected: {expected}, actual: {actual}"
+                    ))
+                },
+                Error::AggregateHashMismatch => {
+                    AggregateHashMismatch::new_err("")
+                },
+                Error::InvalidRitualTransition(phase, event) => {
+                    InvalidRitualTransition::new_err(format!(
+                        "{event} in phase {phase}"
+                    ))
+                },
+                Error::RecoveryBundleDecryptionFailed => {
+                    RecoveryBundleDecryptionFailed::new_err("")
+                },
+                Error::SecureChannelDecryptionFailed => {
+                    RecoveryBundleDecryptionFailed::new_err("secure channel")
+                },
+                Error::InvalidEvaluationDomain => {
+                    InvalidEvaluationDomain::new_err("")
+                },
+                Error::EvaluationDomainMismatch => {
+                    EvaluationDomainMismatch::new_err("")
+                },
+                Error::InvalidTranscriptDegree(expected, actual) => {
+                    InvalidPvssTranscript::new_err(format!(
+                        "expected: {expected} coefficients, actual: {actual}"
+                    ))
+                },
+                Error::NonCanonicalShareIndex(address, actual, expected) => {
+                    InvalidShareIndex::new_err(format!(
+                        "{address}: {actual}, expected {expected}"
+                    ))
+                },
+                Error::RitualMismatch(expected, actual) => {
+                    InvalidPvssTranscript::new_err(format!(
+                        "expected ritual: {expected}, actual: {actual}"
+                    ))
+                },
+                Error::MessageTooLarge(limit) => {
+                    MessageTooLarge::new_err(format!("limit: {limit} bytes"))
+                },
+                Error::TooManyTranscriptShares(actual, limit)
+                | Error::TooManyTranscripts(actual, limit) => {
+                    MessageTooLarge::new_err(format!(
+                        "actual: {actual}, limit: {limit}"
+                    ))
+                },
+                Error::UnknownEpoch(epoch)
+                | Error::BrokenEpochLineage(epoch)
+                | Error::EpochMismatch(epoch) => {
+                    EpochLineageError::new_err(epoch.to_string())
+                },
+                Error::DuplicateExtension(extension_type) => {
+                    InvalidPvssTranscript::new_err(format!(
+                        "duplicate extension: {extension_type}"
+                    ))
+                },
+                Error::UnsupportedTranscriptVersion(version) => {
+                    InvalidPvssTranscript::new_err(format!(
+                        "unsupported version: {version}"
+                    ))
+                },
+                Error::UnsignedTranscript => {
+                    InvalidPvssTranscript::new_err("unsigned transcript")
+                },
+                Error::InvalidTranscriptSignature(dealer) => {
+                    InvalidPvssTranscript::new_err(format!(
+                        "invalid signature of dealer {dealer}"
+                    ))
+                },
+                Error::InvalidMessageSignature(sender) => {
+                    InvalidPvssTranscript::new_err(format!(
+                        "invalid message signature of sender {sender}"
+                    ))
+                },
+                Error::InvalidShareUpdate(position) => {
+                    InvalidPvssTranscript::new_err(format!(
+                        "invalid share update: {position}"
+                    ))
+                },
+                Error::InvalidShareEncryption => {
+                    InvalidPvssTranscript::new_err("invalid share encryption")
+                },
+                Error::CommitmentCacheMismatch => {
+                    InvalidPvssTranscript::new_err("commitment cache mismatch")
+                },
+                Error::MissingShareProofs => {
+                    InvalidPvssTranscript::new_err("missing share proofs")
+                },
+                Error::MismatchedTranscriptShape {
+                    dealer,
+                    expected,
+                    got,
+                } => InvalidTranscriptAggregate::new_err(format!(
+                    "transcript {dealer}: expected {expected:?}, got {got:?}"
+                )),
+                Error::CohortTooLarge(shares_num, max_cohort_size) => {
+                    InvalidDkgParameters::new_err(format!(
+                        "shares_num: {shares_num}, max_cohort_size: {max_cohort_size}"
+                    ))
+                },
+                Error::DealerMismatch(sender, dealer) => {
+                    InvalidPvssTranscript::new_err(format!(
+                        "sent by {sender}, dealt by {dealer}"
+                    ))
+                },
+                Error::RoundDeadlinePassed(round, block) => {
+                    InvalidRitualTransition::new_err(format!(
+                        "deadline of round {round} passed at block {block}"
+                    ))
+                },
+                Error::TransportError(msg) => {
+                    TranscriptSourceError::new_err(msg.to_string())
+                },
+                Error::InvalidComplaint(accuser) => {
+                    InvalidPvssTranscript::new_err(format!(
+                        "invalid complaint from {accuser}"
+                    ))
+                },
+                Error::ExcludedDealer(dealer) => {
+                    UnknownDealer::new_err(format!("excluded dealer {dealer}"))
+                },
+                Error::ObserverCannotDeal(observer) => {
+                    InvalidDkgStateToDeal::new_err(format!(
+                        "observer {observer} can't deal"
+                    ))
+                },
+                Error::UnknownRitual(ritual) | Error::DuplicateRitual(ritual) => {
+                    InvalidRitualTransition::new_err(ritual.to_string())
+                },
+                Error::TooManySessions(limit) => {
+                    InvalidDkgStateToIngest::new_err(format!(
+                        "too many sessions, limit: {limit}"
+                    ))
+                },
+                Error::SessionMessageLimit(ritual, sender, limit) => {
+                    InvalidDkgStateToIngest::new_err(format!(
+                        "ritual: {ritual}, sender: {sender}, message limit: {limit}"
+                    ))
+                },
+                Error::InvalidShareReencryption(address) => {
+                    InvalidTranscriptAggregate::new_err(format!(
+                        "invalid share re-encryption of {address}"
+                    ))
+                },
             },
             _ => default(),
         }
@@ -153,6 +346,18 @@ create_exception!(exceptions, InvalidVariant, PyValueError);
 create_exception!(exceptions, InvalidDkgParameters, PyValueError);
 create_exception!(exceptions, InvalidShareIndex, PyValueError);
 create_exception!(exceptions, NoTranscriptsToAggregate, PyValueError);
+create_exception!(exceptions, TranscriptSourceError, PyValueError);
+create_exception!(exceptions, ValidatorSetMismatch, PyValueError);
+create_exception!(exceptions, InvalidAggregateChunk, PyValueError);
+create_exception!(exceptions, AggregateChunkMismatch, PyValueError);
+create_exception!(exceptions, IncompleteAggregate, PyValueError);
+create_exception!(exceptions, AggregateHashMismatch, PyValueError);
+create_exception!(exceptions, InvalidRitualTransition, PyValueError);
+create_exception!(exceptions, RecoveryBundleDecryptionFailed, PyValueError);
+create_exception!(exceptions, InvalidEvaluationDomain, PyValueError);
+create_exception!(exceptions, MessageTooLarge, PyValueError);
+create_exception!(exceptions, EpochLineageError, PyValueError);
+create_exception!(exceptions, EvaluationDomainMismatch, PyValueError);
 
 fn from_py_bytes<T: FromBytes>(bytes: &[u8]) -> PyResult<T> {
     T::from_bytes(bytes)
@@ -257,25 +462,27 @@ pub fn encrypt(
 #[pyfunction]
 pub fn combine_decryption_shares_simple(
     decryption_shares: Vec<DecryptionShareSimple>,
-) -> SharedSecret {
+) -> PyResult<SharedSecret> {
     let shares = decryption_shares
         .iter()
         .map(|share| share.0.clone())
         .collect::<Vec<_>>();
-    let shared_secret = api::combine_shares_simple(&shares[..]);
-    SharedSecret(shared_secret)
+    let shared_secret = api::combine_shares_simple(&shares[..])
+        .map_err(FerveoPythonError::FerveoError)?;
+    Ok(SharedSecret(shared_secret))
 }
 
 #[pyfunction]
 pub fn combine_decryption_shares_precomputed(
     decryption_shares: Vec<DecryptionSharePrecomputed>,
-) -> SharedSecret {
+) -> PyResult<SharedSecret> {
     let shares = decryption_shares
         .iter()
         .map(|share| share.0.clone())
         .collect::<Vec<_>>();
-    let shared_secret = api::share_combine_precomputed(&shares[..]);
-    SharedSecret(api::SharedSecret(shared_secret))
+    let shared_secret = api::share_combine_precomputed(&shares[..])
+        .map_err(|err| FerveoPythonError::FerveoError(err.into()))?;
+    Ok(SharedSecret(api::SharedSecret(shared_secret)))
 }
 
 #[pyfunction]
@@ -285,6 +492,7 @@ pub fn decrypt_with_shared_secret(
     shared_secret: &SharedSecret,
 ) -> PyResult<Vec<u8>> {
     api::decrypt_with_shared_secret(&ciphertext.0, aad, &shared_secret.0)
+        .map(api::SecretBuffer::into_bytes)
         .map_err(|err| FerveoPythonError::FerveoError(err).into())
 }
 
@@ -401,13 +609,16 @@ pub struct Validator(api::Validator);
 #[pymethods]
 impl Validator {
     #[new]
+    #[pyo3(signature = (address, public_key, share_index, weight = 1))]
     pub fn new(
         address: String,
         public_key: &FerveoPublicKey,
         share_index: u32,
+        weight: u32,
     ) -> PyResult<Self> {
         let validator = api::Validator::new(address, public_key.0, share_index)
-            .map_err(|err| FerveoPythonError::Other(err.to_string()))?;
+            .map_err(|err| FerveoPythonError::Other(err.to_string()))?
+            .with_weight(weight);
         Ok(Self(validator))
     }
 
@@ -416,6 +627,11 @@ impl Validator {
         self.0.address.to_string()
     }
 
+    #[getter]
+    pub fn weight(&self) -> u32 {
+        self.0.weight
+    }
+
     #[getter]
     pub fn public_key(&self) -> FerveoPublicKey {
         FerveoPublicKey(self.0.public_key)
@@ -436,6 +652,19 @@ pub struct DkgPublicKey(InnerDkgPublicKey);
 
 generate_boxed_bytes_serialization!(DkgPublicKey, InnerDkgPublicKey);
 
+#[pymethods]
+impl DkgPublicKey {
+    pub fn verify_derivation(
+        &self,
+        aggregate: &AggregatedTranscript,
+        security_threshold: u32,
+        shares_num: u32,
+    ) -> bool {
+        self.0
+            .verify_derivation(&aggregate.0, security_threshold, shares_num)
+    }
+}
+
 #[pyclass(module = "ferveo")]
 #[derive(derive_more::From, derive_more::AsRef, Clone)]
 pub struct ValidatorMessage(api::ValidatorMessage);
@@ -606,6 +835,30 @@ impl AggregatedTranscript {
         Ok(is_valid)
     }
 
+    #[getter]
+    pub fn public_key(&self) -> DkgPublicKey {
+        DkgPublicKey(self.0.public_key())
+    }
+
+    pub fn to_chunks(&self, max_chunk_size: usize) -> PyResult<Vec<PyObject>> {
+        let chunks = self
+            .0
+            .to_chunks(max_chunk_size)
+            .map_err(FerveoPythonError::FerveoError)?;
+        chunks.iter().map(to_py_bytes).collect()
+    }
+
+    #[staticmethod]
+    pub fn from_chunks(chunks: Vec<&[u8]>) -> PyResult<Self> {
+        let chunks = chunks
+            .into_iter()
+            .map(from_py_bytes)
+            .collect::<PyResult<Vec<api::AggregateChunk>>>()?;
+        let aggregate = api::AggregatedTranscript::from_chunks(chunks)
+            .map_err(FerveoPythonError::FerveoError)?;
+        Ok(Self(aggregate))
+    }
+
     pub fn create_decryption_share_precomputed(
         &self,
         dkg: &Dkg,
@@ -771,6 +1024,7 @@ mod test_ferveo_python {
                     format!("0x{i:040}"),
                     &keypair.public_key(),
                     i as u32,
+                    1,
                 )
                 .unwrap()
             })
@@ -866,7 +1120,7 @@ mod test_ferveo_python {
         // This part is part of the client API
 
         let shared_secret =
-            combine_decryption_shares_precomputed(decryption_shares);
+            combine_decryption_shares_precomputed(decryption_shares).unwrap();
 
         let plaintext =
             decrypt_with_shared_secret(&ciphertext, AAD, &shared_secret)
@@ -936,7 +1190,8 @@ mod test_ferveo_python {
         // Now, the decryption share can be used to decrypt the ciphertext
         // This part is part of the client API
 
-        let shared_secret = combine_decryption_shares_simple(decryption_shares);
+        let shared_secret =
+            combine_decryption_shares_simple(decryption_shares).unwrap();
 
         let plaintext =
             decrypt_with_shared_secret(&ciphertext, AAD, &shared_secret)
@@ -84,6 +84,9 @@ extern "C" {
 
     #[wasm_bindgen(typescript_type = "DecryptionSharePrecomputed[]")]
     pub type DecryptionSharePrecomputedArray;
+
+    #[wasm_bindgen(typescript_type = "Uint8Array[]")]
+    pub type Uint8ArrayArray;
 }
 
 fn unwrap_messages_js(
@@ -285,7 +288,8 @@ pub fn combine_decryption_shares_simple(
     let shares =
         try_from_js_array::<DecryptionShareSimple>(decryption_shares_js)?;
     let shares: Vec<_> = shares.iter().map(|share| share.0.clone()).collect();
-    let shared_secret = api::combine_shares_simple(&shares[..]);
+    let shared_secret =
+        api::combine_shares_simple(&shares[..]).map_err(map_js_err)?;
     Ok(SharedSecret(shared_secret))
 }
 
@@ -299,7 +303,8 @@ pub fn combine_decryption_shares_precomputed(
         .iter()
         .map(|share| share.0.clone())
         .collect::<Vec<_>>();
-    let shared_secret = api::share_combine_precomputed(&shares[..]);
+    let shared_secret =
+        api::share_combine_precomputed(&shares[..]).map_err(map_js_err)?;
     Ok(SharedSecret(api::SharedSecret(shared_secret)))
 }
 
@@ -311,6 +316,7 @@ pub fn decrypt_with_shared_secret(
 ) -> JsResult<Vec<u8>> {
     set_panic_hook();
     api::decrypt_with_shared_secret(&ciphertext.0, aad, &shared_secret.0)
+        .map(api::SecretBuffer::into_bytes)
         .map_err(map_js_err)
 }
 
@@ -329,6 +335,17 @@ impl DkgPublicKey {
     pub fn random() -> DkgPublicKey {
         Self(api::DkgPublicKey::random())
     }
+
+    #[wasm_bindgen(js_name = "verifyDerivation")]
+    pub fn verify_derivation(
+        &self,
+        aggregate: &AggregatedTranscript,
+        security_threshold: u32,
+        shares_num: u32,
+    ) -> bool {
+        self.0
+            .verify_derivation(&aggregate.0, security_threshold, shares_num)
+    }
 }
 
 #[wasm_bindgen]
@@ -422,21 +439,26 @@ pub struct Validator {
     address: EthereumAddress,
     public_key: FerveoPublicKey,
     share_index: u32,
+    weight: u32,
 }
 
 #[wasm_bindgen]
 impl Validator {
+    /// A validator holding `weight` shares from `share_index`, or a single one if `weight`
+    /// is omitted
     #[wasm_bindgen(constructor)]
     pub fn new(
         address: &EthereumAddress,
         public_key: &FerveoPublicKey,
         share_index: u32,
+        weight: Option<u32>,
     ) -> JsResult<Validator> {
         set_panic_hook();
         Ok(Self {
             address: address.clone(),
             public_key: public_key.clone(),
             share_index,
+            weight: weight.unwrap_or(1),
         })
     }
 
@@ -446,9 +468,17 @@ impl Validator {
             address: self.address.0.clone(),
             public_key: self.public_key.0,
             share_index: self.share_index,
+            weight: self.weight,
+            metadata: Default::default(),
+            signing_key: None,
         })
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
     #[wasm_bindgen(getter, js_name = "publicKey")]
     pub fn public_key(&self) -> FerveoPublicKey {
         self.public_key.clone()
@@ -525,6 +555,47 @@ impl AggregatedTranscript {
         Ok(is_valid)
     }
 
+    #[wasm_bindgen(js_name = "publicKey")]
+    pub fn public_key(&self) -> DkgPublicKey {
+        DkgPublicKey(self.0.public_key())
+    }
+
+    #[wasm_bindgen(js_name = "toChunks")]
+    pub fn to_chunks(
+        &self,
+        max_chunk_size: usize,
+    ) -> JsResult<Uint8ArrayArray> {
+        set_panic_hook();
+        let chunks = self.0.to_chunks(max_chunk_size).map_err(map_js_err)?;
+        let chunks = chunks
+            .iter()
+            .map(|chunk| {
+                to_js_bytes(chunk)
+                    .map(|bytes| js_sys::Uint8Array::from(&bytes[..]))
+            })
+            .collect::<JsResult<Vec<_>>>()?;
+        Ok(into_js_array(chunks))
+    }
+
+    #[wasm_bindgen(js_name = "fromChunks")]
+    pub fn from_chunks(
+        chunks: &Uint8ArrayArray,
+    ) -> JsResult<AggregatedTranscript> {
+        set_panic_hook();
+        let chunks: &js_sys::Array = chunks.dyn_ref().ok_or_else(|| {
+            Error::new("Got a non-array argument where an array was expected")
+        })?;
+        let chunks = chunks
+            .iter()
+            .map(|chunk| {
+                from_js_bytes(&js_sys::Uint8Array::new(&chunk).to_vec())
+            })
+            .collect::<JsResult<Vec<api::AggregateChunk>>>()?;
+        let aggregate = api::AggregatedTranscript::from_chunks(chunks)
+            .map_err(map_js_err)?;
+        Ok(AggregatedTranscript(aggregate))
+    }
+
     #[wasm_bindgen(js_name = "createDecryptionSharePrecomputed")]
     pub fn create_decryption_share_precomputed(
         &self,
@@ -616,6 +687,7 @@ pub mod test_common {
             address: gen_address(i),
             public_key: keypair.public_key(),
             share_index: i as u32,
+            weight: 1,
         }
     }
 }
@@ -0,0 +1,126 @@
+use ark_ec::pairing::Pairing;
+use ferveo_common::PublicKey;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{
+    evaluate_commitment, pairings_match, AggregatedPvss, DomainDescriptor,
+    Error, PubliclyVerifiableParams, Result,
+};
+
+/// A single share of an aggregate, blinded by the key of the validator holding it, e.g.
+/// for a contract that stores the share of each validator.
+///
+/// It is verified against the aggregate commitment, without the other shares.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlindedShare<E: Pairing> {
+    pub share_index: u32,
+    /// `Y_i = [f(ω_i) dk_i] H`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub blinded_share: E::G2Affine,
+}
+
+impl<E: Pairing> BlindedShare<E> {
+    /// Check that the share is consistent with the aggregate commitment `coeffs` over
+    /// `domain`, and blinded by `public_key`, i.e. e(G, Y_i) == e(A_i, ek_i) where A_i is the
+    /// commitment evaluated at `ω_i`
+    pub fn verify(
+        &self,
+        coeffs: &[E::G1Affine],
+        domain: &DomainDescriptor<E>,
+        public_key: &PublicKey<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> bool {
+        if coeffs.is_empty() || self.share_index as usize >= domain.size() {
+            return false;
+        }
+        let a_i = evaluate_commitment::<E>(
+            coeffs,
+            &domain.element(self.share_index as usize),
+        );
+        pairings_match::<E>(
+            pvss_params.g,
+            self.blinded_share,
+            a_i,
+            public_key.encryption_key,
+        )
+    }
+}
+
+impl<E: Pairing> AggregatedPvss<E> {
+    /// The share `share_index` of the aggregate, to export it on its own
+    pub fn blinded_share_for(
+        &self,
+        share_index: u32,
+    ) -> Result<BlindedShare<E>> {
+        let blinded_share = self
+            .shares
+            .get(share_index as usize)
+            .ok_or(Error::InvalidShareIndex(share_index))?
+            .into_affine();
+        Ok(BlindedShare {
+            share_index,
+            blinded_share,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_blinded_share {
+    use ark_ec::AffineRepr;
+
+    use super::*;
+    use crate::test_common::*;
+
+    #[test]
+    fn blinded_shares_verify_against_commitment() {
+        let (dkg, _) = setup_dealt_dkg();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = crate::pvss::aggregate(&pvss_list).unwrap();
+        let coeffs = &aggregate.coeffs;
+        let domain = &aggregate.domain;
+        let params = &dkg.pvss_params;
+
+        for validator in dkg.validators.values() {
+            let share =
+                aggregate.blinded_share_for(validator.share_index).unwrap();
+            assert_eq!(
+                share.blinded_share,
+                aggregate.shares[validator.share_index as usize].into_affine()
+            );
+            assert!(share.verify(
+                coeffs,
+                domain,
+                &validator.public_key,
+                params
+            ));
+
+            let bytes = bincode::serialize(&share).unwrap();
+            let share: BlindedShare<E> = bincode::deserialize(&bytes).unwrap();
+            assert!(share.verify(
+                coeffs,
+                domain,
+                &validator.public_key,
+                params
+            ));
+        }
+
+        // Shares don't verify for another validator, or if tampered with
+        let owners = dkg.share_owners();
+        let (holder, other) = (&owners[0].public_key, &owners[1].public_key);
+        let share = aggregate.blinded_share_for(0).unwrap();
+        assert!(!share.verify(coeffs, domain, other, params));
+        let mut other_index = share.clone();
+        other_index.share_index = 1;
+        assert!(!other_index.verify(coeffs, domain, holder, params));
+        let mut tampered = share;
+        tampered.blinded_share = G2::generator();
+        assert!(!tampered.verify(coeffs, domain, holder, params));
+
+        assert!(matches!(
+            aggregate.blinded_share_for(SHARES_NUM),
+            Err(Error::InvalidShareIndex(SHARES_NUM))
+        ));
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{Error, Result};
+
+/// A fragment of a serialized aggregate, small enough to fit in a gossip message.
+///
+/// Every chunk carries the hash of the whole serialized aggregate, so that chunks of
+/// different aggregates can't be mixed up and the reassembled bytes can be checked.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregateChunk {
+    /// Position of this chunk, starting at `0`
+    pub index: u32,
+    /// Number of chunks the aggregate was split into
+    pub total: u32,
+    /// SHA-256 of the whole serialized aggregate
+    pub content_hash: [u8; 32],
+    pub payload: Vec<u8>,
+}
+
+impl AggregateChunk {
+    /// Split `bytes` into chunks with payloads of at most `max_payload_size` bytes.
+    pub fn split(bytes: &[u8], max_payload_size: usize) -> Vec<Self> {
+        let content_hash: [u8; 32] = Sha256::digest(bytes).into();
+        let mut payloads =
+            bytes.chunks(max_payload_size.max(1)).collect::<Vec<_>>();
+        // Empty content still makes for one, empty, chunk
+        if payloads.is_empty() {
+            payloads.push(&[]);
+        }
+        let total = payloads.len() as u32;
+        payloads
+            .into_iter()
+            .enumerate()
+            .map(|(index, payload)| Self {
+                index: index as u32,
+                total,
+                content_hash,
+                payload: payload.to_vec(),
+            })
+            .collect()
+    }
+
+    /// Reassemble the bytes from all of their chunks, in any order.
+    pub fn reassemble(
+        chunks: impl IntoIterator<Item = Self>,
+    ) -> Result<Vec<u8>> {
+        let mut reassembler = ChunkReassembler::default();
+        for chunk in chunks {
+            if let Some(bytes) = reassembler.add(chunk)? {
+                return Ok(bytes);
+            }
+        }
+        Err(Error::IncompleteAggregate(
+            reassembler.total.unwrap_or_default(),
+            reassembler.len() as u32,
+        ))
+    }
+}
+
+/// Collects the chunks of a single aggregate as they are received, e.g. from gossip.
+#[derive(Clone, Debug, Default)]
+pub struct ChunkReassembler {
+    content_hash: Option<[u8; 32]>,
+    total: Option<u32>,
+    payloads: BTreeMap<u32, Vec<u8>>,
+}
+
+impl ChunkReassembler {
+    /// Number of distinct chunks received so far
+    pub fn len(&self) -> usize {
+        self.payloads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.payloads.is_empty()
+    }
+
+    /// Add a chunk. Returns the reassembled bytes once all chunks have been received.
+    ///
+    /// Chunks that are received more than once are ignored.
+    pub fn add(&mut self, chunk: AggregateChunk) -> Result<Option<Vec<u8>>> {
+        if chunk.index >= chunk.total {
+            return Err(Error::InvalidAggregateChunk(chunk.index, chunk.total));
+        }
+        let content_hash = *self.content_hash.get_or_insert(chunk.content_hash);
+        let total = *self.total.get_or_insert(chunk.total);
+        if chunk.content_hash != content_hash || chunk.total != total {
+            return Err(Error::AggregateChunkMismatch);
+        }
+        self.payloads.entry(chunk.index).or_insert(chunk.payload);
+        if self.payloads.len() < total as usize {
+            return Ok(None);
+        }
+
+        let bytes = self
+            .payloads
+            .values()
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        if <[u8; 32]>::from(Sha256::digest(&bytes)) != content_hash {
+            return Err(Error::AggregateHashMismatch);
+        }
+        Ok(Some(bytes))
+    }
+}
+
+#[cfg(test)]
+mod test_chunk {
+    use rand::{seq::SliceRandom, RngCore};
+
+    use super::*;
+
+    #[test]
+    fn split_and_reassemble() {
+        let rng = &mut ark_std::test_rng();
+        let mut bytes = vec![0u8; 1000];
+        rng.fill_bytes(&mut bytes);
+
+        let mut chunks = AggregateChunk::split(&bytes, 64);
+        assert_eq!(chunks.len(), 16);
+        assert!(chunks.iter().all(|chunk| chunk.payload.len() <= 64));
+
+        // Chunks may arrive in any order, and more than once
+        chunks.shuffle(rng);
+        chunks.push(chunks[0].clone());
+        assert_eq!(AggregateChunk::reassemble(chunks.clone()).unwrap(), bytes);
+
+        // A missing chunk
+        assert!(matches!(
+            AggregateChunk::reassemble(chunks[2..].to_vec()),
+            Err(Error::IncompleteAggregate(16, 15))
+        ));
+
+        // A chunk of another aggregate
+        let other = AggregateChunk::split(&bytes[1..], 64);
+        assert!(matches!(
+            AggregateChunk::reassemble([chunks[0].clone(), other[0].clone()]),
+            Err(Error::AggregateChunkMismatch)
+        ));
+
+        // A tampered chunk
+        chunks[3].payload[0] ^= 1;
+        assert!(matches!(
+            AggregateChunk::reassemble(chunks),
+            Err(Error::AggregateHashMismatch)
+        ));
+
+        let empty = AggregateChunk::split(&[], 64);
+        assert_eq!(
+            AggregateChunk::reassemble(empty).unwrap(),
+            Vec::<u8>::new()
+        );
+    }
+}
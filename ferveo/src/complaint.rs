@@ -0,0 +1,288 @@
+use std::ops::Mul;
+
+use ark_ec::{pairing::Pairing, CurveGroup};
+#[cfg(feature = "dkg")]
+use ark_ff::Field;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+#[cfg(feature = "dkg")]
+use ark_std::UniformRand;
+#[cfg(feature = "dkg")]
+use ferveo_common::Keypair;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+#[cfg(feature = "dkg")]
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    evaluate_commitment, pairings_match, Error, EthereumAddress,
+    PubliclyVerifiableParams, PubliclyVerifiableSS, Result, Validator,
+};
+
+/// An accusation that the share `share_index` a dealer encrypted to the accuser doesn't
+/// match the commitment of the dealer's transcript.
+///
+/// The accuser reveals the decrypted share `Z_i = [1 / dk] Y_i`, with a Chaum-Pedersen proof
+/// that `log_H(ek) == log_Z_i(Y_i)`, so that anyone can check `e(G, Z_i) != e(A_i, H)` without
+/// the decryption key of the accuser. A complaint about a valid share doesn't verify.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Complaint<E: Pairing> {
+    pub accuser: EthereumAddress,
+    pub dealer: EthereumAddress,
+    pub share_index: u32,
+    /// The decrypted share, `Z_i = [f(ω_i)] H` if the dealer was honest
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub decrypted_share: E::G2Affine,
+    /// `c = H(accuser, dealer, i, H, ek, Z_i, Y_i, [k] H, [k] Z_i)`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub challenge: E::ScalarField,
+    /// `z = k + c dk`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub response: E::ScalarField,
+}
+
+impl<E: Pairing> Complaint<E> {
+    /// Accuse `dealer` of dealing a faulty share `share_index` to `accuser`, the holder of
+    /// `keypair`. Fails if the share is valid
+    #[cfg(feature = "dkg")]
+    pub fn new<R: RngCore>(
+        pvss: &PubliclyVerifiableSS<E>,
+        dealer: &EthereumAddress,
+        share_index: u32,
+        accuser: &Validator<E>,
+        keypair: &Keypair<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        if keypair.public_key() != accuser.public_key {
+            return Err(Error::ValidatorPublicKeyMismatch);
+        }
+        let y_i = encrypted_share(pvss, share_index, accuser)?;
+        let dk = keypair.decryption_key;
+        let decrypted_share = y_i
+            .mul(dk.inverse().ok_or(Error::ValidatorPublicKeyMismatch)?)
+            .into_affine();
+        let h = pvss_params.h.into_affine();
+        let k = E::ScalarField::rand(rng);
+        let challenge = challenge::<E>(
+            &accuser.address,
+            dealer,
+            share_index,
+            &[
+                h,
+                accuser.public_key.encryption_key,
+                decrypted_share,
+                y_i,
+                h.mul(k).into_affine(),
+                decrypted_share.mul(k).into_affine(),
+            ],
+        )?;
+        let complaint = Self {
+            accuser: accuser.address.clone(),
+            dealer: dealer.clone(),
+            share_index,
+            decrypted_share,
+            challenge,
+            response: k + challenge * dk,
+        };
+        if !complaint.verify(pvss, accuser, pvss_params) {
+            return Err(Error::InvalidComplaint(accuser.address.clone()));
+        }
+        Ok(complaint)
+    }
+
+    /// Check that `accuser` decrypted the share `share_index` of `pvss` to
+    /// [`Complaint::decrypted_share`], and that it doesn't match the commitment of `pvss`
+    pub fn verify(
+        &self,
+        pvss: &PubliclyVerifiableSS<E>,
+        accuser: &Validator<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> bool {
+        if self.accuser != accuser.address {
+            return false;
+        }
+        let y_i = match encrypted_share(pvss, self.share_index, accuser) {
+            Ok(y_i) => y_i,
+            Err(_) => return false,
+        };
+        let h = pvss_params.h.into_affine();
+        let ek = accuser.public_key.encryption_key;
+        let t_h = h.mul(self.response) - ek.mul(self.challenge);
+        let t_z =
+            self.decrypted_share.mul(self.response) - y_i.mul(self.challenge);
+        let proven = challenge::<E>(
+            &self.accuser,
+            &self.dealer,
+            self.share_index,
+            &[
+                h,
+                ek,
+                self.decrypted_share,
+                y_i,
+                t_h.into_affine(),
+                t_z.into_affine(),
+            ],
+        )
+        .map_or(false, |challenge| challenge == self.challenge);
+        let a_i = evaluate_commitment::<E>(
+            &pvss.coeffs,
+            &pvss.domain.element(self.share_index as usize),
+        );
+        proven
+            && !pairings_match::<E>(
+                pvss_params.g,
+                self.decrypted_share,
+                a_i,
+                pvss_params.h,
+            )
+    }
+}
+
+/// The share `share_index` of `pvss`, which must be held by `accuser`
+fn encrypted_share<E: Pairing>(
+    pvss: &PubliclyVerifiableSS<E>,
+    share_index: u32,
+    accuser: &Validator<E>,
+) -> Result<E::G2Affine> {
+    match pvss.shares.get(share_index as usize) {
+        Some(y_i) if accuser.holds_share(share_index) => Ok(y_i.into_affine()),
+        _ => Err(Error::InvalidShareIndex(share_index)),
+    }
+}
+
+fn challenge<E: Pairing>(
+    accuser: &EthereumAddress,
+    dealer: &EthereumAddress,
+    share_index: u32,
+    points: &[E::G2Affine],
+) -> Result<E::ScalarField> {
+    let mut bytes = Vec::new();
+    for point in points {
+        point.serialize_compressed(&mut bytes)?;
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(V1::COMPLAINT_DOMAIN);
+    for address in [accuser, dealer] {
+        let address = address.to_string();
+        hasher.update((address.len() as u32).to_le_bytes());
+        hasher.update(address.as_bytes());
+    }
+    hasher.update(share_index.to_le_bytes());
+    hasher.update(bytes);
+    Ok(E::ScalarField::from_be_bytes_mod_order(&hasher.finalize()))
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_complaint {
+    use ark_ec::AffineRepr;
+
+    use super::*;
+    use crate::{test_common::*, DkgState, Message, ShareEncryptions};
+
+    #[test]
+    fn test_complaint_excludes_dealer() {
+        let rng = &mut ark_std::test_rng();
+        let (mut dkg, keypairs) = setup_dealt_dkg();
+        let accuser = dkg.validators.values().nth(1).unwrap().clone();
+        let keypair = &keypairs[accuser.share_index as usize];
+        let dealer = dkg.vss.keys().next().unwrap().clone();
+        let pvss = dkg.vss[&dealer].clone();
+
+        // Honest shares can't be complained about
+        assert!(matches!(
+            Complaint::new(
+                &pvss,
+                &dealer,
+                accuser.share_index,
+                &accuser,
+                keypair,
+                &dkg.pvss_params,
+                rng
+            ),
+            Err(Error::InvalidComplaint(_))
+        ));
+
+        let mut bad_pvss = pvss;
+        bad_pvss.shares[accuser.share_index as usize] =
+            ShareEncryptions::new(G2::generator()).unwrap();
+        dkg.vss.insert(dealer.clone(), bad_pvss.clone());
+        let complaint = Complaint::new(
+            &bad_pvss,
+            &dealer,
+            accuser.share_index,
+            &accuser,
+            keypair,
+            &dkg.pvss_params,
+            rng,
+        )
+        .unwrap();
+        assert!(complaint.verify(&bad_pvss, &accuser, &dkg.pvss_params));
+
+        // Only the accuser can send it, and it must prove the decryption
+        let other = dkg.validators.values().nth(2).unwrap().clone();
+        let message = Message::Complaint(complaint.clone());
+        assert!(matches!(
+            dkg.verify_message(&other, &message),
+            Err(Error::InvalidComplaint(_))
+        ));
+        let mut forged = complaint.clone();
+        forged.decrypted_share = G2::generator();
+        assert!(!forged.verify(&bad_pvss, &accuser, &dkg.pvss_params));
+
+        dkg.verify_message(&accuser, &message).unwrap();
+        dkg.apply_message(&accuser, &message).unwrap();
+        assert!(!dkg.vss.contains_key(&dealer));
+        assert!(dkg.excluded_dealers.contains(&dealer));
+        // The remaining transcripts still meet the security threshold
+        assert!(matches!(dkg.state, DkgState::Dealt));
+
+        // The excluded dealer can't deal again
+        assert!(matches!(
+            dkg.verify_message(
+                dkg.validators.get(&dealer).unwrap(),
+                &Message::Deal(bad_pvss)
+            ),
+            Err(Error::ExcludedDealer(_))
+        ));
+        assert!(matches!(
+            dkg.verify_message(&accuser, &message),
+            Err(Error::UnknownDealer(_))
+        ));
+
+        // Until too many dealers are excluded
+        let other_dealer = dkg.vss.keys().next().unwrap().clone();
+        dkg.exclude_dealer(&other_dealer);
+        assert!(matches!(
+            dkg.state,
+            DkgState::Sharing {
+                accumulated_shares,
+                ..
+            } if accumulated_shares == SECURITY_THRESHOLD - 1
+        ));
+
+        // The count follows the transcripts held, and the block is kept
+        dkg.state = DkgState::Sharing {
+            accumulated_shares: SECURITY_THRESHOLD + 1,
+            block: 7,
+        };
+        let other_dealer = dkg.vss.keys().next().unwrap().clone();
+        dkg.exclude_dealer(&other_dealer);
+        assert!(matches!(
+            dkg.state,
+            DkgState::Sharing {
+                accumulated_shares,
+                block: 7,
+            } if accumulated_shares == SECURITY_THRESHOLD - 2
+        ));
+
+        // A finished ritual isn't reopened
+        dkg.state = DkgState::Invalid;
+        let other_dealer = dkg.vss.keys().next().unwrap().clone();
+        dkg.exclude_dealer(&other_dealer);
+        assert!(matches!(dkg.state, DkgState::Invalid));
+    }
+}
@@ -0,0 +1,288 @@
+//! Checks of the algebraic invariants the protocol relies on, on live artifacts.
+//!
+//! Every check here is implied by the verification of the artifacts it's given, so they
+//! are never needed for security. They are meant as runtime assertions, e.g. before an
+//! aggregate is published, and as the properties of randomized tests.
+
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::Zero;
+use ferveo_tdec::PrivateKeyShare;
+
+#[cfg(feature = "refresh")]
+use crate::ShareUpdateTranscript;
+use crate::{
+    pairings_match, AggregatedPvss, PubliclyVerifiableParams,
+    PubliclyVerifiableSS, ShareEncryptions,
+};
+
+/// Whether `aggregate` is the transcript of the sum of the polynomials dealt in
+/// `transcripts`, i.e. its commitments, shares and proof of knowledge are the element-wise
+/// sums of theirs
+pub fn aggregate_is_linear<E: Pairing>(
+    transcripts: &[PubliclyVerifiableSS<E>],
+    aggregate: &AggregatedPvss<E>,
+) -> bool {
+    if transcripts.is_empty() {
+        return false;
+    }
+    let same_shape = transcripts.iter().all(|pvss| {
+        pvss.coeffs.len() == aggregate.coeffs.len()
+            && pvss.shares.len() == aggregate.shares.len()
+    });
+    if !same_shape {
+        return false;
+    }
+
+    let mut coeffs = vec![E::G1::zero(); aggregate.coeffs.len()];
+    let mut shares = vec![E::G2::zero(); aggregate.shares.len()];
+    let mut sigma = E::G2::zero();
+    for pvss in transcripts {
+        for (sum, coeff) in coeffs.iter_mut().zip(&pvss.coeffs) {
+            *sum += coeff;
+        }
+        for (sum, share) in shares.iter_mut().zip(&pvss.shares) {
+            *sum += share.as_affine();
+        }
+        sigma += pvss.sigma;
+    }
+
+    E::G1::normalize_batch(&coeffs) == aggregate.coeffs
+        && E::G2::normalize_batch(&shares)
+            .iter()
+            .eq(aggregate.shares.iter().map(ShareEncryptions::as_affine))
+        && sigma.into_affine() == aggregate.sigma
+}
+
+/// Whether applying the refresh `updates` keeps the shared secret, i.e. every update
+/// polynomial of degree `threshold - 1` vanishes at 0 and every update is the evaluation of
+/// its polynomial at the matching point of `domain_points`, encrypted to the matching key
+/// of `encryption_keys` and committed to with `pvss_params`
+#[cfg(feature = "refresh")]
+pub fn refresh_preserves_secret<E: Pairing>(
+    updates: &[ShareUpdateTranscript<E>],
+    domain_points: &[E::ScalarField],
+    encryption_keys: &[E::G2Affine],
+    threshold: usize,
+    pvss_params: &PubliclyVerifiableParams<E>,
+) -> bool {
+    encryption_keys.len() == domain_points.len()
+        && updates.iter().all(|transcript| {
+            domain_points.iter().zip(encryption_keys).enumerate().all(
+                |(share_index, (x_i, ek_i))| {
+                    transcript.verify_update(
+                        share_index as u32,
+                        x_i,
+                        ek_i,
+                        threshold,
+                        domain_points.len(),
+                        pvss_params,
+                    )
+                },
+            )
+        })
+}
+
+/// Whether `recovered`, e.g. a share recovered from the shares of the other validators,
+/// is the share `share_index` committed to by `aggregate`, i.e. e(A_i, H) == e(G, Z_i)
+pub fn recovery_matches_commitment<E: Pairing>(
+    aggregate: &AggregatedPvss<E>,
+    share_index: u32,
+    recovered: &PrivateKeyShare<E>,
+    pvss_params: &PubliclyVerifiableParams<E>,
+) -> bool {
+    let public_key_share = match aggregate.public_key_share(share_index) {
+        Ok(public_key_share) => public_key_share.public_key_share,
+        Err(_) => return false,
+    };
+    pairings_match::<E>(
+        public_key_share,
+        pvss_params.h,
+        pvss_params.g,
+        recovered.private_key_share,
+    )
+}
+
+/// Whether every share of `shares`, in share index order, matches the commitment of
+/// `aggregate`. See [`recovery_matches_commitment`]
+pub fn shares_match_commitment<E: Pairing>(
+    aggregate: &AggregatedPvss<E>,
+    shares: &[PrivateKeyShare<E>],
+    pvss_params: &PubliclyVerifiableParams<E>,
+) -> bool {
+    shares.len() == aggregate.shares.len()
+        && shares.iter().enumerate().all(|(share_index, share)| {
+            recovery_matches_commitment(
+                aggregate,
+                share_index as u32,
+                share,
+                pvss_params,
+            )
+        })
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_consistency {
+    use ark_ec::AffineRepr;
+
+    use super::*;
+    use crate::{pvss::aggregate, test_common::*};
+
+    #[test]
+    fn test_aggregate_is_linear() {
+        let (dkg, _) = setup_dealt_dkg();
+        let transcripts = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = aggregate(&transcripts).unwrap();
+        assert!(aggregate_is_linear(&transcripts, &aggregate));
+
+        // Missing a transcript
+        assert!(!aggregate_is_linear(&transcripts[1..], &aggregate));
+        assert!(!aggregate_is_linear(&[], &aggregate));
+
+        // A tampered share
+        let mut bad_aggregate = aggregate.clone();
+        bad_aggregate.shares[1] =
+            ShareEncryptions::new(G2::generator()).unwrap();
+        assert!(!aggregate_is_linear(&transcripts, &bad_aggregate));
+
+        // A tampered proof of knowledge
+        let mut bad_aggregate = aggregate;
+        bad_aggregate.sigma = G2::generator();
+        assert!(!aggregate_is_linear(&transcripts, &bad_aggregate));
+    }
+
+    #[test]
+    fn test_decrypted_shares_match_commitment() {
+        let (dkg, validator_keypairs) = setup_dealt_dkg();
+        let transcripts = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = aggregate(&transcripts).unwrap();
+        let shares = validator_keypairs
+            .iter()
+            .enumerate()
+            .map(|(share_index, keypair)| {
+                aggregate
+                    .decrypt_private_key_share(
+                        &keypair.decryption_key,
+                        share_index,
+                    )
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let params = &dkg.pvss_params;
+        assert!(shares_match_commitment(&aggregate, &shares, params));
+
+        // The share of another validator
+        assert!(!recovery_matches_commitment(
+            &aggregate, 0, &shares[1], params
+        ));
+        assert!(!recovery_matches_commitment(
+            &aggregate, SHARES_NUM, &shares[0], params
+        ));
+        assert!(!shares_match_commitment(&aggregate, &shares[1..], params));
+    }
+
+    #[cfg(feature = "refresh")]
+    #[test]
+    fn test_refresh_and_recovery() {
+        use ark_poly::EvaluationDomain;
+
+        use crate::refresh::*;
+
+        let rng = &mut ark_std::test_rng();
+        let (dkg, validator_keypairs) = setup_dealt_dkg();
+        let transcripts = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = aggregate(&transcripts).unwrap();
+        let domain_points = dkg.domain.elements().collect::<Vec<_>>();
+        let encryption_keys = dkg
+            .share_owners()
+            .iter()
+            .map(|validator| validator.public_key.encryption_key)
+            .collect::<Vec<_>>();
+        let threshold = SECURITY_THRESHOLD as usize;
+
+        let mut updates = (0..SHARES_NUM)
+            .map(|_| {
+                ShareUpdateTranscript::<E>::new_refresh(
+                    &domain_points,
+                    &encryption_keys,
+                    threshold,
+                    &dkg.pvss_params,
+                    rng,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert!(refresh_preserves_secret(
+            &updates,
+            &domain_points,
+            &encryption_keys,
+            threshold,
+            &dkg.pvss_params
+        ));
+        assert!(!refresh_preserves_secret(
+            &updates,
+            &domain_points,
+            &encryption_keys,
+            threshold + 1,
+            &dkg.pvss_params
+        ));
+
+        // An update that doesn't match its polynomial
+        updates[1].updates[2] = G2::generator();
+        assert!(!refresh_preserves_secret(
+            &updates,
+            &domain_points,
+            &encryption_keys,
+            threshold,
+            &dkg.pvss_params
+        ));
+
+        // The last share is lost, and recovered from the others
+        let x_r = domain_points[SHARES_NUM as usize - 1];
+        let remaining_points = &domain_points[..threshold];
+        let recovery_updates = (0..SHARES_NUM)
+            .map(|_| {
+                prepare_share_updates_for_recovery::<E>(
+                    remaining_points,
+                    &dkg.pvss_params.h.into_affine(),
+                    &x_r,
+                    threshold,
+                    rng,
+                )
+            })
+            .collect::<Vec<_>>();
+        let updated_shares = validator_keypairs[..threshold]
+            .iter()
+            .enumerate()
+            .map(|(share_index, keypair)| {
+                let share = aggregate
+                    .decrypt_private_key_share(
+                        &keypair.decryption_key,
+                        share_index,
+                    )
+                    .unwrap();
+                let updates = recovery_updates
+                    .iter()
+                    .map(|updates| updates[share_index])
+                    .collect::<Vec<_>>();
+                apply_updates_to_private_share::<E>(&share, &updates)
+            })
+            .collect::<Vec<_>>();
+        let recovered = recover_share_from_updated_private_shares::<E>(
+            &x_r,
+            remaining_points,
+            &updated_shares,
+        );
+        assert!(recovery_matches_commitment(
+            &aggregate,
+            SHARES_NUM - 1,
+            &recovered,
+            &dkg.pvss_params
+        ));
+        assert!(!recovery_matches_commitment(
+            &aggregate,
+            0,
+            &recovered,
+            &dkg.pvss_params
+        ));
+    }
+}
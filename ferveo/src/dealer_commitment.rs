@@ -0,0 +1,473 @@
+use std::collections::BTreeSet;
+
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::PrimeField;
+use ark_poly::EvaluationDomain;
+use ark_serialize::CanonicalSerialize;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+#[cfg(feature = "dkg")]
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "dkg")]
+use crate::SigningKeypair;
+use crate::{
+    coeffs_hash, fingerprint::Fingerprint, pairings_match, pok_base,
+    AggregatedPvss, AggregationDiagnosis, Error, EthereumAddress,
+    PubliclyVerifiableDkg, PubliclyVerifiableSS, Result, SigningPublicKey,
+};
+
+/// A succinct commitment to the transcript of a dealer, published by an aggregator service
+/// along with the aggregate.
+///
+/// Validators can verify an aggregate against the commitments of its dealers, and their own
+/// share in it, without receiving every transcript. The dealer signs its commitment, see
+/// [`DealerCommitment::sign`], so that the aggregator can't substitute it.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct DealerCommitment<E: Pairing> {
+    pub dealer: EthereumAddress,
+    /// Fingerprint of the full transcript, to audit the aggregator against the raw
+    /// transcripts, see [`PubliclyVerifiableSS::fingerprint`]
+    pub transcript_fingerprint: Fingerprint,
+    /// `F_0`, the commitment to the secret of the dealer
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub constant_commitment: E::G1Affine,
+    /// The proof of knowledge of the secret of the dealer
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub sigma: E::G2Affine,
+    /// Hash of the commitments of the dealer, which the proof of knowledge is bound to
+    pub coeffs_hash: [u8; 32],
+    /// Hash of the extensions of the transcript, which the proof of knowledge is bound to
+    pub extensions_hash: [u8; 32],
+    /// The signature of the dealer over the commitment, or `None` for commitments made from
+    /// a transcript by someone else, e.g. by a DKG pruning the transcripts it verified
+    signature: Option<DealerSignature<E>>,
+}
+
+/// A Schnorr signature of the dealer over a [`DealerCommitment`], see
+/// [`SigningKeypair::sign`]
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+struct DealerSignature<E: Pairing> {
+    /// `R = [k] H`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    commitment: E::G2Affine,
+    /// `z = k + c s`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    response: E::ScalarField,
+}
+
+impl<E: Pairing> DealerCommitment<E> {
+    /// The unsigned commitment to `transcript`, e.g. to audit a commitment against it
+    pub fn from_transcript(
+        dealer: EthereumAddress,
+        transcript: &PubliclyVerifiableSS<E>,
+    ) -> Result<Self> {
+        Ok(Self {
+            dealer,
+            transcript_fingerprint: transcript.fingerprint()?,
+            constant_commitment: *transcript
+                .coeffs
+                .first()
+                .ok_or(Error::InvalidPvssTranscript)?,
+            sigma: transcript.sigma,
+            coeffs_hash: coeffs_hash::<E>(&transcript.coeffs)?,
+            extensions_hash: transcript.extensions.hash(),
+            signature: None,
+        })
+    }
+
+    /// The commitment to `transcript`, signed by its dealer with their `signing_key`
+    #[cfg(feature = "dkg")]
+    pub fn sign<R: RngCore>(
+        dealer: EthereumAddress,
+        transcript: &PubliclyVerifiableSS<E>,
+        signing_key: &SigningKeypair<E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let mut signed = Self::from_transcript(dealer, transcript)?;
+        let public_key = signing_key.public_key();
+        let (commitment, response) = signing_key.sign(rng, |commitment| {
+            signed.challenge(commitment, &public_key)
+        })?;
+        signed.signature = Some(DealerSignature {
+            commitment,
+            response,
+        });
+        Ok(signed)
+    }
+
+    /// Check that the commitment was signed by the holder of `signing_key`
+    pub fn verify_signature(
+        &self,
+        signing_key: &SigningPublicKey<E>,
+    ) -> Result<bool> {
+        let signature = match &self.signature {
+            Some(signature) => signature,
+            None => return Ok(false),
+        };
+        let c = self.challenge(&signature.commitment, signing_key)?;
+        Ok(signing_key.verify(&signature.commitment, &signature.response, &c))
+    }
+
+    /// Check that the commitment matches the given transcript
+    pub fn matches(
+        &self,
+        transcript: &PubliclyVerifiableSS<E>,
+    ) -> Result<bool> {
+        Ok(self.transcript_fingerprint == transcript.fingerprint()?
+            && transcript.coeffs.first() == Some(&self.constant_commitment)
+            && self.sigma == transcript.sigma
+            && self.coeffs_hash == coeffs_hash::<E>(&transcript.coeffs)?
+            && self.extensions_hash == transcript.extensions.hash())
+    }
+
+    fn challenge(
+        &self,
+        commitment: &E::G2Affine,
+        signing_key: &SigningPublicKey<E>,
+    ) -> Result<E::ScalarField> {
+        let mut points = Vec::new();
+        commitment.serialize_compressed(&mut points)?;
+        signing_key.key.serialize_compressed(&mut points)?;
+        self.constant_commitment.serialize_compressed(&mut points)?;
+        self.sigma.serialize_compressed(&mut points)?;
+        let mut hasher = Sha256::new();
+        hasher.update(V1::DEALER_COMMITMENT_SIGNATURE_DOMAIN);
+        hasher.update(points);
+        hasher.update(self.transcript_fingerprint);
+        hasher.update(self.coeffs_hash);
+        hasher.update(self.extensions_hash);
+        hasher.update(self.dealer.to_string().as_bytes());
+        Ok(E::ScalarField::from_be_bytes_mod_order(&hasher.finalize()))
+    }
+}
+
+impl<E: Pairing> AggregatedPvss<E> {
+    /// Verify the aggregate against the commitments of its dealers instead of their
+    /// transcripts, and check the shares of this validator.
+    ///
+    /// Unlike [`PubliclyVerifiableSS::verify_aggregation`], this doesn't check that the
+    /// shares of other validators are the sums of the dealt shares, only that they are
+    /// consistent with the aggregate commitment.
+    ///
+    /// Every commitment must be signed by its dealer, see [`DealerCommitment::sign`], or
+    /// [`Error::InvalidTranscriptSignature`] is returned.
+    pub fn verify_with_commitments(
+        &self,
+        dkg: &PubliclyVerifiableDkg<E>,
+        commitments: &[DealerCommitment<E>],
+    ) -> Result<bool> {
+        if self.validator_set_hash != dkg.validator_set_hash() {
+            return Err(Error::ValidatorSetMismatch);
+        }
+        self.domain.check(&dkg.domain)?;
+
+        let minimum = dkg.dkg_params.security_threshold();
+        if (commitments.len() as u32) < minimum {
+            return Err(Error::InsufficientTranscriptsForAggregate(
+                minimum,
+                commitments.len() as u32,
+            ));
+        }
+        let mut dealers = BTreeSet::new();
+        for commitment in commitments {
+            let dealer =
+                dkg.validators.get(&commitment.dealer).ok_or_else(|| {
+                    Error::UnknownDealer(commitment.dealer.clone())
+                })?;
+            if !dealers.insert(&commitment.dealer) {
+                return Err(Error::DuplicateDealer(commitment.dealer.clone()));
+            }
+            let signed = match &dealer.signing_key {
+                Some(signing_key) => {
+                    commitment.verify_signature(signing_key)?
+                }
+                None => false,
+            };
+            if !signed {
+                return Err(Error::InvalidTranscriptSignature(
+                    commitment.dealer.clone(),
+                ));
+            }
+        }
+
+        // Each dealer knows their secret: e(F_0, H(validator set, ritual, dealer, coeffs))
+        // == e(G, sigma)
+        let g = dkg.pvss_params.g;
+        for commitment in commitments {
+            let pok_base = pok_base::<E>(
+                &self.validator_set_hash,
+                self.tau,
+                &commitment.dealer,
+                &commitment.coeffs_hash,
+                &commitment.extensions_hash,
+            )?;
+            if !pairings_match::<E>(
+                commitment.constant_commitment,
+                pok_base,
+                g,
+                commitment.sigma,
+            ) {
+                return Err(Error::InvalidPvssTranscript);
+            }
+        }
+
+        // The aggregate is the sum of the committed transcripts
+        let constant_commitment = commitments
+            .iter()
+            .map(|commitment| commitment.constant_commitment.into_group())
+            .sum::<E::G1>();
+        let sigma = commitments
+            .iter()
+            .map(|commitment| commitment.sigma.into_group())
+            .sum::<E::G2>();
+        if self.coeffs.first() != Some(&constant_commitment.into_affine())
+            || sigma.into_affine() != self.sigma
+        {
+            return Err(Error::InvalidTranscriptAggregate);
+        }
+
+        Ok(dkg
+            .me
+            .share_indices()?
+            .all(|share_index| self.verify_share(dkg, share_index as usize)))
+    }
+
+    /// Same as [`PubliclyVerifiableSS::diagnose_aggregation`], and if the aggregate isn't
+    /// the sum of the transcripts of `dkg`, find the dealers whose transcript the aggregator
+    /// replaced by auditing the `commitments` it published along with the aggregate.
+    ///
+    /// The aggregate only holds the sums of the transcripts, so which of them was replaced
+    /// can't be told without the commitments. They are only audited if they add up to the
+    /// aggregate.
+    pub fn diagnose_with_commitments(
+        &self,
+        dkg: &PubliclyVerifiableDkg<E>,
+        commitments: &[DealerCommitment<E>],
+    ) -> Result<AggregationDiagnosis> {
+        let mut diagnosis = self.diagnose_aggregation(dkg);
+        if !diagnosis.aggregate_mismatch {
+            return Ok(diagnosis);
+        }
+        let constant_commitment = commitments
+            .iter()
+            .map(|commitment| commitment.constant_commitment.into_group())
+            .sum::<E::G1>();
+        let sigma = commitments
+            .iter()
+            .map(|commitment| commitment.sigma.into_group())
+            .sum::<E::G2>();
+        if self.coeffs.first() != Some(&constant_commitment.into_affine())
+            || sigma.into_affine() != self.sigma
+        {
+            return Ok(diagnosis);
+        }
+        for commitment in commitments {
+            if let Some(pvss) = dkg.vss.get(&commitment.dealer) {
+                if !commitment.matches(pvss)? {
+                    diagnosis
+                        .substituted_dealers
+                        .push(commitment.dealer.clone());
+                }
+            }
+        }
+        Ok(diagnosis)
+    }
+
+    /// Check that the share `share_index` is consistent with the aggregate commitment,
+    /// i.e. e(G, Y_i) == e(A_i, ek_i) where ek_i is the key of the validator holding it
+    pub fn verify_share(
+        &self,
+        dkg: &PubliclyVerifiableDkg<E>,
+        share_index: usize,
+    ) -> bool {
+        let validator = match dkg.share_owners().get(share_index) {
+            Some(validator) => *validator,
+            None => return false,
+        };
+        let y_i = match self.shares.get(share_index) {
+            Some(y_i) => y_i.into_affine(),
+            None => return false,
+        };
+        let a_i = self.commitment_at(&dkg.domain.element(share_index));
+        pairings_match::<E>(
+            dkg.pvss_params.g,
+            y_i,
+            a_i,
+            dkg.prepared_keys.get_or_prepare(validator),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_dealer_commitment {
+    use ark_ec::AffineRepr;
+    use ark_ff::UniformRand;
+
+    use super::*;
+    use crate::{test_common::*, ShareEncryptions};
+
+    fn sign(
+        dkg: &PubliclyVerifiableDkg<E>,
+        dealer: &EthereumAddress,
+        pvss: &PubliclyVerifiableSS<E>,
+    ) -> DealerCommitment<E> {
+        let signing_key =
+            gen_signing_keypair(dkg.validators[dealer].share_index as usize);
+        DealerCommitment::sign(
+            dealer.clone(),
+            pvss,
+            &signing_key,
+            &mut ark_std::test_rng(),
+        )
+        .unwrap()
+    }
+
+    fn setup() -> (PubliclyVerifiableDkg<E>, Vec<DealerCommitment<E>>) {
+        let (dkg, _) = setup_dealt_dkg();
+        let commitments = dkg
+            .vss
+            .iter()
+            .map(|(dealer, pvss)| sign(&dkg, dealer, pvss))
+            .collect();
+        (dkg, commitments)
+    }
+
+    #[test]
+    fn verify_aggregate_with_commitments() {
+        let (dkg, commitments) = setup();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = crate::pvss::aggregate(&pvss_list).unwrap();
+
+        // A validator without the transcripts
+        let mut light_dkg = dkg.clone();
+        light_dkg.vss.clear();
+        assert!(aggregate
+            .verify_with_commitments(&light_dkg, &commitments)
+            .unwrap());
+
+        // Commitments can be audited against the transcripts
+        for commitment in &commitments {
+            assert!(commitment.matches(&dkg.vss[&commitment.dealer]).unwrap());
+        }
+        assert!(!commitments[0]
+            .matches(&dkg.vss[&commitments[1].dealer])
+            .unwrap());
+
+        // A tampered share of this validator
+        let mut bad_share = aggregate.clone();
+        bad_share.shares[dkg.me.share_index as usize] =
+            ShareEncryptions::new(G2::generator()).unwrap();
+        assert!(!bad_share
+            .verify_with_commitments(&light_dkg, &commitments)
+            .unwrap());
+        assert!(bad_share.verify_share(&dkg, 1));
+
+        // An aggregate that doesn't match the commitments
+        assert!(matches!(
+            aggregate.verify_with_commitments(&light_dkg, &commitments[1..]),
+            Err(Error::InvalidTranscriptAggregate)
+        ));
+        let mut duplicate = commitments.clone();
+        duplicate[1] = duplicate[0].clone();
+        assert!(matches!(
+            aggregate.verify_with_commitments(&light_dkg, &duplicate),
+            Err(Error::DuplicateDealer(_))
+        ));
+
+        // A commitment substituted by the aggregator, or not signed by its dealer
+        let dealer = commitments[0].dealer.clone();
+        let mut substituted = commitments.clone();
+        substituted[0].sigma = G2::generator();
+        assert!(matches!(
+            aggregate.verify_with_commitments(&light_dkg, &substituted),
+            Err(Error::InvalidTranscriptSignature(d)) if d == dealer
+        ));
+        let mut unsigned = commitments.clone();
+        unsigned[0] = DealerCommitment::from_transcript(
+            dealer.clone(),
+            &dkg.vss[&dealer],
+        )
+        .unwrap();
+        assert!(matches!(
+            aggregate.verify_with_commitments(&light_dkg, &unsigned),
+            Err(Error::InvalidTranscriptSignature(d)) if d == dealer
+        ));
+        let mut other_signer = commitments.clone();
+        other_signer[0] = sign(&dkg, &commitments[1].dealer, &dkg.vss[&dealer]);
+        other_signer[0].dealer = dealer.clone();
+        assert!(matches!(
+            aggregate.verify_with_commitments(&light_dkg, &other_signer),
+            Err(Error::InvalidTranscriptSignature(d)) if d == dealer
+        ));
+
+        // A commitment without a valid proof of knowledge, even signed by its dealer
+        let mut forged_pvss = dkg.vss[&dealer].clone();
+        forged_pvss.sigma = G2::generator();
+        let mut forged = commitments;
+        forged[0] = sign(&dkg, &dealer, &forged_pvss);
+        assert!(matches!(
+            aggregate.verify_with_commitments(&light_dkg, &forged),
+            Err(Error::InvalidPvssTranscript)
+        ));
+    }
+
+    #[test]
+    fn diagnose_substituted_transcript() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, commitments) = setup();
+
+        // The aggregator replaces the transcript of a dealer with another valid one
+        let dealer = commitments[1].dealer.clone();
+        let (dealer_dkg, _) =
+            setup_dkg(dkg.validators[&dealer].share_index as usize);
+        let substitute = PubliclyVerifiableSS::new(
+            &ScalarField::rand(rng),
+            &dealer_dkg,
+            rng,
+        )
+        .unwrap();
+        assert!(substitute.verify_full(&dkg));
+        let mut vss = dkg.vss.clone();
+        vss.insert(dealer.clone(), substitute.clone());
+        let pvss_list = vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = crate::pvss::aggregate(&pvss_list).unwrap();
+
+        // Every transcript is valid, so the aggregate alone can't tell who is to blame
+        let diagnosis = aggregate.diagnose_aggregation(&dkg);
+        assert!(diagnosis.offending_dealers.is_empty());
+        assert!(diagnosis.aggregate_mismatch);
+        assert!(diagnosis.substituted_dealers.is_empty());
+
+        // The commitments published along with it name the replaced transcript
+        let mut published = commitments.clone();
+        published[1] = sign(&dkg, &dealer, &substitute);
+        let diagnosis = aggregate
+            .diagnose_with_commitments(&dkg, &published)
+            .unwrap();
+        assert!(diagnosis.aggregate_mismatch);
+        assert_eq!(diagnosis.substituted_dealers, vec![dealer]);
+        assert!(!diagnosis.is_clean());
+
+        // Unless they don't add up to the aggregate
+        let diagnosis = aggregate
+            .diagnose_with_commitments(&dkg, &commitments)
+            .unwrap();
+        assert!(diagnosis.aggregate_mismatch);
+        assert!(diagnosis.substituted_dealers.is_empty());
+
+        // Nothing is reported for a valid aggregate
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = crate::pvss::aggregate(&pvss_list).unwrap();
+        assert!(aggregate
+            .diagnose_with_commitments(&dkg, &published)
+            .unwrap()
+            .is_clean());
+    }
+}
@@ -0,0 +1,622 @@
+//! The secret-handling side of the DKG: dealing PVSS transcripts and decrypting private key
+//! shares. Everything else in the PVSS and DKG modules only handles public data, and is
+//! available under the `verify` feature without this module.
+
+use std::{collections::BTreeSet, ops::Mul};
+
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::{Field, Zero};
+use ark_poly::{
+    polynomial::univariate::DensePolynomial, DenseUVPolynomial,
+    EvaluationDomain, GeneralEvaluationDomain,
+};
+use ark_std::UniformRand;
+use ferveo_common::memlock;
+use ferveo_tdec::{
+    prepare_combine_simple,
+    protocol::{ProtocolVersion, V1},
+    CiphertextHeader, DecryptionSharePrecomputed, DecryptionShareSimple,
+    PrivateKeyShare,
+};
+use measure_time::print_time;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+#[cfg(not(ferveo_mock_crypto))]
+use subproductdomain::fast_multiexp;
+use zeroize::{self, Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::{
+    aggregate_hash, assert_no_share_duplicates, coeffs_hash, pok_base,
+    share_owners, validator_set_hash, Aggregate, AggregatedPvss, DkgState,
+    DomainDescriptor, Error, EthereumAddress, Extensions, Message,
+    PubliclyVerifiableDkg, PubliclyVerifiableParams, PubliclyVerifiableSS,
+    RedealOrigin, Result, ShareEncryptions, ShareProof, Validator,
+    ValidatorSetHash, DEALER_EXTENSION_TYPE, REDEAL_EXTENSION_TYPE,
+    SHARE_PROOFS_EXTENSION_TYPE,
+};
+#[cfg(feature = "refresh")]
+use crate::{apply_updates_to_private_share, PendingUpdates};
+
+/// Secret polynomial used in the PVSS protocol
+/// We wrap this in a struct so that we can zeroize it after use
+pub struct SecretPolynomial<E: Pairing>(pub DensePolynomial<E::ScalarField>);
+
+impl<E: Pairing> SecretPolynomial<E> {
+    pub fn new(
+        s: &E::ScalarField,
+        degree: usize,
+        rng: &mut impl RngCore,
+    ) -> Self {
+        // Our random polynomial, \phi(x) = s + \sum_{i=1}^{t-1} a_i x^i
+        let mut phi = DensePolynomial::<E::ScalarField>::rand(degree, rng);
+        // The coefficients are never resized, so their buffer can stay locked until drop
+        memlock::lock(phi.coeffs.as_slice());
+        phi.coeffs[0] = *s; // setting the first coefficient to secret value
+        Self(phi)
+    }
+
+    /// The evaluations of the polynomial over `domain`, i.e. the plaintext shares.
+    ///
+    /// Unlike [`DensePolynomial::evaluate_over_domain_by_ref`], the FFT runs in a buffer that
+    /// is allocated once at the size of the domain, so that no copy of the coefficients or
+    /// the shares is left behind by a reallocation.
+    pub(crate) fn evaluate_over_domain(
+        &self,
+        domain: &GeneralEvaluationDomain<E::ScalarField>,
+    ) -> SecretEvaluations<E> {
+        debug_assert!(self.0.coeffs.len() <= domain.size());
+        let mut evals = SecretEvaluations::new(domain.size());
+        evals.0[..self.0.coeffs.len()].copy_from_slice(&self.0.coeffs);
+        domain.fft_in_place(&mut evals.0);
+        evals
+    }
+}
+
+impl<E: Pairing> Zeroize for SecretPolynomial<E> {
+    fn zeroize(&mut self) {
+        self.0.coeffs.iter_mut().for_each(|c| c.zeroize());
+    }
+}
+
+// `ZeroizeOnDrop` derivation fails because of missing trait bounds, so we manually introduce
+// required traits
+
+impl<E: Pairing> Drop for SecretPolynomial<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+        memlock::unlock(self.0.coeffs.as_slice());
+    }
+}
+
+impl<E: Pairing> ZeroizeOnDrop for SecretPolynomial<E> {}
+
+/// Evaluations of a [`SecretPolynomial`], one plaintext share per share index. Zeroized on
+/// drop like the polynomial itself
+pub(crate) struct SecretEvaluations<E: Pairing>(Vec<E::ScalarField>);
+
+impl<E: Pairing> SecretEvaluations<E> {
+    fn new(size: usize) -> Self {
+        let evals = vec![E::ScalarField::zero(); size];
+        // Never resized either, see `SecretPolynomial::new`
+        memlock::lock(evals.as_slice());
+        Self(evals)
+    }
+
+    pub(crate) fn get(&self, share_index: usize) -> &E::ScalarField {
+        &self.0[share_index]
+    }
+}
+
+impl<E: Pairing> Zeroize for SecretEvaluations<E> {
+    fn zeroize(&mut self) {
+        self.0.iter_mut().for_each(|eval| eval.zeroize());
+    }
+}
+
+impl<E: Pairing> Drop for SecretEvaluations<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+        memlock::unlock(self.0.as_slice());
+    }
+}
+
+impl<E: Pairing> ZeroizeOnDrop for SecretEvaluations<E> {}
+
+/// Everything dealing a transcript needs to know about the DKG it is dealt in, to deal
+/// without a [`PubliclyVerifiableDkg`], e.g. for a trusted dealer or a test harness
+pub struct DealingContext<'a, E: Pairing> {
+    share_owners: Vec<&'a Validator<E>>,
+    validator_set_hash: ValidatorSetHash,
+    domain: GeneralEvaluationDomain<E::ScalarField>,
+    pvss_params: &'a PubliclyVerifiableParams<E>,
+    security_threshold: u32,
+    tau: u32,
+    dealer: &'a EthereumAddress,
+    share_proofs: bool,
+}
+
+impl<'a, E: Pairing> DealingContext<'a, E> {
+    /// `validators`: the validators to deal to, with their share indices
+    /// `domain`: the evaluation domain, with a point for every share
+    /// `security_threshold`: the number of shares needed to reconstruct the secret
+    /// `tau`: the ritual the transcript is dealt in
+    /// `dealer`: the address the transcript is dealt by, which needn't be a validator
+    pub fn new(
+        validators: &'a [Validator<E>],
+        domain: GeneralEvaluationDomain<E::ScalarField>,
+        pvss_params: &'a PubliclyVerifiableParams<E>,
+        security_threshold: u32,
+        tau: u32,
+        dealer: &'a EthereumAddress,
+    ) -> Result<Self> {
+        assert_no_share_duplicates(validators)?;
+        let share_owners = share_owners(validators);
+        let shares_num = share_owners.len() as u32;
+        if security_threshold == 0
+            || security_threshold > shares_num
+            || domain.size() < shares_num as usize
+        {
+            return Err(Error::InvalidDkgParameters(
+                shares_num,
+                security_threshold,
+            ));
+        }
+        // With no duplicates, this means the share indices are exactly 0..shares_num
+        if let Some(share_index) = validators
+            .iter()
+            .flat_map(Validator::share_indices)
+            .find(|&share_index| share_index >= shares_num)
+        {
+            return Err(Error::InvalidShareIndex(share_index));
+        }
+        Ok(Self {
+            share_owners,
+            validator_set_hash: pvss_params
+                .bind(validator_set_hash(validators)),
+            domain,
+            pvss_params,
+            security_threshold,
+            tau,
+            dealer,
+            share_proofs: false,
+        })
+    }
+
+    /// Attach a [`ShareProof`] to every share of the transcripts dealt in this context, so
+    /// that faulty shares can be attributed to the dealer without their holder
+    pub fn with_share_proofs(mut self) -> Self {
+        self.share_proofs = true;
+        self
+    }
+
+    /// The context of `dkg`, dealing as `dkg.me`
+    pub fn from_dkg(dkg: &'a PubliclyVerifiableDkg<E>) -> Self {
+        Self {
+            share_owners: dkg.share_owners(),
+            validator_set_hash: dkg.validator_set_hash(),
+            domain: dkg.domain,
+            pvss_params: &dkg.pvss_params,
+            security_threshold: dkg.dkg_params.security_threshold(),
+            tau: dkg.dkg_params.tau(),
+            dealer: &dkg.me.address,
+            share_proofs: false,
+        }
+    }
+}
+
+impl<E: Pairing, T> PubliclyVerifiableSS<E, T> {
+    /// Create a new PVSS instance
+    /// `s`: the secret constant coefficient to share
+    /// `dkg`: the current DKG session
+    /// `rng` a cryptographic random number generator
+    pub fn new<R: RngCore>(
+        s: &E::ScalarField,
+        dkg: &PubliclyVerifiableDkg<E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        Self::new_with_extensions(s, dkg, Extensions::default(), rng)
+    }
+
+    /// Same as [`PubliclyVerifiableSS::new`], but derives the secret and the polynomial from
+    /// `seed` with a ChaCha20 DRBG, so that dealing again with the same seed in the same DKG
+    /// yields a byte-identical transcript, e.g. for HSM-backed dealers or to replay an
+    /// audit.
+    ///
+    /// The DRBG seed binds `seed` to the validator set, ritual and dealer, so a seed reused
+    /// in another ritual deals another secret. Anyone who knows `seed` knows the secret.
+    pub fn new_deterministic(
+        seed: &[u8; 32],
+        dkg: &PubliclyVerifiableDkg<E>,
+    ) -> Result<Self> {
+        let mut hasher = Sha256::new();
+        hasher.update(V1::DETERMINISTIC_DEALING_DOMAIN);
+        hasher.update(seed);
+        hasher.update(dkg.validator_set_hash());
+        hasher.update(dkg.dkg_params.tau().to_le_bytes());
+        hasher.update(dkg.me.address.to_string().as_bytes());
+        let drbg_seed = Zeroizing::new(<[u8; 32]>::from(hasher.finalize()));
+        let rng = &mut ChaCha20Rng::from_seed(*drbg_seed);
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+        Self::new(&s, dkg, rng)
+    }
+
+    /// Same as [`PubliclyVerifiableSS::new`], but re-deals `share`, the scalar of the share
+    /// `share_index` of `previous`, instead of a fresh secret, e.g. to reshare a key to
+    /// another cohort.
+    ///
+    /// The transcript claims which share it re-deals, which anyone can check with
+    /// [`PubliclyVerifiableSS::verify_redeal`].
+    pub fn redeal<R: RngCore>(
+        share: &E::ScalarField,
+        share_index: u32,
+        previous: &AggregatedPvss<E>,
+        dkg: &PubliclyVerifiableDkg<E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let origin = RedealOrigin {
+            aggregate_hash: aggregate_hash(previous)?,
+            share_index,
+        };
+        let mut extensions = Extensions::new();
+        extensions
+            .insert(REDEAL_EXTENSION_TYPE, bincode::serialize(&origin)?)?;
+        Self::new_with_extensions(share, dkg, extensions, rng)
+    }
+
+    /// Same as [`PubliclyVerifiableSS::new`], with extensions bound to the proof of
+    /// knowledge of the transcript
+    pub fn new_with_extensions<R: RngCore>(
+        s: &E::ScalarField,
+        dkg: &PubliclyVerifiableDkg<E>,
+        extensions: Extensions,
+        rng: &mut R,
+    ) -> Result<Self> {
+        Self::deal(s, &DealingContext::from_dkg(dkg), extensions, rng)
+    }
+
+    /// Same as [`PubliclyVerifiableSS::new`], but deals in `context` instead of in a
+    /// [`PubliclyVerifiableDkg`]
+    pub fn new_standalone<R: RngCore>(
+        s: &E::ScalarField,
+        context: &DealingContext<'_, E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        Self::deal(s, context, Extensions::default(), rng)
+    }
+
+    fn deal<R: RngCore>(
+        s: &E::ScalarField,
+        context: &DealingContext<'_, E>,
+        mut extensions: Extensions,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let phi = SecretPolynomial::<E>::new(
+            s,
+            (context.security_threshold - 1) as usize,
+            rng,
+        );
+
+        // Evaluations of the polynomial over the domain, zeroized when dealing is done
+        let evals = phi.evaluate_over_domain(&context.domain);
+        // commitment to coeffs, F_i
+        let coeffs = commit_coeffs::<E>(&phi.0.coeffs, context.pvss_params.g);
+        let shares = context
+            .share_owners
+            .iter()
+            .enumerate()
+            .map(|(share_index, validator)| {
+                // ek_{i}^{eval_i}, i = share index
+                ShareEncryptions::new_unchecked(encrypt_share::<E>(
+                    evals.get(share_index), // one share per share index
+                    validator.public_key.encryption_key,
+                ))
+            })
+            .collect::<Vec<ShareEncryptions<E>>>();
+        extensions.insert(
+            DEALER_EXTENSION_TYPE,
+            bincode::serialize(context.dealer)?,
+        )?;
+        if context.share_proofs {
+            let proofs = prove_shares(context, &evals, &shares, rng)?;
+            extensions.insert(
+                SHARE_PROOFS_EXTENSION_TYPE,
+                bincode::serialize(&proofs)?,
+            )?;
+        }
+
+        // Sigma is a proof of knowledge of the secret, sigma = H(validator set, ritual,
+        // dealer, coeffs, extensions)^s. Since the discrete log of the base is unknown, it
+        // can only be computed from s, and only verifies for this transcript, dealer and
+        // ritual
+        let validator_set_hash = context.validator_set_hash;
+        let tau = context.tau;
+        let sigma = pok_base::<E>(
+            &validator_set_hash,
+            tau,
+            context.dealer,
+            &coeffs_hash::<E>(&coeffs)?,
+            &extensions.hash(),
+        )?
+        .mul(*s)
+        .into();
+        let vss = Self {
+            version: V1::TRANSCRIPT_VERSION,
+            coeffs,
+            shares,
+            sigma,
+            validator_set_hash,
+            tau,
+            domain: DomainDescriptor::new(&context.domain),
+            extensions,
+            phantom: Default::default(),
+        };
+        Ok(vss)
+    }
+}
+
+/// The [`ShareProof`] of every share of `shares`, which encrypt `evals`
+fn prove_shares<E: Pairing, R: RngCore>(
+    context: &DealingContext<'_, E>,
+    evals: &SecretEvaluations<E>,
+    shares: &[ShareEncryptions<E>],
+    rng: &mut R,
+) -> Result<Vec<ShareProof<E>>> {
+    let g = context.pvss_params.g.into_affine();
+    context
+        .share_owners
+        .iter()
+        .zip(shares)
+        .enumerate()
+        .map(|(share_index, (validator, share))| {
+            let eval = evals.get(share_index);
+            ShareProof::prove(
+                context.tau,
+                share_index as u32,
+                eval,
+                &g,
+                &validator.public_key.encryption_key,
+                &g.mul(*eval).into_affine(),
+                share.as_affine(),
+                rng,
+            )
+        })
+        .collect()
+}
+
+/// Commitments F_j = g^{a_j} to the coefficients of the secret polynomial
+#[cfg(not(ferveo_mock_crypto))]
+fn commit_coeffs<E: Pairing>(
+    coeffs: &[E::ScalarField],
+    g: E::G1,
+) -> Vec<E::G1Affine> {
+    fast_multiexp(coeffs, g)
+}
+
+/// Stand-in for the commitment to the coefficients under `ferveo_mock_crypto`, which only
+/// commits to the constant term, so that the public key is still correct, and skips the
+/// MSM for the others
+#[cfg(ferveo_mock_crypto)]
+fn commit_coeffs<E: Pairing>(
+    coeffs: &[E::ScalarField],
+    g: E::G1,
+) -> Vec<E::G1Affine> {
+    let mut commitment = vec![g.into_affine(); coeffs.len()];
+    commitment[0] = (g * coeffs[0]).into_affine();
+    commitment
+}
+
+/// Encryption ek^{eval} of a share to the encryption key of its holder
+#[cfg(not(ferveo_mock_crypto))]
+fn encrypt_share<E: Pairing>(
+    eval: &E::ScalarField,
+    encryption_key: E::G2Affine,
+) -> E::G2Affine {
+    fast_multiexp::<E::G2>(&[*eval], encryption_key.into())[0]
+}
+
+/// Stand-in for the encryption of a share under `ferveo_mock_crypto`, which skips the
+/// multiplication. Shares dealt this way can't be decrypted.
+#[cfg(ferveo_mock_crypto)]
+fn encrypt_share<E: Pairing>(
+    _eval: &E::ScalarField,
+    encryption_key: E::G2Affine,
+) -> E::G2Affine {
+    encryption_key
+}
+
+/// Decryption of an encrypted share Y_i = ek_i^{eval} into the private key share Z_i = H^{eval}, see
+/// https://nikkolasg.github.io/ferveo/pvss.html#validator-decryption-of-private-key-shares
+pub(crate) fn decrypt_share<E: Pairing>(
+    encrypted_share: &E::G2Affine,
+    validator_decryption_key: &E::ScalarField,
+) -> PrivateKeyShare<E> {
+    let private_key_share = encrypted_share
+        .mul(
+            validator_decryption_key
+                .inverse()
+                .expect("Validator decryption key must have an inverse"),
+        )
+        .into_affine();
+    PrivateKeyShare { private_key_share }
+}
+
+/// Private key shares of aggregated PVSS transcripts
+impl<E: Pairing, T: Aggregate> PubliclyVerifiableSS<E, T> {
+    pub fn decrypt_private_key_share(
+        &self,
+        validator_decryption_key: &E::ScalarField,
+        share_index: usize,
+    ) -> Result<PrivateKeyShare<E>> {
+        let encrypted_share = self
+            .shares
+            .get(share_index)
+            .ok_or(Error::InvalidShareIndex(share_index as u32))?;
+        Ok(decrypt_share::<E>(
+            encrypted_share.as_affine(),
+            validator_decryption_key,
+        ))
+    }
+
+    pub fn make_decryption_share_simple(
+        &self,
+        ciphertext: &CiphertextHeader<E>,
+        aad: &[u8],
+        validator_decryption_key: &E::ScalarField,
+        share_index: usize,
+        g_inv: &E::G1Prepared,
+    ) -> Result<DecryptionShareSimple<E>> {
+        let private_key_share = self
+            .decrypt_private_key_share(validator_decryption_key, share_index)?;
+        DecryptionShareSimple::create(
+            validator_decryption_key,
+            &private_key_share,
+            ciphertext,
+            aad,
+            g_inv,
+        )
+        .map_err(|e| e.into())
+    }
+
+    /// Create the precomputed decryption share of the share `share_index`, combined with
+    /// the shares of every other validator. Only decrypts if every share is needed to, i.e.
+    /// if the security threshold is the number of shares, see
+    /// [`PubliclyVerifiableSS::make_decryption_share_precomputed_among`] otherwise
+    pub fn make_decryption_share_simple_precomputed(
+        &self,
+        ciphertext_header: &CiphertextHeader<E>,
+        aad: &[u8],
+        validator_decryption_key: &E::ScalarField,
+        share_index: usize,
+        g_inv: &E::G1Prepared,
+    ) -> Result<DecryptionSharePrecomputed<E>> {
+        let participants = (0..self.shares.len()).collect::<Vec<_>>();
+        self.make_decryption_share_precomputed_among(
+            ciphertext_header,
+            aad,
+            validator_decryption_key,
+            share_index,
+            &participants,
+            g_inv,
+        )
+    }
+
+    /// Create the precomputed decryption share of the share `share_index`, to be combined
+    /// with the shares `participants`, which must be exactly `security_threshold` distinct
+    /// shares including `share_index`
+    pub fn make_decryption_share_precomputed_among(
+        &self,
+        ciphertext_header: &CiphertextHeader<E>,
+        aad: &[u8],
+        validator_decryption_key: &E::ScalarField,
+        share_index: usize,
+        participants: &[usize],
+        g_inv: &E::G1Prepared,
+    ) -> Result<DecryptionSharePrecomputed<E>> {
+        let security_threshold = self.coeffs.len() as u32;
+        if participants.len() as u32 != security_threshold {
+            return Err(Error::InvalidDkgParametersForPrecomputedVariant(
+                participants.len() as u32,
+                security_threshold,
+            ));
+        }
+        let mut seen = BTreeSet::new();
+        for &participant in participants {
+            if participant >= self.shares.len() {
+                return Err(Error::InvalidShareIndex(participant as u32));
+            }
+            if !seen.insert(participant) {
+                return Err(Error::DuplicatedShareIndex(participant as u32));
+            }
+        }
+        let position = participants
+            .iter()
+            .position(|&participant| participant == share_index)
+            .ok_or(Error::InvalidShareIndex(share_index as u32))?;
+        let private_key_share = self
+            .decrypt_private_key_share(validator_decryption_key, share_index)?;
+
+        // We use the `prepare_combine_simple` function to precompute the lagrange coefficients
+        let domain_points = participants
+            .iter()
+            .map(|&participant| self.domain().element(participant))
+            .collect::<Vec<_>>();
+        let lagrange_coeffs = prepare_combine_simple::<E>(&domain_points);
+
+        DecryptionSharePrecomputed::new(
+            share_index,
+            validator_decryption_key,
+            &private_key_share,
+            ciphertext_header,
+            aad,
+            &lagrange_coeffs[position],
+            g_inv,
+        )
+        .map_err(|e| e.into())
+    }
+
+    /// Same as [`PubliclyVerifiableSS::decrypt_private_key_share`], but also verifies and
+    /// applies the share updates that `pending` holds for the share, dealt with
+    /// `pvss_params`
+    #[cfg(feature = "refresh")]
+    pub fn decrypt_private_key_share_with_updates(
+        &self,
+        validator_decryption_key: &E::ScalarField,
+        pending: &PendingUpdates<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> Result<PrivateKeyShare<E>> {
+        let share_index = pending.share_index() as usize;
+        let private_key_share = self
+            .decrypt_private_key_share(validator_decryption_key, share_index)?;
+        pending.apply(
+            &private_key_share,
+            &self.domain.element(share_index),
+            validator_decryption_key,
+            self.coeffs.len(),
+            self.shares.len(),
+            pvss_params,
+        )
+    }
+
+    // TODO: Consider relocate to different place, maybe PrivateKeyShare? (see #162, #163)
+    #[cfg(feature = "refresh")]
+    pub fn update_private_key_share_for_recovery(
+        &self,
+        validator_decryption_key: &E::ScalarField,
+        share_index: usize,
+        share_updates: &[E::G2],
+    ) -> Result<PrivateKeyShare<E>> {
+        // Retrieves their private key share
+        let private_key_share = self
+            .decrypt_private_key_share(validator_decryption_key, share_index)?;
+
+        // And updates their share
+        Ok(apply_updates_to_private_share::<E>(
+            &private_key_share,
+            share_updates,
+        ))
+    }
+}
+
+impl<E: Pairing> PubliclyVerifiableDkg<E> {
+    /// Create a new PVSS instance within this DKG session, contributing to the final key
+    /// `rng` is a cryptographic random number generator
+    /// Returns a PVSS dealing message to post on-chain
+    pub fn share<R: RngCore>(&mut self, rng: &mut R) -> Result<Message<E>> {
+        print_time!("PVSS Sharing");
+        if self.me.is_observer() {
+            return Err(Error::ObserverCannotDeal(self.me.address.clone()));
+        }
+        match self.state {
+            DkgState::Sharing { .. } | DkgState::Dealt => {
+                let vss = PubliclyVerifiableSS::<E>::new(
+                    &E::ScalarField::rand(rng),
+                    self,
+                    rng,
+                )?;
+                Ok(Message::Deal(vss))
+            }
+            _ => Err(Error::InvalidDkgStateToDeal),
+        }
+    }
+}
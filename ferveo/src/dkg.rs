@@ -1,21 +1,33 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    mem,
+    ops::RangeInclusive,
+    sync::Arc,
+};
 
 use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
 use ark_poly::EvaluationDomain;
-use ark_std::UniformRand;
-use ferveo_common::PublicKey;
-use measure_time::print_time;
+use ferveo_common::{PublicKey, SecurityConfig};
+use ferveo_tdec::AadPolicy;
+#[cfg(feature = "dkg")]
 use rand::RngCore;
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde::{
+    de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer,
+};
 use serde_with::serde_as;
 
+#[cfg(feature = "dkg")]
+use crate::DkgDriver;
 use crate::{
-    aggregate, assert_no_share_duplicates, AggregatedPvss, Error,
-    EthereumAddress, PubliclyVerifiableParams, PubliclyVerifiableSS, Result,
-    Validator,
+    aggregate, assert_no_share_duplicates, assign_share_indices,
+    AggregatedPvss, CommitteeCommitment, Complaint, DealerCommitment,
+    DkgObserver, DkgObservers, DkgParamsViolation, DomainDescriptor,
+    DomainPolicy, Error, EthereumAddress, Fingerprint, PreparedEncryptionKeys,
+    PubliclyVerifiableParams, PubliclyVerifiableSS, Result, SignedMessage,
+    SignedTranscript, Validator, ValidatorSetHash,
 };
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DkgParams {
     tau: u32,
     security_threshold: u32,
@@ -28,26 +40,21 @@ impl DkgParams {
     /// `security_threshold` is the minimum number of shares required to reconstruct the key
     /// `shares_num` is the total number of shares to be generated
     /// Returns an error if the parameters are invalid
-    /// Parameters must hold: `shares_num` >= `security_threshold`
+    /// Parameters must hold: `1 <= security_threshold <= shares_num`. Any such threshold is
+    /// supported, from 1-of-n to n-of-n, not only a two-thirds majority
     pub fn new(
         tau: u32,
         security_threshold: u32,
         shares_num: u32,
     ) -> Result<Self> {
-        if shares_num < security_threshold
-            || shares_num == 0
-            || security_threshold == 0
-        {
-            return Err(Error::InvalidDkgParameters(
-                shares_num,
-                security_threshold,
-            ));
-        }
-        Ok(Self {
-            tau,
-            security_threshold,
-            shares_num,
-        })
+        Self::builder()
+            .tau(tau)
+            .security_threshold(security_threshold)
+            .shares_num(shares_num)
+            .build()
+            .map_err(|_| {
+                Error::InvalidDkgParameters(shares_num, security_threshold)
+            })
     }
 
     pub fn tau(&self) -> u32 {
@@ -61,17 +68,202 @@ impl DkgParams {
     pub fn shares_num(&self) -> u32 {
         self.shares_num
     }
+
+    /// Start building parameters checked constraint by constraint, see
+    /// [`DkgParamsBuilder`]
+    pub fn builder() -> DkgParamsBuilder {
+        DkgParamsBuilder::default()
+    }
+}
+
+/// Builds [`DkgParams`], checking every constraint on them separately, so that a
+/// misconfigured ritual reports all of its problems at once in
+/// [`Error::DkgParamsViolations`], instead of the first one.
+///
+/// Besides the constraints of [`DkgParams::new`], which builds its parameters with it, the
+/// ritual id can be required to be in a given range and the weights of the validators to
+/// add up to the number of shares
+#[derive(Clone, Debug, Default)]
+pub struct DkgParamsBuilder {
+    tau: u32,
+    security_threshold: u32,
+    shares_num: u32,
+    tau_range: Option<RangeInclusive<u32>>,
+    total_weight: Option<u32>,
+}
+
+impl DkgParamsBuilder {
+    pub fn tau(mut self, tau: u32) -> Self {
+        self.tau = tau;
+        self
+    }
+
+    pub fn security_threshold(mut self, security_threshold: u32) -> Self {
+        self.security_threshold = security_threshold;
+        self
+    }
+
+    pub fn shares_num(mut self, shares_num: u32) -> Self {
+        self.shares_num = shares_num;
+        self
+    }
+
+    /// Require the ritual id to be in `tau_range`, e.g. the ritual ids issued by the
+    /// coordinator
+    pub fn tau_range(mut self, tau_range: RangeInclusive<u32>) -> Self {
+        self.tau_range = Some(tau_range);
+        self
+    }
+
+    /// Require the weights of `validators` to add up to the number of shares
+    pub fn validators<E: Pairing>(
+        mut self,
+        validators: &[Validator<E>],
+    ) -> Self {
+        self.total_weight = Some(crate::total_weight(validators));
+        self
+    }
+
+    /// Every constraint that the parameters violate, in the order they are documented in
+    /// [`DkgParamsViolation`]
+    pub fn violations(&self) -> Vec<DkgParamsViolation> {
+        let mut violations = Vec::new();
+        if self.shares_num == 0 {
+            violations.push(DkgParamsViolation::ZeroShares);
+        }
+        if self.security_threshold == 0 {
+            violations.push(DkgParamsViolation::ZeroThreshold);
+        }
+        if self.security_threshold > self.shares_num {
+            violations.push(DkgParamsViolation::ThresholdAboveShares(
+                self.security_threshold,
+                self.shares_num,
+            ));
+        }
+        if let Some(tau_range) = &self.tau_range {
+            if !tau_range.contains(&self.tau) {
+                violations.push(DkgParamsViolation::TauOutOfRange(
+                    self.tau,
+                    *tau_range.start(),
+                    *tau_range.end(),
+                ));
+            }
+        }
+        match self.total_weight {
+            Some(total_weight) if total_weight != self.shares_num => {
+                violations.push(DkgParamsViolation::WeightMismatch(
+                    total_weight,
+                    self.shares_num,
+                ));
+            }
+            _ => {}
+        }
+        violations
+    }
+
+    pub fn build(&self) -> Result<DkgParams> {
+        let violations = self.violations();
+        if !violations.is_empty() {
+            return Err(Error::DkgParamsViolations(violations));
+        }
+        Ok(DkgParams {
+            tau: self.tau,
+            security_threshold: self.security_threshold,
+            shares_num: self.shares_num,
+        })
+    }
 }
 
 pub type ValidatorsMap<E> = BTreeMap<EthereumAddress, Validator<E>>;
 pub type PVSSMap<E> = BTreeMap<EthereumAddress, PubliclyVerifiableSS<E>>;
+pub type DealerCommitmentMap<E> =
+    BTreeMap<EthereumAddress, DealerCommitment<E>>;
+
+/// What a DKG keeps of the transcripts it received once their aggregate is applied
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum RetentionPolicy {
+    /// Keep every transcript
+    #[default]
+    KeepTranscripts,
+    /// Replace the transcripts with their [`DealerCommitment`], which is enough to verify
+    /// the aggregate, but not to aggregate them again or to diagnose a faulty dealer
+    KeepCommitments,
+}
+
+/// Whether a DKG waits for every validator to deal before it aggregates
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum DropoutPolicy {
+    /// Aggregate once every validator dealt
+    #[default]
+    RequireAllDealers,
+    /// Aggregate once transcripts from `security_threshold` dealers are received. The
+    /// aggregate names its dealers, see [`PubliclyVerifiableSS::contributors`], so that it
+    /// verifies at validators that received more transcripts
+    TolerateDropouts,
+}
+
+/// What [`PubliclyVerifiableDkg::ingest`] did with a message
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Ingested {
+    /// The message was verified and applied
+    Applied,
+    /// The message is a copy of a transcript the DKG already holds, and was dropped without
+    /// being verified
+    Duplicate,
+}
+
+/// How many rejected transcripts a DKG remembers, so that peers can't grow the cache
+/// without bound by relaying invalid transcripts
+const MAX_REJECTED_TRANSCRIPTS: usize = 1024;
+
+/// The transcripts a DKG rejected for a reason of their own, i.e. that don't depend on the
+/// state of the DKG, by sender and fingerprint. They are rejected without being verified
+/// again when they are relayed by other peers. It isn't part of the state of the DKG, so it
+/// isn't serialized
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TranscriptCache {
+    rejected: BTreeSet<(EthereumAddress, Fingerprint)>,
+}
+
+impl TranscriptCache {
+    fn is_rejected(&self, key: &(EthereumAddress, Fingerprint)) -> bool {
+        self.rejected.contains(key)
+    }
 
-#[derive(Debug, Clone)]
+    fn reject(&mut self, key: (EthereumAddress, Fingerprint), err: &Error) {
+        let intrinsic = matches!(
+            err,
+            Error::InvalidPvssTranscript
+                | Error::ValidatorSetMismatch
+                | Error::RitualMismatch(..)
+                | Error::EvaluationDomainMismatch
+                | Error::InvalidTranscriptDegree(..)
+                | Error::DealerMismatch(..)
+        );
+        if intrinsic && self.rejected.len() < MAX_REJECTED_TRANSCRIPTS {
+            self.rejected.insert(key);
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
 pub enum DkgState<E: Pairing> {
     // TODO: Do we need to keep track of the block number?
-    Sharing { accumulated_shares: u32, block: u32 },
+    Sharing {
+        accumulated_shares: u32,
+        block: u32,
+    },
     Dealt,
-    Success { public_key: E::G1Affine },
+    Success {
+        #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+        public_key: E::G1Affine,
+    },
     Invalid,
 }
 
@@ -82,6 +274,30 @@ impl<E: Pairing> DkgState<E> {
             block: 0,
         }
     }
+
+    pub fn phase(&self) -> DkgPhase {
+        match self {
+            DkgState::Sharing { .. } => DkgPhase::Sharing,
+            DkgState::Dealt => DkgPhase::Dealt,
+            DkgState::Success { .. } => DkgPhase::Success,
+            DkgState::Invalid => DkgPhase::Invalid,
+        }
+    }
+}
+
+/// The phase of a [`PubliclyVerifiableDkg`], i.e. its [`DkgState`] without the data of the
+/// phase, e.g. for dashboards and metrics
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DkgPhase {
+    /// Waiting for the security threshold of transcripts
+    Sharing,
+    /// Enough transcripts were received to aggregate them
+    Dealt,
+    /// An aggregate was applied, and the public key of the ritual is final
+    Success,
+    /// The ritual failed and must be restarted. The DKG never enters this phase by itself,
+    /// since it rejects invalid messages instead of applying them
+    Invalid,
 }
 
 /// The DKG context that holds all of the local state for participating in the DKG
@@ -93,10 +309,91 @@ pub struct PubliclyVerifiableDkg<E: Pairing> {
     pub dkg_params: DkgParams,
     pub pvss_params: PubliclyVerifiableParams<E>,
     pub validators: ValidatorsMap<E>,
+    pub prepared_keys: PreparedEncryptionKeys<E>,
     pub vss: PVSSMap<E>,
+    /// The transcripts pruned from `vss` by the retention policy, by dealer
+    pub dealer_commitments: DealerCommitmentMap<E>,
+    /// The dealers whose transcript was excluded by a [`Complaint`]
+    pub excluded_dealers: BTreeSet<EthereumAddress>,
     pub domain: ark_poly::GeneralEvaluationDomain<E::ScalarField>,
+    /// How `domain` was chosen for the number of shares
+    pub domain_policy: DomainPolicy,
     pub me: Validator<E>,
     pub state: DkgState<E>,
+    /// Which AAD this validator creates decryption shares for
+    pub aad_policy: AadPolicy,
+    /// Which checks are run on received messages. Transcripts must be received as a
+    /// [`SignedTranscript`] if it requires signed transcripts, see
+    /// [`PubliclyVerifiableDkg::apply_signed_transcript`]
+    pub security: SecurityConfig,
+    /// What is kept of the transcripts once their aggregate is applied
+    pub retention: RetentionPolicy,
+    /// Whether to aggregate before every validator dealt
+    pub dropout: DropoutPolicy,
+    /// Notified of the progress of the DKG, see [`PubliclyVerifiableDkg::with_observer`]
+    pub(crate) observers: DkgObservers<E>,
+    /// The transcripts rejected by [`PubliclyVerifiableDkg::ingest`]
+    pub(crate) transcript_cache: TranscriptCache,
+}
+
+/// The state of a [`PubliclyVerifiableDkg`] that can't be derived from the rest of it, as
+/// it is serialized
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+struct DkgSnapshot<E: Pairing> {
+    dkg_params: DkgParams,
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    g: E::G1Affine,
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    h: E::G2Affine,
+    validators: Vec<Validator<E>>,
+    vss: PVSSMap<E>,
+    dealer_commitments: DealerCommitmentMap<E>,
+    excluded_dealers: BTreeSet<EthereumAddress>,
+    domain: DomainDescriptor<E>,
+    domain_policy: DomainPolicy,
+    me: EthereumAddress,
+    state: DkgState<E>,
+    aad_policy: AadPolicy,
+    security: SecurityConfig,
+    retention: RetentionPolicy,
+    dropout: DropoutPolicy,
+}
+
+impl<E: Pairing> Serialize for PubliclyVerifiableDkg<E> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        DkgSnapshot {
+            dkg_params: self.dkg_params,
+            g: self.pvss_params.g.into_affine(),
+            h: self.pvss_params.h.into_affine(),
+            validators: self.validators.values().cloned().collect(),
+            vss: self.vss.clone(),
+            dealer_commitments: self.dealer_commitments.clone(),
+            excluded_dealers: self.excluded_dealers.clone(),
+            domain: DomainDescriptor::new(&self.domain),
+            domain_policy: self.domain_policy,
+            me: self.me.address.clone(),
+            state: self.state.clone(),
+            aad_policy: self.aad_policy,
+            security: self.security,
+            retention: self.retention,
+            dropout: self.dropout,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, E: Pairing> Deserialize<'de> for PubliclyVerifiableDkg<E> {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let snapshot = DkgSnapshot::<E>::deserialize(deserializer)?;
+        Self::from_snapshot(snapshot).map_err(serde::de::Error::custom)
+    }
 }
 
 impl<E: Pairing> PubliclyVerifiableDkg<E> {
@@ -111,12 +408,54 @@ impl<E: Pairing> PubliclyVerifiableDkg<E> {
         dkg_params: &DkgParams,
         me: &Validator<E>,
     ) -> Result<Self> {
-        let domain = ark_poly::GeneralEvaluationDomain::<E::ScalarField>::new(
-            dkg_params.shares_num as usize,
+        Self::new_with_security(
+            validators,
+            dkg_params,
+            me,
+            SecurityConfig::default(),
         )
-        .expect("unable to construct domain");
+    }
+
+    /// Same as [`PubliclyVerifiableDkg::new`], with the checks of received messages set by
+    /// `security` instead of the hardened default
+    pub fn new_with_security(
+        validators: &[Validator<E>],
+        dkg_params: &DkgParams,
+        me: &Validator<E>,
+        security: SecurityConfig,
+    ) -> Result<Self> {
+        if dkg_params.shares_num > security.max_cohort_size {
+            return Err(Error::CohortTooLarge(
+                dkg_params.shares_num,
+                security.max_cohort_size,
+            ));
+        }
+        let domain_policy = DomainPolicy::default();
+        let domain = domain_policy
+            .domain::<E::ScalarField>(dkg_params.shares_num as usize)
+            .expect("unable to construct domain");
 
         assert_no_share_duplicates(validators)?;
+        // Every share must have a point in the domain
+        for validator in validators {
+            if let Some(share_index) = validator
+                .share_indices()?
+                .find(|&share_index| share_index >= dkg_params.shares_num)
+            {
+                return Err(Error::InvalidShareIndex(share_index));
+            }
+        }
+        // And every point a share, so that the shares held are exactly `0..shares_num`.
+        // Distinct indices below the number of shares can't add up to more than it
+        let total_weight = crate::total_weight(validators);
+        if total_weight != dkg_params.shares_num {
+            return Err(Error::DkgParamsViolations(vec![
+                DkgParamsViolation::WeightMismatch(
+                    total_weight,
+                    dkg_params.shares_num,
+                ),
+            ]));
+        }
 
         let validators: ValidatorsMap<E> = validators
             .iter()
@@ -139,13 +478,206 @@ impl<E: Pairing> PubliclyVerifiableDkg<E> {
             dkg_params: *dkg_params,
             pvss_params: PubliclyVerifiableParams::<E>::default(),
             vss: PVSSMap::<E>::new(),
+            dealer_commitments: DealerCommitmentMap::<E>::new(),
+            excluded_dealers: BTreeSet::new(),
             domain,
+            domain_policy,
             me: me.clone(),
+            prepared_keys: PreparedEncryptionKeys::new(validators.values()),
             validators,
             state: DkgState::new(),
+            aad_policy: AadPolicy::default(),
+            security,
+            retention: RetentionPolicy::default(),
+            dropout: DropoutPolicy::default(),
+            observers: DkgObservers::new(),
+            transcript_cache: TranscriptCache::default(),
         })
     }
 
+    /// Rebuild a DKG from its snapshot, checking it as [`PubliclyVerifiableDkg::new`] checks
+    /// its arguments, and recomputing what is derived from it
+    fn from_snapshot(snapshot: DkgSnapshot<E>) -> Result<Self> {
+        let me = snapshot
+            .validators
+            .iter()
+            .find(|validator| validator.address == snapshot.me)
+            .ok_or_else(|| Error::DealerNotInValidatorSet(snapshot.me.clone()))?
+            .clone();
+        let dkg = Self::new_with_security(
+            &snapshot.validators,
+            &snapshot.dkg_params,
+            &me,
+            snapshot.security,
+        )?
+        .with_domain_policy(snapshot.domain_policy)?;
+        snapshot.domain.check(&dkg.domain)?;
+        Ok(Self {
+            pvss_params: PubliclyVerifiableParams {
+                g: snapshot.g.into_group(),
+                h: snapshot.h.into_group(),
+            },
+            vss: snapshot.vss,
+            dealer_commitments: snapshot.dealer_commitments,
+            excluded_dealers: snapshot.excluded_dealers,
+            state: snapshot.state,
+            aad_policy: snapshot.aad_policy,
+            retention: snapshot.retention,
+            dropout: snapshot.dropout,
+            ..dkg
+        })
+    }
+
+    /// Serialize this DKG, e.g. to persist it and [`PubliclyVerifiableDkg::restore`] it after
+    /// a restart in the middle of a ritual
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Restore a DKG serialized with [`PubliclyVerifiableDkg::to_bytes`]
+    pub fn restore(bytes: &[u8]) -> Result<Self> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+
+    /// Use `pvss_params` as the generators of the transcripts dealt and verified in this DKG,
+    /// instead of the default ones. Every participant must use the same generators
+    pub fn with_pvss_params(
+        mut self,
+        pvss_params: PubliclyVerifiableParams<E>,
+    ) -> Self {
+        self.pvss_params = pvss_params;
+        self
+    }
+
+    /// Deal the shares over the domain `domain_policy` chooses for their number, e.g. over
+    /// exactly as many points as shares with [`DomainPolicy::Compact`]. Fails if the policy
+    /// has no domain for the number of shares. Must be set before dealing, as transcripts
+    /// dealt over another domain are rejected
+    pub fn with_domain_policy(
+        mut self,
+        domain_policy: DomainPolicy,
+    ) -> Result<Self> {
+        self.domain = domain_policy
+            .domain::<E::ScalarField>(self.dkg_params.shares_num as usize)?;
+        self.domain_policy = domain_policy;
+        Ok(self)
+    }
+
+    /// Prune the transcripts according to `retention` once their aggregate is applied
+    pub fn with_retention_policy(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Aggregate before every validator dealt according to `dropout`
+    pub fn with_dropout_policy(mut self, dropout: DropoutPolicy) -> Self {
+        self.dropout = dropout;
+        self
+    }
+
+    /// Notify `observer` of the progress of this DKG. Observers aren't serialized with the
+    /// DKG, so they must be attached again to a restored DKG
+    pub fn with_observer(mut self, observer: Arc<dyn DkgObserver<E>>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// Move to `state`, notifying the observers if it is another phase
+    fn set_state(&mut self, state: DkgState<E>) {
+        let previous = mem::replace(&mut self.state, state);
+        if previous.phase() != self.state.phase() {
+            self.observers.notify(|observer| {
+                observer.on_phase_change(&previous, &self.state)
+            });
+        }
+    }
+
+    /// Notify the observers if `sender`'s transcript failed verification
+    fn report_deal(
+        &self,
+        sender: &Validator<E>,
+        result: Result<()>,
+    ) -> Result<()> {
+        if let Err(reason) = &result {
+            self.observers.notify(|observer| {
+                observer.on_transcript_rejected(sender, reason)
+            });
+        }
+        result
+    }
+
+    /// The phase the DKG is in
+    pub fn phase(&self) -> DkgPhase {
+        self.state.phase()
+    }
+
+    /// The dealers whose transcript this DKG received, whether it was pruned or not, in
+    /// address order
+    pub fn received_transcripts(&self) -> Vec<&EthereumAddress> {
+        let mut dealers = self
+            .vss
+            .keys()
+            .chain(self.dealer_commitments.keys())
+            .collect::<Vec<_>>();
+        dealers.sort();
+        dealers.dedup();
+        dealers
+    }
+
+    /// The dealers this DKG hasn't received a transcript from yet, leaving out the dealers
+    /// excluded by a [`Complaint`], whose transcripts are rejected
+    pub fn missing_dealers(&self) -> Vec<&Validator<E>> {
+        self.dealers()
+            .filter(|dealer| {
+                !self.vss.contains_key(&dealer.address)
+                    && !self.dealer_commitments.contains_key(&dealer.address)
+                    && !self.excluded_dealers.contains(&dealer.address)
+            })
+            .collect()
+    }
+
+    /// Whether [`PubliclyVerifiableDkg::aggregate`] can be called: the security threshold
+    /// of transcripts was received, and every transcript its [`DropoutPolicy`] waits for
+    pub fn is_ready_to_aggregate(&self) -> bool {
+        self.phase() == DkgPhase::Dealt && self.dealing_complete()
+    }
+
+    /// Whether this DKG received the transcripts its [`DropoutPolicy`] waits for before
+    /// aggregating
+    pub fn dealing_complete(&self) -> bool {
+        match self.dropout {
+            DropoutPolicy::RequireAllDealers => {
+                self.vss.len() == self.dealers().count()
+            }
+            DropoutPolicy::TolerateDropouts => {
+                matches!(self.state, DkgState::Dealt)
+            }
+        }
+    }
+
+    /// Same as [`PubliclyVerifiableDkg::new`], but assigns the share indices of the validators
+    /// with [`assign_share_indices`] instead of taking them as given.
+    pub fn with_canonical_share_indices(
+        validators: &[Validator<E>],
+        dkg_params: &DkgParams,
+        me: &EthereumAddress,
+    ) -> Result<Self> {
+        let validators = assign_share_indices(validators);
+        let me = validators
+            .iter()
+            .find(|validator| &validator.address == me)
+            .ok_or_else(|| Error::DealerNotInValidatorSet(me.clone()))?
+            .clone();
+        Self::new(&validators, dkg_params, &me)
+    }
+
+    /// The validators that deal in this DKG, i.e. that aren't observers
+    pub fn dealers(&self) -> impl Iterator<Item = &Validator<E>> + '_ {
+        self.validators
+            .values()
+            .filter(|validator| !validator.is_observer())
+    }
+
     pub fn get_validator(
         &self,
         public_key: &PublicKey<E>,
@@ -155,48 +687,154 @@ impl<E: Pairing> PubliclyVerifiableDkg<E> {
             .find(|validator| &validator.public_key == public_key)
     }
 
-    /// Create a new PVSS instance within this DKG session, contributing to the final key
-    /// `rng` is a cryptographic random number generator
-    /// Returns a PVSS dealing message to post on-chain
-    pub fn share<R: RngCore>(&mut self, rng: &mut R) -> Result<Message<E>> {
-        print_time!("PVSS Sharing");
-        match self.state {
-            DkgState::Sharing { .. } | DkgState::Dealt => {
-                let vss = PubliclyVerifiableSS::<E>::new(
-                    &E::ScalarField::rand(rng),
-                    self,
-                    rng,
-                )?;
-                Ok(Message::Deal(vss))
-            }
-            _ => Err(Error::InvalidDkgStateToDeal),
-        }
-    }
-
     /// Aggregate all received PVSS messages into a single message, prepared to post on-chain
     pub fn aggregate(&self) -> Result<Message<E>> {
         match self.state {
             DkgState::Dealt => {
                 let public_key = self.public_key();
                 let pvss_list = self.vss.values().cloned().collect::<Vec<_>>();
-                Ok(Message::Aggregate(Aggregation {
-                    vss: aggregate(&pvss_list)?,
-                    public_key,
-                }))
+                let mut vss = aggregate(&pvss_list)?;
+                if self.dropout == DropoutPolicy::TolerateDropouts {
+                    vss.set_contributors(&self.vss.keys().cloned().collect())?;
+                }
+                Ok(Message::Aggregate(Aggregation { vss, public_key }))
             }
             _ => Err(Error::InvalidDkgStateToAggregate),
         }
     }
 
+    /// Commitment to the validator set of this DKG and to its PVSS params, which transcripts
+    /// are bound to, see [`PubliclyVerifiableParams::bind`]
+    pub fn validator_set_hash(&self) -> ValidatorSetHash {
+        self.pvss_params
+            .bind(crate::validator_set_hash(self.validators.values()))
+    }
+
+    /// Commitment to the validators backing the public key of this DKG, to prove their
+    /// membership with [`MembershipProof`]
+    pub fn committee_commitment(&self) -> CommitteeCommitment<E> {
+        CommitteeCommitment::new(self.validators.values(), self.public_key())
+    }
+
+    /// The holder of each share dealt in this DKG, by share index
+    pub fn share_owners(&self) -> Vec<&Validator<E>> {
+        crate::share_owners(self.validators.values())
+    }
+
+    /// The point of the domain at which the share `share_index` is evaluated, to compute
+    /// Lagrange coefficients for decryption shares or recovery without recomputing the
+    /// domain. Share `i` is always evaluated at `ω^i`, where `ω` generates the domain that
+    /// [`PubliclyVerifiableDkg::domain_policy`] chooses for the number of shares: this
+    /// mapping is part of the protocol and doesn't change across releases of a
+    /// [`ferveo_tdec::protocol::ProtocolVersion`].
+    pub fn domain_point(&self, share_index: u32) -> Result<E::ScalarField> {
+        if share_index >= self.dkg_params.shares_num() {
+            return Err(Error::InvalidShareIndex(share_index));
+        }
+        Ok(self.domain.element(share_index as usize))
+    }
+
+    /// The domain points of every share, in share index order, see
+    /// [`PubliclyVerifiableDkg::domain_point`]
+    pub fn domain_points(&self) -> Vec<E::ScalarField> {
+        self.domain
+            .elements()
+            .take(self.dkg_params.shares_num() as usize)
+            .collect()
+    }
+
     /// Returns the public key generated by the DKG
     pub fn public_key(&self) -> E::G1Affine {
-        self.vss
-            .values()
-            .map(|vss| vss.coeffs[0].into_group())
+        self.contributions()
+            .map(|(constant_commitment, _)| constant_commitment.into_group())
             .sum::<E::G1>()
             .into_affine()
     }
 
+    /// The commitment `F_0` to the secret and the proof of knowledge `sigma` of every
+    /// received transcript, whether it was pruned or not
+    pub(crate) fn contributions(
+        &self,
+    ) -> impl Iterator<Item = (E::G1Affine, E::G2Affine)> + '_ {
+        self.vss
+            .values()
+            .filter_map(|pvss| pvss.contribution())
+            .chain(self.dealer_commitments.values().map(|commitment| {
+                (commitment.constant_commitment, commitment.sigma)
+            }))
+    }
+
+    /// The contributions of `dealers`, the [`PubliclyVerifiableSS::contributors`] of an
+    /// aggregate, which must be at least `security_threshold` distinct dealers whose
+    /// transcripts this DKG received
+    pub(crate) fn contributions_of(
+        &self,
+        dealers: &[EthereumAddress],
+    ) -> Result<Vec<(E::G1Affine, E::G2Affine)>> {
+        let threshold = self.dkg_params.security_threshold();
+        if !dealers.windows(2).all(|pair| pair[0] < pair[1]) {
+            return Err(Error::InvalidTranscriptAggregate);
+        }
+        if (dealers.len() as u32) < threshold {
+            return Err(Error::InsufficientTranscriptsForAggregate(
+                threshold,
+                dealers.len() as u32,
+            ));
+        }
+        dealers
+            .iter()
+            .map(|dealer| {
+                match (
+                    self.vss.get(dealer),
+                    self.dealer_commitments.get(dealer),
+                ) {
+                    (Some(pvss), _) => {
+                        pvss.contribution().ok_or(Error::InvalidPvssTranscript)
+                    }
+                    (None, Some(commitment)) => {
+                        Ok((commitment.constant_commitment, commitment.sigma))
+                    }
+                    (None, None) => Err(Error::UnknownDealer(dealer.clone())),
+                }
+            })
+            .collect()
+    }
+
+    /// The public key generated by the DKG if it applies the aggregate `vss`, i.e. the sum
+    /// of the secret commitments of its contributors
+    fn aggregate_public_key(
+        &self,
+        vss: &AggregatedPvss<E>,
+    ) -> Result<E::G1Affine> {
+        match vss.contributors() {
+            Some(dealers) => Ok(self
+                .contributions_of(&dealers)?
+                .into_iter()
+                .map(|(constant_commitment, _)| {
+                    constant_commitment.into_group()
+                })
+                .sum::<E::G1>()
+                .into_affine()),
+            None => Ok(self.public_key()),
+        }
+    }
+
+    /// Replace the received transcripts with their [`DealerCommitment`], e.g. to free
+    /// memory once their aggregate is verified. See [`RetentionPolicy::KeepCommitments`]
+    pub fn prune_transcripts(&mut self) -> Result<()> {
+        let commitments = self
+            .vss
+            .iter()
+            .map(|(dealer, pvss)| {
+                DealerCommitment::from_transcript(dealer.clone(), pvss)
+                    .map(|commitment| (dealer.clone(), commitment))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.dealer_commitments.extend(commitments);
+        self.vss.clear();
+        Ok(())
+    }
+
     /// `payload` is the content of the message
     pub fn verify_message(
         &self,
@@ -210,47 +848,346 @@ impl<E: Pairing> PubliclyVerifiableDkg<E> {
                     DkgState::Sharing { .. } | DkgState::Dealt
                 ) =>
             {
-                if !self.validators.contains_key(&sender.address) {
-                    Err(Error::UnknownDealer(sender.clone().address))
-                } else if self.vss.contains_key(&sender.address) {
-                    Err(Error::DuplicateDealer(sender.clone().address))
-                } else if !pvss.verify_optimistic() {
-                    Err(Error::InvalidPvssTranscript)
+                let result = if self.security.require_signed_transcripts {
+                    Err(Error::UnsignedTranscript)
                 } else {
-                    Ok(())
-                }
+                    self.verify_deal(sender, pvss)
+                };
+                self.report_deal(sender, result)
             }
             Message::Aggregate(Aggregation { vss, public_key })
                 if matches!(self.state, DkgState::Dealt) =>
             {
-                let minimum_shares = self.dkg_params.shares_num
-                    - self.dkg_params.security_threshold;
-                let actual_shares = vss.shares.len() as u32;
+                let threshold = self.dkg_params.security_threshold;
+                let contributions = vss.contributors().map_or_else(
+                    || self.contributions().count(),
+                    |dealers| dealers.len(),
+                ) as u32;
                 // We reject aggregations that fail to meet the security threshold
-                if actual_shares < minimum_shares {
+                if vss.shares.len() as u32 != self.dkg_params.shares_num {
+                    Err(Error::InvalidTranscriptAggregate)
+                } else if contributions < threshold {
                     Err(Error::InsufficientTranscriptsForAggregate(
-                        minimum_shares,
-                        actual_shares,
+                        threshold,
+                        contributions,
                     ))
-                } else if vss.verify_aggregation(self).is_err() {
+                } else if (self.security.strict_subgroup_checks
+                    && vss.check_subgroups().is_err())
+                    || vss.verify_aggregation(self).is_err()
+                {
                     Err(Error::InvalidTranscriptAggregate)
-                } else if &self.public_key() == public_key {
+                } else if &self.aggregate_public_key(vss)? == public_key {
                     Ok(())
                 } else {
                     Err(Error::InvalidDkgPublicKey)
                 }
             }
+            Message::Complaint(complaint)
+                if matches!(
+                    self.state,
+                    DkgState::Sharing { .. } | DkgState::Dealt
+                ) =>
+            {
+                self.verify_complaint(sender, complaint)
+            }
             _ => Err(Error::InvalidDkgStateToVerify),
         }
     }
 
+    /// Check that `complaint` was sent by its accuser, and exposes a faulty share of a
+    /// transcript this DKG holds
+    fn verify_complaint(
+        &self,
+        sender: &Validator<E>,
+        complaint: &Complaint<E>,
+    ) -> Result<()> {
+        let pvss = self
+            .vss
+            .get(&complaint.dealer)
+            .ok_or_else(|| Error::UnknownDealer(complaint.dealer.clone()))?;
+        match self.validators.get(&sender.address) {
+            Some(accuser)
+                if complaint.verify(pvss, accuser, &self.pvss_params) =>
+            {
+                Ok(())
+            }
+            _ => Err(Error::InvalidComplaint(sender.address.clone())),
+        }
+    }
+
+    /// Same as [`PubliclyVerifiableDkg::verify_message`] for the transcript of a
+    /// [`SignedTranscript`], which must also be signed with the signing key of its dealer
+    pub fn verify_signed_transcript(
+        &self,
+        signed: &SignedTranscript<E>,
+    ) -> Result<()> {
+        let sender = self
+            .validators
+            .get(&signed.dealer)
+            .ok_or_else(|| Error::UnknownDealer(signed.dealer.clone()))?;
+        let verified = match &sender.signing_key {
+            Some(signing_key) => signed.verify(signing_key)?,
+            None => false,
+        };
+        if !verified {
+            return Err(Error::InvalidTranscriptSignature(
+                signed.dealer.clone(),
+            ));
+        }
+        if !matches!(self.state, DkgState::Sharing { .. } | DkgState::Dealt) {
+            return Err(Error::InvalidDkgStateToVerify);
+        }
+        let result = self.verify_deal(sender, &signed.transcript);
+        self.report_deal(sender, result)
+    }
+
+    /// Verify `signed` with [`PubliclyVerifiableDkg::verify_signed_transcript`], then apply
+    /// its transcript as dealt by its signer
+    pub fn apply_signed_transcript(
+        &mut self,
+        signed: &SignedTranscript<E>,
+    ) -> Result<()> {
+        self.verify_signed_transcript(signed)?;
+        let sender = self.validators[&signed.dealer].clone();
+        self.apply_authenticated_message(
+            &sender,
+            &Message::Deal(signed.transcript.clone()),
+        )
+    }
+
+    /// Authenticate `signed` against the validator set and the ritual of the DKG, then
+    /// verify and apply its payload as sent by its signer. A transcript in an envelope of its
+    /// dealer counts as signed, see [`SecurityConfig::require_signed_transcripts`]
+    pub fn apply_signed_message(
+        &mut self,
+        signed: &SignedMessage<E>,
+    ) -> Result<()> {
+        let sender = self
+            .validators
+            .get(&signed.sender)
+            .ok_or_else(|| Error::UnknownDealer(signed.sender.clone()))?
+            .clone();
+        let tau = self.dkg_params.tau();
+        if signed.ritual_id != tau {
+            return Err(Error::RitualMismatch(tau, signed.ritual_id));
+        }
+        let verified = match &sender.signing_key {
+            Some(signing_key) => signed.verify(signing_key)?,
+            None => false,
+        };
+        if !verified {
+            return Err(Error::InvalidMessageSignature(sender.address));
+        }
+        match signed.payload.ritual_id() {
+            Some(claimed) if claimed != tau => {
+                return Err(Error::RitualMismatch(tau, claimed))
+            }
+            _ => {}
+        }
+        self.verify_authenticated_message(&sender, &signed.payload)?;
+        self.apply_authenticated_message(&sender, &signed.payload)
+    }
+
+    /// Same as [`PubliclyVerifiableDkg::verify_message`], for a message whose sender is
+    /// already authenticated, e.g. by a signature or by the chain it was read from, so that
+    /// transcripts don't need to be signed
+    fn verify_authenticated_message(
+        &self,
+        sender: &Validator<E>,
+        payload: &Message<E>,
+    ) -> Result<()> {
+        match payload {
+            Message::Deal(pvss) => {
+                if !matches!(
+                    self.state,
+                    DkgState::Sharing { .. } | DkgState::Dealt
+                ) {
+                    return Err(Error::InvalidDkgStateToVerify);
+                }
+                self.report_deal(sender, self.verify_deal(sender, pvss))
+            }
+            payload => self.verify_message(sender, payload),
+        }
+    }
+
+    /// Rebuild the DKG of `me` from the messages of its ritual, in the order they were
+    /// included, e.g. as stored by a chain or a coordinator, to resume the ritual after a
+    /// crash without a snapshot of the DKG. See [`PubliclyVerifiableDkg::replay`]
+    pub fn from_messages(
+        validators: &[Validator<E>],
+        dkg_params: &DkgParams,
+        me: &Validator<E>,
+        messages: &[(Validator<E>, Message<E>)],
+    ) -> Result<Self> {
+        let mut dkg = Self::new(validators, dkg_params, me)?;
+        dkg.replay(messages)?;
+        Ok(dkg)
+    }
+
+    /// Verify and apply `messages`, a history of the ritual in the order it was included,
+    /// so that the same history always rebuilds the same DKG. The source of the history is
+    /// trusted to authenticate the senders, so transcripts don't need to be signed.
+    ///
+    /// Messages that don't verify are skipped, as they were when they were first received.
+    /// Once an aggregate is applied the ritual is over, and the rest of the history, e.g. the
+    /// same aggregate posted by the other validators, is skipped without being verified.
+    /// Returns the number of messages applied
+    pub fn replay(
+        &mut self,
+        messages: &[(Validator<E>, Message<E>)],
+    ) -> Result<usize> {
+        let mut applied = 0;
+        for (sender, message) in messages {
+            if matches!(
+                self.state,
+                DkgState::Success { .. } | DkgState::Invalid
+            ) {
+                break;
+            }
+            if self.verify_authenticated_message(sender, message).is_err() {
+                continue;
+            }
+            self.apply_authenticated_message(sender, message)?;
+            applied += 1;
+        }
+        Ok(applied)
+    }
+
+    /// Verify and apply `payload`, as received from gossip, where the same transcript is
+    /// relayed by many peers. The transport is trusted to authenticate `sender`, as for
+    /// [`PubliclyVerifiableDkg::verify_message`].
+    ///
+    /// Transcripts are told apart by their [`PubliclyVerifiableSS::fingerprint`]: a copy of
+    /// the transcript the DKG holds from `sender` is dropped as a duplicate, another
+    /// transcript from `sender` is a double-deal rejected with
+    /// [`Error::ConflictingTranscript`], and a copy of a transcript that was rejected for a
+    /// reason of its own is rejected with [`Error::KnownInvalidTranscript`], without being
+    /// verified again. Other messages are verified and applied as they are
+    pub fn ingest(
+        &mut self,
+        sender: &Validator<E>,
+        payload: &Message<E>,
+    ) -> Result<Ingested> {
+        if let Message::Deal(pvss) = payload {
+            let fingerprint = pvss.fingerprint()?;
+            if let Some(held) = self.vss.get(&sender.address) {
+                return if held.fingerprint()? == fingerprint {
+                    Ok(Ingested::Duplicate)
+                } else {
+                    Err(Error::ConflictingTranscript(sender.address.clone()))
+                };
+            }
+            let key = (sender.address.clone(), fingerprint);
+            if self.transcript_cache.is_rejected(&key) {
+                return Err(Error::KnownInvalidTranscript(
+                    sender.address.clone(),
+                ));
+            }
+            if let Err(err) = self.verify_message(sender, payload) {
+                self.transcript_cache.reject(key, &err);
+                return Err(err);
+            }
+        } else {
+            self.verify_message(sender, payload)?;
+        }
+        self.apply_message(sender, payload)?;
+        Ok(Ingested::Applied)
+    }
+
+    /// The positions in `transcripts` of the transcripts that fail
+    /// [`PubliclyVerifiableSS::verify_full`], or the subgroup checks if they are strict.
+    /// Transcripts are checked with [`crate::find_invalid_transcripts`] if verification is
+    /// batched, one by one otherwise
+    pub fn invalid_transcripts(
+        &self,
+        transcripts: &[PubliclyVerifiableSS<E>],
+    ) -> Vec<usize> {
+        let mut invalid = if self.security.batch_verification {
+            crate::find_invalid_transcripts(transcripts, self)
+        } else {
+            transcripts
+                .iter()
+                .enumerate()
+                .filter(|(_, pvss)| !pvss.verify_full(self))
+                .map(|(position, _)| position)
+                .collect()
+        };
+        if self.security.strict_subgroup_checks {
+            invalid.extend(
+                transcripts
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, pvss)| pvss.check_subgroups().is_err())
+                    .map(|(position, _)| position),
+            );
+            invalid.sort_unstable();
+            invalid.dedup();
+        }
+        invalid
+    }
+
+    fn verify_deal(
+        &self,
+        sender: &Validator<E>,
+        pvss: &PubliclyVerifiableSS<E>,
+    ) -> Result<()> {
+        if !self.validators.contains_key(&sender.address) {
+            Err(Error::UnknownDealer(sender.clone().address))
+        } else if sender.is_observer() {
+            Err(Error::ObserverCannotDeal(sender.clone().address))
+        } else if self.excluded_dealers.contains(&sender.address) {
+            Err(Error::ExcludedDealer(sender.clone().address))
+        } else if self.vss.contains_key(&sender.address) {
+            Err(Error::DuplicateDealer(sender.clone().address))
+        } else if pvss.validator_set_hash != self.validator_set_hash() {
+            Err(Error::ValidatorSetMismatch)
+        } else if pvss.tau != self.dkg_params.tau() {
+            Err(Error::RitualMismatch(self.dkg_params.tau(), pvss.tau))
+        } else if pvss.domain.check(&self.domain).is_err() {
+            Err(Error::EvaluationDomainMismatch)
+        } else if let Err(e) =
+            pvss.check_degree(self.dkg_params.security_threshold())
+        {
+            Err(e)
+        } else if pvss.shares.len() != self.share_owners().len()
+            || (self.security.strict_subgroup_checks
+                && pvss.check_subgroups().is_err())
+            || !pvss.verify_optimistic_with_params(
+                &sender.address,
+                &self.pvss_params,
+            )
+        {
+            Err(Error::InvalidPvssTranscript)
+        } else {
+            pvss.check_dealer(&sender.address)
+        }
+    }
+
     /// After consensus has agreed to include a verified
     /// message on the blockchain, we apply the chains
-    /// to the state machine
+    /// to the state machine.
+    ///
+    /// Transcripts are rejected with [`Error::UnsignedTranscript`] if they must be signed, see
+    /// [`PubliclyVerifiableDkg::apply_signed_transcript`] instead
     pub fn apply_message(
         &mut self,
         sender: &Validator<E>,
         payload: &Message<E>,
+    ) -> Result<()> {
+        if matches!(payload, Message::Deal(_))
+            && self.security.require_signed_transcripts
+        {
+            return Err(Error::UnsignedTranscript);
+        }
+        self.apply_authenticated_message(sender, payload)
+    }
+
+    /// Same as [`PubliclyVerifiableDkg::apply_message`], for a message whose sender is
+    /// already authenticated, see [`PubliclyVerifiableDkg::verify_authenticated_message`]
+    fn apply_authenticated_message(
+        &mut self,
+        sender: &Validator<E>,
+        payload: &Message<E>,
     ) -> Result<()> {
         match payload {
             Message::Deal(pvss)
@@ -262,12 +1199,21 @@ impl<E: Pairing> PubliclyVerifiableDkg<E> {
                 if !self.validators.contains_key(&sender.address) {
                     return Err(Error::UnknownDealer(sender.clone().address));
                 }
+                if sender.is_observer() {
+                    return Err(Error::ObserverCannotDeal(
+                        sender.clone().address,
+                    ));
+                }
+                pvss.check_dealer(&sender.address)?;
 
                 // TODO: Throw error instead of silently accepting excess shares?
                 // if self.vss.len() < self.dkg_params.shares_num as usize {
                 //     self.vss.insert(sender.address.clone(), pvss.clone());
                 // }
                 self.vss.insert(sender.address.clone(), pvss.clone());
+                self.observers.notify(|observer| {
+                    observer.on_transcript_received(sender, pvss)
+                });
 
                 // we keep track of the amount of shares seen until the security
                 // threshold is met. Then we may change the state of the DKG
@@ -279,22 +1225,72 @@ impl<E: Pairing> PubliclyVerifiableDkg<E> {
                     *accumulated_shares += 1;
                     if *accumulated_shares >= self.dkg_params.security_threshold
                     {
-                        self.state = DkgState::Dealt;
+                        self.set_state(DkgState::Dealt);
                     }
                 }
                 Ok(())
             }
-            Message::Aggregate(_) if matches!(self.state, DkgState::Dealt) => {
+            Message::Aggregate(Aggregation { vss, .. })
+                if matches!(self.state, DkgState::Dealt) =>
+            {
+                // Transcripts left out of the aggregate don't contribute to the key
+                if let Some(dealers) = vss.contributors() {
+                    self.vss.retain(|dealer, _| dealers.contains(dealer));
+                    self.dealer_commitments
+                        .retain(|dealer, _| dealers.contains(dealer));
+                }
                 // change state and cache the final key
-                self.state = DkgState::Success {
-                    public_key: self.public_key(),
-                };
+                let public_key = self.public_key();
+                self.set_state(DkgState::Success { public_key });
+                self.observers.notify(|observer| {
+                    observer.on_aggregation_complete(&public_key)
+                });
+                match self.retention {
+                    RetentionPolicy::KeepTranscripts => Ok(()),
+                    RetentionPolicy::KeepCommitments => {
+                        self.prune_transcripts()
+                    }
+                }
+            }
+            Message::Complaint(complaint)
+                if matches!(
+                    self.state,
+                    DkgState::Sharing { .. } | DkgState::Dealt
+                ) =>
+            {
+                self.exclude_dealer(&complaint.dealer);
                 Ok(())
             }
             _ => Err(Error::InvalidDkgStateToIngest),
         }
     }
 
+    /// Drop the transcript of `dealer` and reject any other transcript from it, e.g. once a
+    /// [`Complaint`] exposed it. The DKG goes back to sharing if the remaining transcripts
+    /// don't meet the security threshold anymore. A finished ritual keeps its state
+    pub fn exclude_dealer(&mut self, dealer: &EthereumAddress) {
+        self.vss.remove(dealer);
+        self.excluded_dealers.insert(dealer.clone());
+        let accumulated_shares = self.vss.len() as u32;
+        let state = match self.state {
+            DkgState::Sharing { block, .. } => DkgState::Sharing {
+                accumulated_shares,
+                block,
+            },
+            DkgState::Dealt
+                if accumulated_shares
+                    < self.dkg_params.security_threshold() =>
+            {
+                DkgState::Sharing {
+                    accumulated_shares,
+                    block: 0,
+                }
+            }
+            _ => return,
+        };
+        self.set_state(state);
+    }
+
     pub fn deal(
         &mut self,
         sender: &Validator<E>,
@@ -331,15 +1327,88 @@ pub struct Aggregation<E: Pairing> {
 pub enum Message<E: Pairing> {
     Deal(PubliclyVerifiableSS<E>),
     Aggregate(Aggregation<E>),
+    Complaint(Complaint<E>),
+}
+
+impl<E: Pairing> Message<E> {
+    /// The ritual the message claims to belong to, or `None` for complaints, which are only
+    /// bound to the transcript they accuse
+    pub fn ritual_id(&self) -> Option<u32> {
+        match self {
+            Message::Deal(pvss) => Some(pvss.tau),
+            Message::Aggregate(Aggregation { vss, .. }) => Some(vss.tau),
+            Message::Complaint(_) => None,
+        }
+    }
+}
+
+/// The network layer carrying the messages of a DKG between its validators, e.g. libp2p
+/// gossip, a Tendermint application or channels in tests. See [`run_dkg`]
+pub trait Transport<E: Pairing> {
+    /// The validator this transport sends messages as
+    fn identity(&self) -> &Validator<E>;
+
+    /// Send `message` to every other validator. Delivery may be delayed, reordered or
+    /// duplicated
+    fn broadcast(&mut self, message: &Message<E>) -> Result<()>;
+
+    /// The next message received, with the validator that sent it, or `None` if nothing
+    /// arrived before the transport timed out. The transport is trusted to authenticate the
+    /// sender
+    fn receive(&mut self) -> Result<Option<(Validator<E>, Message<E>)>>;
+}
+
+/// How [`run_dkg`] retries when its transport goes quiet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How many times in a row the driver re-broadcasts its transcript when nothing is
+    /// received, before it stops waiting for the missing dealers
+    pub max_retries: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_retries: 3 }
+    }
+}
+
+/// Run a DKG over `transport` as the validator it identifies, until an aggregate is applied,
+/// and return the finalized DKG. See [`DkgDriver`] for how messages are handled.
+#[cfg(feature = "dkg")]
+pub fn run_dkg<E: Pairing, T: Transport<E>, R: RngCore>(
+    transport: &mut T,
+    validators: &[Validator<E>],
+    params: &DkgParams,
+    retry: &RetryPolicy,
+    rng: &mut R,
+) -> Result<PubliclyVerifiableDkg<E>> {
+    let me = transport.identity().clone();
+    let mut driver = DkgDriver::new(validators, params, &me, retry, rng)?;
+    if let Some(deal) = driver.deal() {
+        transport.broadcast(deal)?;
+    }
+    while driver.is_dealing() {
+        let received = transport.receive()?;
+        if let Some(message) = driver.handle(received)? {
+            transport.broadcast(&message)?;
+        }
+    }
+    if let Some(aggregate) = driver.finish()? {
+        transport.broadcast(&aggregate)?;
+    }
+    Ok(driver.into_dkg())
 }
 
 /// Test initializing DKG
-#[cfg(test)]
+#[cfg(all(test, feature = "dkg"))]
 mod test_dkg_init {
+    use ferveo_common::SecurityConfig;
+
     use crate::{
+        check_share_indices,
         dkg::{PubliclyVerifiableDkg, Validator},
         test_common::*,
-        DkgParams,
+        DkgParams, DkgParamsViolation, Error, Message, ValidatorMetadata,
     };
 
     /// Test that dkg fails to start if the `me` input
@@ -353,6 +1422,9 @@ mod test_dkg_init {
             address: gen_address((SHARES_NUM + 1) as usize),
             public_key: unknown_keypair.public_key(),
             share_index: SHARES_NUM + 5, // Not in the validator set
+            weight: 1,
+            metadata: Default::default(),
+            signing_key: None,
         };
         let err = PubliclyVerifiableDkg::<E>::new(
             &gen_validators(&known_keypairs),
@@ -363,14 +1435,226 @@ mod test_dkg_init {
 
         assert_eq!(err.to_string(), "Expected validator to be a part of the DKG validator set: 0x0000000000000000000000000000000000000005")
     }
+
+    /// Test that dkg fails to start with more shares than the security configuration allows
+    #[test]
+    fn test_dkg_fail_cohort_too_large() {
+        let validators = gen_validators(&gen_keypairs(SHARES_NUM));
+        let dkg_params =
+            DkgParams::new(TAU, SECURITY_THRESHOLD, SHARES_NUM).unwrap();
+        let security = SecurityConfig {
+            max_cohort_size: SHARES_NUM - 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            PubliclyVerifiableDkg::<E>::new_with_security(
+                &validators,
+                &dkg_params,
+                &validators[0],
+                security,
+            ),
+            Err(Error::CohortTooLarge(SHARES_NUM, _))
+        ));
+        let dkg = PubliclyVerifiableDkg::<E>::new_with_security(
+            &validators,
+            &dkg_params,
+            &validators[0],
+            SecurityConfig::relaxed_for_benchmarks(),
+        )
+        .unwrap();
+        assert_eq!(dkg.security, SecurityConfig::relaxed_for_benchmarks());
+    }
+
+    /// Test that dkg fails to start unless the validators hold exactly the shares of the
+    /// ritual
+    #[test]
+    fn test_dkg_fail_shares_not_covered() {
+        let mut validators = gen_validators(&gen_keypairs(SHARES_NUM));
+        let dkg_params =
+            DkgParams::new(TAU, SECURITY_THRESHOLD, SHARES_NUM).unwrap();
+
+        // The last share has no holder
+        let missing = validators.pop().unwrap();
+        assert!(matches!(
+            PubliclyVerifiableDkg::<E>::new(
+                &validators,
+                &dkg_params,
+                &validators[0]
+            ),
+            Err(Error::DkgParamsViolations(violations))
+                if violations == [DkgParamsViolation::WeightMismatch(SHARES_NUM - 1, SHARES_NUM)]
+        ));
+
+        // Unless another validator holds it
+        let last = validators.pop().unwrap().with_weight(2);
+        validators.push(last);
+        assert!(PubliclyVerifiableDkg::<E>::new(
+            &validators,
+            &dkg_params,
+            &validators[0]
+        )
+        .is_ok());
+
+        // Share indices past `u32::MAX` are rejected
+        let share_index = missing.share_index;
+        validators.push(missing.with_weight(u32::MAX));
+        assert!(matches!(
+            PubliclyVerifiableDkg::<E>::new(
+                &validators,
+                &dkg_params,
+                &validators[0]
+            ),
+            Err(Error::InvalidShareIndex(index)) if index == share_index
+        ));
+    }
+
+    /// Test that nodes derive the same share indices from the validator set alone
+    #[test]
+    fn test_dkg_canonical_share_indices() {
+        let validators = gen_validators(&gen_keypairs(SHARES_NUM));
+        let dkg_params =
+            DkgParams::new(TAU, SECURITY_THRESHOLD, SHARES_NUM).unwrap();
+        let dkg = PubliclyVerifiableDkg::<E>::with_canonical_share_indices(
+            &validators,
+            &dkg_params,
+            &validators[1].address,
+        )
+        .unwrap();
+        let assigned = dkg.validators.values().cloned().collect::<Vec<_>>();
+        assert!(check_share_indices(&assigned).is_ok());
+        assert_eq!(dkg.me, dkg.validators[&validators[1].address]);
+
+        // Transcripts are dealt over the canonical indices
+        let mut dealer = dkg.clone();
+        let pvss = match dealer.share(&mut ark_std::test_rng()).unwrap() {
+            Message::Deal(pvss) => pvss,
+            _ => panic!("Expected Deal"),
+        };
+        assert!(pvss.verify_full(&dkg));
+
+        // The order and the given indices of the validators don't matter
+        let mut shuffled = validators.clone();
+        shuffled.reverse();
+        shuffled
+            .iter_mut()
+            .for_each(|validator| validator.share_index = 0);
+        let other = PubliclyVerifiableDkg::<E>::with_canonical_share_indices(
+            &shuffled,
+            &dkg_params,
+            &validators[2].address,
+        )
+        .unwrap();
+        assert_eq!(other.validators, dkg.validators);
+        assert_eq!(other.validator_set_hash(), dkg.validator_set_hash());
+
+        // Indices assigned in another order are rejected
+        let (first, second) =
+            (assigned[0].share_index, assigned[1].share_index);
+        let mut swapped = assigned;
+        swapped[0].share_index = second;
+        swapped[1].share_index = first;
+        assert!(matches!(
+            check_share_indices(&swapped),
+            Err(Error::NonCanonicalShareIndex(..))
+        ));
+    }
+
+    /// Test that validators encoded before weights decode with the legacy decoder only
+    #[test]
+    fn test_dkg_legacy_validator_encoding() {
+        let (dkg, _) = setup_dkg(0);
+        let validator = dkg.validators.values().nth(1).unwrap().clone();
+        // The encoding of the validators before weights, i.e. of their address, public
+        // key and share index
+        let legacy = bincode::serialize(&(
+            &validator.address,
+            &validator.public_key,
+            validator.share_index,
+        ))
+        .unwrap();
+        // Legacy validators didn't announce a signing key
+        assert_eq!(
+            Validator::<E>::from_legacy_bytes(&legacy).unwrap(),
+            Validator {
+                signing_key: None,
+                ..validator.clone()
+            }
+        );
+        assert!(bincode::deserialize::<Validator<E>>(&legacy).is_err());
+
+        let weighted = validator.with_weight(2);
+        let bytes = bincode::serialize(&weighted).unwrap();
+        assert_eq!(
+            bincode::deserialize::<Validator<E>>(&bytes).unwrap(),
+            weighted
+        );
+        assert!(Validator::<E>::from_legacy_bytes(&bytes).is_err());
+    }
+
+    /// Test that validator metadata is carried along, but doesn't change the cohort
+    #[test]
+    fn test_dkg_validator_metadata() {
+        let (dkg, _) = setup_dkg(0);
+        let mut validators =
+            dkg.validators.values().cloned().collect::<Vec<_>>();
+        assert_eq!(
+            validators[1].to_string(),
+            validators[1].address.to_string()
+        );
+        validators[1].metadata = ValidatorMetadata {
+            operator: Some(gen_address(SHARES_NUM as usize)),
+            endpoint: Some("https://node-1.example".to_string()),
+            label: Some("node-1".to_string()),
+        };
+        assert_eq!(
+            validators[1].to_string(),
+            format!("node-1 ({})", validators[1].address)
+        );
+        let labeled =
+            PubliclyVerifiableDkg::new(&validators, &dkg.dkg_params, &dkg.me)
+                .unwrap();
+        assert_eq!(labeled.validator_set_hash(), dkg.validator_set_hash());
+        let restored =
+            PubliclyVerifiableDkg::<E>::restore(&labeled.to_bytes().unwrap())
+                .unwrap();
+        assert_eq!(
+            restored.validators[&validators[1].address].metadata,
+            validators[1].metadata
+        );
+    }
+
+    /// Test that share indices map to the domain points of the transcripts
+    #[test]
+    fn test_dkg_domain_points() {
+        let (dkg, _) = setup_dealt_dkg();
+        let points = dkg.domain_points();
+        assert_eq!(points.len(), SHARES_NUM as usize);
+        let aggregate =
+            crate::aggregate(&dkg.vss.values().cloned().collect::<Vec<_>>())
+                .unwrap();
+        assert_eq!(points, aggregate.domain_points());
+        for (share_index, point) in points.iter().enumerate() {
+            assert_eq!(&dkg.domain_point(share_index as u32).unwrap(), point);
+        }
+        assert!(matches!(
+            dkg.domain_point(SHARES_NUM),
+            Err(Error::InvalidShareIndex(SHARES_NUM))
+        ));
+    }
 }
 
 /// Test the dealing phase of the DKG
-#[cfg(test)]
+#[cfg(all(test, feature = "dkg"))]
 mod test_dealing {
+    use ark_bls12_381::Fq2;
     use ark_ec::AffineRepr;
+    use ark_ff::One;
+    use ferveo_common::SecurityConfig;
 
-    use crate::{test_common::*, DkgState, DkgState::Dealt, Validator};
+    use crate::{
+        test_common::*, DkgState, DkgState::Dealt, DomainDescriptor, Error,
+        Ingested, Message, PubliclyVerifiableDkg, ShareEncryptions, Validator,
+    };
 
     /// Test that dealing correct PVSS transcripts
     /// pass verification an application and that
@@ -437,6 +1721,9 @@ mod test_dealing {
             address: gen_address(unknown_validator_i as usize),
             public_key: ferveo_common::Keypair::<E>::new(rng).public_key(),
             share_index: dkg.dkg_params.shares_num + 5, // Not in the validator set
+            weight: 1,
+            metadata: Default::default(),
+            signing_key: None,
         };
         // check that verification fails
         assert!(dkg.verify_message(&sender, &pvss).is_err());
@@ -452,6 +1739,33 @@ mod test_dealing {
         ));
     }
 
+    /// Test that transcripts name their dealer, and are rejected if sent by another
+    /// validator
+    #[test]
+    fn test_pvss_from_other_dealer_rejected() {
+        let rng = &mut ark_std::test_rng();
+        let (mut dkg, _) = setup_dkg(0);
+        let pvss = dkg.share(rng).unwrap();
+        let transcript = match &pvss {
+            Message::Deal(transcript) => transcript,
+            _ => panic!("Expected Deal"),
+        };
+        assert_eq!(transcript.dealer(), Some(dkg.me.address.clone()));
+
+        // A relay can't pass the transcript off as another validator's
+        let other = dkg.validators[&gen_address(1)].clone();
+        assert!(matches!(
+            dkg.apply_message(&other, &pvss),
+            Err(Error::DealerMismatch(sender, dealer))
+                if sender == other.address && dealer == dkg.me.address
+        ));
+        assert!(dkg.vss.is_empty());
+        assert!(dkg.verify_message(&other, &pvss).is_err());
+
+        let sender = dkg.me.clone();
+        assert!(dkg.apply_message(&sender, &pvss).is_ok());
+    }
+
     /// Test that if a validator sends two pvss transcripts,
     /// the second fails to verify
     #[test]
@@ -487,6 +1801,119 @@ mod test_dealing {
         assert!(dkg.verify_message(&sender, &pvss).is_err());
     }
 
+    /// Test that gossiped transcripts are deduplicated, and verified at most once
+    #[test]
+    fn test_ingest_gossiped_transcripts() {
+        let rng = &mut ark_std::test_rng();
+        let (mut dkg, _) = setup_dkg(0);
+        let (mut dealer_dkg, _) = setup_dkg(1);
+        let dealer = dealer_dkg.me.clone();
+        let pvss = dealer_dkg.share(rng).unwrap();
+
+        // Copies relayed by other peers are dropped
+        assert_eq!(dkg.ingest(&dealer, &pvss).unwrap(), Ingested::Applied);
+        assert_eq!(dkg.ingest(&dealer, &pvss).unwrap(), Ingested::Duplicate);
+        assert_eq!(dkg.vss.len(), 1);
+
+        // Another transcript from the same dealer is a double-deal
+        let other = dealer_dkg.share(rng).unwrap();
+        assert!(matches!(
+            dkg.ingest(&dealer, &other),
+            Err(Error::ConflictingTranscript(address)) if address == dealer.address
+        ));
+
+        // A transcript sent by another validator than its dealer is only verified once
+        let (mut sender_dkg, _) = setup_dkg(2);
+        let sender = sender_dkg.me.clone();
+        let (mut third_dkg, _) = setup_dkg(3);
+        let misattributed = third_dkg.share(rng).unwrap();
+        assert!(dkg.ingest(&sender, &misattributed).is_err());
+        assert!(matches!(
+            dkg.ingest(&sender, &misattributed),
+            Err(Error::KnownInvalidTranscript(address)) if address == sender.address
+        ));
+        let own = sender_dkg.share(rng).unwrap();
+        assert_eq!(dkg.ingest(&sender, &own).unwrap(), Ingested::Applied);
+        assert_eq!(dkg.vss.len(), 2);
+    }
+
+    /// Test that a transcript dealt for another validator set is rejected,
+    /// even if the encryption keys of both validator sets coincide by index
+    #[test]
+    fn test_pvss_from_other_cohort_rejected() {
+        let rng = &mut ark_std::test_rng();
+        let (mut cohort_a, keypairs) = setup_dkg(0);
+        let mut validators =
+            cohort_a.validators.values().cloned().collect::<Vec<_>>();
+        validators.last_mut().unwrap().address = gen_address(42);
+        let cohort_b = PubliclyVerifiableDkg::<E>::new(
+            &validators,
+            &cohort_a.dkg_params,
+            &validators[0],
+        )
+        .unwrap();
+        assert_eq!(keypairs.len(), cohort_b.validators.len());
+        assert_ne!(
+            cohort_a.validator_set_hash(),
+            cohort_b.validator_set_hash()
+        );
+
+        let sender = cohort_a.me.clone();
+        let message = cohort_a.share(rng).unwrap();
+        assert!(cohort_a.verify_message(&sender, &message).is_ok());
+        assert!(matches!(
+            cohort_b.verify_message(&sender, &message),
+            Err(Error::ValidatorSetMismatch)
+        ));
+
+        // Relabeling the transcript breaks its proof of knowledge
+        let mut pvss = match message {
+            Message::Deal(pvss) => pvss,
+            _ => panic!("Expected Deal"),
+        };
+        pvss.validator_set_hash = cohort_b.validator_set_hash();
+        assert!(matches!(
+            cohort_b.verify_message(&sender, &Message::Deal(pvss)),
+            Err(Error::InvalidPvssTranscript)
+        ));
+    }
+
+    /// Test that a transcript dealt over another evaluation domain is rejected
+    #[test]
+    fn test_pvss_from_other_domain_rejected() {
+        let rng = &mut ark_std::test_rng();
+        let (mut dkg, _) = setup_dkg(0);
+        let sender = dkg.me.clone();
+        let mut pvss = match dkg.share(rng).unwrap() {
+            Message::Deal(pvss) => pvss,
+            _ => panic!("Expected Deal"),
+        };
+        pvss.domain =
+            DomainDescriptor::for_shares(2 * SHARES_NUM as usize).unwrap();
+        assert!(matches!(
+            dkg.verify_message(&sender, &Message::Deal(pvss)),
+            Err(Error::EvaluationDomainMismatch)
+        ));
+    }
+
+    /// Test that transcripts are checked as the security configuration of the DKG sets
+    #[test]
+    fn test_invalid_transcripts_with_security_config() {
+        let (mut dkg, _) = setup_dealt_dkg();
+        let mut transcripts = dkg.vss.values().cloned().collect::<Vec<_>>();
+        assert!(dkg.invalid_transcripts(&transcripts).is_empty());
+
+        transcripts[1].shares[0] =
+            ShareEncryptions::new(G2::generator()).unwrap();
+        // The proof of knowledge isn't part of the full verification
+        transcripts[2].sigma = G2::new_unchecked(Fq2::one(), Fq2::one());
+        assert_eq!(dkg.invalid_transcripts(&transcripts), [1, 2]);
+        dkg.security = SecurityConfig::relaxed_for_benchmarks();
+        assert_eq!(dkg.invalid_transcripts(&transcripts), [1]);
+        dkg.security.batch_verification = false;
+        assert_eq!(dkg.invalid_transcripts(&transcripts), [1]);
+    }
+
     /// Test that if a validators tries to verify it's own
     /// share message, it passes
     #[test]
@@ -583,11 +2010,110 @@ mod test_dealing {
 }
 
 /// Test aggregating transcripts into final key
-#[cfg(test)]
+#[cfg(all(test, feature = "dkg"))]
 mod test_aggregation {
     use ark_ec::AffineRepr;
 
-    use crate::{dkg::*, test_common::*, DkgState, Message};
+    use crate::{dkg::*, test_common::*, DkgState, Error, Message};
+
+    #[test]
+    fn test_restore_mid_ritual() {
+        let rng = &mut ark_std::test_rng();
+        let (mut dkg, _) = setup_dkg(0);
+        let messages = (0..SHARES_NUM as usize)
+            .map(|i| {
+                let (mut dkg, _) = setup_dkg(i);
+                (dkg.me.clone(), dkg.share(rng).unwrap())
+            })
+            .collect::<Vec<_>>();
+        let (sender, message) = &messages[0];
+        dkg.apply_message(sender, message).unwrap();
+
+        // The restored DKG goes on where the persisted one stopped
+        let mut restored =
+            PubliclyVerifiableDkg::<E>::restore(&dkg.to_bytes().unwrap())
+                .unwrap();
+        assert_eq!(restored.vss, dkg.vss);
+        assert_eq!(restored.validators, dkg.validators);
+        assert_eq!(restored.domain, dkg.domain);
+        assert!(matches!(
+            restored.state,
+            DkgState::Sharing {
+                accumulated_shares: 1,
+                ..
+            }
+        ));
+        assert!(matches!(
+            restored.verify_message(sender, message),
+            Err(Error::DuplicateDealer(_))
+        ));
+        for (sender, message) in &messages[1..] {
+            restored.verify_message(sender, message).unwrap();
+            restored.apply_message(sender, message).unwrap();
+        }
+        let aggregate = restored.aggregate().unwrap();
+        let mut restored =
+            PubliclyVerifiableDkg::<E>::restore(&restored.to_bytes().unwrap())
+                .unwrap();
+        restored.verify_message(sender, &aggregate).unwrap();
+        restored.apply_message(sender, &aggregate).unwrap();
+        assert!(matches!(restored.state, DkgState::Success { .. }));
+
+        assert!(PubliclyVerifiableDkg::<E>::restore(&[]).is_err());
+    }
+
+    #[test]
+    fn test_rebuild_from_messages() {
+        let rng = &mut ark_std::test_rng();
+        let mut history = (0..SHARES_NUM as usize)
+            .map(|i| {
+                let (mut dkg, _) = setup_dkg(i);
+                (dkg.me.clone(), dkg.share(rng).unwrap())
+            })
+            .collect::<Vec<_>>();
+        // A retransmitted transcript, and a transcript the chain included but that was
+        // rejected when it was received
+        history.insert(2, history[0].clone());
+        let (sender, mut invalid) = history[1].clone();
+        if let Message::Deal(pvss) = &mut invalid {
+            pvss.shares.pop();
+        }
+        history.insert(1, (sender, invalid));
+        let (validators, me) = {
+            let (dkg, _) = setup_dkg(0);
+            (dkg.validators.values().cloned().collect::<Vec<_>>(), dkg.me)
+        };
+        let params =
+            DkgParams::new(TAU, SECURITY_THRESHOLD, SHARES_NUM).unwrap();
+
+        // A crash in the middle of dealing
+        let mut dkg = PubliclyVerifiableDkg::<E>::from_messages(
+            &validators,
+            &params,
+            &me,
+            &history[..2],
+        )
+        .unwrap();
+        assert_eq!(dkg.vss.len(), 1);
+        assert_eq!(dkg.replay(&history[2..]).unwrap(), SHARES_NUM as usize - 1);
+        assert_eq!(dkg.vss.len(), SHARES_NUM as usize);
+
+        // Every validator posts the same aggregate, the first one ends the ritual
+        let aggregate = dkg.aggregate().unwrap();
+        history
+            .extend(validators.iter().map(|v| (v.clone(), aggregate.clone())));
+        let rebuilt = PubliclyVerifiableDkg::<E>::from_messages(
+            &validators,
+            &params,
+            &me,
+            &history,
+        )
+        .unwrap();
+        assert!(matches!(rebuilt.state, DkgState::Success { .. }));
+        assert_eq!(rebuilt.vss, dkg.vss);
+        dkg.apply_message(&me, &aggregate).unwrap();
+        assert_eq!(rebuilt.public_key(), dkg.public_key());
+    }
 
     /// Test that if the security threshold is
     /// met, we can create a final key
@@ -601,6 +2127,337 @@ mod test_aggregation {
         assert!(matches!(dkg.state, DkgState::Success { .. }));
     }
 
+    /// Test that the transcripts are pruned to their commitments once aggregated, and that
+    /// the aggregate still verifies against them
+    #[test]
+    fn test_aggregate_with_retention_policy() {
+        let (dkg, _) = setup_dealt_dkg();
+        let mut dkg =
+            dkg.with_retention_policy(RetentionPolicy::KeepCommitments);
+        let public_key = dkg.public_key();
+        let message = dkg.aggregate().unwrap();
+        let sender = dkg.me.clone();
+        dkg.verify_message(&sender, &message).unwrap();
+        dkg.apply_message(&sender, &message).unwrap();
+
+        assert!(dkg.vss.is_empty());
+        assert_eq!(dkg.dealer_commitments.len(), SHARES_NUM as usize);
+        assert_eq!(dkg.public_key(), public_key);
+        let aggregate = match message {
+            Message::Aggregate(Aggregation { vss, .. }) => vss,
+            _ => panic!("Expected Aggregate"),
+        };
+        assert!(aggregate.verify_aggregation(&dkg).unwrap());
+        let mut forged = aggregate;
+        forged.sigma = G2::generator();
+        assert!(forged.verify_aggregation(&dkg).is_err());
+    }
+
+    /// Test that the phase, the received transcripts and the missing dealers follow the
+    /// progress of the DKG
+    #[test]
+    fn test_progress_queries() {
+        let rng = &mut ark_std::test_rng();
+        let (mut dkg, _) = setup_dkg(0);
+        let messages = (0..SHARES_NUM as usize)
+            .map(|i| {
+                let (mut dkg, _) = setup_dkg(i);
+                (dkg.me.clone(), dkg.share(rng).unwrap())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(dkg.phase(), DkgPhase::Sharing);
+        assert!(dkg.received_transcripts().is_empty());
+        assert_eq!(dkg.missing_dealers().len(), SHARES_NUM as usize);
+
+        for (dealt, (sender, message)) in messages.iter().enumerate() {
+            assert!(!dkg.is_ready_to_aggregate());
+            dkg.apply_message(sender, message).unwrap();
+            assert_eq!(dkg.received_transcripts().len(), dealt + 1);
+            assert!(dkg.received_transcripts().contains(&&sender.address));
+            assert_eq!(
+                dkg.missing_dealers().len(),
+                SHARES_NUM as usize - dealt - 1
+            );
+            assert!(!dkg
+                .missing_dealers()
+                .iter()
+                .any(|dealer| dealer.address == sender.address));
+            if dealt + 1 == SECURITY_THRESHOLD as usize {
+                assert_eq!(dkg.phase(), DkgPhase::Dealt);
+            }
+        }
+        assert!(dkg.is_ready_to_aggregate());
+
+        // Pruned transcripts still count as received
+        let message = dkg.aggregate().unwrap();
+        let mut dkg =
+            dkg.with_retention_policy(RetentionPolicy::KeepCommitments);
+        let me = dkg.me.clone();
+        dkg.apply_message(&me, &message).unwrap();
+        assert_eq!(dkg.phase(), DkgPhase::Success);
+        assert!(dkg.vss.is_empty());
+        assert_eq!(dkg.received_transcripts().len(), SHARES_NUM as usize);
+        assert!(dkg.missing_dealers().is_empty());
+        assert!(!dkg.is_ready_to_aggregate());
+    }
+
+    /// Test that an aggregate of the transcripts of some dealers verifies at a validator
+    /// that received more of them, and finalizes the key of those dealers
+    #[test]
+    fn test_aggregate_with_dropouts() {
+        let rng = &mut ark_std::test_rng();
+        let messages = (0..SHARES_NUM as usize)
+            .map(|i| {
+                let (mut dkg, _) = setup_dkg(i);
+                (dkg.me.clone(), dkg.share(rng).unwrap())
+            })
+            .collect::<Vec<_>>();
+        let setup = |my_index, dealt: usize| {
+            let (dkg, _) = setup_dkg(my_index);
+            let mut dkg =
+                dkg.with_dropout_policy(DropoutPolicy::TolerateDropouts);
+            for (sender, message) in &messages[..dealt] {
+                dkg.apply_message(sender, message).unwrap();
+            }
+            dkg
+        };
+
+        // The aggregator only heard from the security threshold of dealers
+        let aggregator = setup(0, SECURITY_THRESHOLD as usize);
+        assert!(aggregator.dealing_complete());
+        let message = aggregator.aggregate().unwrap();
+        let aggregate = match &message {
+            Message::Aggregate(Aggregation { vss, .. }) => vss.clone(),
+            _ => panic!("Expected Aggregate"),
+        };
+        let contributors = aggregate.contributors().unwrap();
+        assert_eq!(
+            contributors,
+            aggregator.vss.keys().cloned().collect::<Vec<_>>()
+        );
+
+        // Another validator received every transcript
+        let mut dkg = setup(1, SHARES_NUM as usize);
+        assert!(aggregate.verify_aggregation(&dkg).unwrap());
+        dkg.verify_message(&aggregator.me, &message).unwrap();
+        dkg.apply_message(&aggregator.me, &message).unwrap();
+        assert_eq!(dkg.vss.len(), SECURITY_THRESHOLD as usize);
+        assert!(matches!(
+            dkg.state,
+            DkgState::Success { public_key } if public_key == aggregator.public_key()
+        ));
+
+        // Naming fewer dealers than the security threshold, or unknown ones, is rejected
+        let mut forged = aggregate.clone();
+        forged
+            .set_contributors(&contributors[1..].iter().cloned().collect())
+            .unwrap();
+        let dkg = setup(1, SHARES_NUM as usize);
+        assert!(matches!(
+            forged.verify_aggregation(&dkg),
+            Err(Error::InsufficientTranscriptsForAggregate(_, _))
+        ));
+        let mut forged = aggregate;
+        forged
+            .set_contributors(&[gen_address(SHARES_NUM as usize + 1)].into())
+            .unwrap();
+        assert!(forged.verify_aggregation(&dkg).is_err());
+
+        // Without the policy, a validator waits for every dealer
+        let (mut dkg, _) = setup_dkg(1);
+        for (sender, message) in &messages[..SECURITY_THRESHOLD as usize] {
+            dkg.apply_message(sender, message).unwrap();
+        }
+        assert!(!dkg.dealing_complete());
+    }
+
+    /// Test that an aggregate of the transcripts of some dealers is diagnosed against the
+    /// transcripts of those dealers only
+    #[test]
+    fn test_diagnose_aggregate_with_dropouts() {
+        let rng = &mut ark_std::test_rng();
+        let messages = (0..SHARES_NUM as usize)
+            .map(|i| {
+                let (mut dkg, _) = setup_dkg(i);
+                (dkg.me.clone(), dkg.share(rng).unwrap())
+            })
+            .collect::<Vec<_>>();
+        let setup = |my_index, dealt: usize| {
+            let (dkg, _) = setup_dkg(my_index);
+            let mut dkg =
+                dkg.with_dropout_policy(DropoutPolicy::TolerateDropouts);
+            for (sender, message) in &messages[..dealt] {
+                dkg.apply_message(sender, message).unwrap();
+            }
+            dkg
+        };
+        let aggregator = setup(0, SECURITY_THRESHOLD as usize);
+        let aggregate = match aggregator.aggregate().unwrap() {
+            Message::Aggregate(Aggregation { vss, .. }) => vss,
+            _ => panic!("Expected Aggregate"),
+        };
+        let contributors = aggregate.contributors().unwrap();
+
+        // The transcript of the dealer that was left out doesn't count, even if invalid
+        let mut dkg = setup(1, SHARES_NUM as usize);
+        assert!(aggregate.diagnose_aggregation(&dkg).is_clean());
+        let dropped = &messages[SHARES_NUM as usize - 1].0.address;
+        dkg.vss.get_mut(dropped).unwrap().sigma = G2::generator();
+        assert!(aggregate.diagnose_aggregation(&dkg).is_clean());
+
+        // A contributor whose transcript the validator didn't receive is reported
+        let dkg = setup(1, SECURITY_THRESHOLD as usize - 1);
+        let diagnosis = aggregate.diagnose_aggregation(&dkg);
+        assert_eq!(
+            diagnosis.missing_contributors,
+            contributors[SECURITY_THRESHOLD as usize - 1..]
+        );
+        assert!(diagnosis.offending_dealers.is_empty());
+        assert!(!diagnosis.aggregate_mismatch);
+    }
+
+    /// Test that an observer runs the DKG to its public key without dealing or holding shares
+    #[test]
+    fn test_observer_verifies_without_shares() {
+        let rng = &mut ark_std::test_rng();
+        let keypairs = gen_keypairs(SHARES_NUM + 1);
+        let mut validators = gen_validators(&keypairs);
+        let observer = validators.pop().unwrap().with_weight(0);
+        assert!(observer.is_observer());
+        assert_eq!(observer.share_indices().unwrap().len(), 0);
+        validators.push(observer.clone());
+        let params =
+            DkgParams::new(TAU, SECURITY_THRESHOLD, SHARES_NUM).unwrap();
+        let setup = |me: &Validator<E>| {
+            PubliclyVerifiableDkg::<E>::new(&validators, &params, me).unwrap()
+        };
+
+        let mut dkg = setup(&observer);
+        assert!(matches!(
+            dkg.share(rng),
+            Err(Error::ObserverCannotDeal(address)) if address == observer.address
+        ));
+        assert_eq!(dkg.dealers().count(), SHARES_NUM as usize);
+        let messages = validators[..SHARES_NUM as usize]
+            .iter()
+            .map(|validator| {
+                (validator.clone(), setup(validator).share(rng).unwrap())
+            })
+            .collect::<Vec<_>>();
+        for (sender, message) in &messages {
+            dkg.verify_message(sender, message).unwrap();
+            dkg.apply_message(sender, message).unwrap();
+        }
+        assert!(dkg.dealing_complete());
+        assert_eq!(dkg.share_owners().len(), SHARES_NUM as usize);
+
+        // Transcripts from the observer are rejected, even if dealt for it
+        let (_, message) = &messages[0];
+        assert!(matches!(
+            dkg.verify_message(&observer, message),
+            Err(Error::ObserverCannotDeal(_))
+        ));
+
+        // It verifies the aggregate of a dealer and exports the public key
+        let mut dealer = setup(&validators[0]);
+        for (sender, message) in &messages {
+            dealer.apply_message(sender, message).unwrap();
+        }
+        let aggregate = dealer.aggregate().unwrap();
+        dkg.verify_message(&validators[0], &aggregate).unwrap();
+        dkg.apply_message(&validators[0], &aggregate).unwrap();
+        assert!(matches!(
+            dkg.state,
+            DkgState::Success { public_key } if public_key == dealer.public_key()
+        ));
+    }
+
+    /// Test that a DKG dealt over a mixed-radix domain of exactly as many points as shares
+    /// verifies and aggregates like a radix-2 one
+    #[test]
+    fn test_aggregate_over_compact_domain() {
+        let rng = &mut ark_std::test_rng();
+        let shares_num = 6;
+        let security_threshold = 4;
+        let setup = |my_index| {
+            let (dkg, _) = setup_dkg_for_n_validators(
+                security_threshold,
+                shares_num,
+                my_index,
+            );
+            dkg.with_domain_policy(DomainPolicy::Compact).unwrap()
+        };
+        let mut dkg = setup(0);
+        assert_eq!(dkg.domain.size(), shares_num as usize);
+        let (padded, _) =
+            setup_dkg_for_n_validators(security_threshold, shares_num, 0);
+        assert_eq!(padded.domain.size(), 8);
+
+        for my_index in 0..shares_num as usize {
+            let mut dealer = setup(my_index);
+            let sender = dealer.me.clone();
+            let message = dealer.share(rng).unwrap();
+            let pvss = match &message {
+                Message::Deal(pvss) => pvss.clone(),
+                _ => panic!("Expected Deal"),
+            };
+            assert_eq!(pvss.shares.len(), shares_num as usize);
+            assert!(pvss.verify_full(&dkg));
+            assert!(!pvss.verify_full(&padded));
+            dkg.verify_message(&sender, &message).unwrap();
+            dkg.apply_message(&sender, &message).unwrap();
+        }
+        let restored =
+            PubliclyVerifiableDkg::<E>::restore(&dkg.to_bytes().unwrap())
+                .unwrap();
+        assert_eq!(restored.domain, dkg.domain);
+
+        let aggregate = dkg.aggregate().unwrap();
+        let sender = dkg.me.clone();
+        dkg.verify_message(&sender, &aggregate).unwrap();
+        dkg.apply_message(&sender, &aggregate).unwrap();
+        assert!(matches!(dkg.state, DkgState::Success { .. }));
+    }
+
+    /// Test that a DKG with other generators deals, verifies and aggregates with them
+    #[test]
+    fn test_aggregate_with_custom_pvss_params() {
+        let rng = &mut ark_std::test_rng();
+        let pvss_params = PubliclyVerifiableParams::<E> {
+            g: G1::generator() * ScalarField::from(2u64),
+            h: G2::generator() * ScalarField::from(3u64),
+        };
+        let setup = |my_index| {
+            let (dkg, _) = setup_dkg(my_index);
+            dkg.with_pvss_params(pvss_params.clone())
+        };
+        let mut dkg = setup(0);
+        // The params are bound to the ritual, so transcripts can't be replayed across params
+        assert_ne!(
+            dkg.validator_set_hash(),
+            setup_dkg(0).0.validator_set_hash()
+        );
+        for my_index in 0..SHARES_NUM as usize {
+            let mut dealer = setup(my_index);
+            let sender = dealer.me.clone();
+            let message = dealer.share(rng).unwrap();
+            let pvss = match &message {
+                Message::Deal(pvss) => pvss,
+                _ => panic!("Expected Deal"),
+            };
+            // The default generators don't verify the proof of knowledge
+            assert!(!pvss.verify_optimistic(&sender.address));
+            assert!(pvss
+                .verify_optimistic_with_params(&sender.address, &pvss_params));
+            dkg.apply_message(&sender, &message).unwrap();
+        }
+        let aggregate = dkg.aggregate().unwrap();
+        let sender = dkg.me.clone();
+        dkg.verify_message(&sender, &aggregate).unwrap();
+        dkg.apply_message(&sender, &aggregate).unwrap();
+        assert!(matches!(dkg.state, DkgState::Success { .. }));
+    }
+
     /// Test that aggregate only succeeds if we are in
     /// the state [`DkgState::Dealt]
     #[test]
@@ -670,10 +2527,174 @@ mod test_aggregation {
     }
 }
 
+/// Test running the DKG over a transport, between validators on their own threads
+#[cfg(all(test, feature = "dkg"))]
+mod test_transport {
+    use std::{
+        sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        thread,
+        time::Duration,
+    };
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::{dkg::*, test_common::*};
+
+    type Envelope = (Validator<E>, Message<E>);
+
+    struct ChannelTransport {
+        me: Validator<E>,
+        peers: Vec<Sender<Envelope>>,
+        inbox: Receiver<Envelope>,
+    }
+
+    impl Transport<E> for ChannelTransport {
+        fn identity(&self) -> &Validator<E> {
+            &self.me
+        }
+
+        fn broadcast(&mut self, message: &Message<E>) -> Result<()> {
+            for peer in &self.peers {
+                // Peers that are done stop listening
+                peer.send((self.me.clone(), message.clone())).ok();
+            }
+            Ok(())
+        }
+
+        fn receive(&mut self) -> Result<Option<Envelope>> {
+            match self.inbox.recv_timeout(Duration::from_millis(100)) {
+                Ok(envelope) => Ok(Some(envelope)),
+                Err(RecvTimeoutError::Timeout) => Ok(None),
+                Err(RecvTimeoutError::Disconnected) => {
+                    Err(Error::TransportError("disconnected".to_string()))
+                }
+            }
+        }
+    }
+
+    /// Run a DKG between the first `online` validators, returning their public keys
+    fn run_validators(online: usize) -> Vec<Result<G1>> {
+        let validators = gen_validators(&gen_keypairs(SHARES_NUM));
+        let params =
+            DkgParams::new(TAU, SECURITY_THRESHOLD, SHARES_NUM).unwrap();
+        let (senders, inboxes): (Vec<_>, Vec<_>) =
+            (0..online).map(|_| channel::<Envelope>()).unzip();
+        let handles = inboxes
+            .into_iter()
+            .enumerate()
+            .map(|(i, inbox)| {
+                let mut transport = ChannelTransport {
+                    me: validators[i].clone(),
+                    peers: senders.clone(),
+                    inbox,
+                };
+                let validators = validators.clone();
+                thread::spawn(move || {
+                    let rng = &mut ChaCha20Rng::seed_from_u64(i as u64);
+                    run_dkg(
+                        &mut transport,
+                        &validators,
+                        &params,
+                        &RetryPolicy { max_retries: 2 },
+                        rng,
+                    )
+                    .map(|dkg| dkg.public_key())
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(senders);
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_run_dkg_over_channels() {
+        let public_keys = run_validators(SHARES_NUM as usize)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert!(public_keys.iter().all(|key| key == &public_keys[0]));
+    }
+
+    #[test]
+    fn test_run_dkg_without_missing_dealers() {
+        // The validators that are online meet the security threshold on their own
+        let public_keys = run_validators(SECURITY_THRESHOLD as usize)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert!(public_keys.iter().all(|key| key == &public_keys[0]));
+
+        // But not without another one
+        let results = run_validators(SECURITY_THRESHOLD as usize - 1);
+        assert!(results.iter().all(|result| matches!(
+            result,
+            Err(Error::InsufficientTranscriptsForAggregate(_, _))
+        )));
+    }
+}
+
+/// Test a ceremony of a large cohort with cheap stand-ins for the pairings. Run with
+/// `RUSTFLAGS="--cfg ferveo_mock_crypto"`, which is never set by a dependent crate
+#[cfg(all(test, feature = "dkg", ferveo_mock_crypto))]
+mod test_mock_crypto {
+    use ark_ec::AffineRepr;
+    use ark_std::UniformRand;
+
+    use crate::{
+        dkg::*, test_common::*, verify_full_batch, DkgState,
+        PubliclyVerifiableSS, ShareEncryptions,
+    };
+
+    #[test]
+    fn test_large_cohort_ceremony() {
+        let rng = &mut ark_std::test_rng();
+        let shares_num = 1024;
+        let security_threshold = shares_num * 2 / 3;
+        let (mut dkg, _) =
+            setup_dkg_for_n_validators(security_threshold, shares_num, 0);
+        let validators =
+            dkg.share_owners().into_iter().cloned().collect::<Vec<_>>();
+
+        let mut dealer_dkg = dkg.clone();
+        for (i, dealer) in validators.iter().enumerate() {
+            dealer_dkg.me = dealer.clone();
+            let mut pvss = PubliclyVerifiableSS::<E>::new(
+                &ScalarField::rand(rng),
+                &dealer_dkg,
+                rng,
+            )
+            .unwrap();
+            // Every tenth dealer sends a transcript with a missing share
+            if i % 10 == 9 {
+                pvss.shares[0] = ShareEncryptions::new_unchecked(G2::zero());
+                assert!(!pvss.verify_full(&dkg));
+                continue;
+            }
+            let message = Message::Deal(pvss);
+            dkg.verify_message(dealer, &message).unwrap();
+            dkg.apply_message(dealer, &message).unwrap();
+        }
+        assert_eq!(dkg.vss.len(), validators.len() - validators.len() / 10);
+        let transcripts = dkg.vss.values().cloned().collect::<Vec<_>>();
+        assert!(verify_full_batch(&transcripts, &dkg));
+
+        let aggregate = dkg.aggregate().unwrap();
+        let sender = dkg.me.clone();
+        dkg.verify_message(&sender, &aggregate).unwrap();
+        dkg.apply_message(&sender, &aggregate).unwrap();
+        assert!(matches!(dkg.state, DkgState::Success { .. }));
+    }
+}
+
 /// Test DKG parameters
-#[cfg(test)]
+#[cfg(all(test, feature = "dkg"))]
 mod test_dkg_params {
-    use crate::test_common::*;
+    use super::DkgParams;
+    use crate::{test_common::*, DkgParamsViolation, Error};
 
     #[test]
     fn test_shares_num_less_than_security_threshold() {
@@ -687,4 +2708,46 @@ mod test_dkg_params {
             super::DkgParams::new(TAU, SECURITY_THRESHOLD, SHARES_NUM);
         assert!(dkg_params.is_ok());
     }
+
+    #[test]
+    fn test_dkg_params_builder_reports_every_violation() {
+        let validators = gen_validators(&gen_keypairs(SHARES_NUM));
+        let builder = DkgParams::builder()
+            .tau(TAU)
+            .security_threshold(SECURITY_THRESHOLD)
+            .shares_num(SHARES_NUM)
+            .tau_range(0..=TAU)
+            .validators(&validators);
+        assert_eq!(
+            builder.build().unwrap(),
+            DkgParams::new(TAU, SECURITY_THRESHOLD, SHARES_NUM).unwrap()
+        );
+
+        let builder = builder
+            .tau(TAU + 1)
+            .security_threshold(SHARES_NUM + 2)
+            .shares_num(SHARES_NUM + 1);
+        assert_eq!(
+            builder.violations(),
+            [
+                DkgParamsViolation::ThresholdAboveShares(
+                    SHARES_NUM + 2,
+                    SHARES_NUM + 1
+                ),
+                DkgParamsViolation::TauOutOfRange(TAU + 1, 0, TAU),
+                DkgParamsViolation::WeightMismatch(SHARES_NUM, SHARES_NUM + 1),
+            ]
+        );
+        assert!(matches!(
+            builder.build(),
+            Err(Error::DkgParamsViolations(violations)) if violations.len() == 3
+        ));
+        assert_eq!(
+            DkgParams::builder().violations(),
+            [
+                DkgParamsViolation::ZeroShares,
+                DkgParamsViolation::ZeroThreshold
+            ]
+        );
+    }
 }
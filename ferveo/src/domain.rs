@@ -0,0 +1,226 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::FftField;
+use ark_poly::{
+    EvaluationDomain, GeneralEvaluationDomain, MixedRadixEvaluationDomain,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{Error, Result};
+
+/// How the evaluation domain of a DKG is chosen for its number of shares. Every participant
+/// of a DKG must use the same policy, since it moves the domain points
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum DomainPolicy {
+    /// The smallest radix-2 domain holding the shares, i.e. padded to a power of two
+    #[default]
+    Radix2,
+    /// A radix-2 or mixed-radix domain of exactly as many points as shares, e.g. 96 shares
+    /// are dealt over 96 points instead of 128. The scalar field of BLS12-381 only has
+    /// multiplicative subgroups of order `2^k` and `3 * 2^k`, so other numbers of shares,
+    /// e.g. 100, are rejected rather than padded
+    Compact,
+}
+
+impl DomainPolicy {
+    /// The domain of `shares_num` shares under this policy
+    pub fn domain<F: FftField>(
+        &self,
+        shares_num: usize,
+    ) -> Result<GeneralEvaluationDomain<F>> {
+        let domain = match self {
+            DomainPolicy::Radix2 => {
+                GeneralEvaluationDomain::<F>::new(shares_num)
+            }
+            DomainPolicy::Compact => domain_of_size::<F>(shares_num),
+        };
+        domain.ok_or(Error::InvalidEvaluationDomain)
+    }
+}
+
+/// The domain of exactly `size` points this version constructs, radix-2 for powers of two
+/// and mixed-radix otherwise
+fn domain_of_size<F: FftField>(
+    size: usize,
+) -> Option<GeneralEvaluationDomain<F>> {
+    let domain = if size.is_power_of_two() {
+        GeneralEvaluationDomain::<F>::new(size)
+    } else {
+        MixedRadixEvaluationDomain::<F>::new(size)
+            .map(GeneralEvaluationDomain::MixedRadix)
+    };
+    domain.filter(|domain| domain.size() == size)
+}
+
+/// The evaluation domain that shares are dealt over.
+///
+/// Domain points are not stored anywhere, they are reconstructed from the number of shares
+/// at runtime. Since a different choice of domain by arkworks would silently move every
+/// domain point, artifacts carry the size and generator of their domain, and loading an
+/// artifact fails if the domain reconstructed by this version doesn't match.
+#[serde_as]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    bound(serialize = "", deserialize = ""),
+    try_from = "RawDomainDescriptor<E>"
+)]
+pub struct DomainDescriptor<E: Pairing> {
+    size: u64,
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    group_gen: E::ScalarField,
+}
+
+impl<E: Pairing> DomainDescriptor<E> {
+    pub fn new(domain: &GeneralEvaluationDomain<E::ScalarField>) -> Self {
+        Self {
+            size: domain.size() as u64,
+            group_gen: domain.group_gen(),
+        }
+    }
+
+    /// The descriptor of the domain this version constructs for `shares_num` shares, under
+    /// the default [`DomainPolicy`]
+    pub fn for_shares(shares_num: usize) -> Result<Self> {
+        Self::for_shares_with(shares_num, DomainPolicy::default())
+    }
+
+    /// The descriptor of the domain this version constructs for `shares_num` shares under
+    /// `policy`
+    pub fn for_shares_with(
+        shares_num: usize,
+        policy: DomainPolicy,
+    ) -> Result<Self> {
+        policy
+            .domain::<E::ScalarField>(shares_num)
+            .map(|domain| Self::new(&domain))
+    }
+
+    /// The descriptor of the domain of `size` generated by `group_gen`, which must be the
+    /// domain this version constructs for that size
+    pub fn from_parts(size: u64, group_gen: E::ScalarField) -> Result<Self> {
+        RawDomainDescriptor { size, group_gen }.try_into()
+    }
+
+    pub fn size(&self) -> usize {
+        self.size as usize
+    }
+
+    pub fn group_gen(&self) -> E::ScalarField {
+        self.group_gen
+    }
+
+    pub fn domain(&self) -> GeneralEvaluationDomain<E::ScalarField> {
+        // Descriptors are only constructed from domains of this version, so this can't fail
+        domain_of_size::<E::ScalarField>(self.size())
+            .expect("Unable to construct an evaluation domain")
+    }
+
+    pub fn element(&self, i: usize) -> E::ScalarField {
+        self.domain().element(i)
+    }
+
+    /// Check that `domain` is the domain described by this descriptor
+    pub fn check(
+        &self,
+        domain: &GeneralEvaluationDomain<E::ScalarField>,
+    ) -> Result<()> {
+        if Self::new(domain) == *self {
+            Ok(())
+        } else {
+            Err(Error::EvaluationDomainMismatch)
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = ""))]
+struct RawDomainDescriptor<E: Pairing> {
+    size: u64,
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    group_gen: E::ScalarField,
+}
+
+impl<E: Pairing> TryFrom<RawDomainDescriptor<E>> for DomainDescriptor<E> {
+    type Error = Error;
+
+    fn try_from(raw: RawDomainDescriptor<E>) -> Result<Self> {
+        let expected = domain_of_size::<E::ScalarField>(raw.size as usize)
+            .map(|domain| Self::new(&domain))
+            .ok_or(Error::InvalidEvaluationDomain)?;
+        let descriptor = Self {
+            size: raw.size,
+            group_gen: raw.group_gen,
+        };
+        if descriptor == expected {
+            Ok(descriptor)
+        } else {
+            Err(Error::EvaluationDomainMismatch)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_domain {
+    use ark_ff::One;
+
+    use super::*;
+    use crate::test_common::*;
+
+    #[test]
+    fn domain_descriptor_is_checked_on_load() {
+        let descriptor = DomainDescriptor::<E>::for_shares(5).unwrap();
+        assert_eq!(descriptor.size(), 8);
+        assert_eq!(descriptor.domain().size(), 8);
+        assert!(descriptor.check(&descriptor.domain()).is_ok());
+
+        let bytes = bincode::serialize(&descriptor).unwrap();
+        let loaded: DomainDescriptor<E> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(loaded, descriptor);
+
+        // A domain with another generator, e.g. chosen by another version of arkworks
+        let shifted = DomainDescriptor::<E> {
+            size: descriptor.size,
+            group_gen: descriptor.group_gen + ScalarField::one(),
+        };
+        let bytes = bincode::serialize(&shifted).unwrap();
+        assert!(bincode::deserialize::<DomainDescriptor<E>>(&bytes).is_err());
+        assert!(matches!(
+            shifted.check(&descriptor.domain()),
+            Err(Error::EvaluationDomainMismatch)
+        ));
+    }
+
+    #[test]
+    fn compact_domains_are_not_padded() {
+        for shares_num in [4, 6, 12, 96] {
+            let descriptor = DomainDescriptor::<E>::for_shares_with(
+                shares_num,
+                DomainPolicy::Compact,
+            )
+            .unwrap();
+            assert_eq!(descriptor.size(), shares_num);
+            assert_eq!(descriptor.domain().size(), shares_num);
+
+            // Mixed-radix descriptors load like radix-2 ones
+            let bytes = bincode::serialize(&descriptor).unwrap();
+            let loaded: DomainDescriptor<E> =
+                bincode::deserialize(&bytes).unwrap();
+            assert_eq!(loaded, descriptor);
+        }
+        assert_eq!(DomainDescriptor::<E>::for_shares(96).unwrap().size(), 128);
+
+        // No domain has exactly that many points
+        for shares_num in [5, 100] {
+            assert!(matches!(
+                DomainDescriptor::<E>::for_shares_with(
+                    shares_num,
+                    DomainPolicy::Compact
+                ),
+                Err(Error::InvalidEvaluationDomain)
+            ));
+        }
+    }
+}
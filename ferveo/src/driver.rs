@@ -0,0 +1,275 @@
+use std::collections::BTreeMap;
+
+use ark_ec::pairing::Pairing;
+#[cfg(feature = "async")]
+use async_trait::async_trait;
+use rand::RngCore;
+
+use crate::{
+    DkgParams, DkgState, Error, EthereumAddress, Ingested, Message,
+    PubliclyVerifiableDkg, Result, RetryPolicy, Validator,
+};
+
+/// The message loop of a validator in a DKG, independent of how messages are carried.
+/// [`crate::run_dkg`] drives it over a [`crate::Transport`], and [`DkgDriver::run`] over an
+/// [`AsyncTransport`].
+///
+/// The driver deals its transcript when it is created, unless it runs as an observer, then
+/// applies the messages it receives: duplicates are dropped, invalid messages are skipped,
+/// and aggregates received before the transcripts they aggregate are held until those
+/// arrive. Once every validator dealt, or the transport stayed quiet for
+/// `retry.max_retries` re-broadcasts with enough transcripts to meet the security
+/// threshold, the driver aggregates the transcripts it holds unless it already applied an
+/// aggregate of them.
+#[derive(Clone, Debug)]
+pub struct DkgDriver<E: Pairing> {
+    dkg: PubliclyVerifiableDkg<E>,
+    deal: Option<Message<E>>,
+    /// Aggregates that don't verify yet, by sender
+    pending: BTreeMap<EthereumAddress, (Validator<E>, Message<E>)>,
+    retry: RetryPolicy,
+    retries: u32,
+    gave_up: bool,
+}
+
+impl<E: Pairing> DkgDriver<E> {
+    pub fn new<R: RngCore>(
+        validators: &[Validator<E>],
+        params: &DkgParams,
+        me: &Validator<E>,
+        retry: &RetryPolicy,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let mut dkg = PubliclyVerifiableDkg::new(validators, params, me)?;
+        // Observers only verify the messages of the others
+        let deal = if me.is_observer() {
+            None
+        } else {
+            let deal = dkg.share(rng)?;
+            dkg.apply_message(me, &deal)?;
+            Some(deal)
+        };
+        Ok(Self {
+            dkg,
+            deal,
+            pending: BTreeMap::new(),
+            retry: *retry,
+            retries: 0,
+            gave_up: false,
+        })
+    }
+
+    pub fn dkg(&self) -> &PubliclyVerifiableDkg<E> {
+        &self.dkg
+    }
+
+    pub fn into_dkg(self) -> PubliclyVerifiableDkg<E> {
+        self.dkg
+    }
+
+    /// The transcript of the driver, to broadcast once it starts
+    pub fn deal(&self) -> Option<&Message<E>> {
+        self.deal.as_ref()
+    }
+
+    /// Whether the driver still waits for transcripts
+    pub fn is_dealing(&self) -> bool {
+        !self.gave_up
+            && !self.dkg.dealing_complete()
+            && !matches!(self.dkg.state, DkgState::Success { .. })
+    }
+
+    /// Handle what the transport received, `None` if it timed out. Returns the message to
+    /// broadcast in response, if any
+    pub fn handle(
+        &mut self,
+        received: Option<(Validator<E>, Message<E>)>,
+    ) -> Result<Option<Message<E>>> {
+        match received {
+            Some((sender, message @ Message::Deal(_))) => {
+                if !matches!(
+                    self.dkg.ingest(&sender, &message),
+                    Ok(Ingested::Applied)
+                ) {
+                    return Ok(None);
+                }
+                self.retries = 0;
+            }
+            Some((sender, message @ Message::Complaint(_))) => {
+                if self.dkg.verify_message(&sender, &message).is_ok() {
+                    self.dkg.apply_message(&sender, &message)?;
+                }
+            }
+            Some((sender, message)) => {
+                self.pending
+                    .insert(sender.address.clone(), (sender, message));
+            }
+            None if self.retries < self.retry.max_retries => {
+                self.retries += 1;
+                return Ok(self.deal.clone());
+            }
+            None => self.gave_up = true,
+        }
+        let verified = self.pending.values().find(|(sender, message)| {
+            self.dkg.verify_message(sender, message).is_ok()
+        });
+        if let Some((sender, message)) = verified.cloned() {
+            self.dkg.apply_message(&sender, &message)?;
+        }
+        Ok(None)
+    }
+
+    /// Aggregate the transcripts once dealing is over, returning the aggregate to broadcast,
+    /// or `None` if an aggregate of the others was applied
+    pub fn finish(&mut self) -> Result<Option<Message<E>>> {
+        match self.dkg.state {
+            DkgState::Success { .. } => Ok(None),
+            DkgState::Dealt => {
+                let aggregate = self.dkg.aggregate()?;
+                let me = self.dkg.me.clone();
+                self.dkg.apply_message(&me, &aggregate)?;
+                Ok(Some(aggregate))
+            }
+            _ => Err(Error::InsufficientTranscriptsForAggregate(
+                self.dkg.dkg_params.security_threshold(),
+                self.dkg.vss.len() as u32,
+            )),
+        }
+    }
+
+    /// Run the DKG over `transport` until an aggregate is applied. Resolves to the public
+    /// key of the ritual, or to the error that aborted the ritual, e.g. an error of the
+    /// transport or too few transcripts to aggregate
+    #[cfg(feature = "async")]
+    pub async fn run(
+        &mut self,
+        mut transport: impl AsyncTransport<E>,
+    ) -> Result<E::G1Affine> {
+        if let Some(deal) = &self.deal {
+            transport.broadcast(deal).await?;
+        }
+        while self.is_dealing() {
+            let received = transport.receive().await?;
+            if let Some(message) = self.handle(received)? {
+                transport.broadcast(&message).await?;
+            }
+        }
+        if let Some(aggregate) = self.finish()? {
+            transport.broadcast(&aggregate).await?;
+        }
+        Ok(self.dkg.public_key())
+    }
+}
+
+/// Same as [`crate::Transport`], for async network layers, e.g. on a tokio runtime.
+/// Messages are sent as the validator of the [`DkgDriver`] that runs over the transport
+#[cfg(feature = "async")]
+#[async_trait]
+pub trait AsyncTransport<E: Pairing>: Send {
+    /// Send `message` to every other validator. Delivery may be delayed, reordered or
+    /// duplicated
+    async fn broadcast(&mut self, message: &Message<E>) -> Result<()>;
+
+    /// The next message received, with the validator that sent it, or `None` if nothing
+    /// arrived before the transport timed out. The transport is trusted to authenticate the
+    /// sender
+    async fn receive(&mut self) -> Result<Option<(Validator<E>, Message<E>)>>;
+}
+
+#[cfg(feature = "async")]
+#[async_trait]
+impl<E: Pairing, T: AsyncTransport<E> + ?Sized> AsyncTransport<E> for &mut T {
+    async fn broadcast(&mut self, message: &Message<E>) -> Result<()> {
+        (**self).broadcast(message).await
+    }
+
+    async fn receive(&mut self) -> Result<Option<(Validator<E>, Message<E>)>> {
+        (**self).receive().await
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod test_driver {
+    use std::{
+        sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        thread,
+        time::Duration,
+    };
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::test_common::*;
+
+    type Envelope = (Validator<E>, Message<E>);
+
+    struct ChannelTransport {
+        me: Validator<E>,
+        peers: Vec<Sender<Envelope>>,
+        inbox: Receiver<Envelope>,
+    }
+
+    #[async_trait]
+    impl AsyncTransport<E> for ChannelTransport {
+        async fn broadcast(&mut self, message: &Message<E>) -> Result<()> {
+            for peer in &self.peers {
+                // Peers that are done stop listening
+                peer.send((self.me.clone(), message.clone())).ok();
+            }
+            Ok(())
+        }
+
+        async fn receive(&mut self) -> Result<Option<Envelope>> {
+            match self.inbox.recv_timeout(Duration::from_millis(100)) {
+                Ok(envelope) => Ok(Some(envelope)),
+                Err(RecvTimeoutError::Timeout) => Ok(None),
+                Err(RecvTimeoutError::Disconnected) => {
+                    Err(Error::TransportError("disconnected".to_string()))
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_async_driver() {
+        let validators = gen_validators(&gen_keypairs(SHARES_NUM));
+        let params =
+            DkgParams::new(TAU, SECURITY_THRESHOLD, SHARES_NUM).unwrap();
+        let (senders, inboxes): (Vec<_>, Vec<_>) =
+            (0..SHARES_NUM).map(|_| channel::<Envelope>()).unzip();
+        let handles = inboxes
+            .into_iter()
+            .enumerate()
+            .map(|(i, inbox)| {
+                let mut transport = ChannelTransport {
+                    me: validators[i].clone(),
+                    peers: senders.clone(),
+                    inbox,
+                };
+                let validators = validators.clone();
+                thread::spawn(move || {
+                    let rng = &mut ChaCha20Rng::seed_from_u64(i as u64);
+                    let mut driver = DkgDriver::new(
+                        &validators,
+                        &params,
+                        &validators[i],
+                        &RetryPolicy { max_retries: 2 },
+                        rng,
+                    )
+                    .unwrap();
+                    let public_key =
+                        pollster::block_on(driver.run(&mut transport));
+                    assert!(!driver.is_dealing());
+                    public_key
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(senders);
+        let public_keys = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap().unwrap())
+            .collect::<Vec<_>>();
+        assert!(public_keys.iter().all(|key| key == &public_keys[0]));
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::BTreeMap;
+
+use ark_ec::pairing::Pairing;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+
+use crate::{AggregatedPvss, Error, Result, ValidatorSetHash};
+
+/// A finalized epoch: the aggregate its ceremony produced, and the validators and threshold
+/// the ceremony was run with.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct EpochEntry<E: Pairing> {
+    pub epoch: u32,
+    pub aggregate_hash: [u8; 32],
+    pub validator_set_hash: ValidatorSetHash,
+    pub threshold: u32,
+    /// The DKG public key, which resharing carries over from an epoch to the next
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub public_key: E::G1Affine,
+    /// The epoch this one was reshared from, or `None` for a fresh ceremony
+    pub predecessor: Option<u32>,
+}
+
+impl<E: Pairing> EpochEntry<E> {
+    pub fn new(
+        epoch: u32,
+        aggregate: &AggregatedPvss<E>,
+        predecessor: Option<u32>,
+    ) -> Result<Self> {
+        let public_key = *aggregate
+            .coeffs
+            .first()
+            .ok_or(Error::InvalidTranscriptAggregate)?;
+        Ok(Self {
+            epoch,
+            aggregate_hash: aggregate_hash(aggregate)?,
+            validator_set_hash: aggregate.validator_set_hash,
+            threshold: aggregate.coeffs.len() as u32,
+            public_key,
+            predecessor,
+        })
+    }
+}
+
+/// Hash of an aggregate, as recorded in an [`EpochEntry`]
+pub fn aggregate_hash<E: Pairing>(
+    aggregate: &AggregatedPvss<E>,
+) -> Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    hasher.update(V1::AGGREGATE_HASH_DOMAIN);
+    hasher.update(bincode::serialize(aggregate)?);
+    Ok(hasher.finalize().into())
+}
+
+/// The finalized epochs of a single key lineage, e.g. as recorded on chain.
+///
+/// The first entry anchors the lineage. Every later entry must be reshared from the latest
+/// one, and so keep its public key, which lets services reject artifacts of a ceremony that
+/// forked from the lineage. Deserializing a registry checks its lineage like
+/// [`EpochRegistry::from_entries`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    bound(serialize = "", deserialize = ""),
+    try_from = "RawEpochRegistry<E>"
+)]
+pub struct EpochRegistry<E: Pairing> {
+    entries: BTreeMap<u32, EpochEntry<E>>,
+}
+
+impl<E: Pairing> Default for EpochRegistry<E> {
+    fn default() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+impl<E: Pairing> EpochRegistry<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuild a registry from its entries, checking the continuity of the lineage
+    pub fn from_entries(
+        entries: impl IntoIterator<Item = EpochEntry<E>>,
+    ) -> Result<Self> {
+        let mut registry = Self::new();
+        for entry in entries {
+            registry.finalize(entry)?;
+        }
+        Ok(registry)
+    }
+
+    /// Record a finalized epoch, which must continue the lineage of the latest epoch.
+    /// On error, the registry is left unchanged.
+    pub fn finalize(&mut self, entry: EpochEntry<E>) -> Result<()> {
+        if let Some(latest) = self.latest() {
+            if entry.epoch <= latest.epoch
+                || entry.predecessor != Some(latest.epoch)
+                || entry.public_key != latest.public_key
+            {
+                return Err(Error::BrokenEpochLineage(entry.epoch));
+            }
+        }
+        self.entries.insert(entry.epoch, entry);
+        Ok(())
+    }
+
+    pub fn get(&self, epoch: u32) -> Option<&EpochEntry<E>> {
+        self.entries.get(&epoch)
+    }
+
+    pub fn latest(&self) -> Option<&EpochEntry<E>> {
+        self.entries.values().next_back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Check that `aggregate` is the aggregate finalized in `epoch`
+    pub fn verify_aggregate(
+        &self,
+        epoch: u32,
+        aggregate: &AggregatedPvss<E>,
+    ) -> Result<()> {
+        let entry = self.get(epoch).ok_or(Error::UnknownEpoch(epoch))?;
+        if entry.aggregate_hash != aggregate_hash(aggregate)? {
+            return Err(Error::EpochMismatch(epoch));
+        }
+        Ok(())
+    }
+
+    /// The epoch `aggregate` was finalized in, if any
+    pub fn find_aggregate(
+        &self,
+        aggregate: &AggregatedPvss<E>,
+    ) -> Result<Option<&EpochEntry<E>>> {
+        let hash = aggregate_hash(aggregate)?;
+        Ok(self
+            .entries
+            .values()
+            .find(|entry| entry.aggregate_hash == hash))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(bound(deserialize = ""))]
+struct RawEpochRegistry<E: Pairing> {
+    entries: BTreeMap<u32, EpochEntry<E>>,
+}
+
+impl<E: Pairing> TryFrom<RawEpochRegistry<E>> for EpochRegistry<E> {
+    type Error = Error;
+
+    fn try_from(raw: RawEpochRegistry<E>) -> Result<Self> {
+        if let Some((_, entry)) = raw
+            .entries
+            .iter()
+            .find(|(epoch, entry)| **epoch != entry.epoch)
+        {
+            return Err(Error::BrokenEpochLineage(entry.epoch));
+        }
+        Self::from_entries(raw.entries.into_values())
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_epoch {
+    use ark_ec::AffineRepr;
+
+    use super::*;
+    use crate::test_common::*;
+
+    fn setup() -> (AggregatedPvss<E>, EpochEntry<E>) {
+        let (dkg, _) = setup_dealt_dkg();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = crate::pvss::aggregate(&pvss_list).unwrap();
+        let entry = EpochEntry::new(1, &aggregate, None).unwrap();
+        (aggregate, entry)
+    }
+
+    #[test]
+    fn epoch_registry_tracks_lineage() {
+        let (aggregate, genesis) = setup();
+        assert_eq!(genesis.threshold, SECURITY_THRESHOLD);
+        assert_eq!(genesis.public_key, aggregate.coeffs[0]);
+
+        let mut registry = EpochRegistry::new();
+        registry.finalize(genesis.clone()).unwrap();
+        registry.verify_aggregate(1, &aggregate).unwrap();
+        assert_eq!(
+            registry.find_aggregate(&aggregate).unwrap(),
+            Some(&genesis)
+        );
+
+        // A resharing keeps the public key, with new validators and shares
+        let reshared = EpochEntry {
+            epoch: 2,
+            aggregate_hash: [2; 32],
+            validator_set_hash: [2; 32],
+            threshold: SECURITY_THRESHOLD + 1,
+            predecessor: Some(1),
+            ..genesis
+        };
+        registry.finalize(reshared.clone()).unwrap();
+        assert_eq!(registry.latest(), Some(&reshared));
+        assert!(matches!(
+            registry.verify_aggregate(2, &aggregate),
+            Err(Error::EpochMismatch(2))
+        ));
+        assert!(matches!(
+            registry.verify_aggregate(3, &aggregate),
+            Err(Error::UnknownEpoch(3))
+        ));
+
+        // Forks of the lineage are rejected
+        let forks = [
+            // A fresh ceremony
+            EpochEntry {
+                epoch: 3,
+                predecessor: None,
+                ..reshared
+            },
+            // Reshared from an older epoch
+            EpochEntry {
+                epoch: 3,
+                predecessor: Some(1),
+                ..reshared
+            },
+            // With another key
+            EpochEntry {
+                epoch: 3,
+                predecessor: Some(2),
+                public_key: G1::generator(),
+                ..reshared
+            },
+            // Replacing a finalized epoch
+            EpochEntry {
+                predecessor: Some(2),
+                ..reshared
+            },
+        ];
+        for fork in forks {
+            assert!(matches!(
+                registry.finalize(fork),
+                Err(Error::BrokenEpochLineage(_))
+            ));
+        }
+        assert_eq!(registry.len(), 2);
+
+        // Registries can be persisted and rebuilt
+        let bytes = bincode::serialize(&registry).unwrap();
+        assert_eq!(
+            bincode::deserialize::<EpochRegistry<E>>(&bytes).unwrap(),
+            registry
+        );
+        assert_eq!(
+            EpochRegistry::from_entries([genesis.clone(), reshared.clone()])
+                .unwrap(),
+            registry
+        );
+
+        // But not from a registry that breaks the lineage
+        let forked = EpochRegistry {
+            entries: [
+                (1, genesis.clone()),
+                (
+                    2,
+                    EpochEntry {
+                        public_key: G1::generator(),
+                        ..reshared.clone()
+                    },
+                ),
+            ]
+            .into(),
+        };
+        let mislabeled = EpochRegistry {
+            entries: [(1, genesis), (3, reshared)].into(),
+        };
+        for registry in [forked, mislabeled] {
+            let bytes = bincode::serialize(&registry).unwrap();
+            assert!(bincode::deserialize::<EpochRegistry<E>>(&bytes).is_err());
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{Error, Result};
+
+/// Optional data carried by transcripts and aggregates, keyed by extension type.
+///
+/// Each extension is serialized as its type and its length-prefixed data, so that nodes
+/// parse, hash and relay extensions they don't know, and ignore them otherwise. New fields
+/// can then be added as extensions without another change of the wire format.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Extensions(BTreeMap<u16, Vec<u8>>);
+
+impl Extensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an extension, which must be the only one of its type
+    pub fn insert(&mut self, extension_type: u16, data: Vec<u8>) -> Result<()> {
+        if self.0.contains_key(&extension_type) {
+            return Err(Error::DuplicateExtension(extension_type));
+        }
+        self.0.insert(extension_type, data);
+        Ok(())
+    }
+
+    pub fn get(&self, extension_type: u16) -> Option<&[u8]> {
+        self.0.get(&extension_type).map(Vec::as_slice)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &[u8])> {
+        self.0.iter().map(|(t, data)| (*t, data.as_slice()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Hash of the extensions, which binds them to the proof of knowledge of a transcript
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(V1::EXTENSIONS_HASH_DOMAIN);
+        hasher.update((self.0.len() as u32).to_le_bytes());
+        for (extension_type, data) in &self.0 {
+            hasher.update(extension_type.to_le_bytes());
+            hasher.update((data.len() as u32).to_le_bytes());
+            hasher.update(data);
+        }
+        hasher.finalize().into()
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_extensions {
+    use ark_std::UniformRand;
+
+    use super::*;
+    use crate::{test_common::*, DealerCommitment, PubliclyVerifiableSS};
+
+    #[test]
+    fn extensions_are_bound_to_transcripts() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, _) = setup_dkg(0);
+        let dealer = &dkg.me.address;
+        let mut extensions = Extensions::new();
+        extensions.insert(7, b"future field".to_vec()).unwrap();
+        assert!(matches!(
+            extensions.insert(7, vec![]),
+            Err(Error::DuplicateExtension(7))
+        ));
+        assert_eq!(extensions.get(7), Some(&b"future field"[..]));
+        assert_eq!(extensions.get(8), None);
+
+        let s = ScalarField::rand(rng);
+        let pvss = PubliclyVerifiableSS::<E>::new_with_extensions(
+            &s,
+            &dkg,
+            extensions.clone(),
+            rng,
+        )
+        .unwrap();
+        assert!(pvss.verify_optimistic(dealer));
+        assert!(pvss.verify_full(&dkg));
+
+        // Nodes that don't know an extension still parse and relay it
+        let bytes = bincode::serialize(&pvss).unwrap();
+        let relayed: PubliclyVerifiableSS<E> =
+            bincode::deserialize(&bytes).unwrap();
+        assert_eq!(relayed.extensions, pvss.extensions);
+        assert_eq!(relayed.extensions.get(7), Some(&b"future field"[..]));
+        assert!(relayed.verify_optimistic(dealer));
+
+        // But can't strip or alter them
+        let mut stripped = pvss.clone();
+        stripped.extensions = Extensions::default();
+        assert!(!stripped.verify_optimistic(dealer));
+        let mut altered = pvss.clone();
+        altered.extensions = Extensions::default();
+        altered.extensions.insert(7, b"other".to_vec()).unwrap();
+        assert!(!altered.verify_optimistic(dealer));
+
+        let commitment =
+            DealerCommitment::from_transcript(dealer.clone(), &pvss).unwrap();
+        assert_eq!(commitment.extensions_hash, pvss.extensions.hash());
+        assert!(!commitment.matches(&stripped).unwrap());
+    }
+}
@@ -0,0 +1,144 @@
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+use sha2::{Digest, Sha256};
+
+use crate::Result;
+#[cfg(feature = "verify")]
+use crate::{AggregatedPvss, DealtPublicKey, PubliclyVerifiableSS};
+
+/// A short, stable digest of a transcript, an aggregate or a DKG public key, for nodes and
+/// contracts to compare what they hold without exchanging it.
+///
+/// Fingerprints hash the canonical (compressed) encoding of each field with its own domain
+/// tag, so they don't depend on the serde framing the value was received in.
+pub type Fingerprint = [u8; 32];
+
+/// Hash `points` with their count, so that the fields hashed after them can't shift into them
+fn update_points<P: CanonicalSerialize>(
+    hasher: &mut Sha256,
+    points: &[P],
+) -> Result<()> {
+    hasher.update((points.len() as u32).to_le_bytes());
+    let mut bytes = Vec::new();
+    for point in points {
+        point.serialize_compressed(&mut bytes)?;
+    }
+    hasher.update(bytes);
+    Ok(())
+}
+
+/// Fingerprint of the DKG public key `public_key`
+pub fn public_key_fingerprint<E: Pairing>(
+    public_key: &E::G1Affine,
+) -> Result<Fingerprint> {
+    let mut bytes = Vec::new();
+    public_key.serialize_compressed(&mut bytes)?;
+    let mut hasher = Sha256::new();
+    hasher.update(V1::PUBLIC_KEY_FINGERPRINT_DOMAIN);
+    hasher.update(bytes);
+    Ok(hasher.finalize().into())
+}
+
+#[cfg(feature = "verify")]
+impl<E: Pairing, T> PubliclyVerifiableSS<E, T> {
+    fn fingerprint_with_domain(
+        &self,
+        domain_tag: &[u8],
+    ) -> Result<Fingerprint> {
+        let mut hasher = Sha256::new();
+        hasher.update(domain_tag);
+        hasher.update([self.version]);
+        hasher.update(self.tau.to_le_bytes());
+        hasher.update(self.validator_set_hash);
+        hasher.update((self.domain.size() as u64).to_le_bytes());
+        update_points(&mut hasher, &[self.domain.group_gen()])?;
+        update_points(&mut hasher, &self.coeffs)?;
+        update_points(&mut hasher, &self.shares)?;
+        update_points(&mut hasher, &[self.sigma])?;
+        hasher.update(self.extensions.hash());
+        Ok(hasher.finalize().into())
+    }
+}
+
+#[cfg(feature = "verify")]
+impl<E: Pairing> PubliclyVerifiableSS<E> {
+    /// Fingerprint of this transcript
+    pub fn fingerprint(&self) -> Result<Fingerprint> {
+        self.fingerprint_with_domain(V1::TRANSCRIPT_FINGERPRINT_DOMAIN)
+    }
+}
+
+#[cfg(feature = "verify")]
+impl<E: Pairing> AggregatedPvss<E> {
+    /// Fingerprint of this aggregate. It never equals the fingerprint of a transcript, even
+    /// of an aggregate of a single transcript
+    pub fn fingerprint(&self) -> Result<Fingerprint> {
+        self.fingerprint_with_domain(V1::AGGREGATE_FINGERPRINT_DOMAIN)
+    }
+}
+
+#[cfg(feature = "verify")]
+impl<E: Pairing> DealtPublicKey<E> {
+    /// Fingerprint of this public key, see [`public_key_fingerprint`]
+    pub fn fingerprint(&self) -> Result<Fingerprint> {
+        public_key_fingerprint::<E>(&self.0)
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_fingerprint {
+    use ark_ec::AffineRepr;
+
+    use super::*;
+    use crate::{api, test_common::*};
+
+    #[test]
+    fn test_fingerprints() {
+        let (dkg, _) = setup_dealt_dkg();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let pvss = &pvss_list[0];
+
+        // Independent of the serde framing
+        let fingerprint = pvss.fingerprint().unwrap();
+        let roundtrip: PubliclyVerifiableSS<E> =
+            bincode::deserialize(&bincode::serialize(pvss).unwrap()).unwrap();
+        assert_eq!(roundtrip.fingerprint().unwrap(), fingerprint);
+        assert_ne!(pvss_list[1].fingerprint().unwrap(), fingerprint);
+        let mut tampered = pvss.clone();
+        tampered.sigma = G2::generator();
+        assert_ne!(tampered.fingerprint().unwrap(), fingerprint);
+        let mut other_ritual = pvss.clone();
+        other_ritual.tau += 1;
+        assert_ne!(other_ritual.fingerprint().unwrap(), fingerprint);
+
+        // Aggregates and transcripts are domain separated
+        let aggregate = crate::pvss::aggregate(&pvss_list).unwrap();
+        let single = crate::pvss::aggregate(&pvss_list[..1]).unwrap();
+        assert_ne!(single.fingerprint().unwrap(), fingerprint);
+        let dealers = dkg.vss.keys().cloned().collect::<Vec<_>>();
+        let aggregate_of = |range: std::ops::Range<usize>| {
+            let mut aggregate =
+                crate::pvss::aggregate(&pvss_list[range.clone()]).unwrap();
+            aggregate
+                .set_contributors(&dealers[range].iter().cloned().collect())
+                .unwrap();
+            aggregate
+        };
+        let merged = aggregate_of(0..2).merge(&aggregate_of(2..4)).unwrap();
+        assert_eq!(
+            merged.fingerprint().unwrap(),
+            aggregate_of(0..4).fingerprint().unwrap()
+        );
+
+        let public_key = aggregate.public_key();
+        assert_eq!(
+            public_key.fingerprint().unwrap(),
+            api::DkgPublicKey(public_key.0).fingerprint().unwrap()
+        );
+        assert_ne!(
+            public_key.fingerprint().unwrap(),
+            DealtPublicKey::<E>(G1::generator()).fingerprint().unwrap()
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Introspection of the build a node runs, e.g. for fleet management to check that the
+//! members of a committee run compatible builds before starting a ceremony.
+
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_std::UniformRand;
+use ferveo_tdec::{
+    decrypt_symmetric, encrypt,
+    protocol::{ProtocolVersion, V1},
+    SecretBox, SuiteId,
+};
+use serde::{Deserialize, Serialize};
+
+type E = ark_bls12_381::Bls12_381;
+
+/// The features this crate was built with
+const FEATURES: &[(&str, bool)] = &[
+    ("dkg", cfg!(feature = "dkg")),
+    ("verify", cfg!(feature = "verify")),
+    ("stream", cfg!(feature = "stream")),
+    ("async", cfg!(feature = "async")),
+    ("refresh", cfg!(feature = "refresh")),
+    ("tdec-fast", cfg!(feature = "tdec-fast")),
+    ("tdec-simple", cfg!(feature = "tdec-simple")),
+    ("serde-json", cfg!(feature = "serde-json")),
+    ("memlock", cfg!(feature = "memlock")),
+    ("zstd", cfg!(feature = "zstd")),
+    ("unsafe-debug", cfg!(feature = "unsafe-debug")),
+    ("parallel", cfg!(feature = "parallel")),
+    ("bindings-python", cfg!(feature = "bindings-python")),
+    ("bindings-wasm", cfg!(feature = "bindings-wasm")),
+];
+
+/// The versions of the wire formats a build supports
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolVersions {
+    pub protocol: Vec<u8>,
+    pub aad_schema: Vec<u8>,
+    pub envelope: Vec<u8>,
+    pub transcript: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SelfTestStatus {
+    Passed,
+    Failed,
+}
+
+/// Version, features and health of this build, as returned by [`info`].
+///
+/// Nodes running builds with different versions or protocol versions shouldn't take part in
+/// the same ceremony.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Info {
+    pub version: String,
+    pub features: Vec<String>,
+    pub curve: String,
+    /// The cipher suite identifier of `curve`
+    pub suite: SuiteId,
+    pub protocol_versions: ProtocolVersions,
+    pub self_test: SelfTestStatus,
+}
+
+impl Info {
+    /// Whether `other` runs the same version with the same protocol versions, and both
+    /// builds pass their self-test
+    pub fn is_compatible_with(&self, other: &Info) -> bool {
+        self.version == other.version
+            && self.suite == other.suite
+            && self.protocol_versions == other.protocol_versions
+            && self.self_test == SelfTestStatus::Passed
+            && other.self_test == SelfTestStatus::Passed
+    }
+}
+
+/// Describe this build. Runs [`self_test`], which takes a few pairings.
+pub fn info() -> Info {
+    Info {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(feature, _)| feature.to_string())
+            .collect(),
+        curve: "BLS12-381".to_string(),
+        suite: SuiteId::of::<E>(),
+        protocol_versions: ProtocolVersions {
+            protocol: vec![V1::VERSION],
+            aad_schema: vec![V1::AAD_SCHEMA_VERSION],
+            envelope: vec![V1::ENVELOPE_VERSION],
+            transcript: vec![V1::TRANSCRIPT_VERSION],
+        },
+        self_test: if self_test() {
+            SelfTestStatus::Passed
+        } else {
+            SelfTestStatus::Failed
+        },
+    }
+}
+
+/// Check the pairing and the encryption of this build on random inputs, e.g. to catch a
+/// miscompiled backend before it takes part in a ceremony.
+pub fn self_test() -> bool {
+    let rng = &mut rand::thread_rng();
+    let secret = <E as Pairing>::ScalarField::rand(rng);
+    let g = <E as Pairing>::G1Affine::generator();
+    let h = <E as Pairing>::G2Affine::generator();
+    let pubkey = (g * secret).into_affine();
+    let privkey = (h * secret).into_affine();
+    if E::pairing(pubkey, h) != E::pairing(g, privkey) {
+        return false;
+    }
+
+    let msg = b"ferveo self-test";
+    let aad = b"ferveo self-test aad";
+    let g_inv = <E as Pairing>::G1Prepared::from(-g);
+    encrypt::<E>(SecretBox::new(msg.to_vec()), aad, &pubkey, rng)
+        .and_then(|ciphertext| {
+            decrypt_symmetric(&ciphertext, aad, &privkey, &g_inv)
+        })
+        .map(|plaintext| plaintext.as_bytes() == msg)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn info_describes_build() {
+        let info = info();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.self_test, SelfTestStatus::Passed);
+        assert_eq!(info.protocol_versions.protocol, vec![V1::VERSION]);
+        assert_eq!(
+            info.features.contains(&"dkg".to_string()),
+            cfg!(feature = "dkg")
+        );
+        assert!(info.is_compatible_with(&info));
+
+        let bytes = bincode::serialize(&info).unwrap();
+        assert_eq!(bincode::deserialize::<Info>(&bytes).unwrap(), info);
+
+        let newer = Info {
+            version: "99.0.0".to_string(),
+            ..info.clone()
+        };
+        assert!(!info.is_compatible_with(&newer));
+        let failed = Info {
+            self_test: SelfTestStatus::Failed,
+            ..info.clone()
+        };
+        assert!(!info.is_compatible_with(&failed));
+    }
+}
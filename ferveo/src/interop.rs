@@ -0,0 +1,314 @@
+//! Adapters for shares dealt by other DKG stacks, e.g. drand and other kyber-based
+//! implementations of Pedersen or Groth21 DKGs.
+//!
+//! These stacks share a polynomial `f` with commitments `[a_j] G` to its coefficients, like
+//! ferveo does, but they hand out scalar shares `f(i + 1)` instead of blinded shares
+//! `[f(ω_i) dk_i] H` at the points of an FFT domain. A committee can keep its key by
+//! importing the scalar shares, as long as decryption shares are combined at the evaluation
+//! points of the original DKG, see [`ExternalShare::evaluation_point`].
+//!
+//! The byte layout follows kyber: points are compressed, scalars are big-endian, and shares
+//! are encoded as `index || value` with a big-endian `u32` index.
+
+use std::ops::Mul;
+
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ferveo_common::Keypair;
+use ferveo_tdec::{CiphertextHeader, DecryptionShareSimple, PrivateKeyShare};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{AggregatedPvss, Error, PubliclyVerifiableParams, Result};
+
+/// Commitments to the coefficients of a shared polynomial, in the format of other DKG stacks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalPublicPoly<E: Pairing> {
+    pub commitments: Vec<E::G1Affine>,
+}
+
+impl<E: Pairing> ExternalPublicPoly<E> {
+    /// Export the commitments of a ferveo aggregate.
+    /// Only the commitments carry over, since ferveo shares are dealt at other points.
+    pub fn from_aggregate(aggregate: &AggregatedPvss<E>) -> Self {
+        Self {
+            commitments: aggregate.coeffs.clone(),
+        }
+    }
+
+    pub fn public_key(&self) -> E::G1Affine {
+        self.commitments[0]
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.commitments.len()
+    }
+
+    /// Evaluate the committed polynomial at `x`, i.e. compute `[f(x)] G`
+    pub fn commitment_at(&self, x: &E::ScalarField) -> E::G1 {
+        self.commitments
+            .iter()
+            .rev()
+            .fold(E::G1::zero(), |acc, coeff| acc * x + coeff)
+    }
+
+    /// Check a share against the commitments, i.e. `[v_i] G == [f(i + 1)] G` for the
+    /// generator `G` of `pvss_params`
+    pub fn verify_share(
+        &self,
+        share: &ExternalShare<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> bool {
+        pvss_params.g.mul(share.value)
+            == self.commitment_at(&share.evaluation_point())
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        for commitment in &self.commitments {
+            commitment.serialize_compressed(&mut bytes)?;
+        }
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let point_size = E::G1Affine::generator().compressed_size();
+        if bytes.is_empty() || bytes.len() % point_size != 0 {
+            return Err(Error::InvalidByteLength(
+                point_size * (bytes.len() / point_size).max(1),
+                bytes.len(),
+            ));
+        }
+        let commitments = bytes
+            .chunks(point_size)
+            .map(E::G1Affine::deserialize_compressed)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(Self { commitments })
+    }
+}
+
+/// A scalar share `f(index + 1)` dealt by another DKG stack.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExternalShare<E: Pairing> {
+    pub index: u32,
+    pub value: E::ScalarField,
+}
+
+impl<E: Pairing> Zeroize for ExternalShare<E> {
+    fn zeroize(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+impl<E: Pairing> Drop for ExternalShare<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<E: Pairing> ZeroizeOnDrop for ExternalShare<E> {}
+
+impl<E: Pairing> std::fmt::Debug for ExternalShare<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ExternalShare({}, <redacted>)", self.index)
+    }
+}
+
+impl<E: Pairing> ExternalShare<E> {
+    /// The point the share was evaluated at. Decryption shares created from imported shares
+    /// must be combined with Lagrange coefficients at these points.
+    pub fn evaluation_point(&self) -> E::ScalarField {
+        E::ScalarField::from(self.index as u64 + 1)
+    }
+
+    /// `Z_i = [f(x_i)] H`, the private key share in the form that ferveo uses, for the `H`
+    /// of `pvss_params`
+    pub fn private_key_share(
+        &self,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> PrivateKeyShare<E> {
+        PrivateKeyShare {
+            private_key_share: pvss_params.h.mul(self.value).into_affine(),
+        }
+    }
+
+    /// `Y_i = [f(x_i) dk_i] H`, the share blinded by the key of the validator, as it would
+    /// appear in a ferveo aggregate dealt with `pvss_params`
+    pub fn blinded_share(
+        &self,
+        validator_keypair: &Keypair<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> E::G2Affine {
+        pvss_params
+            .h
+            .mul(self.value * validator_keypair.decryption_key)
+            .into_affine()
+    }
+
+    pub fn create_decryption_share_simple(
+        &self,
+        ciphertext_header: &CiphertextHeader<E>,
+        aad: &[u8],
+        validator_keypair: &Keypair<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> Result<DecryptionShareSimple<E>> {
+        DecryptionShareSimple::create(
+            &validator_keypair.decryption_key,
+            &self.private_key_share(pvss_params),
+            ciphertext_header,
+            aad,
+            &pvss_params.g_inv(),
+        )
+        .map_err(Error::from)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.index.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.value.into_bigint().to_bytes_be());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let scalar_size = E::ScalarField::from(0u64).compressed_size();
+        if bytes.len() != 4 + scalar_size {
+            return Err(Error::InvalidByteLength(4 + scalar_size, bytes.len()));
+        }
+        let (index, value) = bytes.split_at(4);
+        let mut value = value.to_vec();
+        value.reverse();
+        // Rejects non-canonical encodings
+        let share = Self {
+            index: u32::from_be_bytes(index.try_into().unwrap()),
+            value: E::ScalarField::deserialize_compressed(value.as_slice())?,
+        };
+        value.zeroize();
+        Ok(share)
+    }
+}
+
+#[cfg(test)]
+mod test_interop {
+    use ark_poly::{
+        univariate::DensePolynomial, DenseUVPolynomial, Polynomial,
+    };
+    use ark_std::test_rng;
+    use ferveo_tdec::{
+        decrypt_with_shared_secret, encrypt, prepare_combine_simple,
+        share_combine_simple, SecretBox,
+    };
+
+    use super::*;
+    use crate::test_common::*;
+
+    #[test]
+    fn decrypt_with_imported_shares() {
+        let rng = &mut test_rng();
+        let threshold = SECURITY_THRESHOLD as usize;
+        let keypairs = gen_keypairs(SHARES_NUM);
+        let params = PubliclyVerifiableParams::<E>::default();
+        let g = params.g;
+
+        // A DKG run by another stack, dealing `f(i + 1)` to validator `i`
+        let f = DensePolynomial::<ScalarField>::rand(threshold - 1, rng);
+        let poly = ExternalPublicPoly::<E> {
+            commitments: f
+                .coeffs
+                .iter()
+                .map(|a| g.mul(a).into_affine())
+                .collect(),
+        };
+        let shares = (0..SHARES_NUM)
+            .map(|index| ExternalShare::<E> {
+                index,
+                value: f.evaluate(&ScalarField::from(index as u64 + 1)),
+            })
+            .collect::<Vec<_>>();
+
+        // Import
+        let poly =
+            ExternalPublicPoly::<E>::from_bytes(&poly.to_bytes().unwrap())
+                .unwrap();
+        assert_eq!(poly.threshold(), threshold);
+        let shares = shares
+            .iter()
+            .map(|share| ExternalShare::<E>::from_bytes(&share.to_bytes()))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert!(shares.iter().all(|share| poly.verify_share(share, &params)));
+        let mut bad_share = shares[0].clone();
+        bad_share.value += ScalarField::from(1u64);
+        assert!(!poly.verify_share(&bad_share, &params));
+
+        // Blinded shares pass the same check as shares in a ferveo aggregate,
+        // e(G, Y_i) == e(A_i, ek_i)
+        for (share, keypair) in shares.iter().zip(&keypairs) {
+            let a_i = poly.commitment_at(&share.evaluation_point());
+            assert_eq!(
+                E::pairing(g, share.blinded_share(keypair, &params)),
+                E::pairing(a_i, params.public_key(keypair).encryption_key)
+            );
+        }
+
+        // Decrypt under the imported key
+        let ciphertext = encrypt::<E>(
+            SecretBox::new(MSG.to_vec()),
+            AAD,
+            &poly.public_key(),
+            rng,
+        )
+        .unwrap();
+        let header = ciphertext.header().unwrap();
+        let g_inv = params.g_inv();
+        let selected = [3, 0, 2];
+        let decryption_shares = selected
+            .iter()
+            .map(|&i| {
+                shares[i]
+                    .create_decryption_share_simple(
+                        &header,
+                        AAD,
+                        &keypairs[i],
+                        &params,
+                    )
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let evaluation_points = selected
+            .iter()
+            .map(|&i| shares[i].evaluation_point())
+            .collect::<Vec<_>>();
+        let shared_secret = share_combine_simple::<E>(
+            &decryption_shares,
+            &prepare_combine_simple::<E>(&evaluation_points),
+        );
+        let plaintext = decrypt_with_shared_secret(
+            &ciphertext,
+            AAD,
+            &shared_secret,
+            &g_inv,
+        )
+        .unwrap();
+        assert_eq!(plaintext.as_bytes(), MSG);
+    }
+
+    #[test]
+    fn export_aggregate_commitments() {
+        let (dkg, _) = setup_dealt_dkg();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = crate::pvss::aggregate(&pvss_list).unwrap();
+        let poly = ExternalPublicPoly::from_aggregate(&aggregate);
+        assert_eq!(poly.public_key(), dkg.public_key());
+        let bytes = poly.to_bytes().unwrap();
+        assert_eq!(ExternalPublicPoly::<E>::from_bytes(&bytes).unwrap(), poly);
+        assert!(ExternalPublicPoly::<E>::from_bytes(&bytes[1..]).is_err());
+
+        // The shares of ferveo are dealt at the points of its domain
+        let x = aggregate.domain_points()[1];
+        assert_eq!(poly.commitment_at(&x), aggregate.commitment_at(&x));
+
+        // Non-canonical scalars are rejected
+        let mut bytes = vec![0u8; 4];
+        bytes.extend_from_slice(&[0xff; 32]);
+        assert!(ExternalShare::<E>::from_bytes(&bytes).is_err());
+    }
+}
@@ -9,21 +9,154 @@ pub mod bindings_python;
 #[cfg(feature = "bindings-wasm")]
 pub mod bindings_wasm;
 
+#[cfg(feature = "verify")]
+pub mod abi;
 pub mod api;
+#[cfg(feature = "verify")]
+pub mod blinded_share;
+pub mod chunk;
+#[cfg(feature = "verify")]
+pub mod complaint;
+#[cfg(feature = "verify")]
+pub mod consistency;
+#[cfg(feature = "verify")]
+pub mod dealer_commitment;
+#[cfg(feature = "dkg")]
+pub mod dealing;
+#[cfg(feature = "verify")]
 pub mod dkg;
+#[cfg(feature = "verify")]
+pub mod domain;
+#[cfg(feature = "dkg")]
+pub mod driver;
+#[cfg(feature = "verify")]
+pub mod epoch;
+pub mod extensions;
+pub mod fingerprint;
+pub mod info;
+#[cfg(feature = "dkg")]
+pub mod interop;
+#[cfg(feature = "verify")]
+pub mod manager;
+pub mod membership;
+pub mod merkle;
+#[cfg(feature = "verify")]
+pub mod observer;
+#[cfg(feature = "verify")]
+pub mod prevalidation;
+#[cfg(feature = "verify")]
 pub mod primitives;
+#[cfg(feature = "verify")]
 pub mod pvss;
+#[cfg(feature = "dkg")]
+pub mod recovery;
+#[cfg(feature = "refresh")]
 pub mod refresh;
+#[cfg(feature = "dkg")]
+pub mod ritual;
+#[cfg(feature = "dkg")]
+pub mod secure_channel;
+#[cfg(feature = "verify")]
+pub mod session;
+#[cfg(feature = "dkg")]
+pub mod share_cache;
+#[cfg(feature = "verify")]
+pub mod share_ownership;
+#[cfg(feature = "verify")]
+pub mod share_proof;
+#[cfg(feature = "verify")]
+pub mod share_reencryption;
+#[cfg(feature = "verify")]
+pub mod share_tree;
+#[cfg(feature = "verify")]
+pub mod signed_message;
+#[cfg(feature = "verify")]
+pub mod signed_transcript;
+pub mod signing_key;
+#[cfg(feature = "stream")]
+pub mod stream;
+#[cfg(feature = "dkg")]
+pub mod trusted_dealer;
 pub mod validator;
+#[cfg(feature = "verify")]
+pub mod verified_commitment;
+#[cfg(feature = "verify")]
+pub mod verify;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "dkg"))]
 mod test_common;
 
+#[cfg(feature = "verify")]
+pub use abi::*;
+#[cfg(feature = "verify")]
+pub use blinded_share::*;
+pub use chunk::*;
+#[cfg(feature = "verify")]
+pub use complaint::*;
+#[cfg(feature = "verify")]
+pub use consistency::*;
+#[cfg(feature = "verify")]
+pub use dealer_commitment::*;
+#[cfg(feature = "dkg")]
+pub use dealing::*;
+#[cfg(feature = "verify")]
 pub use dkg::*;
+#[cfg(feature = "verify")]
+pub use domain::*;
+#[cfg(feature = "dkg")]
+pub use driver::*;
+#[cfg(feature = "verify")]
+pub use epoch::*;
+pub use extensions::*;
+pub use ferveo_tdec::protocol;
+pub use fingerprint::*;
+pub use info::*;
+#[cfg(feature = "dkg")]
+pub use interop::*;
+#[cfg(feature = "verify")]
+pub use manager::*;
+pub use membership::*;
+pub use merkle::*;
+#[cfg(feature = "verify")]
+pub use observer::*;
+#[cfg(feature = "verify")]
+pub use prevalidation::*;
+#[cfg(feature = "verify")]
 pub use primitives::*;
+#[cfg(feature = "verify")]
 pub use pvss::*;
+#[cfg(feature = "dkg")]
+pub use recovery::*;
+#[cfg(feature = "refresh")]
 pub use refresh::*;
+#[cfg(feature = "dkg")]
+pub use ritual::*;
+#[cfg(feature = "dkg")]
+pub use secure_channel::*;
+#[cfg(feature = "verify")]
+pub use session::*;
+#[cfg(feature = "dkg")]
+pub use share_cache::*;
+#[cfg(feature = "verify")]
+pub use share_ownership::*;
+#[cfg(feature = "verify")]
+pub use share_proof::*;
+#[cfg(feature = "verify")]
+pub use share_reencryption::*;
+#[cfg(feature = "verify")]
+pub use share_tree::*;
+#[cfg(feature = "verify")]
+pub use signed_message::*;
+#[cfg(feature = "verify")]
+pub use signed_transcript::*;
+pub use signing_key::*;
+#[cfg(feature = "stream")]
+pub use stream::*;
+#[cfg(feature = "dkg")]
+pub use trusted_dealer::*;
 pub use validator::*;
+#[cfg(feature = "verify")]
+pub use verified_commitment::*;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -58,6 +191,14 @@ pub enum Error {
     #[error("DKG received a PVSS transcript from a dealer that has already been dealt: {0}")]
     DuplicateDealer(EthereumAddress),
 
+    /// A dealer sent another transcript than the one the DKG already holds from it
+    #[error("Dealer {0} dealt conflicting transcripts")]
+    ConflictingTranscript(EthereumAddress),
+
+    /// A copy of a transcript that already failed verification, which isn't verified again
+    #[error("The transcript of dealer {0} was already rejected")]
+    KnownInvalidTranscript(EthereumAddress),
+
     /// DKG received an invalid transcript for which optimistic verification failed
     #[error("DKG received an invalid transcript")]
     InvalidPvssTranscript,
@@ -94,6 +235,10 @@ pub enum Error {
     #[error("Invalid byte length. Expected {0}, got {1}")]
     InvalidByteLength(usize, usize),
 
+    /// Bytes that aren't the canonical ABI encoding of a transcript or point
+    #[error("Invalid ABI encoding: {0}")]
+    InvalidAbiEncoding(String),
+
     /// Invalid variant
     #[error("Invalid variant: {0}")]
     InvalidVariant(String),
@@ -102,10 +247,21 @@ pub enum Error {
     #[error("Invalid DKG parameters: number of shares {0}, threshold {1}")]
     InvalidDkgParameters(u32, u32),
 
+    /// Every constraint on DKG parameters that a [`DkgParamsBuilder`] found violated
+    #[error(
+        "Invalid DKG parameters: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    DkgParamsViolations(Vec<DkgParamsViolation>),
+
     /// Failed to access a share for a given share index
     #[error("Invalid share index: {0}")]
     InvalidShareIndex(u32),
 
+    /// The share at a share index isn't encrypted to the key of the validator
+    #[error("Not the holder of share: {0}")]
+    NotShareHolder(u32),
+
     /// Failed to produce a precomputed variant decryption share
     #[error("Invalid DKG parameters for precomputed variant: number of shares {0}, threshold {1}")]
     InvalidDkgParametersForPrecomputedVariant(u32, u32),
@@ -114,14 +270,219 @@ pub enum Error {
     #[error("Duplicated share index: {0}")]
     DuplicatedShareIndex(u32),
 
+    /// A validator sent different decryption shares for the same ciphertext
+    #[error("Conflicting decryption shares for share index: {0}")]
+    ConflictingDecryptionShares(u32),
+
     /// Creating a transcript aggregate requires at least one transcript
     #[error("No transcripts to aggregate")]
     NoTranscriptsToAggregate,
+
+    /// Failed to fetch PVSS transcripts from a transcript source
+    #[error("Transcript source error: {0}")]
+    TranscriptSourceError(String),
+
+    /// The PVSS transcript was dealt for a different validator set
+    #[error("PVSS transcript was dealt for a different validator set")]
+    ValidatorSetMismatch,
+
+    /// The index of an aggregate chunk is out of range
+    #[error("Invalid aggregate chunk: index {0}, total {1}")]
+    InvalidAggregateChunk(u32, u32),
+
+    /// An aggregate chunk belongs to a different aggregate than the previous chunks
+    #[error("Aggregate chunk doesn't match the previous chunks")]
+    AggregateChunkMismatch,
+
+    /// Not all chunks of an aggregate were received
+    #[error("Incomplete aggregate (expected {0} chunks, got {1})")]
+    IncompleteAggregate(u32, u32),
+
+    /// The reassembled aggregate doesn't match the announced hash
+    #[error("Reassembled aggregate doesn't match its content hash")]
+    AggregateHashMismatch,
+
+    /// The ritual event is not valid in the current phase of the ritual
+    #[error("Invalid ritual transition: {1} in phase {0}")]
+    InvalidRitualTransition(&'static str, &'static str),
+
+    /// The decryption context bundle couldn't be sealed or opened with the given key
+    #[error("Failed to seal or open the decryption context bundle")]
+    RecoveryBundleDecryptionFailed,
+
+    /// A message of a pairwise channel couldn't be opened, e.g. it was sealed for another
+    /// channel or tampered with
+    #[error("Failed to open the secure channel message")]
+    SecureChannelDecryptionFailed,
+
+    /// No evaluation domain can be constructed for the number of shares
+    #[error("Unable to construct an evaluation domain")]
+    InvalidEvaluationDomain,
+
+    /// The evaluation domain of an artifact differs from the domain of this version
+    #[error(
+        "Evaluation domain doesn't match the domain the shares were dealt over"
+    )]
+    EvaluationDomainMismatch,
+
+    /// The commitment of a transcript is to a polynomial of the wrong degree
+    #[error("Transcript commits to {1} coefficients, expected {0}")]
+    InvalidTranscriptDegree(u32, u32),
+
+    /// The share index of a validator differs from its canonical share index
+    #[error("Validator {0} has share index {1}, expected {2}")]
+    NonCanonicalShareIndex(EthereumAddress, u32, u32),
+
+    /// The PVSS transcript was dealt in a different ritual
+    #[error("PVSS transcript was dealt in ritual {1}, expected {0}")]
+    RitualMismatch(u32, u32),
+
+    /// A serialized message is larger than the deserialization limit
+    #[error("Message is too large: more than {0} bytes")]
+    MessageTooLarge(u64),
+
+    /// A transcript has more shares than the deserialization limit
+    #[error("Transcript has too many shares: {0} > {1}")]
+    TooManyTranscriptShares(usize, usize),
+
+    /// More transcripts were received for an aggregate than the deserialization limit
+    #[error("Too many transcripts to aggregate: {0} > {1}")]
+    TooManyTranscripts(usize, usize),
+
+    /// The epoch is not in the epoch registry
+    #[error("Unknown epoch: {0}")]
+    UnknownEpoch(u32),
+
+    /// The epoch doesn't continue the lineage of the latest finalized epoch
+    #[error("Epoch {0} doesn't continue the epoch lineage")]
+    BrokenEpochLineage(u32),
+
+    /// The artifact doesn't match the one finalized in the epoch
+    #[error("Artifact doesn't match the one finalized in epoch {0}")]
+    EpochMismatch(u32),
+
+    /// An extension of the same type was already added
+    #[error("Duplicate extension: {0}")]
+    DuplicateExtension(u16),
+
+    /// The transcript was serialized in a format version this build doesn't support.
+    /// Transcripts encoded before the version are version `0`, see
+    /// [`PubliclyVerifiableSS::from_legacy_bytes`]
+    #[error("Unsupported transcript version: {0}")]
+    UnsupportedTranscriptVersion(u8),
+
+    /// A pending share update doesn't match the commitments to its update polynomial
+    #[error("Share update {0} doesn't match its commitments")]
+    InvalidShareUpdate(usize),
+
+    /// The DKG requires transcripts to be signed by their dealer
+    #[error("Transcript isn't signed by its dealer")]
+    UnsignedTranscript,
+
+    /// The signature of a transcript doesn't verify against the key of its dealer
+    #[error("Invalid transcript signature of dealer {0}")]
+    InvalidTranscriptSignature(EthereumAddress),
+
+    /// The signature of a message doesn't verify against the key of its sender
+    #[error("Invalid message signature of sender {0}")]
+    InvalidMessageSignature(EthereumAddress),
+
+    /// A share encryption isn't in the prime order subgroup of G2, or is the identity
+    #[error("Invalid share encryption")]
+    InvalidShareEncryption,
+
+    /// The DKG has more shares than the security configuration allows
+    #[error("Too many shares for the security configuration: {0} > {1}")]
+    CohortTooLarge(u32, u32),
+
+    /// The transcript wasn't dealt with proofs of its shares
+    #[error("Transcript has no share proofs")]
+    MissingShareProofs,
+
+    /// A cached commitment was evaluated from another transcript
+    #[error("Cached commitment doesn't match the transcript")]
+    CommitmentCacheMismatch,
+
+    /// A transcript to aggregate doesn't have the `(coefficients, shares)` counts of the
+    /// first one. `dealer` is the position of its transcript among the aggregated ones
+    #[error("Transcript {dealer} has shape {got:?} (coefficients, shares), expected {expected:?}")]
+    MismatchedTranscriptShape {
+        dealer: usize,
+        expected: (usize, usize),
+        got: (usize, usize),
+    },
+
+    /// The proof of a share re-encryption doesn't verify against the shares of the validator
+    #[error("Invalid share re-encryption of validator {0}")]
+    InvalidShareReencryption(EthereumAddress),
+
+    /// A transcript was sent by another validator than the dealer it names
+    #[error("Transcript sent by {0} claims to be dealt by {1}")]
+    DealerMismatch(EthereumAddress, EthereumAddress),
+
+    /// A contribution to a round of a DKG session arrived after the deadline of the round
+    #[error("Deadline of round {0} passed at block {1}")]
+    RoundDeadlinePassed(&'static str, u32),
+
+    /// The transport of a DKG failed to send or receive messages
+    #[error("Transport error: {0}")]
+    TransportError(String),
+
+    /// A complaint doesn't expose a faulty share of the dealer it accuses, or wasn't sent by
+    /// its accuser
+    #[error("Invalid complaint from {0}")]
+    InvalidComplaint(EthereumAddress),
+
+    /// A transcript was sent by a dealer excluded from the DKG by a complaint
+    #[error("Dealer {0} was excluded from the DKG")]
+    ExcludedDealer(EthereumAddress),
+
+    /// A transcript was dealt by, or for, an observer, which holds no shares
+    #[error("Validator {0} is an observer and can't deal")]
+    ObserverCannotDeal(EthereumAddress),
+
+    /// No session of the ritual is managed by the [`DkgManager`]
+    #[error("Unknown ritual: {0}")]
+    UnknownRitual(u32),
+
+    /// A session of the ritual is already managed by the [`DkgManager`]
+    #[error("Ritual {0} already has a session")]
+    DuplicateRitual(u32),
+
+    /// The [`DkgManager`] already runs as many sessions as its limits allow
+    #[error("Too many concurrent sessions: limit is {0}")]
+    TooManySessions(usize),
+
+    /// A sender sent the session of the ritual as many messages as its limits allow
+    #[error("Sender {1} reached its limit of {2} messages in ritual {0}")]
+    SessionMessageLimit(u32, EthereumAddress, u32),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-#[cfg(test)]
+/// A constraint on DKG parameters that doesn't hold, see [`DkgParamsBuilder`]
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum DkgParamsViolation {
+    #[error("the number of shares is zero")]
+    ZeroShares,
+
+    #[error("the security threshold is zero")]
+    ZeroThreshold,
+
+    /// The security threshold, then the number of shares
+    #[error("the security threshold {0} exceeds the number of shares {1}")]
+    ThresholdAboveShares(u32, u32),
+
+    /// The ritual id, then the first and last ritual ids allowed
+    #[error("the ritual id {0} is outside of {1}..={2}")]
+    TauOutOfRange(u32, u32, u32),
+
+    /// The total weight of the validators, then the number of shares
+    #[error("the validators hold {0} shares, not {1}")]
+    WeightMismatch(u32, u32),
+}
+
+#[cfg(all(test, feature = "refresh"))]
 mod test_dkg_full {
     use std::collections::HashMap;
 
@@ -227,7 +588,7 @@ mod test_dkg_full {
             &dkg.pvss_params.g_inv(),
         )
         .unwrap();
-        assert_eq!(plaintext, MSG);
+        assert_eq!(plaintext.as_bytes(), MSG);
     }
 
     #[test_case(4; "number of shares (validators) is a power of 2")]
@@ -251,11 +612,15 @@ mod test_dkg_full {
         let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
         let pvss_aggregated = aggregate(&pvss_list).unwrap();
         pvss_aggregated.verify_aggregation(&dkg).unwrap();
-        let domain_points = dkg
-            .domain
-            .elements()
-            .take(validator_keypairs.len())
-            .collect::<Vec<_>>();
+        // The aggregate carries the domain of the DKG
+        let domain_points = pvss_aggregated.domain_points();
+        assert_eq!(
+            domain_points,
+            dkg.domain
+                .elements()
+                .take(validator_keypairs.len())
+                .collect::<Vec<_>>()
+        );
 
         let mut decryption_shares: Vec<DecryptionSharePrecomputed<E>> =
             validator_keypairs
@@ -270,7 +635,6 @@ mod test_dkg_full {
                             AAD,
                             &validator_keypair.decryption_key,
                             validator.share_index as usize,
-                            &domain_points,
                             &dkg.pvss_params.g_inv(),
                         )
                         .unwrap()
@@ -280,7 +644,8 @@ mod test_dkg_full {
         assert_eq!(domain_points.len(), decryption_shares.len());
 
         let shared_secret =
-            ferveo_tdec::share_combine_precomputed::<E>(&decryption_shares);
+            ferveo_tdec::share_combine_precomputed::<E>(&decryption_shares)
+                .unwrap();
 
         // Combination works, let's decrypt
         let plaintext = ferveo_tdec::decrypt_with_shared_secret(
@@ -290,7 +655,7 @@ mod test_dkg_full {
             &dkg.pvss_params.g_inv(),
         )
         .unwrap();
-        assert_eq!(plaintext, MSG);
+        assert_eq!(plaintext.as_bytes(), MSG);
     }
 
     #[test]
@@ -324,11 +689,20 @@ mod test_dkg_full {
         .for_each(
             |(aggregated_share, validator_keypair, decryption_share)| {
                 assert!(decryption_share.verify(
-                    aggregated_share,
+                    aggregated_share.as_affine(),
                     &validator_keypair.public_key().encryption_key,
                     &dkg.pvss_params.h,
                     &ciphertext,
                 ));
+                // Keys prepared once per DKG may be used instead
+                let validator =
+                    dkg.get_validator(&validator_keypair.public_key()).unwrap();
+                assert!(decryption_share.verify_with_prepared_key(
+                    aggregated_share.as_affine(),
+                    dkg.prepared_keys.get(&validator.address).unwrap(),
+                    &dkg.pvss_params.h,
+                    &ciphertext,
+                ));
             },
         );
 
@@ -339,7 +713,7 @@ mod test_dkg_full {
         let mut with_bad_decryption_share = decryption_share.clone();
         with_bad_decryption_share.decryption_share = TargetField::zero();
         assert!(!with_bad_decryption_share.verify(
-            &pvss_aggregated.shares[0],
+            pvss_aggregated.shares[0].as_affine(),
             &validator_keypairs[0].public_key().encryption_key,
             &dkg.pvss_params.h,
             &ciphertext,
@@ -349,7 +723,7 @@ mod test_dkg_full {
         let mut with_bad_checksum = decryption_share;
         with_bad_checksum.validator_checksum.checksum = G1Affine::zero();
         assert!(!with_bad_checksum.verify(
-            &pvss_aggregated.shares[0],
+            pvss_aggregated.shares[0].as_affine(),
             &validator_keypairs[0].public_key().encryption_key,
             &dkg.pvss_params.h,
             &ciphertext,
@@ -621,4 +995,156 @@ mod test_dkg_full {
 
         assert_eq!(old_shared_secret, new_shared_secret);
     }
+
+    #[test]
+    fn test_dkg_simple_tdec_lazy_share_refreshing() {
+        let rng = &mut test_rng();
+
+        let (dkg, validator_keypairs) =
+            setup_dealt_dkg_with(SECURITY_THRESHOLD, SHARES_NUM);
+        let ciphertext = ferveo_tdec::encrypt::<E>(
+            SecretBox::new(MSG.to_vec()),
+            AAD,
+            &dkg.public_key(),
+            rng,
+        )
+        .unwrap();
+        let (pvss_aggregated, _, old_shared_secret) =
+            make_shared_secret_simple_tdec(
+                &dkg,
+                AAD,
+                &ciphertext.header().unwrap(),
+                validator_keypairs.as_slice(),
+            );
+
+        // Every validator is offline for two refresh epochs, and keeps the update
+        // transcripts of the other validators until it decrypts its share again
+        let domain_points = dkg.domain.elements().collect::<Vec<_>>();
+        let encryption_keys = dkg
+            .share_owners()
+            .iter()
+            .map(|validator| validator.public_key.encryption_key)
+            .collect::<Vec<_>>();
+        let mut pending = (0..SHARES_NUM)
+            .map(PendingUpdates::<E>::new)
+            .collect::<Vec<_>>();
+        for _epoch in 0..2 {
+            for _dealer in 0..SHARES_NUM {
+                let transcript = ShareUpdateTranscript::<E>::new_refresh(
+                    &domain_points,
+                    &encryption_keys,
+                    SECURITY_THRESHOLD as usize,
+                    &dkg.pvss_params,
+                    rng,
+                )
+                .unwrap();
+                for pending in &mut pending {
+                    pending.push(transcript.clone());
+                }
+            }
+        }
+
+        let decryption_shares = validator_keypairs
+            .iter()
+            .zip(&pending)
+            .map(|(validator_keypair, pending)| {
+                let updated_share = pvss_aggregated
+                    .decrypt_private_key_share_with_updates(
+                        &validator_keypair.decryption_key,
+                        pending,
+                        &dkg.pvss_params,
+                    )
+                    .unwrap();
+                DecryptionShareSimple::create(
+                    &validator_keypair.decryption_key,
+                    &updated_share,
+                    &ciphertext.header().unwrap(),
+                    AAD,
+                    &dkg.pvss_params.g_inv(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let lagrange = ferveo_tdec::prepare_combine_simple::<E>(
+            &domain_points[..SECURITY_THRESHOLD as usize],
+        );
+        let new_shared_secret = ferveo_tdec::share_combine_simple::<E>(
+            &decryption_shares[..SECURITY_THRESHOLD as usize],
+            &lagrange,
+        );
+        assert_eq!(old_shared_secret, new_shared_secret);
+
+        // Updates that don't match their commitments, or that would change the shared
+        // secret, are rejected
+        let mut tampered = pending[0].clone();
+        let mut transcript = ShareUpdateTranscript::<E>::new_refresh(
+            &domain_points,
+            &encryption_keys,
+            SECURITY_THRESHOLD as usize,
+            &dkg.pvss_params,
+            rng,
+        )
+        .unwrap();
+        let ek_1 = &encryption_keys[1];
+        let (threshold, shares_num) =
+            (SECURITY_THRESHOLD as usize, SHARES_NUM as usize);
+        assert!(transcript.verify_update(
+            1,
+            &domain_points[1],
+            ek_1,
+            threshold,
+            shares_num,
+            &dkg.pvss_params
+        ));
+
+        // Updates are encrypted to the holder of their share
+        assert!(!transcript.verify_update(
+            1,
+            &domain_points[1],
+            &encryption_keys[2],
+            threshold,
+            shares_num,
+            &dkg.pvss_params
+        ));
+
+        // Transcripts of another shape are rejected
+        assert!(!transcript.verify_update(
+            1,
+            &domain_points[1],
+            ek_1,
+            threshold + 1,
+            shares_num,
+            &dkg.pvss_params
+        ));
+        let mut truncated = transcript.clone();
+        truncated.updates.pop();
+        assert!(!truncated.verify_update(
+            1,
+            &domain_points[1],
+            ek_1,
+            threshold,
+            shares_num,
+            &dkg.pvss_params
+        ));
+
+        transcript.updates[0] = G2::generator();
+        tampered.push(transcript.clone());
+        assert!(matches!(
+            pvss_aggregated.decrypt_private_key_share_with_updates(
+                &validator_keypairs[0].decryption_key,
+                &tampered,
+                &dkg.pvss_params,
+            ),
+            Err(Error::InvalidShareUpdate(8))
+        ));
+        transcript.coeffs[0] = G1Affine::generator();
+        assert!(!transcript.verify_update(
+            1,
+            &domain_points[1],
+            ek_1,
+            threshold,
+            shares_num,
+            &dkg.pvss_params
+        ));
+    }
 }
@@ -0,0 +1,300 @@
+use std::collections::BTreeMap;
+
+use ark_ec::pairing::Pairing;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    DkgState, Error, EthereumAddress, Message, PubliclyVerifiableDkg, Result,
+    Validator,
+};
+
+/// How much a [`DkgManager`] takes on
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionLimits {
+    /// The number of sessions run concurrently
+    pub max_sessions: usize,
+    /// The number of messages a session verifies from each sender, valid or not, which
+    /// bounds the verification work a peer can cause in a single ritual without exhausting
+    /// the budget of the others
+    pub max_messages_per_sender: u32,
+    /// The number of blocks a session runs for before it's considered stalled, and
+    /// collected by [`DkgManager::collect_garbage`]
+    pub max_session_blocks: u32,
+}
+
+impl Default for SessionLimits {
+    fn default() -> Self {
+        Self {
+            max_sessions: 64,
+            max_messages_per_sender: 64,
+            max_session_blocks: 7200,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ManagedSession<E: Pairing> {
+    dkg: PubliclyVerifiableDkg<E>,
+    started_at: u32,
+    messages: BTreeMap<EthereumAddress, u32>,
+}
+
+/// The DKG sessions a validator runs concurrently, keyed by ritual id, i.e. by the `tau` of
+/// their [`crate::DkgParams`].
+///
+/// Messages are routed to the session of the ritual they were received for, and checked
+/// against the ritual they claim, see [`Message::ritual_id`]. Sessions stay in the manager
+/// once they are over or stalled, until [`DkgManager::collect_garbage`] hands them over.
+#[derive(Clone, Debug)]
+pub struct DkgManager<E: Pairing> {
+    sessions: BTreeMap<u32, ManagedSession<E>>,
+    limits: SessionLimits,
+}
+
+impl<E: Pairing> Default for DkgManager<E> {
+    fn default() -> Self {
+        Self::new(SessionLimits::default())
+    }
+}
+
+impl<E: Pairing> DkgManager<E> {
+    pub fn new(limits: SessionLimits) -> Self {
+        Self {
+            sessions: BTreeMap::new(),
+            limits,
+        }
+    }
+
+    pub fn limits(&self) -> &SessionLimits {
+        &self.limits
+    }
+
+    /// Start managing `dkg` as the session of its ritual, at `block`
+    pub fn start(
+        &mut self,
+        dkg: PubliclyVerifiableDkg<E>,
+        block: u32,
+    ) -> Result<()> {
+        let ritual = dkg.dkg_params.tau();
+        if self.sessions.contains_key(&ritual) {
+            return Err(Error::DuplicateRitual(ritual));
+        }
+        if self.sessions.len() >= self.limits.max_sessions {
+            return Err(Error::TooManySessions(self.limits.max_sessions));
+        }
+        self.sessions.insert(
+            ritual,
+            ManagedSession {
+                dkg,
+                started_at: block,
+                messages: BTreeMap::new(),
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get(&self, ritual: u32) -> Option<&PubliclyVerifiableDkg<E>> {
+        self.sessions.get(&ritual).map(|session| &session.dkg)
+    }
+
+    pub fn get_mut(
+        &mut self,
+        ritual: u32,
+    ) -> Option<&mut PubliclyVerifiableDkg<E>> {
+        self.sessions
+            .get_mut(&ritual)
+            .map(|session| &mut session.dkg)
+    }
+
+    /// The rituals of the managed sessions, in ascending order
+    pub fn rituals(&self) -> impl Iterator<Item = u32> + '_ {
+        self.sessions.keys().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Verify `message`, received from `sender` for `ritual`, and apply it to the session of
+    /// the ritual. Returns the state of the session.
+    ///
+    /// Messages that reach verification count towards the limit of their sender, see
+    /// [`SessionLimits::max_messages_per_sender`]. On error, the session is left unchanged,
+    /// except for the message count of the sender.
+    pub fn handle_message(
+        &mut self,
+        ritual: u32,
+        sender: &Validator<E>,
+        message: &Message<E>,
+    ) -> Result<&DkgState<E>> {
+        let limit = self.limits.max_messages_per_sender;
+        let session = self
+            .sessions
+            .get_mut(&ritual)
+            .ok_or(Error::UnknownRitual(ritual))?;
+        match message.ritual_id() {
+            Some(claimed) if claimed != ritual => {
+                return Err(Error::RitualMismatch(ritual, claimed))
+            }
+            _ => {}
+        }
+        // Only the validators of the ritual have a budget, so that unknown senders can't
+        // grow the session
+        if !session.dkg.validators.contains_key(&sender.address) {
+            return Err(Error::UnknownDealer(sender.address.clone()));
+        }
+        let messages =
+            session.messages.entry(sender.address.clone()).or_default();
+        if *messages >= limit {
+            return Err(Error::SessionMessageLimit(
+                ritual,
+                sender.address.clone(),
+                limit,
+            ));
+        }
+        *messages += 1;
+        session.dkg.verify_message(sender, message)?;
+        session.dkg.apply_message(sender, message)?;
+        Ok(&session.dkg.state)
+    }
+
+    /// Stop managing `ritual`, returning its session
+    pub fn remove(&mut self, ritual: u32) -> Option<PubliclyVerifiableDkg<E>> {
+        self.sessions.remove(&ritual).map(|session| session.dkg)
+    }
+
+    /// Stop managing the sessions that are over at `block`, i.e. that succeeded, were
+    /// invalidated, or stalled for more than [`SessionLimits::max_session_blocks`], returning
+    /// them, e.g. to persist the final key of the ritual
+    pub fn collect_garbage(
+        &mut self,
+        block: u32,
+    ) -> Vec<PubliclyVerifiableDkg<E>> {
+        let max_session_blocks = self.limits.max_session_blocks;
+        let over = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| {
+                matches!(
+                    session.dkg.state,
+                    DkgState::Success { .. } | DkgState::Invalid
+                ) || block.saturating_sub(session.started_at)
+                    > max_session_blocks
+            })
+            .map(|(ritual, _)| *ritual)
+            .collect::<Vec<_>>();
+        over.into_iter()
+            .filter_map(|ritual| self.remove(ritual))
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_manager {
+    use super::*;
+    use crate::{test_common::*, DkgParams};
+
+    #[test]
+    fn test_manager_routes_by_ritual() {
+        let rng = &mut ark_std::test_rng();
+        let keypairs = gen_keypairs(SHARES_NUM);
+        let validators = gen_validators(&keypairs);
+        let setup = |ritual: u32, me: &Validator<E>| {
+            PubliclyVerifiableDkg::<E>::new(
+                &validators,
+                &DkgParams::new(ritual, SECURITY_THRESHOLD, SHARES_NUM)
+                    .unwrap(),
+                me,
+            )
+            .unwrap()
+        };
+        let mut manager = DkgManager::<E>::new(SessionLimits {
+            max_sessions: 2,
+            max_messages_per_sender: 2,
+            max_session_blocks: 100,
+        });
+        for ritual in [TAU, TAU + 1] {
+            manager.start(setup(ritual, &validators[0]), 0).unwrap();
+        }
+        assert!(matches!(
+            manager.start(setup(TAU, &validators[0]), 0),
+            Err(Error::DuplicateRitual(TAU))
+        ));
+        assert!(matches!(
+            manager.start(setup(TAU + 2, &validators[0]), 0),
+            Err(Error::TooManySessions(2))
+        ));
+
+        // Transcripts of a ritual are only accepted by its session
+        let deals = validators
+            .iter()
+            .map(|validator| setup(TAU, validator).share(rng).unwrap())
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            manager.handle_message(TAU + 1, &validators[0], &deals[0]),
+            Err(Error::RitualMismatch(expected, actual))
+                if expected == TAU + 1 && actual == TAU
+        ));
+        assert!(matches!(
+            manager.handle_message(TAU + 2, &validators[0], &deals[0]),
+            Err(Error::UnknownRitual(_))
+        ));
+        for (validator, deal) in validators.iter().zip(&deals) {
+            manager.handle_message(TAU, validator, deal).unwrap();
+        }
+        assert_eq!(manager.get(TAU).unwrap().vss.len(), SHARES_NUM as usize);
+        assert!(manager.get(TAU + 1).unwrap().vss.is_empty());
+
+        // Finished sessions are collected, the others are kept
+        let aggregate = manager.get(TAU).unwrap().aggregate().unwrap();
+        let state = manager
+            .handle_message(TAU, &validators[0], &aggregate)
+            .unwrap();
+        assert!(matches!(state, DkgState::Success { .. }));
+        let collected = manager.collect_garbage(1);
+        assert_eq!(collected.len(), 1);
+        assert_eq!(collected[0].dkg_params.tau(), TAU);
+        assert_eq!(manager.rituals().collect::<Vec<_>>(), vec![TAU + 1]);
+
+        // Messages rejected before verification don't count towards any limit
+        let deal = setup(TAU + 1, &validators[1]).share(rng).unwrap();
+        let stranger =
+            gen_validators(&gen_keypairs(SHARES_NUM + 1)).pop().unwrap();
+        for _ in 0..3 {
+            assert!(matches!(
+                manager.handle_message(TAU + 1, &validators[0], &deals[0]),
+                Err(Error::RitualMismatch(..))
+            ));
+            assert!(matches!(
+                manager.handle_message(TAU + 1, &stranger, &deal),
+                Err(Error::UnknownDealer(_))
+            ));
+        }
+
+        // The others count towards the limit of their sender, valid or not
+        for _ in 0..2 {
+            assert!(manager
+                .handle_message(TAU + 1, &validators[0], &deal)
+                .is_err());
+        }
+        assert!(matches!(
+            manager.handle_message(TAU + 1, &validators[0], &deal),
+            Err(Error::SessionMessageLimit(ritual, sender, 2))
+                if ritual == TAU + 1 && sender == validators[0].address
+        ));
+        // Without exhausting the budget of the other senders
+        manager
+            .handle_message(TAU + 1, &validators[1], &deal)
+            .unwrap();
+
+        // Stalled sessions are collected
+        assert!(manager.collect_garbage(100).is_empty());
+        let collected = manager.collect_garbage(101);
+        assert_eq!(collected.len(), 1);
+        assert!(manager.is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+use ark_ec::pairing::Pairing;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    merkle_proof, merkle_root, update_with_validator, Error, EthereumAddress,
+    MerkleProof, Result, Validator,
+};
+
+/// Commitment to the committee backing a DKG public key: a Merkle root over its
+/// validators, ordered by share index.
+///
+/// A requester that trusts the commitment, e.g. because it is recorded on chain next to
+/// the public key, checks that a peer is in the committee with a [`MembershipProof`],
+/// without the full validator list.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitteeCommitment<E: Pairing> {
+    /// The public key of the DKG
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub public_key: E::G1Affine,
+    pub validators_root: [u8; 32],
+    pub num_validators: u32,
+}
+
+impl<E: Pairing> CommitteeCommitment<E> {
+    pub fn new<'a>(
+        validators: impl IntoIterator<Item = &'a Validator<E>>,
+        public_key: E::G1Affine,
+    ) -> Self {
+        let leaves = committee_leaves(validators);
+        Self {
+            public_key,
+            validators_root: merkle_root(&leaves),
+            num_validators: leaves.len() as u32,
+        }
+    }
+}
+
+/// Proof that `validator` is in the committee of a [`CommitteeCommitment`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct MembershipProof<E: Pairing> {
+    pub validator: Validator<E>,
+    pub proof: MerkleProof,
+}
+
+impl<E: Pairing> MembershipProof<E> {
+    /// Proof that the validator with `address` is one of `validators`
+    pub fn new<'a>(
+        validators: impl IntoIterator<Item = &'a Validator<E>>,
+        address: &EthereumAddress,
+    ) -> Result<Self> {
+        let validators = sorted_by_share_index(validators);
+        let leaf_index = validators
+            .iter()
+            .position(|validator| &validator.address == address)
+            .ok_or_else(|| Error::DealerNotInValidatorSet(address.clone()))?;
+        let leaves = validators
+            .iter()
+            .map(|validator| committee_leaf(validator))
+            .collect::<Vec<_>>();
+        Ok(Self {
+            validator: validators[leaf_index].clone(),
+            proof: merkle_proof(&leaves, leaf_index)
+                .expect("The validator is one of the leaves"),
+        })
+    }
+
+    /// Whether the validator is in the committee of `commitment`
+    pub fn verify(&self, commitment: &CommitteeCommitment<E>) -> bool {
+        self.proof.verify(
+            committee_leaf(&self.validator),
+            commitment.num_validators as usize,
+            &commitment.validators_root,
+        )
+    }
+
+    /// Whether the validator is in the committee of `commitment` and holds the share
+    /// `share_index`, e.g. the share index of a decryption share it sent
+    pub fn verify_share_holder(
+        &self,
+        commitment: &CommitteeCommitment<E>,
+        share_index: u32,
+    ) -> bool {
+        self.validator.holds_share(share_index) && self.verify(commitment)
+    }
+}
+
+fn sorted_by_share_index<'a, E: Pairing>(
+    validators: impl IntoIterator<Item = &'a Validator<E>>,
+) -> Vec<&'a Validator<E>> {
+    let mut validators = validators.into_iter().collect::<Vec<_>>();
+    validators.sort_by_key(|validator| validator.share_index);
+    validators
+}
+
+fn committee_leaves<'a, E: Pairing>(
+    validators: impl IntoIterator<Item = &'a Validator<E>>,
+) -> Vec<[u8; 32]> {
+    sorted_by_share_index(validators)
+        .into_iter()
+        .map(committee_leaf)
+        .collect()
+}
+
+fn committee_leaf<E: Pairing>(validator: &Validator<E>) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(V1::COMMITTEE_MEMBER_DOMAIN);
+    update_with_validator(&mut hasher, validator);
+    hasher.finalize().into()
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_membership {
+    use super::*;
+    use crate::test_common::*;
+
+    #[test]
+    fn test_membership_proofs() {
+        let (dkg, _) = setup_dealt_dkg();
+        let commitment = dkg.committee_commitment();
+        assert_eq!(commitment.public_key, dkg.public_key());
+        assert_eq!(commitment.num_validators, SHARES_NUM);
+
+        for validator in dkg.validators.values() {
+            let proof = MembershipProof::new(
+                dkg.validators.values(),
+                &validator.address,
+            )
+            .unwrap();
+            assert_eq!(&proof.validator, validator);
+            assert!(proof.verify(&commitment));
+            assert!(
+                proof.verify_share_holder(&commitment, validator.share_index)
+            );
+            assert!(!proof
+                .verify_share_holder(&commitment, validator.share_index + 1));
+
+            // Another key for the same validator
+            let mut forged = proof.clone();
+            forged.validator.public_key =
+                gen_keypairs(SHARES_NUM + 1)[SHARES_NUM as usize].public_key();
+            assert!(!forged.verify(&commitment));
+        }
+
+        // A validator of another committee
+        let (other_dkg, _) = setup_dkg_for_n_validators(2, 3, 0);
+        let other = MembershipProof::new(
+            other_dkg.validators.values(),
+            &other_dkg.me.address,
+        )
+        .unwrap();
+        assert!(!other.verify(&commitment));
+
+        assert!(matches!(
+            MembershipProof::new(
+                dkg.validators.values(),
+                &gen_address(SHARES_NUM as usize)
+            ),
+            Err(Error::DealerNotInValidatorSet(_))
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+//! Binary Merkle trees over 32-byte leaf hashes.
+//!
+//! Trees are padded with empty leaves, all zeroes, to the next power of two, so that an
+//! inclusion proof is a sibling per level and the index of the leaf tells which side each
+//! sibling is on. Leaves must be hashed with their own domain tag, inner nodes are hashed
+//! with [`ProtocolVersion::MERKLE_NODE_DOMAIN`].
+
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The empty leaf the tree is padded with
+const EMPTY_LEAF: [u8; 32] = [0; 32];
+
+/// Proof that a leaf is at `leaf_index` in a tree
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub leaf_index: u32,
+    /// The sibling of the leaf, then of each of its ancestors below the root
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerkleProof {
+    /// The root of the tree of `num_leaves` leaves that `leaf` is in, according to the
+    /// proof, or `None` if the proof doesn't fit the size of the tree
+    pub fn root(&self, leaf: [u8; 32], num_leaves: usize) -> Option<[u8; 32]> {
+        if self.leaf_index as usize >= num_leaves
+            || self.siblings.len() != tree_depth(num_leaves)
+        {
+            return None;
+        }
+        let (root, _) = self.siblings.iter().fold(
+            (leaf, self.leaf_index),
+            |(node, index), sibling| {
+                let parent = if index % 2 == 0 {
+                    hash_node(&node, sibling)
+                } else {
+                    hash_node(sibling, &node)
+                };
+                (parent, index / 2)
+            },
+        );
+        Some(root)
+    }
+
+    /// Whether `leaf` is in the tree of `num_leaves` leaves with root `root`
+    pub fn verify(
+        &self,
+        leaf: [u8; 32],
+        num_leaves: usize,
+        root: &[u8; 32],
+    ) -> bool {
+        self.root(leaf, num_leaves).as_ref() == Some(root)
+    }
+}
+
+/// The root of the tree of `leaves`. The root of an empty tree is the empty leaf
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let levels = tree_levels(leaves);
+    levels.last().expect("A tree has a root")[0]
+}
+
+/// Proof of the leaf at `leaf_index` in the tree of `leaves`
+pub fn merkle_proof(
+    leaves: &[[u8; 32]],
+    leaf_index: usize,
+) -> Option<MerkleProof> {
+    if leaf_index >= leaves.len() {
+        return None;
+    }
+    let levels = tree_levels(leaves);
+    let siblings = levels[..levels.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(depth, level)| level[(leaf_index >> depth) ^ 1])
+        .collect();
+    Some(MerkleProof {
+        leaf_index: leaf_index as u32,
+        siblings,
+    })
+}
+
+fn tree_depth(num_leaves: usize) -> usize {
+    num_leaves.max(1).next_power_of_two().trailing_zeros() as usize
+}
+
+/// Every level of the padded tree, from the leaves to the root
+fn tree_levels(leaves: &[[u8; 32]]) -> Vec<Vec<[u8; 32]>> {
+    let mut level = leaves.to_vec();
+    level.resize(leaves.len().max(1).next_power_of_two(), EMPTY_LEAF);
+    let mut levels = vec![level];
+    while levels.last().expect("There is a level").len() > 1 {
+        let next = levels
+            .last()
+            .expect("There is a level")
+            .chunks(2)
+            .map(|pair| hash_node(&pair[0], &pair[1]))
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(V1::MERKLE_NODE_DOMAIN);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod test_merkle {
+    use super::*;
+
+    fn leaves(n: u8) -> Vec<[u8; 32]> {
+        (0..n).map(|i| [i + 1; 32]).collect()
+    }
+
+    #[test]
+    fn test_merkle_proofs() {
+        for n in [1, 2, 3, 4, 5, 8, 13] {
+            let leaves = leaves(n);
+            let root = merkle_root(&leaves);
+            for (leaf_index, leaf) in leaves.iter().enumerate() {
+                let proof = merkle_proof(&leaves, leaf_index).unwrap();
+                assert!(proof.verify(*leaf, leaves.len(), &root));
+
+                // Another leaf, position, or tree size
+                assert!(!proof.verify([0xff; 32], leaves.len(), &root));
+                assert!(!proof.verify(*leaf, leaves.len() * 2 + 1, &root));
+                let mut moved = proof.clone();
+                moved.leaf_index = (leaf_index as u32 + 1) % n as u32;
+                assert!(n == 1 || !moved.verify(*leaf, leaves.len(), &root));
+            }
+            assert!(merkle_proof(&leaves, leaves.len()).is_none());
+        }
+        // A leaf can't be proven at a padding position
+        let leaves = leaves(3);
+        let mut proof = merkle_proof(&leaves, 2).unwrap();
+        proof.leaf_index = 3;
+        assert!(!proof.verify(EMPTY_LEAF, 3, &merkle_root(&leaves)));
+    }
+}
@@ -0,0 +1,152 @@
+use std::{fmt, sync::Arc};
+
+use ark_ec::pairing::Pairing;
+
+use crate::{DkgState, Error, PubliclyVerifiableSS, Validator};
+
+/// Callbacks on the progress of a [`crate::PubliclyVerifiableDkg`], e.g. to export metrics,
+/// log or update a UI. See [`crate::PubliclyVerifiableDkg::with_observer`].
+///
+/// Observers are called synchronously while the DKG handles a message, so they should
+/// return quickly, e.g. by sending the event to a channel. Every callback does nothing by
+/// default.
+pub trait DkgObserver<E: Pairing>: Send + Sync {
+    /// A transcript of `dealer` was applied
+    fn on_transcript_received(
+        &self,
+        _dealer: &Validator<E>,
+        _transcript: &PubliclyVerifiableSS<E>,
+    ) {
+    }
+
+    /// A transcript of `dealer` failed verification with `reason`
+    fn on_transcript_rejected(&self, _dealer: &Validator<E>, _reason: &Error) {}
+
+    /// An aggregate was applied, finalizing the ritual with `public_key`
+    fn on_aggregation_complete(&self, _public_key: &E::G1Affine) {}
+
+    /// The DKG moved from the phase of `from` to the phase of `to`, e.g. from sharing to
+    /// dealt once the security threshold of transcripts was received
+    fn on_phase_change(&self, _from: &DkgState<E>, _to: &DkgState<E>) {}
+}
+
+/// The observers of a DKG. They aren't part of the state of the DKG, so they aren't
+/// serialized, and must be attached again to a restored DKG
+#[derive(Clone)]
+pub(crate) struct DkgObservers<E: Pairing>(Vec<Arc<dyn DkgObserver<E>>>);
+
+impl<E: Pairing> DkgObservers<E> {
+    pub(crate) fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub(crate) fn push(&mut self, observer: Arc<dyn DkgObserver<E>>) {
+        self.0.push(observer);
+    }
+
+    pub(crate) fn notify(&self, event: impl Fn(&dyn DkgObserver<E>)) {
+        self.0.iter().for_each(|observer| event(observer.as_ref()));
+    }
+}
+
+impl<E: Pairing> fmt::Debug for DkgObservers<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DkgObservers({})", self.0.len())
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_observer {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::test_common::*;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl DkgObserver<E> for Recorder {
+        fn on_transcript_received(
+            &self,
+            dealer: &Validator<E>,
+            _transcript: &PubliclyVerifiableSS<E>,
+        ) {
+            self.record(format!("received {}", dealer.share_index));
+        }
+
+        fn on_transcript_rejected(
+            &self,
+            dealer: &Validator<E>,
+            reason: &Error,
+        ) {
+            self.record(format!("rejected {}: {reason}", dealer.share_index));
+        }
+
+        fn on_aggregation_complete(&self, _public_key: &G1) {
+            self.record("aggregated".to_string());
+        }
+
+        fn on_phase_change(&self, from: &DkgState<E>, to: &DkgState<E>) {
+            let phase = |state: &DkgState<E>| match state {
+                DkgState::Sharing { .. } => "sharing",
+                DkgState::Dealt => "dealt",
+                DkgState::Success { .. } => "success",
+                DkgState::Invalid => "invalid",
+            };
+            self.record(format!("{} -> {}", phase(from), phase(to)));
+        }
+    }
+
+    impl Recorder {
+        fn record(&self, event: String) {
+            self.0.lock().unwrap().push(event);
+        }
+
+        fn events(&self) -> Vec<String> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    #[test]
+    fn observers_are_notified_of_progress() {
+        let rng = &mut ark_std::test_rng();
+        let recorder = Arc::new(Recorder::default());
+        let (dkg, _) = setup_dkg(0);
+        let mut dkg = dkg.with_observer(recorder.clone());
+        let deals = (0..SHARES_NUM as usize)
+            .map(|i| {
+                let (mut dealer_dkg, _) = setup_dkg(i);
+                let deal = dealer_dkg.share(rng).unwrap();
+                (dealer_dkg.me.clone(), deal)
+            })
+            .collect::<Vec<_>>();
+
+        // A transcript sent by another dealer is rejected
+        let (first, deal) = &deals[0];
+        let (second, _) = &deals[1];
+        let reason = dkg.verify_message(second, deal).unwrap_err();
+        assert!(dkg.verify_message(first, deal).is_ok());
+        let mut expected =
+            vec![format!("rejected {}: {reason}", second.share_index)];
+        assert_eq!(recorder.events(), expected);
+
+        for (sender, deal) in &deals[..SECURITY_THRESHOLD as usize] {
+            dkg.apply_message(sender, deal).unwrap();
+            expected.push(format!("received {}", sender.share_index));
+        }
+        expected.push("sharing -> dealt".to_string());
+        let aggregate = dkg.aggregate().unwrap();
+        let me = dkg.me.clone();
+        dkg.apply_message(&me, &aggregate).unwrap();
+        expected.push("dealt -> success".to_string());
+        expected.push("aggregated".to_string());
+        assert_eq!(recorder.events(), expected);
+
+        // Observers aren't serialized with the DKG
+        let restored = crate::PubliclyVerifiableDkg::<E>::restore(
+            &dkg.to_bytes().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(format!("{:?}", restored.observers), "DkgObservers(0)");
+    }
+}
@@ -0,0 +1,221 @@
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_serialize::CanonicalSerialize;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+use serde::Deserialize;
+
+use crate::{DkgParams, PubliclyVerifiableSS, ValidatorSetHash};
+
+/// Why [`PubliclyVerifiableSS::prevalidate`] rejected a serialized transcript
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum TranscriptRejection {
+    /// The transcript ends before the fields that are checked do
+    #[error("Transcript is truncated")]
+    Truncated,
+
+    /// The transcript was serialized in a format version this build doesn't support
+    #[error("Unsupported transcript version: {0}")]
+    UnsupportedVersion(u8),
+
+    /// The length of the field `field` doesn't match the points it holds
+    #[error("Malformed length of field {field}")]
+    MalformedLength { field: &'static str },
+
+    /// The transcript doesn't commit to `security_threshold` coefficients
+    #[error("Transcript has {got} coefficients, expected {expected}")]
+    WrongCoefficientCount { expected: u64, got: u64 },
+
+    /// The transcript doesn't have `shares_num` shares
+    #[error("Transcript has {got} shares, expected {expected}")]
+    WrongShareCount { expected: u64, got: u64 },
+
+    /// The transcript was dealt in another ritual
+    #[error("Transcript was dealt in ritual {got}, expected {expected}")]
+    WrongRitual { expected: u32, got: u32 },
+}
+
+/// The header every serialized transcript starts with, see [`crate::transcript_version`]
+#[derive(Deserialize)]
+struct TranscriptHeader {
+    magic: [u8; 4],
+    version: u8,
+}
+
+/// The leading fields of a serialized transcript of version 1, up to the last one
+/// prevalidation checks. Points are borrowed as the byte strings `SerdeAs` encodes them to,
+/// so that none is deserialized.
+///
+/// A transcript format that changes these fields must come with a new version, and a
+/// layout of its own here.
+#[derive(Deserialize)]
+struct TranscriptFieldsV1<'a> {
+    _header: TranscriptHeader,
+    coeffs: &'a [u8],
+    shares: &'a [u8],
+    sigma: &'a [u8],
+    _validator_set_hash: ValidatorSetHash,
+    tau: u32,
+}
+
+/// The number of `point_size` byte points in `field`, which `SerdeAs` encodes as their
+/// count followed by the points
+fn point_count(
+    field: &[u8],
+    point_size: usize,
+    name: &'static str,
+) -> Result<u64, TranscriptRejection> {
+    let malformed = TranscriptRejection::MalformedLength { field: name };
+    if field.len() < 8 {
+        return Err(malformed);
+    }
+    let (count, points) = field.split_at(8);
+    let count = u64::from_le_bytes(count.try_into().expect("8 bytes"));
+    if count.checked_mul(point_size as u64) != Some(points.len() as u64) {
+        return Err(malformed);
+    }
+    Ok(count)
+}
+
+impl<E: Pairing> PubliclyVerifiableSS<E> {
+    /// Check the structure of a serialized transcript against the parameters of the DKG it
+    /// is meant for, without deserializing any point: its version, its coefficient and share
+    /// counts, the lengths of its fields and its ritual.
+    ///
+    /// This is a cheap filter for relays to drop garbage before paying for deserialization
+    /// and pairings. A transcript that passes it may still be invalid.
+    pub fn prevalidate(
+        bytes: &[u8],
+        expected_params: &DkgParams,
+    ) -> Result<(), TranscriptRejection> {
+        let header: TranscriptHeader = bincode::deserialize(bytes)
+            .map_err(|_| TranscriptRejection::Truncated)?;
+        // Transcripts encoded before the version header are reported as version 0
+        let version = if header.magic == V1::TRANSCRIPT_MAGIC {
+            header.version
+        } else {
+            0
+        };
+        if version != V1::TRANSCRIPT_VERSION {
+            return Err(TranscriptRejection::UnsupportedVersion(version));
+        }
+        let fields: TranscriptFieldsV1<'_> = bincode::deserialize(bytes)
+            .map_err(|_| TranscriptRejection::Truncated)?;
+
+        let g1_size = E::G1Affine::generator().compressed_size();
+        let g2_size = E::G2Affine::generator().compressed_size();
+        let coeffs = point_count(fields.coeffs, g1_size, "coeffs")?;
+        let expected = expected_params.security_threshold() as u64;
+        if coeffs != expected {
+            return Err(TranscriptRejection::WrongCoefficientCount {
+                expected,
+                got: coeffs,
+            });
+        }
+        let shares = point_count(fields.shares, g2_size, "shares")?;
+        let expected = expected_params.shares_num() as u64;
+        if shares != expected {
+            return Err(TranscriptRejection::WrongShareCount {
+                expected,
+                got: shares,
+            });
+        }
+        // The proof of knowledge, a single point
+        if fields.sigma.len() != g2_size {
+            return Err(TranscriptRejection::MalformedLength {
+                field: "sigma",
+            });
+        }
+        if fields.tau != expected_params.tau() {
+            return Err(TranscriptRejection::WrongRitual {
+                expected: expected_params.tau(),
+                got: fields.tau,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_prevalidation {
+    use super::*;
+    use crate::test_common::*;
+
+    #[test]
+    fn test_prevalidate_transcripts() {
+        let (dkg, _) = setup_dealt_dkg();
+        let params = &dkg.dkg_params;
+        let pvss = dkg.vss.values().next().unwrap();
+        let bytes = bincode::serialize(pvss).unwrap();
+        assert_eq!(
+            PubliclyVerifiableSS::<E>::prevalidate(&bytes, params),
+            Ok(())
+        );
+
+        let mut other_version = bytes.clone();
+        other_version[4] = 2;
+        assert_eq!(
+            PubliclyVerifiableSS::<E>::prevalidate(&other_version, params),
+            Err(TranscriptRejection::UnsupportedVersion(2))
+        );
+        // Transcripts without the version tag, e.g. encoded before it
+        let mut unversioned = bytes.clone();
+        unversioned[0] = 0;
+        assert_eq!(
+            PubliclyVerifiableSS::<E>::prevalidate(&unversioned, params),
+            Err(TranscriptRejection::UnsupportedVersion(0))
+        );
+        assert_eq!(
+            PubliclyVerifiableSS::<E>::prevalidate(&bytes[..20], params),
+            Err(TranscriptRejection::Truncated)
+        );
+        assert_eq!(
+            PubliclyVerifiableSS::<E>::prevalidate(&[], params),
+            Err(TranscriptRejection::Truncated)
+        );
+
+        // A point count that doesn't match the length of the field, after the 5 byte
+        // header and the 8 byte length of the field
+        let mut bad_count = bytes.clone();
+        bad_count[13] += 1;
+        assert_eq!(
+            PubliclyVerifiableSS::<E>::prevalidate(&bad_count, params),
+            Err(TranscriptRejection::MalformedLength { field: "coeffs" })
+        );
+
+        let mut short = pvss.clone();
+        short.shares.pop();
+        assert_eq!(
+            PubliclyVerifiableSS::<E>::prevalidate(
+                &bincode::serialize(&short).unwrap(),
+                params
+            ),
+            Err(TranscriptRejection::WrongShareCount {
+                expected: SHARES_NUM as u64,
+                got: SHARES_NUM as u64 - 1
+            })
+        );
+        let mut low_degree = pvss.clone();
+        low_degree.coeffs.pop();
+        assert_eq!(
+            PubliclyVerifiableSS::<E>::prevalidate(
+                &bincode::serialize(&low_degree).unwrap(),
+                params
+            ),
+            Err(TranscriptRejection::WrongCoefficientCount {
+                expected: SECURITY_THRESHOLD as u64,
+                got: SECURITY_THRESHOLD as u64 - 1
+            })
+        );
+        let mut other_ritual = pvss.clone();
+        other_ritual.tau += 1;
+        assert_eq!(
+            PubliclyVerifiableSS::<E>::prevalidate(
+                &bincode::serialize(&other_ritual).unwrap(),
+                params
+            ),
+            Err(TranscriptRejection::WrongRitual {
+                expected: TAU,
+                got: TAU + 1
+            })
+        );
+    }
+}
@@ -1,4 +1,10 @@
-use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{batch_inversion, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain,
+    GeneralEvaluationDomain, Polynomial,
+};
+use rand_core::RngCore;
 
 // pub fn batch_to_projective<A: ark_ec::AffineCurve>(
 //     p: &[A],
@@ -15,3 +21,138 @@ pub fn batch_to_projective_g1<E: Pairing>(p: &[E::G1Affine]) -> Vec<E::G1> {
 pub fn batch_to_projective_g2<E: Pairing>(p: &[E::G2Affine]) -> Vec<E::G2> {
     p.iter().map(|a| a.into_group()).collect::<Vec<_>>()
 }
+
+/// Evaluate the commitment polynomial with coefficient commitments `coeffs` at `x`, i.e.
+/// compute `[f(x)] G`
+pub fn evaluate_commitment<E: Pairing>(
+    coeffs: &[E::G1Affine],
+    x: &E::ScalarField,
+) -> E::G1 {
+    // Horner's method: F_0 + x * (F_1 + x * (F_2 + ...))
+    coeffs
+        .iter()
+        .rev()
+        .fold(E::G1::zero(), |acc, coeff| acc * x + coeff)
+}
+
+/// Whether e(a, b) == e(c, d)
+#[cfg(not(ferveo_mock_crypto))]
+pub fn pairings_match<E: Pairing>(
+    a: impl Into<E::G1Affine>,
+    b: impl Into<E::G2Affine>,
+    c: impl Into<E::G1Affine>,
+    d: impl Into<E::G2Prepared>,
+) -> bool {
+    E::pairing(a.into(), b.into()) == E::pairing(c.into(), d)
+}
+
+/// Stand-in for the pairing check under `ferveo_mock_crypto`, which only rejects the
+/// identity
+#[cfg(ferveo_mock_crypto)]
+pub fn pairings_match<E: Pairing>(
+    a: impl Into<E::G1Affine>,
+    b: impl Into<E::G2Affine>,
+    c: impl Into<E::G1Affine>,
+    _d: impl Into<E::G2Prepared>,
+) -> bool {
+    !(a.into().is_zero() || b.into().is_zero() || c.into().is_zero())
+}
+
+/// Whether e(a, b) == e(c, d), given the prepared negation `c_inv` of `c`, with a single
+/// final exponentiation
+#[cfg(not(ferveo_mock_crypto))]
+pub fn prepared_pairings_match<E: Pairing>(
+    a: impl Into<E::G1Prepared>,
+    b: impl Into<E::G2Prepared>,
+    c_inv: &E::G1Prepared,
+    d: impl Into<E::G2Prepared>,
+) -> bool {
+    E::multi_pairing([a.into(), c_inv.clone()], [b.into(), d.into()]).is_zero()
+}
+
+/// Stand-in for [`prepared_pairings_match`] under `ferveo_mock_crypto`, which only
+/// rejects the identity
+#[cfg(ferveo_mock_crypto)]
+pub fn prepared_pairings_match<E: Pairing>(
+    a: impl Into<E::G1Affine>,
+    b: impl Into<E::G2Affine>,
+    _c_inv: &E::G1Prepared,
+    d: impl Into<E::G2Affine>,
+) -> bool {
+    !(a.into().is_zero() || b.into().is_zero() || d.into().is_zero())
+}
+
+/// The commitments A_i to the evaluations over `domain` of the polynomial whose
+/// coefficients are committed to by `coeffs`
+#[cfg(not(ferveo_mock_crypto))]
+pub fn share_commitments<E: Pairing>(
+    coeffs: &[E::G1Affine],
+    domain: &GeneralEvaluationDomain<E::ScalarField>,
+) -> Vec<E::G1> {
+    let mut commitment = batch_to_projective_g1::<E>(coeffs);
+    domain.fft_in_place(&mut commitment);
+    commitment
+}
+
+/// Stand-in for the commitments under `ferveo_mock_crypto`, which skips the FFT over the
+/// group and commits every share to the constant term
+#[cfg(ferveo_mock_crypto)]
+pub fn share_commitments<E: Pairing>(
+    coeffs: &[E::G1Affine],
+    domain: &GeneralEvaluationDomain<E::ScalarField>,
+) -> Vec<E::G1> {
+    let constant = coeffs.first().map(|c| c.into_group()).unwrap_or_default();
+    vec![constant; domain.size()]
+}
+
+/// The low-degree test of SCRAPE, section 3.2 of https://eprint.iacr.org/2017/216.pdf:
+/// whether `evals[i]` commits to the evaluation at `points[i]` of a polynomial of degree
+/// less than `degree_bound`.
+///
+/// The evaluations are checked against a random codeword of the dual code, which takes a
+/// single multi-scalar multiplication and `O(n^2)` field operations, and neither pairings
+/// nor FFTs. Evaluations of a polynomial of higher degree pass with probability at most
+/// `1 / |F|`.
+pub fn scrape_low_degree_check<G: CurveGroup, R: RngCore>(
+    evals: &[G::Affine],
+    points: &[G::ScalarField],
+    degree_bound: usize,
+    rng: &mut R,
+) -> bool {
+    let n = evals.len();
+    if points.len() != n {
+        return false;
+    }
+    // Any n evaluations are those of a polynomial of degree less than n
+    if degree_bound >= n {
+        return true;
+    }
+
+    // v_i = 1 / Π_{j != i} (x_i - x_j)
+    let mut v = points
+        .iter()
+        .enumerate()
+        .map(|(i, x_i)| {
+            points
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, x_j)| *x_i - x_j)
+                .product::<G::ScalarField>()
+        })
+        .collect::<Vec<_>>();
+    if v.iter().any(|v_i| v_i.is_zero()) {
+        // Repeated points
+        return false;
+    }
+    batch_inversion(&mut v);
+
+    // The dual code is { (v_i f(x_i))_i : deg f < n - degree_bound }
+    let f = DensePolynomial::<G::ScalarField>::rand(n - degree_bound - 1, rng);
+    let scalars = v
+        .iter()
+        .zip(points)
+        .map(|(v_i, x_i)| *v_i * f.evaluate(x_i))
+        .collect::<Vec<_>>();
+    G::msm_unchecked(evals, &scalars).is_zero()
+}
@@ -1,30 +1,114 @@
-use std::{marker::PhantomData, ops::Mul};
+use std::{collections::BTreeSet, marker::PhantomData};
 
+#[cfg(not(ferveo_mock_crypto))]
+use ark_ec::VariableBaseMSM;
 use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, Group};
-use ark_ff::{Field, Zero};
-use ark_poly::{
-    polynomial::univariate::DensePolynomial, DenseUVPolynomial,
-    EvaluationDomain,
+#[cfg(not(ferveo_mock_crypto))]
+use ark_ff::UniformRand;
+use ark_ff::Zero;
+use ark_poly::EvaluationDomain;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read,
+    SerializationError, Valid, Validate, Write,
 };
+use ferveo_common::{DeserializationLimits, Keypair, PublicKey};
 use ferveo_tdec::{
-    prepare_combine_simple, CiphertextHeader, DecryptionSharePrecomputed,
-    DecryptionShareSimple, PrivateKeyShare,
+    hash_to_g2,
+    protocol::{ProtocolVersion, V1},
+    PublicKeyShare, VerificationReport,
 };
 use itertools::Itertools;
-use rand::RngCore;
-use serde::{Deserialize, Serialize};
+use rand_core::RngCore;
+use serde::{
+    de::{DeserializeOwned, Error as _},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use serde_with::serde_as;
-use subproductdomain::fast_multiexp;
-use zeroize::{self, Zeroize, ZeroizeOnDrop};
+use sha2::{Digest, Sha256};
 
 use crate::{
-    apply_updates_to_private_share, assert_no_share_duplicates,
-    batch_to_projective_g1, batch_to_projective_g2, Error, PVSSMap,
-    PubliclyVerifiableDkg, Result, Validator,
+    aggregate_hash, assert_no_share_duplicates, batch_to_projective_g1,
+    evaluate_commitment, pairings_match, prepared_pairings_match,
+    scrape_low_degree_check, share_commitments, share_owners, DomainDescriptor,
+    Error, EthereumAddress, Extensions, PVSSMap, PreparedEncryptionKeys,
+    PubliclyVerifiableDkg, Result, Validator, ValidatorSetHash,
 };
 
-/// These are the blinded evaluations of shares of a single random polynomial
-pub type ShareEncryptions<E> = <E as Pairing>::G2Affine;
+/// The blinded evaluation `Y_i = ek_i^{f(ω_i)}` of a share of a random polynomial, i.e. the
+/// share encrypted to its holder.
+///
+/// Share encryptions are in the prime order subgroup of G2 and are never the identity. Both
+/// are checked when they are deserialized. They serialize as the bare point, so transcripts
+/// keep their wire format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShareEncryptions<E: Pairing>(E::G2Affine);
+
+impl<E: Pairing> ShareEncryptions<E> {
+    pub fn new(point: E::G2Affine) -> Result<Self> {
+        let share = Self(point);
+        share.check().map_err(|_| Error::InvalidShareEncryption)?;
+        Ok(share)
+    }
+
+    /// Wrap a point known to be valid, e.g. computed from valid share encryptions
+    pub(crate) fn new_unchecked(point: E::G2Affine) -> Self {
+        Self(point)
+    }
+
+    pub fn as_affine(&self) -> &E::G2Affine {
+        &self.0
+    }
+
+    pub fn into_affine(self) -> E::G2Affine {
+        self.0
+    }
+
+    pub fn into_group(self) -> E::G2 {
+        self.0.into_group()
+    }
+}
+
+impl<E: Pairing> Valid for ShareEncryptions<E> {
+    fn check(&self) -> std::result::Result<(), SerializationError> {
+        self.0.check()?;
+        if self.0.is_zero() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl<E: Pairing> CanonicalSerialize for ShareEncryptions<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> std::result::Result<(), SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for ShareEncryptions<E> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> std::result::Result<Self, SerializationError> {
+        let share = Self(E::G2Affine::deserialize_with_mode(
+            reader,
+            compress,
+            Validate::No,
+        )?);
+        if validate == Validate::Yes {
+            share.check()?;
+        }
+        Ok(share)
+    }
+}
 
 /// Marker struct for unaggregated PVSS transcripts
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -43,7 +127,12 @@ impl Aggregate for Aggregated {}
 /// Type alias for aggregated PVSS transcripts
 pub type AggregatedPvss<E> = PubliclyVerifiableSS<E, Aggregated>;
 
-/// The choice of group generators
+/// The choice of group generators.
+///
+/// `g` is the base of the commitments of the transcripts, and `h` the base of the private
+/// key shares. Since a share decrypts to `[s_i] H'` for the base `H'` of the encryption key it
+/// was dealt to, the encryption keys of the validators must be built on `h`, see
+/// [`PubliclyVerifiableParams::public_key`]
 #[derive(Clone, Debug)]
 pub struct PubliclyVerifiableParams<E: Pairing> {
     pub g: E::G1,
@@ -54,6 +143,42 @@ impl<E: Pairing> PubliclyVerifiableParams<E> {
     pub fn g_inv(&self) -> E::G1Prepared {
         E::G1Prepared::from(-self.g)
     }
+
+    /// The public key of `keypair` for rituals dealt with these params, i.e. `[dk] h`. Same
+    /// as [`Keypair::public_key`] for the default params
+    pub fn public_key(&self, keypair: &Keypair<E>) -> PublicKey<E> {
+        PublicKey {
+            encryption_key: (self.h * keypair.decryption_key).into_affine(),
+        }
+    }
+
+    fn is_default(&self) -> bool {
+        self.g == E::G1::generator() && self.h == E::G2::generator()
+    }
+
+    /// Bind `validator_set_hash` to these params, so that transcripts dealt with other
+    /// generators are rejected as dealt for another validator set. The hash is left as it is
+    /// for the default params
+    pub fn bind(
+        &self,
+        validator_set_hash: ValidatorSetHash,
+    ) -> ValidatorSetHash {
+        if self.is_default() {
+            return validator_set_hash;
+        }
+        let mut generators = Vec::new();
+        self.g
+            .serialize_compressed(&mut generators)
+            .expect("Generator is serializable");
+        self.h
+            .serialize_compressed(&mut generators)
+            .expect("Generator is serializable");
+        let mut hasher = Sha256::new();
+        hasher.update(V1::PVSS_PARAMS_DOMAIN);
+        hasher.update(validator_set_hash);
+        hasher.update(generators);
+        hasher.finalize().into()
+    }
 }
 
 impl<E: Pairing> Default for PubliclyVerifiableParams<E> {
@@ -65,129 +190,297 @@ impl<E: Pairing> Default for PubliclyVerifiableParams<E> {
     }
 }
 
-/// Secret polynomial used in the PVSS protocol
-/// We wrap this in a struct so that we can zeroize it after use
-pub struct SecretPolynomial<E: Pairing>(pub DensePolynomial<E::ScalarField>);
-
-impl<E: Pairing> SecretPolynomial<E> {
-    pub fn new(
-        s: &E::ScalarField,
-        degree: usize,
-        rng: &mut impl RngCore,
-    ) -> Self {
-        // Our random polynomial, \phi(x) = s + \sum_{i=1}^{t-1} a_i x^i
-        let mut phi = DensePolynomial::<E::ScalarField>::rand(degree, rng);
-        phi.coeffs[0] = *s; // setting the first coefficient to secret value
-        Self(phi)
-    }
-}
-
-impl<E: Pairing> Zeroize for SecretPolynomial<E> {
-    fn zeroize(&mut self) {
-        self.0.coeffs.iter_mut().for_each(|c| c.zeroize());
+/// Hash of the commitments to the coefficients of a transcript
+pub fn coeffs_hash<E: Pairing>(coeffs: &[E::G1Affine]) -> Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    for coeff in coeffs {
+        let mut bytes = Vec::new();
+        coeff.serialize_compressed(&mut bytes)?;
+        hasher.update(bytes);
     }
+    Ok(hasher.finalize().into())
 }
 
-// `ZeroizeOnDrop` derivation fails because of missing trait bounds, so we manually introduce
-// required traits
-
-impl<E: Pairing> Drop for SecretPolynomial<E> {
-    fn drop(&mut self) {
-        self.zeroize();
-    }
+/// The base of the proof of knowledge of a transcript, hashed to the curve from everything
+/// the proof is bound to: the validator set and ritual it was dealt in, its dealer, its
+/// commitments and its extensions. Since its discrete log is unknown, a proof of knowledge
+/// can't be replayed in another ritual or by another dealer, or moved to other commitments
+/// or extensions.
+pub fn pok_base<E: Pairing>(
+    validator_set_hash: &ValidatorSetHash,
+    tau: u32,
+    dealer: &EthereumAddress,
+    coeffs_hash: &[u8; 32],
+    extensions_hash: &[u8; 32],
+) -> Result<E::G2Affine> {
+    let dealer = dealer.to_string();
+    let mut message = V1::POK_BASE_DOMAIN.to_vec();
+    message.extend_from_slice(validator_set_hash);
+    message.extend_from_slice(&tau.to_le_bytes());
+    message.extend_from_slice(&(dealer.len() as u32).to_le_bytes());
+    message.extend_from_slice(dealer.as_bytes());
+    message.extend_from_slice(coeffs_hash);
+    message.extend_from_slice(extensions_hash);
+    Ok(hash_to_g2(&message)?)
 }
 
-impl<E: Pairing> ZeroizeOnDrop for SecretPolynomial<E> {}
-
 /// Each validator posts a transcript to the chain. Once enough
 /// validators have done this (their total voting power exceeds
 /// 2/3 the total), this will be aggregated into a final key
 #[serde_as]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct PubliclyVerifiableSS<E: Pairing, T = Unaggregated> {
-    /// Used in Feldman commitment to the VSS polynomial, F = g^{\phi}
-    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    /// Version of the transcript format, encoded after [`ProtocolVersion::TRANSCRIPT_MAGIC`].
+    /// Must stay the first field, see [`PubliclyVerifiableSS::from_bytes_limited`]
+    #[serde(
+        serialize_with = "serialize_transcript_version",
+        deserialize_with = "deserialize_transcript_version"
+    )]
+    pub version: u8,
+
+    /// Used in Feldman commitment to the VSS polynomial, F = g^{\phi}. Transcripts without
+    /// coefficients are rejected when deserialized
+    #[serde_as(as = "ferveo_common::serialization::NonEmptySerdeAs")]
     pub coeffs: Vec<E::G1Affine>,
 
     /// The shares to be dealt to each validator
     #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
-    // pub shares: Vec<ShareEncryptions<E>>, // TODO: Using a custom type instead of referring to E:G2Affine breaks the serialization
-    pub shares: Vec<E::G2Affine>,
+    pub shares: Vec<ShareEncryptions<E>>,
 
-    /// Proof of Knowledge
+    /// Proof of knowledge of the secret, `sigma = [s] pok_base`. For aggregates, the sum of
+    /// the proofs of the aggregated transcripts
     #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
     pub sigma: E::G2Affine,
 
+    /// The validator set this transcript was dealt for
+    pub validator_set_hash: ValidatorSetHash,
+
+    /// The ritual this transcript was dealt in, i.e. its ritual id
+    pub tau: u32,
+
+    /// The evaluation domain the shares were dealt over
+    #[serde(bound = "")]
+    pub domain: DomainDescriptor<E>,
+
+    /// Extensions of the transcript. The extensions of a transcript are bound to its proof
+    /// of knowledge, while those of an aggregate are set by the aggregator. Every versioned
+    /// transcript carries them, see [`PubliclyVerifiableSS::from_legacy_bytes`] otherwise
+    pub extensions: Extensions,
+
     /// Marker struct to distinguish between aggregated and
     /// non aggregated PVSS transcripts
-    phantom: PhantomData<T>,
+    pub(crate) phantom: PhantomData<T>,
+}
+
+impl<E: Pairing> PubliclyVerifiableSS<E> {
+    /// Verify the pvss transcript from a validator. This is not the full check,
+    /// i.e. we optimistically do not check the commitment. This is deferred
+    /// until the aggregation step
+    ///
+    /// The transcript must be dealt with the default generators, see
+    /// [`PubliclyVerifiableSS::verify_optimistic_with_params`] otherwise.
+    pub fn verify_optimistic(&self, dealer: &EthereumAddress) -> bool {
+        self.verify_optimistic_with_params(
+            dealer,
+            &PubliclyVerifiableParams::default(),
+        )
+    }
+
+    /// Same as [`PubliclyVerifiableSS::verify_optimistic`], for a transcript dealt with
+    /// `pvss_params`
+    pub fn verify_optimistic_with_params(
+        &self,
+        dealer: &EthereumAddress,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> bool {
+        self.verify_optimistic_report_with_params(dealer, pvss_params)
+            .is_valid()
+    }
+
+    /// Same as [`PubliclyVerifiableSS::verify_optimistic`], but reports which checks failed.
+    pub fn verify_optimistic_report(
+        &self,
+        dealer: &EthereumAddress,
+    ) -> VerificationReport {
+        self.verify_optimistic_report_with_params(
+            dealer,
+            &PubliclyVerifiableParams::default(),
+        )
+    }
+
+    /// Same as [`PubliclyVerifiableSS::verify_optimistic_report`], for a transcript dealt
+    /// with `pvss_params`
+    pub fn verify_optimistic_report_with_params(
+        &self,
+        dealer: &EthereumAddress,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> VerificationReport {
+        let mut report = VerificationReport::new();
+        let (pok_base, (constant_commitment, sigma)) =
+            match (self.pok_base(dealer), self.contribution()) {
+                (Ok(pok_base), Some(contribution)) => (pok_base, contribution),
+                _ => {
+                    report.fail("proof_of_knowledge");
+                    return report;
+                }
+            };
+        // We're only checking the proof of knowledge here, sigma ?= H^s
+        // "Does the first coefficient of the secret polynomial match the proof of knowledge?"
+        report.check("proof_of_knowledge", || {
+            prepared_pairings_match::<E>(
+                constant_commitment, // F_0 = g^s
+                pok_base,
+                &pvss_params.g_inv(),
+                sigma, // H^s
+            )
+        });
+        report
+    }
+
+    /// The commitment `F_0` to the secret and the proof of knowledge `sigma` of this
+    /// transcript, or `None` if it has no commitments
+    pub(crate) fn contribution(&self) -> Option<(E::G1Affine, E::G2Affine)> {
+        self.coeffs
+            .first()
+            .map(|constant_commitment| (*constant_commitment, self.sigma))
+    }
+
+    /// The base of the proof of knowledge of this transcript, if it was dealt by `dealer`
+    pub fn pok_base(&self, dealer: &EthereumAddress) -> Result<E::G2Affine> {
+        pok_base::<E>(
+            &self.validator_set_hash,
+            self.tau,
+            dealer,
+            &coeffs_hash::<E>(&self.coeffs)?,
+            &self.extensions.hash(),
+        )
+    }
+}
+
+impl<E: Pairing, T> PubliclyVerifiableSS<E, T>
+where
+    Self: DeserializeOwned,
+{
+    /// Deserialize a transcript received from an untrusted peer, within `limits`.
+    /// Transcripts of other format versions are rejected before being deserialized, see
+    /// [`transcript_version`].
+    pub fn from_bytes_limited(
+        bytes: &[u8],
+        limits: &DeserializationLimits,
+    ) -> Result<Self> {
+        let version = transcript_version(bytes);
+        if version != V1::TRANSCRIPT_VERSION {
+            return Err(Error::UnsupportedTranscriptVersion(version));
+        }
+        let pvss: Self = deserialize_limited(bytes, limits)?;
+        pvss.check_limits(limits)?;
+        Ok(pvss)
+    }
+}
+
+/// The encoding of a [`PubliclyVerifiableSS`] before the version, of its coefficients,
+/// shares and proof of knowledge alone
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = ""))]
+struct LegacyTranscript<E: Pairing> {
+    #[serde_as(as = "ferveo_common::serialization::NonEmptySerdeAs")]
+    coeffs: Vec<E::G1Affine>,
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    shares: Vec<ShareEncryptions<E>>,
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    sigma: E::G2Affine,
 }
 
 impl<E: Pairing, T> PubliclyVerifiableSS<E, T> {
-    /// Create a new PVSS instance
-    /// `s`: the secret constant coefficient to share
-    /// `dkg`: the current DKG session
-    /// `rng` a cryptographic random number generator
-    pub fn new<R: RngCore>(
-        s: &E::ScalarField,
+    /// Decode a transcript or an aggregate from its bincode encoding before the version,
+    /// see [`transcript_version`]. It didn't carry the ritual, validator set and domain it
+    /// was dealt for, so they are taken from `dkg`, and it has no extensions
+    pub fn from_legacy_bytes(
+        bytes: &[u8],
         dkg: &PubliclyVerifiableDkg<E>,
-        rng: &mut R,
     ) -> Result<Self> {
-        let phi = SecretPolynomial::<E>::new(
-            s,
-            (dkg.dkg_params.security_threshold() - 1) as usize,
+        let legacy: LegacyTranscript<E> = bincode::deserialize(bytes)?;
+        Ok(Self {
+            version: V1::TRANSCRIPT_VERSION,
+            coeffs: legacy.coeffs,
+            shares: legacy.shares,
+            sigma: legacy.sigma,
+            validator_set_hash: dkg.validator_set_hash(),
+            tau: dkg.dkg_params.tau(),
+            domain: DomainDescriptor::new(&dkg.domain),
+            extensions: Extensions::default(),
+            phantom: Default::default(),
+        })
+    }
+
+    /// The number of coefficients and of shares of the transcript
+    pub fn shape(&self) -> (usize, usize) {
+        (self.coeffs.len(), self.shares.len())
+    }
+
+    /// Check that `public_key_shares`, the public key shares of the validators in share
+    /// index order, are the commitment of this transcript evaluated over its domain, i.e.
+    /// `A_i = [f(ω_i)] G` for the polynomial `f` that the coefficients commit to.
+    ///
+    /// This uses [`scrape_low_degree_check`] instead of evaluating the commitment polynomial
+    /// at every domain point: the shares, together with the public key `F_0` at zero and the
+    /// commitment evaluated at a random point, must be the evaluations of a polynomial of
+    /// degree less than the threshold, which is then `f` but with negligible probability.
+    /// The encrypted shares still need a pairing each to be checked, see
+    /// [`PubliclyVerifiableSS::verify_full`], as every share is encrypted to another key.
+    pub fn verify_public_key_shares<R: RngCore>(
+        &self,
+        public_key_shares: &[E::G1Affine],
+        rng: &mut R,
+    ) -> bool {
+        let public_key = match self.coeffs.first() {
+            Some(public_key) => *public_key,
+            None => return false,
+        };
+        if public_key_shares.len() != self.shares.len() {
+            return false;
+        }
+        let r = E::ScalarField::rand(rng);
+        let evals = [
+            public_key,
+            evaluate_commitment::<E>(&self.coeffs, &r).into_affine(),
+        ]
+        .into_iter()
+        .chain(public_key_shares.iter().copied())
+        .collect::<Vec<_>>();
+        let points = [E::ScalarField::zero(), r]
+            .into_iter()
+            .chain(self.domain.domain().elements().take(self.shares.len()))
+            .collect::<Vec<_>>();
+        scrape_low_degree_check::<E::G1, _>(
+            &evals,
+            &points,
+            self.coeffs.len(),
             rng,
-        );
+        )
+    }
 
-        // Evaluations of the polynomial over the domain
-        let evals = phi.0.evaluate_over_domain_by_ref(dkg.domain);
-        // commitment to coeffs, F_i
-        let coeffs = fast_multiexp(&phi.0.coeffs, dkg.pvss_params.g);
-        let shares = dkg
-            .validators
-            .values()
-            .map(|validator| {
-                // ek_{i}^{eval_i}, i = validator index
-                fast_multiexp(
-                    // &evals.evals[i..i] = &evals.evals[i]
-                    &[evals.evals[validator.share_index as usize]], // one share per validator
-                    validator.public_key.encryption_key.into_group(),
-                )[0]
-            })
-            .collect::<Vec<ShareEncryptions<E>>>();
-        if shares.len() != dkg.validators.len() {
-            return Err(Error::InsufficientValidators(
-                shares.len() as u32,
-                dkg.validators.len() as u32,
+    /// Check that the transcript has no more shares, or coefficients, than `limits` allow
+    pub fn check_limits(&self, limits: &DeserializationLimits) -> Result<()> {
+        let count = self.shares.len().max(self.coeffs.len());
+        if count > limits.max_transcript_shares {
+            return Err(Error::TooManyTranscriptShares(
+                count,
+                limits.max_transcript_shares,
             ));
         }
-
-        // TODO: Cross check proof of knowledge check with the whitepaper; this check proves that there is a relationship between the secret and the pvss transcript
-        // Sigma is a proof of knowledge of the secret, sigma = h^s
-        let sigma = E::G2Affine::generator().mul(*s).into(); //todo hash to curve
-        let vss = Self {
-            coeffs,
-            shares,
-            sigma,
-            phantom: Default::default(),
-        };
-        Ok(vss)
+        Ok(())
     }
 
-    /// Verify the pvss transcript from a validator. This is not the full check,
-    /// i.e. we optimistically do not check the commitment. This is deferred
-    /// until the aggregation step
-    pub fn verify_optimistic(&self) -> bool {
-        let pvss_params = PubliclyVerifiableParams::<E>::default();
-        // We're only checking the proof of knowledge here, sigma ?= h^s
-        // "Does the first coefficient of the secret polynomial match the proof of knowledge?"
-        E::pairing(
-            self.coeffs[0].into_group(), // F_0 = g^s
-            pvss_params.h,
-        ) == E::pairing(
-            pvss_params.g,
-            self.sigma, // h^s
-        )
+    /// Check that every point of the transcript is in the prime order subgroup of its group.
+    /// Deserialized transcripts are already checked, this is for transcripts built otherwise
+    pub fn check_subgroups(&self) -> Result<()> {
+        self.coeffs
+            .check()
+            .and_then(|_| self.shares.check())
+            .and_then(|_| self.sigma.check())
+            .map_err(|_| Error::InvalidPvssTranscript)
     }
 
     /// Part of checking the validity of an aggregated PVSS transcript
@@ -198,62 +491,453 @@ impl<E: Pairing, T> PubliclyVerifiableSS<E, T> {
     /// transcript was at fault so that the can issue a new one. This
     /// function may also be used for that purpose.
     pub fn verify_full(&self, dkg: &PubliclyVerifiableDkg<E>) -> bool {
+        self.verify_full_report(dkg).is_valid()
+    }
+
+    /// Same as [`PubliclyVerifiableSS::verify_full`], but reports which checks failed,
+    /// including the share indices of the shares that don't match the commitment.
+    pub fn verify_full_report(
+        &self,
+        dkg: &PubliclyVerifiableDkg<E>,
+    ) -> VerificationReport {
+        let mut report = self.shape_report(dkg);
+        if !report.is_valid() {
+            return report;
+        }
         let validators = dkg.validators.values().cloned().collect::<Vec<_>>();
-        do_verify_full(
+        report.extend(do_verify_full_report(
             &self.coeffs,
             &self.shares,
             &dkg.pvss_params,
             &validators,
+            &dkg.prepared_keys,
             &dkg.domain,
+        ));
+        report
+    }
+
+    /// The checks of [`PubliclyVerifiableSS::verify_full_report`] that come before the
+    /// shares are checked: the ritual, domain, degree and share count of a transcript of
+    /// `dkg`
+    pub(crate) fn shape_report(
+        &self,
+        dkg: &PubliclyVerifiableDkg<E>,
+    ) -> VerificationReport {
+        let mut report = VerificationReport::new();
+        // The proof of knowledge is bound to the ritual the transcript claims, so a
+        // transcript replayed from another ritual must be caught here
+        if !report.check("ritual", || self.tau == dkg.dkg_params.tau()) {
+            return report;
+        }
+        if !report.check("evaluation_domain", || {
+            self.domain.check(&dkg.domain).is_ok()
+        }) {
+            return report;
+        }
+        report.check("polynomial_degree", || {
+            self.check_degree(dkg.dkg_params.security_threshold())
+                .is_ok()
+                && self.shares.len() == dkg.share_owners().len()
+        });
+        report
+    }
+
+    /// Check #3 in 4.2.3 section of https://eprint.iacr.org/2022/898.pdf: the dealt polynomial
+    /// has degree `security_threshold - 1`.
+    ///
+    /// Since the transcript commits to every coefficient, this is a check of the number of
+    /// commitments. Shares are checked against the commitments in [`do_verify_full`], so they
+    /// are evaluations of the same polynomial.
+    pub fn check_degree(&self, security_threshold: u32) -> Result<()> {
+        if self.coeffs.len() == security_threshold as usize {
+            Ok(())
+        } else {
+            Err(Error::InvalidTranscriptDegree(
+                security_threshold,
+                self.coeffs.len() as u32,
+            ))
+        }
+    }
+
+    /// Whether the transcript has the ritual, domain, degree and share count of a transcript
+    /// of `dkg`
+    fn has_shape_of(&self, dkg: &PubliclyVerifiableDkg<E>) -> bool {
+        self.tau == dkg.dkg_params.tau()
+            && self.domain.check(&dkg.domain).is_ok()
+            && self
+                .check_degree(dkg.dkg_params.security_threshold())
+                .is_ok()
+            && self.shares.len() == dkg.share_owners().len()
+    }
+
+    /// Whether the encrypted share `share_index` matches the commitment and is encrypted to
+    /// `validator`, its holder. Lets a validator check its own share without
+    /// [`PubliclyVerifiableSS::verify_full`], which checks every share.
+    ///
+    /// The transcript must be dealt for the ritual, validator set and domain of `dkg`. The
+    /// commitment polynomial is only evaluated at the domain point of the share, and a
+    /// single pairing check e(G, Y_i) == e(A_i, ek_i) is done.
+    pub fn verify_my_share(
+        &self,
+        share_index: u32,
+        validator: &Validator<E>,
+        dkg: &PubliclyVerifiableDkg<E>,
+    ) -> bool {
+        if self.tau != dkg.dkg_params.tau()
+            || self.validator_set_hash != dkg.validator_set_hash()
+            || self.domain.check(&dkg.domain).is_err()
+        {
+            return false;
+        }
+        let y_i = match self.shares.get(share_index as usize) {
+            Some(share) if validator.holds_share(share_index) => {
+                share.into_affine()
+            }
+            _ => return false,
+        };
+        let a_i = evaluate_commitment::<E>(
+            &self.coeffs,
+            &dkg.domain.element(share_index as usize),
+        );
+        pairings_match::<E>(
+            dkg.pvss_params.g,
+            y_i,
+            a_i,
+            validator.public_key.encryption_key,
         )
     }
 }
 
-// TODO: Return validator that failed the check
 pub fn do_verify_full<E: Pairing>(
     pvss_coefficients: &[E::G1Affine],
-    pvss_encrypted_shares: &[E::G2Affine],
+    pvss_encrypted_shares: &[ShareEncryptions<E>],
     pvss_params: &PubliclyVerifiableParams<E>,
     validators: &[Validator<E>],
+    prepared_keys: &PreparedEncryptionKeys<E>,
     domain: &ark_poly::GeneralEvaluationDomain<E::ScalarField>,
 ) -> bool {
-    let mut commitment = batch_to_projective_g1::<E>(pvss_coefficients);
-    domain.fft_in_place(&mut commitment);
+    do_verify_full_report(
+        pvss_coefficients,
+        pvss_encrypted_shares,
+        pvss_params,
+        validators,
+        prepared_keys,
+        domain,
+    )
+    .is_valid()
+}
+
+/// Same as [`do_verify_full`], but checks every share and reports the indices of the
+/// invalid ones.
+pub fn do_verify_full_report<E: Pairing>(
+    pvss_coefficients: &[E::G1Affine],
+    pvss_encrypted_shares: &[ShareEncryptions<E>],
+    pvss_params: &PubliclyVerifiableParams<E>,
+    validators: &[Validator<E>],
+    prepared_keys: &PreparedEncryptionKeys<E>,
+    domain: &ark_poly::GeneralEvaluationDomain<E::ScalarField>,
+) -> VerificationReport {
+    let commitment = E::G1::normalize_batch(&share_commitments::<E>(
+        pvss_coefficients,
+        domain,
+    ));
+    check_share_commitments(
+        &commitment,
+        pvss_encrypted_shares,
+        pvss_params,
+        validators,
+        prepared_keys,
+    )
+}
+
+/// The share checks of [`do_verify_full_report`], against `commitment`, the commitments
+/// `A_i` to the shares evaluated over the domain, e.g. by a [`crate::VerifiedCommitment`]
+///
+/// Every share index of `validators` must have exactly one share, so missing and surplus
+/// shares are reported as failed, and so are validators holding the same share index.
+pub(crate) fn check_share_commitments<E: Pairing>(
+    commitment: &[E::G1Affine],
+    pvss_encrypted_shares: &[ShareEncryptions<E>],
+    pvss_params: &PubliclyVerifiableParams<E>,
+    validators: &[Validator<E>],
+    prepared_keys: &PreparedEncryptionKeys<E>,
+) -> VerificationReport {
+    let mut report = VerificationReport::new();
+    if !report.check("share_owners", || {
+        assert_no_share_duplicates(validators).is_ok()
+    }) {
+        return report;
+    }
+    let owners = share_owners(validators);
+
+    // Each validator checks that their shares are correct. A share without an owner or an
+    // owner without a share fails the check
+    let count = owners.len().max(pvss_encrypted_shares.len());
+    let check_share = |share_index: usize| {
+        let (owner, y_i, a_i) = match (
+            owners.get(share_index),
+            pvss_encrypted_shares.get(share_index),
+            commitment.get(share_index),
+        ) {
+            (Some(owner), Some(y_i), Some(a_i)) => {
+                (*owner, y_i.into_affine(), *a_i)
+            }
+            _ => return false,
+        };
+        // Validator checks aggregated shares against commitment
+        let ek_i = prepared_keys.get_or_prepare(owner);
+        // We verify that e(G, Y_i) = e(A_i, ek_i) for validator i
+        // See #4 in 4.2.3 section of https://eprint.iacr.org/2022/898.pdf
+        // e(G,Y) = e(A, ek)
+        pairings_match::<E>(pvss_params.g, y_i, a_i, ek_i)
+    };
+    #[cfg(feature = "parallel")]
+    report.check_each_par("share_commitments", count, check_share);
+    #[cfg(not(feature = "parallel"))]
+    report.check_each("share_commitments", count, check_share);
+    report
+}
+
+/// The format version of a serialized transcript or aggregate. Transcripts encoded before
+/// the version, which don't start with [`ProtocolVersion::TRANSCRIPT_MAGIC`], are version
+/// `0`, see [`PubliclyVerifiableSS::from_legacy_bytes`]
+pub fn transcript_version(bytes: &[u8]) -> u8 {
+    bytes
+        .strip_prefix(&V1::TRANSCRIPT_MAGIC)
+        .and_then(|rest| rest.first())
+        .copied()
+        .unwrap_or(0)
+}
 
-    assert_no_share_duplicates(validators).expect("Validators must be unique");
+fn serialize_transcript_version<S: Serializer>(
+    version: &u8,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    (V1::TRANSCRIPT_MAGIC, *version).serialize(serializer)
+}
 
-    // Each validator checks that their share is correct
-    validators
+fn deserialize_transcript_version<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<u8, D::Error> {
+    let (magic, version) = <([u8; 4], u8)>::deserialize(deserializer)?;
+    let version = if magic == V1::TRANSCRIPT_MAGIC {
+        version
+    } else {
+        0
+    };
+    if version != V1::TRANSCRIPT_VERSION {
+        return Err(D::Error::custom(Error::UnsupportedTranscriptVersion(
+            version,
+        )));
+    }
+    Ok(version)
+}
+
+/// Same as [`ferveo_common::deserialize_limited`], with [`Error::MessageTooLarge`] for
+/// messages over the size limit
+pub(crate) fn deserialize_limited<T: DeserializeOwned>(
+    bytes: &[u8],
+    limits: &DeserializationLimits,
+) -> Result<T> {
+    ferveo_common::deserialize_limited(bytes, limits).map_err(
+        |err| match *err {
+            bincode::ErrorKind::SizeLimit => {
+                Error::MessageTooLarge(limits.max_message_size)
+            }
+            _ => Error::BincodeError(err),
+        },
+    )
+}
+
+/// Verify many transcripts at once, e.g. all the transcripts of a block. Equivalent to
+/// [`PubliclyVerifiableSS::verify_full`] on each transcript, but the share checks of all
+/// transcripts are combined with random scalars into a single multi-pairing, whose size
+/// doesn't depend on the number of transcripts.
+///
+/// A failed batch doesn't tell which transcripts are invalid, see [`find_invalid_transcripts`].
+pub fn verify_full_batch<E: Pairing, T>(
+    transcripts: &[PubliclyVerifiableSS<E, T>],
+    dkg: &PubliclyVerifiableDkg<E>,
+) -> bool {
+    let transcripts = transcripts.iter().collect::<Vec<_>>();
+    do_verify_full_batch(&transcripts, dkg, &mut rand::thread_rng())
+}
+
+/// The positions in `transcripts` of the transcripts that fail
+/// [`PubliclyVerifiableSS::verify_full`].
+///
+/// Transcripts are checked with [`verify_full_batch`], and failed batches are bisected, so
+/// that each invalid transcript costs a logarithmic number of batch checks.
+pub fn find_invalid_transcripts<E: Pairing, T>(
+    transcripts: &[PubliclyVerifiableSS<E, T>],
+    dkg: &PubliclyVerifiableDkg<E>,
+) -> Vec<usize> {
+    // Malformed transcripts would fail every batch they are in, so they are set aside first
+    let (batch, malformed): (Vec<_>, Vec<_>) = transcripts
         .iter()
-        .zip(pvss_encrypted_shares.iter())
         .enumerate()
-        .all(|(share_index, (validator, y_i))| {
-            // TODO: Check #3 is missing
-            // See #3 in 4.2.3 section of https://eprint.iacr.org/2022/898.pdf
-
-            // Validator checks aggregated shares against commitment
-            let ek_i = validator.public_key.encryption_key.into_group();
-            let a_i = &commitment[share_index];
-            // We verify that e(G, Y_i) = e(A_i, ek_i) for validator i
-            // See #4 in 4.2.3 section of https://eprint.iacr.org/2022/898.pdf
-            // e(G,Y) = e(A, ek)
-            E::pairing(pvss_params.g, *y_i) == E::pairing(a_i, ek_i)
+        .partition(|(_, transcript)| transcript.has_shape_of(dkg));
+    let mut invalid = malformed
+        .into_iter()
+        .map(|(position, _)| position)
+        .collect::<Vec<_>>();
+    bisect_invalid_transcripts(
+        &batch,
+        dkg,
+        &mut rand::thread_rng(),
+        &mut invalid,
+    );
+    invalid.sort_unstable();
+    invalid
+}
+
+fn bisect_invalid_transcripts<E: Pairing, T, R: RngCore>(
+    batch: &[(usize, &PubliclyVerifiableSS<E, T>)],
+    dkg: &PubliclyVerifiableDkg<E>,
+    rng: &mut R,
+    invalid: &mut Vec<usize>,
+) {
+    let transcripts = batch
+        .iter()
+        .map(|(_, transcript)| *transcript)
+        .collect::<Vec<_>>();
+    if do_verify_full_batch(&transcripts, dkg, rng) {
+        return;
+    }
+    if let [(position, _)] = batch {
+        invalid.push(*position);
+        return;
+    }
+    let (left, right) = batch.split_at(batch.len() / 2);
+    bisect_invalid_transcripts(left, dkg, rng, invalid);
+    bisect_invalid_transcripts(right, dkg, rng, invalid);
+}
+
+/// Checks that for every transcript `k` and validator `i`, e(G, Y_ki) == e(A_ki, ek_i).
+///
+/// With random scalars `r_k` and `s_i`, the checks are combined into
+/// e(G, Σ_k Σ_i r_k s_i Y_ki) == Π_i e(s_i Σ_k r_k A_ki, ek_i). Since the commitment is
+/// linear in the coefficients, Σ_k r_k A_ki is the commitment of Σ_k r_k F_k at `ω_i`, so a
+/// single FFT is needed. If any check fails, so does the combined one, except with
+/// negligible probability.
+#[cfg(not(ferveo_mock_crypto))]
+fn do_verify_full_batch<E: Pairing, T, R: RngCore>(
+    transcripts: &[&PubliclyVerifiableSS<E, T>],
+    dkg: &PubliclyVerifiableDkg<E>,
+    rng: &mut R,
+) -> bool {
+    if transcripts.is_empty() {
+        return true;
+    }
+    if !transcripts
+        .iter()
+        .all(|transcript| transcript.has_shape_of(dkg))
+    {
+        return false;
+    }
+    let r = (0..transcripts.len())
+        .map(|_| E::ScalarField::rand(rng))
+        .collect::<Vec<_>>();
+    let owners = dkg.share_owners();
+    let s = (0..owners.len())
+        .map(|_| E::ScalarField::rand(rng))
+        .collect::<Vec<_>>();
+
+    // Σ_k r_k F_k
+    let mut commitment = (0..dkg.dkg_params.security_threshold() as usize)
+        .map(|j| {
+            let coeffs = transcripts
+                .iter()
+                .map(|transcript| transcript.coeffs[j])
+                .collect::<Vec<_>>();
+            E::G1::msm_unchecked(&coeffs, &r)
         })
+        .collect::<Vec<_>>();
+    dkg.domain.fft_in_place(&mut commitment);
+
+    // Σ_k Σ_i r_k s_i Y_ki
+    let (shares, scalars): (Vec<_>, Vec<_>) = transcripts
+        .iter()
+        .zip(&r)
+        .flat_map(|(transcript, r_k)| {
+            transcript
+                .shares
+                .iter()
+                .zip(&s)
+                .map(move |(y_ki, s_i)| (y_ki.into_affine(), *r_k * s_i))
+        })
+        .unzip();
+    let y = E::G2::msm_unchecked(&shares, &scalars);
+
+    let mut pairings_a = vec![dkg.pvss_params.g_inv()];
+    let mut pairings_b = vec![E::G2Prepared::from(y.into_affine())];
+    for ((validator, a_i), s_i) in owners.into_iter().zip(&commitment).zip(&s) {
+        pairings_a.push(E::G1Prepared::from((*a_i * s_i).into_affine()));
+        pairings_b.push(dkg.prepared_keys.get_or_prepare(validator));
+    }
+    E::multi_pairing(pairings_a, pairings_b).is_zero()
+}
+
+/// Stand-in for the batch verification under `ferveo_mock_crypto`, which skips the MSMs
+/// and pairings and only checks the shape of the transcripts and that no share is the
+/// identity
+#[cfg(ferveo_mock_crypto)]
+fn do_verify_full_batch<E: Pairing, T, R: RngCore>(
+    transcripts: &[&PubliclyVerifiableSS<E, T>],
+    dkg: &PubliclyVerifiableDkg<E>,
+    _rng: &mut R,
+) -> bool {
+    transcripts.iter().all(|transcript| {
+        transcript.has_shape_of(dkg)
+            && transcript
+                .shares
+                .iter()
+                .all(|y_i| !y_i.as_affine().is_zero())
+    })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn do_verify_aggregation<E: Pairing>(
     pvss_agg_coefficients: &[E::G1Affine],
-    pvss_agg_encrypted_shares: &[E::G2Affine],
+    pvss_agg_encrypted_shares: &[ShareEncryptions<E>],
+    pvss_agg_sigma: &E::G2Affine,
     pvss_params: &PubliclyVerifiableParams<E>,
     validators: &[Validator<E>],
+    prepared_keys: &PreparedEncryptionKeys<E>,
     domain: &ark_poly::GeneralEvaluationDomain<E::ScalarField>,
     vss: &PVSSMap<E>,
+) -> Result<bool> {
+    do_verify_aggregation_of(
+        pvss_agg_coefficients,
+        pvss_agg_encrypted_shares,
+        pvss_agg_sigma,
+        pvss_params,
+        validators,
+        prepared_keys,
+        domain,
+        vss.values().filter_map(|pvss| pvss.contribution()),
+    )
+}
+
+/// Same as [`do_verify_aggregation`], against the `(F_0, sigma)` contributions of the
+/// aggregated transcripts instead of the transcripts
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn do_verify_aggregation_of<E: Pairing>(
+    pvss_agg_coefficients: &[E::G1Affine],
+    pvss_agg_encrypted_shares: &[ShareEncryptions<E>],
+    pvss_agg_sigma: &E::G2Affine,
+    pvss_params: &PubliclyVerifiableParams<E>,
+    validators: &[Validator<E>],
+    prepared_keys: &PreparedEncryptionKeys<E>,
+    domain: &ark_poly::GeneralEvaluationDomain<E::ScalarField>,
+    contributions: impl IntoIterator<Item = (E::G1Affine, E::G2Affine)>,
 ) -> Result<bool> {
     let is_valid = do_verify_full(
         pvss_agg_coefficients,
         pvss_agg_encrypted_shares,
         pvss_params,
         validators,
+        prepared_keys,
         domain,
     );
     if !is_valid {
@@ -262,169 +946,553 @@ pub fn do_verify_aggregation<E: Pairing>(
 
     // Now, we verify that the aggregated PVSS transcript is a valid aggregation
     let mut y = E::G1::zero();
-    for (_, pvss) in vss.iter() {
-        y += pvss.coeffs[0].into_group();
+    let mut sigma = E::G2::zero();
+    for (constant_commitment, pvss_sigma) in contributions {
+        y += constant_commitment;
+        sigma += pvss_sigma;
     }
-    if y.into_affine() == pvss_agg_coefficients[0] {
+    if y.into_affine() == pvss_agg_coefficients[0]
+        && sigma.into_affine() == *pvss_agg_sigma
+    {
         Ok(true)
     } else {
         Err(Error::InvalidTranscriptAggregate)
     }
 }
 
+/// The faults behind an aggregate that fails verification, see
+/// [`PubliclyVerifiableSS::diagnose_aggregation`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AggregationDiagnosis {
+    /// The dealers whose transcripts are invalid, who should be asked to deal again
+    pub offending_dealers: Vec<EthereumAddress>,
+    /// The [`PubliclyVerifiableSS::contributors`] of the aggregate whose transcripts the
+    /// DKG doesn't hold, so that it can't be checked against them
+    pub missing_contributors: Vec<EthereumAddress>,
+    /// Whether the aggregate isn't the sum of the transcripts, i.e. the aggregator is at
+    /// fault. Only checked if no contributor is missing
+    pub aggregate_mismatch: bool,
+    /// The dealers whose transcript the aggregator replaced with another one, which can
+    /// only be told from the commitments it published, see
+    /// [`AggregatedPvss::diagnose_with_commitments`]
+    pub substituted_dealers: Vec<EthereumAddress>,
+}
+
+impl AggregationDiagnosis {
+    /// Whether no fault was found
+    pub fn is_clean(&self) -> bool {
+        self.offending_dealers.is_empty()
+            && self.missing_contributors.is_empty()
+            && !self.aggregate_mismatch
+            && self.substituted_dealers.is_empty()
+    }
+}
+
+/// The public key of a DKG, `F_0 = [s] G` where `s` is the shared secret
+#[serde_as]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DealtPublicKey<E: Pairing>(
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")] pub E::G1Affine,
+);
+
+/// Extension type of a [`RedealOrigin`]
+pub const REDEAL_EXTENSION_TYPE: u16 = 1;
+
+/// The share of a previous aggregate that a transcript re-deals, carried in its extensions
+/// so that the proof of knowledge binds it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedealOrigin {
+    /// Hash of the previous aggregate, see [`aggregate_hash`]
+    pub aggregate_hash: [u8; 32],
+    pub share_index: u32,
+}
+
+/// Extension type of the address of the dealer of a transcript
+pub const DEALER_EXTENSION_TYPE: u16 = 3;
+
+/// Extension type of the dealers of the transcripts an aggregate sums, see
+/// [`PubliclyVerifiableSS::contributors`]
+pub const CONTRIBUTORS_EXTENSION_TYPE: u16 = 4;
+
+impl<E: Pairing> PubliclyVerifiableSS<E> {
+    /// The address of the dealer of this transcript, as claimed by the dealer, or `None` for
+    /// transcripts dealt before it was carried. The claim is bound to the proof of knowledge,
+    /// so it is authentic if the transcript verifies for it with
+    /// [`PubliclyVerifiableSS::verify_optimistic`], e.g. to tell the dealer of a relayed
+    /// transcript.
+    pub fn dealer(&self) -> Option<EthereumAddress> {
+        self.extensions
+            .get(DEALER_EXTENSION_TYPE)
+            .and_then(|data| bincode::deserialize(data).ok())
+    }
+
+    /// Check that this transcript doesn't claim another dealer than `sender`
+    pub(crate) fn check_dealer(&self, sender: &EthereumAddress) -> Result<()> {
+        match self.dealer() {
+            Some(dealer) if &dealer != sender => {
+                Err(Error::DealerMismatch(sender.clone(), dealer))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The share this transcript re-deals, if it was dealt with
+    /// [`PubliclyVerifiableSS::redeal`]
+    pub fn redeal_origin(&self) -> Option<RedealOrigin> {
+        self.extensions
+            .get(REDEAL_EXTENSION_TYPE)
+            .and_then(|data| bincode::deserialize(data).ok())
+    }
+
+    /// Check that this transcript re-deals the share of `previous` it claims to, i.e. that
+    /// the commitment to its secret is the public key share `A_i = [f(ω_i)] G` of that share.
+    ///
+    /// With the proof of knowledge, see [`PubliclyVerifiableSS::verify_optimistic`], this
+    /// shows that the dealer knows the share.
+    pub fn verify_redeal(&self, previous: &AggregatedPvss<E>) -> Result<bool> {
+        let origin = match self.redeal_origin() {
+            Some(origin) => origin,
+            None => return Ok(false),
+        };
+        let share_index = origin.share_index as usize;
+        let constant_commitment = match self.coeffs.first() {
+            Some(constant_commitment) => constant_commitment,
+            None => return Ok(false),
+        };
+        if origin.aggregate_hash != aggregate_hash(previous)?
+            || share_index >= previous.shares.len()
+        {
+            return Ok(false);
+        }
+        let a_i = previous.commitment_at(&previous.domain.element(share_index));
+        Ok(*constant_commitment == a_i.into_affine())
+    }
+}
+
 /// Extra methods available to aggregated PVSS transcripts
 impl<E: Pairing, T: Aggregate> PubliclyVerifiableSS<E, T> {
     /// Verify that this PVSS instance is a valid aggregation of
     /// the PVSS instances, produced by [`aggregate`],
     /// and received by the DKG context `dkg`
     /// Returns the total nr of shares in the aggregated PVSS
+    ///
+    /// The transcripts `dkg` pruned to their [`crate::DealerCommitment`], see
+    /// [`crate::RetentionPolicy`], are accounted for by their commitments.
+    ///
+    /// If the aggregate names its [`PubliclyVerifiableSS::contributors`], it is verified
+    /// against the transcripts of those dealers only, which `dkg` must all hold.
     pub fn verify_aggregation(
         &self,
         dkg: &PubliclyVerifiableDkg<E>,
     ) -> Result<bool> {
+        match self.contributors() {
+            Some(dealers) => {
+                self.verify_aggregation_of(dkg, dkg.contributions_of(&dealers)?)
+            }
+            None => self.verify_aggregation_of(dkg, dkg.contributions()),
+        }
+    }
+
+    /// The dealers of the transcripts this aggregate sums, in ascending order, if the
+    /// aggregator recorded them, e.g. because some dealers dropped out of the DKG. See
+    /// [`crate::DropoutPolicy`]
+    pub fn contributors(&self) -> Option<Vec<EthereumAddress>> {
+        self.extensions
+            .get(CONTRIBUTORS_EXTENSION_TYPE)
+            .and_then(|data| bincode::deserialize(data).ok())
+    }
+
+    /// Record `dealers` as the [`PubliclyVerifiableSS::contributors`] of this aggregate,
+    /// replacing the ones it recorded
+    pub fn set_contributors(
+        &mut self,
+        dealers: &BTreeSet<EthereumAddress>,
+    ) -> Result<()> {
+        let mut extensions = Extensions::new();
+        for (extension_type, data) in self.extensions.iter() {
+            if extension_type != CONTRIBUTORS_EXTENSION_TYPE {
+                extensions.insert(extension_type, data.to_vec())?;
+            }
+        }
+        let dealers = dealers.iter().collect::<Vec<_>>();
+        extensions.insert(
+            CONTRIBUTORS_EXTENSION_TYPE,
+            bincode::serialize(&dealers)?,
+        )?;
+        self.extensions = extensions;
+        Ok(())
+    }
+
+    /// Same as [`PubliclyVerifiableSS::verify_aggregation`], against the transcripts of
+    /// `vss` instead of the ones received by `dkg`, e.g. the union of the transcripts of
+    /// partial aggregates merged with [`AggregatedPvss::merge`]
+    pub fn verify_aggregation_with(
+        &self,
+        dkg: &PubliclyVerifiableDkg<E>,
+        vss: &PVSSMap<E>,
+    ) -> Result<bool> {
+        self.verify_aggregation_of(
+            dkg,
+            vss.values().filter_map(|pvss| pvss.contribution()),
+        )
+    }
+
+    fn verify_aggregation_of(
+        &self,
+        dkg: &PubliclyVerifiableDkg<E>,
+        contributions: impl IntoIterator<Item = (E::G1Affine, E::G2Affine)>,
+    ) -> Result<bool> {
+        if self.validator_set_hash != dkg.validator_set_hash() {
+            return Err(Error::ValidatorSetMismatch);
+        }
+        self.domain.check(&dkg.domain)?;
+        self.check_degree(dkg.dkg_params.security_threshold())?;
         let validators = dkg.validators.values().cloned().collect::<Vec<_>>();
-        do_verify_aggregation(
+        do_verify_aggregation_of(
             &self.coeffs,
             &self.shares,
+            &self.sigma,
             &dkg.pvss_params,
             &validators,
+            &dkg.prepared_keys,
             &dkg.domain,
-            &dkg.vss,
+            contributions,
         )
     }
 
-    pub fn decrypt_private_key_share(
+    /// Find out why this aggregate fails [`PubliclyVerifiableSS::verify_aggregation`]: which
+    /// of the transcripts of `dkg` are invalid, and whether the aggregate isn't their sum.
+    /// If the aggregate names its [`PubliclyVerifiableSS::contributors`], only the
+    /// transcripts of those dealers are diagnosed.
+    ///
+    /// Each transcript is checked on its own, so this is much slower than verifying the
+    /// aggregate, and is meant to be run once verification failed.
+    pub fn diagnose_aggregation(
         &self,
-        validator_decryption_key: &E::ScalarField,
-        share_index: usize,
-    ) -> Result<PrivateKeyShare<E>> {
-        // Decrypt private key shares https://nikkolasg.github.io/ferveo/pvss.html#validator-decryption-of-private-key-shares
-        let private_key_share = self
-            .shares
-            .get(share_index)
-            .ok_or(Error::InvalidShareIndex(share_index as u32))?
-            .mul(
-                validator_decryption_key
-                    .inverse()
-                    .expect("Validator decryption key must have an inverse"),
-            )
-            .into_affine();
-        Ok(PrivateKeyShare { private_key_share })
+        dkg: &PubliclyVerifiableDkg<E>,
+    ) -> AggregationDiagnosis {
+        let contributors = self.contributors();
+        let missing_contributors = contributors
+            .iter()
+            .flatten()
+            .filter(|dealer| !dkg.vss.contains_key(dealer))
+            .cloned()
+            .collect::<Vec<_>>();
+        let (dealers, transcripts): (Vec<_>, Vec<_>) = dkg
+            .vss
+            .iter()
+            .filter(|(dealer, _)| {
+                contributors
+                    .as_ref()
+                    .map_or(true, |contributors| contributors.contains(dealer))
+            })
+            .map(|(dealer, pvss)| (dealer.clone(), pvss.clone()))
+            .unzip();
+        let invalid = find_invalid_transcripts(&transcripts, dkg);
+        let offending_dealers = dealers
+            .into_iter()
+            .zip(&transcripts)
+            .enumerate()
+            .filter(|(position, (dealer, pvss))| {
+                invalid.contains(position)
+                    || pvss.validator_set_hash != dkg.validator_set_hash()
+                    || pvss.tau != dkg.dkg_params.tau()
+                    || !pvss.verify_optimistic(dealer)
+            })
+            .map(|(_, (dealer, _))| dealer)
+            .collect();
+        let aggregate_mismatch = missing_contributors.is_empty()
+            && match aggregate(&transcripts) {
+                Ok(expected) => {
+                    expected.coeffs != self.coeffs
+                        || expected.shares != self.shares
+                        || expected.sigma != self.sigma
+                }
+                Err(_) => true,
+            };
+        AggregationDiagnosis {
+            offending_dealers,
+            missing_contributors,
+            aggregate_mismatch,
+            substituted_dealers: vec![],
+        }
     }
 
-    pub fn make_decryption_share_simple(
-        &self,
-        ciphertext: &CiphertextHeader<E>,
-        aad: &[u8],
-        validator_decryption_key: &E::ScalarField,
-        share_index: usize,
-        g_inv: &E::G1Prepared,
-    ) -> Result<DecryptionShareSimple<E>> {
-        let private_key_share = self
-            .decrypt_private_key_share(validator_decryption_key, share_index)?;
-        DecryptionShareSimple::create(
-            validator_decryption_key,
-            &private_key_share,
-            ciphertext,
-            aad,
-            g_inv,
-        )
-        .map_err(|e| e.into())
+    /// The evaluation domain of the DKG this transcript was aggregated in, which has a point
+    /// for every share
+    pub fn domain(&self) -> ark_poly::GeneralEvaluationDomain<E::ScalarField> {
+        self.domain.domain()
     }
 
-    pub fn make_decryption_share_simple_precomputed(
-        &self,
-        ciphertext_header: &CiphertextHeader<E>,
-        aad: &[u8],
-        validator_decryption_key: &E::ScalarField,
-        share_index: usize,
-        domain_points: &[E::ScalarField],
-        g_inv: &E::G1Prepared,
-    ) -> Result<DecryptionSharePrecomputed<E>> {
-        let private_key_share = self
-            .decrypt_private_key_share(validator_decryption_key, share_index)?;
-
-        // We use the `prepare_combine_simple` function to precompute the lagrange coefficients
-        let lagrange_coeffs = prepare_combine_simple::<E>(domain_points);
-
-        DecryptionSharePrecomputed::new(
-            share_index,
-            validator_decryption_key,
-            &private_key_share,
-            ciphertext_header,
-            aad,
-            &lagrange_coeffs[share_index],
-            g_inv,
-        )
-        .map_err(|e| e.into())
+    /// The domain points of the shares, in share index order
+    pub fn domain_points(&self) -> Vec<E::ScalarField> {
+        self.domain().elements().take(self.shares.len()).collect()
+    }
+
+    /// The public key the aggregate shares. Aggregates always commit to it, since
+    /// transcripts without coefficients are rejected when deserialized
+    pub fn public_key(&self) -> DealtPublicKey<E> {
+        DealtPublicKey(self.coeffs[0])
     }
 
-    // TODO: Consider relocate to different place, maybe PrivateKeyShare? (see #162, #163)
-    pub fn update_private_key_share_for_recovery(
+    /// The public key share `A_i = [f(ω_i)] G` of the share `share_index`
+    pub fn public_key_share(
         &self,
-        validator_decryption_key: &E::ScalarField,
-        share_index: usize,
-        share_updates: &[E::G2],
-    ) -> Result<PrivateKeyShare<E>> {
-        // Retrieves their private key share
-        let private_key_share = self
-            .decrypt_private_key_share(validator_decryption_key, share_index)?;
-
-        // And updates their share
-        Ok(apply_updates_to_private_share::<E>(
-            &private_key_share,
-            share_updates,
-        ))
+        share_index: u32,
+    ) -> Result<PublicKeyShare<E>> {
+        if share_index as usize >= self.shares.len() {
+            return Err(Error::InvalidShareIndex(share_index));
+        }
+        let x = self.domain.element(share_index as usize);
+        Ok(PublicKeyShare {
+            public_key_share: self.commitment_at(&x).into_affine(),
+        })
+    }
+
+    /// Evaluate the aggregate commitment polynomial at `x`, i.e. compute `[f(x)] G`.
+    /// For a domain point `ω_i`, this is the public key share `A_i` of validator `i`.
+    pub fn commitment_at(&self, x: &E::ScalarField) -> E::G1 {
+        evaluate_commitment::<E>(&self.coeffs, x)
     }
 }
 
-/// Aggregate the PVSS instances in `pvss` from DKG session `dkg`
-/// into a new PVSS instance
+/// Aggregate the PVSS instances in `pvss_list` into a new PVSS instance
 /// See: https://nikkolasg.github.io/ferveo/pvss.html?highlight=aggregate#aggregation
-pub(crate) fn aggregate<E: Pairing>(
+///
+/// The transcripts must have been verified, e.g. with [`PubliclyVerifiableSS::verify_full`],
+/// and be dealt by distinct dealers in the same ritual, over the same domain. Only their
+/// shapes, ritual and domain are checked here: a transcript whose coefficient or share
+/// count differs from the first one's is reported with
+/// [`Error::MismatchedTranscriptShape`], before anything is summed.
+pub fn aggregate<E: Pairing>(
     pvss_list: &[PubliclyVerifiableSS<E>],
 ) -> Result<PubliclyVerifiableSS<E, Aggregated>> {
-    let mut pvss_iter = pvss_list.iter();
-    let first_pvss = pvss_iter
-        .next()
-        .ok_or_else(|| Error::NoTranscriptsToAggregate)?;
-    let mut coeffs = batch_to_projective_g1::<E>(&first_pvss.coeffs);
-    let mut sigma = first_pvss.sigma;
-
-    let mut shares = batch_to_projective_g2::<E>(&first_pvss.shares);
-
-    // So now we're iterating over the PVSS instances, and adding their coefficients and shares, and their sigma
-    // sigma is the sum of all the sigma_i, which is the proof of knowledge of the secret polynomial
-    // Aggregating is just adding the corresponding values in pvss instances, so pvss = pvss + pvss_j
-    for next_pvss in pvss_iter {
-        sigma = (sigma + next_pvss.sigma).into();
-        coeffs
-            .iter_mut()
-            .zip_eq(next_pvss.coeffs.iter())
-            .for_each(|(a, b)| *a += b);
-        shares
-            .iter_mut()
-            .zip_eq(next_pvss.shares.iter())
-            .for_each(|(a, b)| *a += b);
-    }
-    let shares = E::G2::normalize_batch(&shares);
-
-    Ok(PubliclyVerifiableSS {
-        coeffs: E::G1::normalize_batch(&coeffs),
-        shares,
-        sigma,
-        phantom: Default::default(),
-    })
+    if let Some(first) = pvss_list.first() {
+        let expected = first.shape();
+        if let Some((dealer, pvss)) = pvss_list
+            .iter()
+            .enumerate()
+            .find(|(_, pvss)| pvss.shape() != expected)
+        {
+            return Err(Error::MismatchedTranscriptShape {
+                dealer,
+                expected,
+                got: pvss.shape(),
+            });
+        }
+    }
+    let mut aggregator = PvssAggregator::default();
+    for pvss in pvss_list {
+        aggregator.add(pvss);
+    }
+    aggregator.finish()
+}
+
+/// Aggregates PVSS transcripts one at a time, e.g. as they are received from the chain.
+#[derive(Clone, Debug)]
+pub struct PvssAggregator<E: Pairing> {
+    coeffs: Vec<E::G1>,
+    shares: Vec<E::G2>,
+    sigma: E::G2,
+    validator_set_hash: ValidatorSetHash,
+    tau: u32,
+    /// The ritual of the first transcript that wasn't dealt in the ritual of the first one
+    other_tau: Option<u32>,
+    domain: Option<DomainDescriptor<E>>,
+    mixed_domains: bool,
+    /// The first transcript that didn't match the degree or share count of the first one,
+    /// and its shape
+    mismatched_shape: Option<(usize, (usize, usize))>,
+    count: usize,
+}
+
+impl<E: Pairing> Default for PvssAggregator<E> {
+    fn default() -> Self {
+        Self {
+            coeffs: vec![],
+            shares: vec![],
+            sigma: E::G2::zero(),
+            validator_set_hash: Default::default(),
+            tau: 0,
+            other_tau: None,
+            domain: None,
+            mixed_domains: false,
+            mismatched_shape: None,
+            count: 0,
+        }
+    }
 }
 
-#[cfg(test)]
+impl<E: Pairing> PvssAggregator<E> {
+    /// Number of transcripts aggregated so far
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn add(&mut self, pvss: &PubliclyVerifiableSS<E>) {
+        // Aggregating is just adding the corresponding values in pvss instances, so pvss = pvss + pvss_j
+        // sigma is the sum of all the sigma_i, which is the proof of knowledge of the secret polynomial
+        if self.is_empty() {
+            self.coeffs = batch_to_projective_g1::<E>(&pvss.coeffs);
+            self.shares =
+                pvss.shares.iter().map(|y_i| y_i.into_group()).collect();
+            self.validator_set_hash = pvss.validator_set_hash;
+            self.tau = pvss.tau;
+            self.domain = Some(pvss.domain);
+        } else if (self.coeffs.len(), self.shares.len()) != pvss.shape() {
+            // Can't be summed, and fails the aggregate in `finish`
+            self.mismatched_shape
+                .get_or_insert((self.count, pvss.shape()));
+        } else {
+            if self.tau != pvss.tau {
+                self.other_tau.get_or_insert(pvss.tau);
+            }
+            self.mixed_domains |= self.domain != Some(pvss.domain);
+            self.coeffs
+                .iter_mut()
+                .zip_eq(pvss.coeffs.iter())
+                .for_each(|(a, b)| *a += b);
+            self.shares
+                .iter_mut()
+                .zip_eq(pvss.shares.iter())
+                .for_each(|(a, b)| *a += b.as_affine());
+        }
+        self.sigma += pvss.sigma;
+        self.count += 1;
+    }
+
+    pub fn finish(&self) -> Result<PubliclyVerifiableSS<E, Aggregated>> {
+        if self.is_empty() {
+            return Err(Error::NoTranscriptsToAggregate);
+        }
+        if let Some((dealer, got)) = self.mismatched_shape {
+            return Err(Error::MismatchedTranscriptShape {
+                dealer,
+                expected: (self.coeffs.len(), self.shares.len()),
+                got,
+            });
+        }
+        if let Some(other_tau) = self.other_tau {
+            return Err(Error::RitualMismatch(self.tau, other_tau));
+        }
+        let domain = match self.domain {
+            Some(domain) if !self.mixed_domains => domain,
+            _ => return Err(Error::EvaluationDomainMismatch),
+        };
+        Ok(PubliclyVerifiableSS {
+            version: V1::TRANSCRIPT_VERSION,
+            coeffs: E::G1::normalize_batch(&self.coeffs),
+            shares: E::G2::normalize_batch(&self.shares)
+                .into_iter()
+                .map(ShareEncryptions::new_unchecked)
+                .collect(),
+            sigma: self.sigma.into_affine(),
+            validator_set_hash: self.validator_set_hash,
+            tau: self.tau,
+            domain,
+            extensions: Extensions::default(),
+            phantom: Default::default(),
+        })
+    }
+}
+
+impl<E: Pairing> AggregatedPvss<E> {
+    /// Merge two partial aggregates, e.g. of chunks of the transcripts of a ritual that were
+    /// aggregated in parallel, into the aggregate of their union.
+    ///
+    /// Both must be aggregated in the same ritual, for the same validator set and domain,
+    /// and record their [`PubliclyVerifiableSS::contributors`], see
+    /// [`PubliclyVerifiableSS::set_contributors`], which must be distinct. The merged
+    /// aggregate records the union of the contributors, and is verified with
+    /// [`PubliclyVerifiableSS::verify_aggregation_with`] against the union of the
+    /// transcripts of the chunks.
+    pub fn merge(mut self, other: &Self) -> Result<Self> {
+        if self.tau != other.tau {
+            return Err(Error::RitualMismatch(self.tau, other.tau));
+        }
+        if self.validator_set_hash != other.validator_set_hash {
+            return Err(Error::ValidatorSetMismatch);
+        }
+        if self.domain != other.domain {
+            return Err(Error::EvaluationDomainMismatch);
+        }
+        if self.shape() != other.shape() {
+            return Err(Error::MismatchedTranscriptShape {
+                dealer: 1,
+                expected: self.shape(),
+                got: other.shape(),
+            });
+        }
+        // Without the contributors of both, a transcript summed into both would go unnoticed
+        let (ours, theirs) = match (self.contributors(), other.contributors()) {
+            (Some(ours), Some(theirs)) => (ours, theirs),
+            _ => return Err(Error::InvalidTranscriptAggregate),
+        };
+        let mut contributors = ours.into_iter().collect::<BTreeSet<_>>();
+        for dealer in theirs {
+            if !contributors.insert(dealer.clone()) {
+                return Err(Error::DuplicateDealer(dealer));
+            }
+        }
+        self.set_contributors(&contributors)?;
+        let coeffs = self
+            .coeffs
+            .iter()
+            .zip(&other.coeffs)
+            .map(|(a, b)| *a + b)
+            .collect::<Vec<_>>();
+        let shares = self
+            .shares
+            .iter()
+            .zip(&other.shares)
+            .map(|(a, b)| *a.as_affine() + b.as_affine())
+            .collect::<Vec<_>>();
+        self.coeffs = E::G1::normalize_batch(&coeffs);
+        self.shares = E::G2::normalize_batch(&shares)
+            .into_iter()
+            .map(ShareEncryptions::new_unchecked)
+            .collect();
+        self.sigma = (self.sigma + other.sigma).into_affine();
+        Ok(self)
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
 mod test_pvss {
+    use std::ops::Mul;
+
     use ark_bls12_381::Bls12_381 as EllipticCurve;
     use ark_ec::AffineRepr;
     use ark_ff::UniformRand;
 
     use super::*;
-    use crate::{test_common::*, DkgParams};
+    use crate::{
+        test_common::*, DealingContext, DkgParams, Message, Validator,
+    };
+
+    #[test]
+    fn test_prepared_pairings_match() {
+        let params = PubliclyVerifiableParams::<EllipticCurve> {
+            g: G1::generator() * ScalarField::from(2u64),
+            h: G2::generator() * ScalarField::from(3u64),
+        };
+        assert!(prepared_pairings_match::<EllipticCurve>(
+            params.g,
+            params.h,
+            &params.g_inv(),
+            params.h
+        ));
+        assert!(!prepared_pairings_match::<EllipticCurve>(
+            params.g,
+            params.h,
+            &params.g_inv(),
+            G2::generator()
+        ));
+    }
 
     /// Test the happy flow that a pvss with the correct form is created
     /// and that appropriate validations pass
@@ -445,13 +1513,177 @@ mod test_pvss {
         // Check that the correct number of shares were created
         assert_eq!(pvss.shares.len(), dkg.validators.len());
         // Check that the prove of knowledge is correct
-        assert_eq!(pvss.sigma, G2::generator().mul(s));
+        let pok_base = pok_base::<EllipticCurve>(
+            &dkg.validator_set_hash(),
+            dkg.dkg_params.tau(),
+            &dkg.me.address,
+            &coeffs_hash::<EllipticCurve>(&pvss.coeffs).unwrap(),
+            &Extensions::default().hash(),
+        )
+        .unwrap();
+        assert_eq!(pvss.tau, dkg.dkg_params.tau());
+        assert_eq!(pvss.sigma, pok_base.mul(s));
         // Check that the optimistic verify returns true
-        assert!(pvss.verify_optimistic());
+        assert!(pvss.verify_optimistic(&dkg.me.address));
         // Check that the full verify returns true
         assert!(pvss.verify_full(&dkg));
     }
 
+    /// Check that a transcript dealt without a DKG verifies in the DKG of the same
+    /// validators, and that the context is validated
+    #[test]
+    fn test_new_standalone_pvss() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, keypairs) = setup_dkg(0);
+        let validators = gen_validators(&keypairs);
+        let domain =
+            ark_poly::GeneralEvaluationDomain::new(SHARES_NUM as usize)
+                .unwrap();
+        let params = PubliclyVerifiableParams::<E>::default();
+        let context = DealingContext::new(
+            &validators,
+            domain,
+            &params,
+            SECURITY_THRESHOLD,
+            TAU,
+            &dkg.me.address,
+        )
+        .unwrap();
+        let s = ScalarField::rand(rng);
+        let pvss = PubliclyVerifiableSS::<E>::new_standalone(&s, &context, rng)
+            .unwrap();
+        assert!(pvss.verify_optimistic(&dkg.me.address));
+        assert!(pvss.verify_full(&dkg));
+        assert_eq!(pvss.coeffs[0], G1::generator().mul(s));
+
+        let dealer = gen_address(SHARES_NUM as usize);
+        let context_with = |validators: &[Validator<E>], threshold| {
+            DealingContext::new(
+                validators, domain, &params, threshold, TAU, &dealer,
+            )
+            .err()
+        };
+        assert!(matches!(
+            context_with(&validators, 0),
+            Some(Error::InvalidDkgParameters(..))
+        ));
+        assert!(matches!(
+            context_with(&validators, SHARES_NUM + 1),
+            Some(Error::InvalidDkgParameters(..))
+        ));
+        let mut gap = validators.clone();
+        gap[1].share_index = SHARES_NUM;
+        assert!(matches!(
+            context_with(&gap, SECURITY_THRESHOLD),
+            Some(Error::InvalidShareIndex(..))
+        ));
+        let mut duplicate = validators.clone();
+        duplicate[1].share_index = 0;
+        assert!(matches!(
+            context_with(&duplicate, SECURITY_THRESHOLD),
+            Some(Error::DuplicatedShareIndex(0))
+        ));
+        // The dealer needn't be a validator
+        assert!(context_with(&validators, SECURITY_THRESHOLD).is_none());
+    }
+
+    /// Check that share encryptions serialize as bare points, and that invalid ones are
+    /// rejected
+    #[test]
+    fn test_share_encryptions_serialization() {
+        let (dkg, _) = setup_dkg(0);
+        let pvss = PubliclyVerifiableSS::<E>::new(
+            &ScalarField::rand(&mut ark_std::test_rng()),
+            &dkg,
+            &mut ark_std::test_rng(),
+        )
+        .unwrap();
+        let points = pvss
+            .shares
+            .iter()
+            .map(|y_i| y_i.into_affine())
+            .collect::<Vec<_>>();
+        let mut bytes = vec![];
+        pvss.shares.serialize_compressed(&mut bytes).unwrap();
+        let mut point_bytes = vec![];
+        points.serialize_compressed(&mut point_bytes).unwrap();
+        assert_eq!(bytes, point_bytes);
+        assert_eq!(
+            Vec::<ShareEncryptions<E>>::deserialize_compressed(&bytes[..])
+                .unwrap(),
+            pvss.shares
+        );
+
+        // The identity is rejected, when constructed or deserialized
+        assert!(matches!(
+            ShareEncryptions::<E>::new(G2::zero()),
+            Err(Error::InvalidShareEncryption)
+        ));
+        let mut with_identity = points;
+        with_identity[1] = G2::zero();
+        let mut bytes = vec![];
+        with_identity.serialize_compressed(&mut bytes).unwrap();
+        assert!(
+            Vec::<ShareEncryptions<E>>::deserialize_compressed(&bytes[..])
+                .is_err()
+        );
+        let mut tampered = pvss;
+        tampered.shares[1] = ShareEncryptions::new_unchecked(G2::zero());
+        assert!(PubliclyVerifiableSS::<E>::from_bytes_limited(
+            &bincode::serialize(&tampered).unwrap(),
+            &DeserializationLimits::default(),
+        )
+        .is_err());
+    }
+
+    /// Check that the zeroized evaluations of a secret polynomial are its FFT
+    #[test]
+    fn test_secret_evaluations() {
+        let rng = &mut ark_std::test_rng();
+        let s = ScalarField::rand(rng);
+        for (degree, domain_size) in [(0, 1), (2, 4), (3, 4), (2, 16)] {
+            let domain =
+                ark_poly::GeneralEvaluationDomain::new(domain_size).unwrap();
+            let phi = crate::SecretPolynomial::<E>::new(&s, degree, rng);
+            let expected = phi.0.evaluate_over_domain_by_ref(domain);
+            let evals = phi.evaluate_over_domain(&domain);
+            for (share_index, eval) in expected.evals.iter().enumerate() {
+                assert_eq!(evals.get(share_index), eval);
+            }
+        }
+    }
+
+    /// Check that transcripts dealt from a seed are reproducible
+    #[test]
+    fn test_new_deterministic_pvss() {
+        let (dkg, _) = setup_dkg(0);
+        let seed = [7u8; 32];
+        let pvss =
+            PubliclyVerifiableSS::<E>::new_deterministic(&seed, &dkg).unwrap();
+        assert!(pvss.verify_optimistic(&dkg.me.address));
+        assert!(pvss.verify_full(&dkg));
+        let replayed =
+            PubliclyVerifiableSS::<E>::new_deterministic(&seed, &dkg).unwrap();
+        assert_eq!(
+            bincode::serialize(&pvss).unwrap(),
+            bincode::serialize(&replayed).unwrap()
+        );
+
+        // Another seed, dealer or ritual deals another secret
+        let (other_dealer, _) = setup_dkg(1);
+        let mut other_ritual = dkg.clone();
+        other_ritual.dkg_params =
+            DkgParams::new(TAU + 1, SECURITY_THRESHOLD, SHARES_NUM).unwrap();
+        let others = [
+            PubliclyVerifiableSS::<E>::new_deterministic(&[8u8; 32], &dkg),
+            PubliclyVerifiableSS::<E>::new_deterministic(&seed, &other_dealer),
+            PubliclyVerifiableSS::<E>::new_deterministic(&seed, &other_ritual),
+        ];
+        for other in others {
+            assert_ne!(other.unwrap().coeffs[0], pvss.coeffs[0]);
+        }
+    }
+
     /// Check that if the proof of knowledge is wrong,
     /// the optimistic verification of PVSS fails
     #[test]
@@ -468,7 +1700,135 @@ mod test_pvss {
                 .expect("Test failed");
 
         pvss.sigma = G2::zero();
-        assert!(!pvss.verify_optimistic());
+        assert!(!pvss.verify_optimistic(&dkg.me.address));
+        let report = pvss.verify_optimistic_report(&dkg.me.address);
+        assert_eq!(
+            report.failures().map(|o| o.check).collect::<Vec<_>>(),
+            ["proof_of_knowledge"]
+        );
+    }
+
+    /// Check that a re-dealt share is linked to the share of the previous aggregate
+    #[test]
+    fn test_redeal() {
+        let rng = &mut ark_std::test_rng();
+        // With a threshold of 1, every share of the previous aggregate is its secret
+        let (previous_dkg, _) = setup_dkg_for_n_validators(1, SHARES_NUM, 0);
+        let s = ScalarField::rand(rng);
+        let previous = aggregate(&[PubliclyVerifiableSS::<E>::new(
+            &s,
+            &previous_dkg,
+            rng,
+        )
+        .unwrap()])
+        .unwrap();
+
+        let (dkg, _) = setup_dkg(0);
+        let pvss =
+            PubliclyVerifiableSS::<E>::redeal(&s, 2, &previous, &dkg, rng)
+                .unwrap();
+        assert_eq!(pvss.redeal_origin().unwrap().share_index, 2);
+        assert!(pvss.verify_redeal(&previous).unwrap());
+        assert!(pvss.verify_optimistic(&dkg.me.address));
+        assert!(pvss.verify_full(&dkg));
+
+        // A transcript that doesn't re-deal a share
+        let fresh = PubliclyVerifiableSS::<E>::new(&s, &dkg, rng).unwrap();
+        assert!(!fresh.verify_redeal(&previous).unwrap());
+
+        // The dealer must know the share
+        let wrong = PubliclyVerifiableSS::<E>::redeal(
+            &ScalarField::rand(rng),
+            2,
+            &previous,
+            &dkg,
+            rng,
+        )
+        .unwrap();
+        assert!(!wrong.verify_redeal(&previous).unwrap());
+
+        // And can't change which share it claims without breaking the proof of knowledge
+        let mut relabeled = pvss;
+        relabeled.extensions = Extensions::new();
+        relabeled
+            .extensions
+            .insert(
+                REDEAL_EXTENSION_TYPE,
+                bincode::serialize(&RedealOrigin {
+                    aggregate_hash: aggregate_hash(&previous).unwrap(),
+                    share_index: 3,
+                })
+                .unwrap(),
+            )
+            .unwrap();
+        assert!(relabeled.verify_redeal(&previous).unwrap());
+        assert!(!relabeled.verify_optimistic(&dkg.me.address));
+    }
+
+    /// Check that a proof of knowledge is bound to its dealer, ritual and commitments
+    #[test]
+    fn test_verify_pvss_replayed_proof_of_knowledge() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, _) = setup_dkg(0);
+        let s = ScalarField::rand(rng);
+        let pvss = PubliclyVerifiableSS::<E>::new(&s, &dkg, rng).unwrap();
+        let dealer = &dkg.me.address;
+        assert!(pvss.verify_optimistic(dealer));
+
+        // Another validator can't claim the transcript as their own
+        let other_dealer = &gen_address(1);
+        assert!(!pvss.verify_optimistic(other_dealer));
+        let (other_dkg, _) = setup_dkg(1);
+        assert!(matches!(
+            other_dkg.verify_message(
+                &dkg.validators[other_dealer],
+                &Message::Deal(pvss.clone())
+            ),
+            Err(Error::InvalidPvssTranscript)
+        ));
+
+        // Or replay it in another ritual
+        let mut replayed = pvss.clone();
+        replayed.tau += 1;
+        assert!(!replayed.verify_optimistic(dealer));
+        assert!(matches!(
+            other_dkg.verify_message(&dkg.me, &Message::Deal(replayed)),
+            Err(Error::RitualMismatch(expected, actual))
+                if expected == TAU && actual == TAU + 1
+        ));
+        // Even a transcript dealt in another ritual of the same validator set
+        let validators = dkg.validators.values().cloned().collect::<Vec<_>>();
+        let next_dkg = PubliclyVerifiableDkg::<E>::new(
+            &validators,
+            &DkgParams::new(TAU + 1, SECURITY_THRESHOLD, SHARES_NUM).unwrap(),
+            &dkg.me,
+        )
+        .unwrap();
+        let next = PubliclyVerifiableSS::<E>::new(&s, &next_dkg, rng).unwrap();
+        assert!(next.verify_optimistic(dealer));
+        assert!(next.verify_full(&next_dkg));
+        assert!(!next.verify_full(&dkg));
+        assert!(!next.verify_full_report(&dkg).get("ritual").unwrap().passed);
+
+        // Or reuse the proof for other commitments, even to the same secret
+        let mut malleated = pvss.clone();
+        malleated.coeffs[1] = (malleated.coeffs[1] + G1::generator()).into();
+        assert!(!malleated.verify_optimistic(dealer));
+        let other = PubliclyVerifiableSS::<E>::new(&s, &dkg, rng).unwrap();
+        let mut swapped = other.clone();
+        swapped.sigma = pvss.sigma;
+        assert!(other.verify_optimistic(dealer));
+        assert!(!swapped.verify_optimistic(dealer));
+
+        // A transcript without commitments has no proof of knowledge, and doesn't decode
+        let mut empty = pvss;
+        empty.coeffs.clear();
+        assert!(!empty.verify_optimistic(dealer));
+        let bytes = bincode::serialize(&empty).unwrap();
+        assert!(bincode::deserialize::<PubliclyVerifiableSS<EllipticCurve>>(
+            &bytes
+        )
+        .is_err());
     }
 
     /// Check that if PVSS shares are tampered with, the full verification fails
@@ -481,17 +1841,258 @@ mod test_pvss {
             PubliclyVerifiableSS::<EllipticCurve>::new(&s, &dkg, rng).unwrap();
 
         // So far, everything works
-        assert!(pvss.verify_optimistic());
+        assert!(pvss.verify_optimistic(&dkg.me.address));
         assert!(pvss.verify_full(&dkg));
 
         // Now, we're going to tamper with the PVSS shares
         let mut bad_pvss = pvss;
-        bad_pvss.shares[0] = G2::zero();
+        bad_pvss.shares[0] = ShareEncryptions::new_unchecked(G2::zero());
 
         // Optimistic verification should not catch this issue
-        assert!(bad_pvss.verify_optimistic());
+        assert!(bad_pvss.verify_optimistic(&dkg.me.address));
         // Full verification should catch this issue
         assert!(!bad_pvss.verify_full(&dkg));
+
+        // And report which share is at fault
+        bad_pvss.shares[2] = ShareEncryptions::new_unchecked(G2::zero());
+        let report = bad_pvss.verify_full_report(&dkg);
+        assert!(report.get("evaluation_domain").unwrap().passed);
+        let outcome = report.get("share_commitments").unwrap();
+        assert!(!outcome.passed);
+        assert_eq!(outcome.failed_indices, [0, 2]);
+        assert!(bad_pvss
+            .verify_optimistic_report(&dkg.me.address)
+            .is_valid());
+
+        // Each validator only checks its own share
+        for validator in dkg.validators.values() {
+            let share_index = validator.share_index;
+            assert_eq!(
+                bad_pvss.verify_my_share(share_index, validator, &dkg),
+                share_index != 0 && share_index != 2
+            );
+            assert!(!bad_pvss.verify_my_share(
+                share_index + 1,
+                validator,
+                &dkg
+            ));
+        }
+        assert!(!bad_pvss.verify_my_share(SHARES_NUM, &dkg.me, &dkg));
+
+        // A transcript from another domain can't be checked share by share
+        let mut other_domain = bad_pvss;
+        other_domain.domain = DomainDescriptor::for_shares(8).unwrap();
+        let report = other_domain.verify_full_report(&dkg);
+        assert!(!report.get("evaluation_domain").unwrap().passed);
+        assert!(report.get("share_commitments").is_none());
+    }
+
+    /// Check that a share is checked at the domain point of the DKG, not the one the
+    /// transcript claims
+    #[test]
+    fn test_verify_my_share_in_other_domain() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, _) = setup_dkg(0);
+        let valid = PubliclyVerifiableSS::<EllipticCurve>::new(
+            &ScalarField::rand(rng),
+            &dkg,
+            rng,
+        )
+        .unwrap();
+
+        // A dealer evaluates its polynomial on the points of a larger domain
+        let mut deal_on = |domain: DomainDescriptor<EllipticCurve>| {
+            let poly = (0..SECURITY_THRESHOLD)
+                .map(|_| ScalarField::rand(rng))
+                .collect::<Vec<_>>();
+            let mut pvss = valid.clone();
+            pvss.coeffs = poly
+                .iter()
+                .map(|a| G1::generator().mul(*a).into_affine())
+                .collect();
+            pvss.shares = dkg
+                .share_owners()
+                .iter()
+                .enumerate()
+                .map(|(i, validator)| {
+                    let x = domain.element(i);
+                    let y = poly
+                        .iter()
+                        .rev()
+                        .fold(ScalarField::zero(), |y, a| y * x + a);
+                    ShareEncryptions::new(
+                        validator
+                            .public_key
+                            .encryption_key
+                            .mul(y)
+                            .into_affine(),
+                    )
+                    .unwrap()
+                })
+                .collect();
+            pvss.domain = domain;
+            pvss
+        };
+        let honest = deal_on(DomainDescriptor::new(&dkg.domain));
+        assert!(honest.verify_my_share(
+            1,
+            &dkg.validators[&gen_address(1)],
+            &dkg
+        ));
+        let other_domain = deal_on(DomainDescriptor::for_shares(16).unwrap());
+        for validator in dkg.validators.values() {
+            assert!(!other_domain.verify_my_share(
+                validator.share_index,
+                validator,
+                &dkg
+            ));
+        }
+
+        // Nor is a transcript of another ritual or validator set
+        let mut other_ritual = honest.clone();
+        other_ritual.tau += 1;
+        assert!(!other_ritual.verify_my_share(
+            1,
+            &dkg.validators[&gen_address(1)],
+            &dkg
+        ));
+        let mut other_validators = honest;
+        other_validators.validator_set_hash = [0; 32];
+        assert!(!other_validators.verify_my_share(
+            1,
+            &dkg.validators[&gen_address(1)],
+            &dkg
+        ));
+    }
+
+    /// Check that transcripts of a polynomial of the wrong degree fail verification, even
+    /// when their shares match their commitment
+    #[test]
+    fn test_verify_pvss_wrong_degree() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, _) = setup_dkg(0);
+        let s = ScalarField::rand(rng);
+        let good_pvss = PubliclyVerifiableSS::<E>::new(&s, &dkg, rng).unwrap();
+
+        for threshold in [SECURITY_THRESHOLD - 1, SECURITY_THRESHOLD + 1] {
+            // Same validators, different threshold
+            let (other_dkg, _) =
+                setup_dkg_for_n_validators(threshold, SHARES_NUM, 0);
+            let pvss =
+                PubliclyVerifiableSS::<E>::new(&s, &other_dkg, rng).unwrap();
+            assert_eq!(pvss.validator_set_hash, dkg.validator_set_hash());
+            assert!(pvss.verify_optimistic(&dkg.me.address));
+            assert!(pvss.verify_full(&other_dkg));
+
+            assert!(!pvss.verify_full(&dkg));
+            let report = pvss.verify_full_report(&dkg);
+            assert_eq!(
+                report.failures().map(|o| o.check).collect::<Vec<_>>(),
+                ["polynomial_degree"]
+            );
+            assert!(matches!(
+                pvss.check_degree(SECURITY_THRESHOLD),
+                Err(Error::InvalidTranscriptDegree(expected, actual))
+                    if expected == SECURITY_THRESHOLD && actual == threshold
+            ));
+            assert!(matches!(
+                dkg.verify_message(&dkg.me, &Message::Deal(pvss.clone())),
+                Err(Error::InvalidTranscriptDegree(..))
+            ));
+
+            // Can't be aggregated with transcripts of the right degree
+            assert!(matches!(
+                aggregate(&[good_pvss.clone(), pvss.clone()]),
+                Err(Error::MismatchedTranscriptShape { dealer: 1, .. })
+            ));
+
+            // An aggregate of the wrong degree is rejected too
+            let bad_aggregate = aggregate(&[pvss]).unwrap();
+            assert!(matches!(
+                bad_aggregate.verify_aggregation(&dkg),
+                Err(Error::InvalidTranscriptDegree(..))
+            ));
+        }
+    }
+
+    /// Check that a batch of transcripts verifies iff every transcript does, and that the
+    /// invalid transcripts of a failed batch are found
+    #[test]
+    fn test_verify_full_batch() {
+        let (dkg, _) = setup_dealt_dkg();
+        let mut transcripts = dkg.vss.values().cloned().collect::<Vec<_>>();
+        assert!(verify_full_batch(&transcripts, &dkg));
+        assert!(find_invalid_transcripts(&transcripts, &dkg).is_empty());
+        assert!(verify_full_batch::<E, Unaggregated>(&[], &dkg));
+
+        // A share that doesn't match the commitment
+        transcripts[1].shares[2] =
+            ShareEncryptions::new(G2::generator()).unwrap();
+        assert!(!transcripts[1].verify_full(&dkg));
+        assert!(!verify_full_batch(&transcripts, &dkg));
+        assert!(verify_full_batch(&transcripts[2..], &dkg));
+        assert_eq!(find_invalid_transcripts(&transcripts, &dkg), [1]);
+
+        // Shares swapped between two validators pass neither batch nor individual checks
+        transcripts[2].shares.swap(0, 1);
+        assert_eq!(find_invalid_transcripts(&transcripts, &dkg), [1, 2]);
+
+        // A malformed transcript
+        transcripts[0].coeffs.pop();
+        assert!(!verify_full_batch(&transcripts[..1], &dkg));
+        assert_eq!(find_invalid_transcripts(&transcripts, &dkg), [0, 1, 2]);
+    }
+
+    /// Check that the full verification of bare shares and coefficients reports missing and
+    /// surplus shares, and validators sharing a share index, instead of skipping them
+    #[test]
+    fn test_do_verify_full_report_shape() {
+        let (dkg, _) = setup_dealt_dkg();
+        let pvss = dkg.vss.values().next().unwrap().clone();
+        let mut validators =
+            dkg.validators.values().cloned().collect::<Vec<_>>();
+        let report_for =
+            |shares: &[ShareEncryptions<E>], validators: &[Validator<E>]| {
+                do_verify_full_report(
+                    &pvss.coeffs,
+                    shares,
+                    &dkg.pvss_params,
+                    validators,
+                    &dkg.prepared_keys,
+                    &dkg.domain,
+                )
+            };
+        assert!(report_for(&pvss.shares, &validators).is_valid());
+
+        // The owners of missing shares
+        let outcome = report_for(&pvss.shares[..2], &validators)
+            .get("share_commitments")
+            .unwrap()
+            .clone();
+        assert_eq!(outcome.failed_indices, [2, 3]);
+        assert!(!do_verify_full(
+            &pvss.coeffs,
+            &pvss.shares[..2],
+            &dkg.pvss_params,
+            &validators,
+            &dkg.prepared_keys,
+            &dkg.domain,
+        ));
+
+        // Shares without an owner
+        let mut surplus = pvss.shares.clone();
+        surplus.push(pvss.shares[0]);
+        let outcome = report_for(&surplus, &validators)
+            .get("share_commitments")
+            .unwrap()
+            .clone();
+        assert_eq!(outcome.failed_indices, [SHARES_NUM as usize]);
+
+        // Validators holding the same share fail the check instead of panicking
+        validators[1].share_index = 0;
+        let report = report_for(&pvss.shares, &validators);
+        assert!(!report.get("share_owners").unwrap().passed);
+        assert!(report.get("share_commitments").is_none());
     }
 
     // TODO: Move this code to dkg.rs
@@ -536,12 +2137,275 @@ mod test_pvss {
         );
         // Check that the correct number of shares were created
         assert_eq!(aggregate.shares.len(), dkg.validators.len());
-        // Check that the optimistic verify returns true
-        assert!(aggregate.verify_optimistic());
+        // Check that the proof of knowledge is the sum of the proofs of the dealers
+        let sigma = dkg
+            .vss
+            .values()
+            .map(|pvss| pvss.sigma.into_group())
+            .sum::<<E as Pairing>::G2>();
+        assert_eq!(aggregate.sigma, sigma.into_affine());
+        assert_eq!(aggregate.tau, dkg.dkg_params.tau());
         // Check that the full verify returns true
         assert!(aggregate.verify_full(&dkg));
         // Check that the verification of aggregation passes
         assert!(aggregate.verify_aggregation(&dkg).expect("Test failed"),);
+        // But not with a forged proof of knowledge
+        let mut forged = aggregate;
+        forged.sigma = G2::generator();
+        assert!(matches!(
+            forged.verify_aggregation(&dkg),
+            Err(Error::InvalidTranscriptAggregate)
+        ));
+
+        // Transcripts of different rituals can't be aggregated
+        let mut other_ritual = pvss_list;
+        other_ritual[1].tau += 1;
+        assert!(matches!(
+            crate::pvss::aggregate(&other_ritual),
+            Err(Error::RitualMismatch(expected, actual))
+                if expected == TAU && actual == TAU + 1
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_mismatched_shapes() {
+        let (dkg, _) = setup_dealt_dkg();
+        let mut pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let expected = pvss_list[0].shape();
+        // A truncated transcript, e.g. from malformed gossip
+        pvss_list[2].shares.pop();
+        let got = (expected.0, expected.1 - 1);
+        assert!(matches!(
+            aggregate(&pvss_list),
+            Err(Error::MismatchedTranscriptShape { dealer: 2, expected: e, got: g })
+                if e == expected && g == got
+        ));
+        let mut aggregator = PvssAggregator::default();
+        for pvss in &pvss_list {
+            aggregator.add(pvss);
+        }
+        assert!(matches!(
+            aggregator.finish(),
+            Err(Error::MismatchedTranscriptShape { dealer: 2, expected: e, got: g })
+                if e == expected && g == got
+        ));
+
+        pvss_list[2].shares.truncate(0);
+        pvss_list[3].coeffs.pop();
+        assert!(matches!(
+            aggregate(&pvss_list),
+            Err(Error::MismatchedTranscriptShape { dealer: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_merge_partial_aggregates() {
+        let (dkg, _) = setup_dealt_dkg();
+        let dealers = dkg.vss.keys().cloned().collect::<Vec<_>>();
+        let aggregate_of = |dealers: &[EthereumAddress]| {
+            let pvss_list =
+                dealers.iter().map(|dealer| dkg.vss[dealer].clone());
+            let mut aggregate =
+                aggregate(&pvss_list.collect::<Vec<_>>()).unwrap();
+            aggregate
+                .set_contributors(&dealers.iter().cloned().collect())
+                .unwrap();
+            aggregate
+        };
+        let (left, right) = dealers.split_at(dealers.len() / 2);
+        let left = aggregate_of(left);
+        let right = aggregate_of(right);
+
+        let merged = left.clone().merge(&right).unwrap();
+        assert_eq!(merged, aggregate_of(&dealers));
+        assert!(merged.verify_aggregation(&dkg).unwrap());
+
+        // Transcripts can't be summed twice, nor merged without their dealers
+        assert!(matches!(
+            merged.clone().merge(&right),
+            Err(Error::DuplicateDealer(dealer)) if dealer == dealers[2]
+        ));
+        let mut anonymous = right.clone();
+        anonymous.extensions = Extensions::default();
+        assert!(matches!(
+            left.clone().merge(&anonymous),
+            Err(Error::InvalidTranscriptAggregate)
+        ));
+
+        // A partial aggregate only verifies against the transcripts it records
+        let chunk = dkg
+            .vss
+            .iter()
+            .take(dealers.len() / 2)
+            .map(|(dealer, pvss)| (dealer.clone(), pvss.clone()))
+            .collect::<PVSSMap<E>>();
+        assert!(left.verify_aggregation_with(&dkg, &chunk).unwrap());
+        assert!(left.verify_aggregation(&dkg).unwrap());
+        assert!(anonymous.verify_aggregation(&dkg).is_err());
+
+        let mut other_ritual = right;
+        other_ritual.tau += 1;
+        assert!(matches!(
+            left.merge(&other_ritual),
+            Err(Error::RitualMismatch(expected, actual))
+                if expected == TAU && actual == TAU + 1
+        ));
+    }
+
+    /// Check that evaluating the commitment polynomial matches the commitments to shares
+    #[test]
+    fn test_aggregate_commitment_at() {
+        let (dkg, _) = setup_dealt_dkg();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = aggregate(&pvss_list).unwrap();
+
+        assert_eq!(
+            aggregate.commitment_at(&ark_bls12_381::Fr::zero()),
+            aggregate.coeffs[0]
+        );
+        let mut commitment =
+            batch_to_projective_g1::<EllipticCurve>(&aggregate.coeffs);
+        dkg.domain.fft_in_place(&mut commitment);
+        for (share_index, domain_point) in
+            dkg.domain.elements().take(dkg.validators.len()).enumerate()
+        {
+            assert_eq!(
+                aggregate.commitment_at(&domain_point),
+                commitment[share_index]
+            );
+            assert_eq!(
+                aggregate
+                    .public_key_share(share_index as u32)
+                    .unwrap()
+                    .public_key_share,
+                commitment[share_index]
+            );
+        }
+        assert_eq!(aggregate.public_key().0, dkg.public_key());
+        assert!(matches!(
+            aggregate.public_key_share(SHARES_NUM),
+            Err(Error::InvalidShareIndex(SHARES_NUM))
+        ));
+    }
+
+    /// Check that transcripts of unknown format versions are rejected
+    #[test]
+    fn test_transcript_version() {
+        let (dkg, _) = setup_dealt_dkg();
+        let pvss = dkg.vss.values().next().unwrap().clone();
+        assert_eq!(pvss.version, V1::TRANSCRIPT_VERSION);
+        let limits = DeserializationLimits::default();
+        let bytes = bincode::serialize(&pvss).unwrap();
+        assert_eq!(
+            PubliclyVerifiableSS::<E>::from_bytes_limited(&bytes, &limits)
+                .unwrap(),
+            pvss
+        );
+
+        let mut future = pvss;
+        future.version = V1::TRANSCRIPT_VERSION + 1;
+        let bytes = bincode::serialize(&future).unwrap();
+        assert!(matches!(
+            PubliclyVerifiableSS::<E>::from_bytes_limited(&bytes, &limits),
+            Err(Error::UnsupportedTranscriptVersion(version))
+                if version == V1::TRANSCRIPT_VERSION + 1
+        ));
+        assert!(
+            bincode::deserialize::<PubliclyVerifiableSS<E>>(&bytes).is_err()
+        );
+        let aggregate =
+            aggregate(&dkg.vss.values().cloned().collect::<Vec<_>>()).unwrap();
+        assert_eq!(aggregate.version, V1::TRANSCRIPT_VERSION);
+    }
+
+    /// Check that transcripts encoded before the version decode with the legacy decoder only
+    #[test]
+    fn test_legacy_transcript_encoding() {
+        let (dkg, _) = setup_dealt_dkg();
+        let pvss = dkg.vss.values().next().unwrap().clone();
+        // The encoding before the version, of the coefficients, shares and proof of
+        // knowledge alone
+        let legacy = bincode::serialize(&(
+            ferveo_common::to_bytes(&pvss.coeffs).unwrap(),
+            ferveo_common::to_bytes(&pvss.shares).unwrap(),
+            ferveo_common::to_bytes(&pvss.sigma).unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(transcript_version(&legacy), 0);
+        assert_eq!(
+            PubliclyVerifiableSS::<E>::from_legacy_bytes(&legacy, &dkg)
+                .unwrap(),
+            pvss
+        );
+        let limits = DeserializationLimits::default();
+        assert!(matches!(
+            PubliclyVerifiableSS::<E>::from_bytes_limited(&legacy, &limits),
+            Err(Error::UnsupportedTranscriptVersion(0))
+        ));
+        assert!(
+            bincode::deserialize::<PubliclyVerifiableSS<E>>(&legacy).is_err()
+        );
+
+        let bytes = bincode::serialize(&pvss).unwrap();
+        assert_eq!(transcript_version(&bytes), V1::TRANSCRIPT_VERSION);
+        assert!(
+            PubliclyVerifiableSS::<E>::from_legacy_bytes(&bytes, &dkg).is_err()
+        );
+    }
+
+    /// Check the public key shares of an aggregate with the SCRAPE low-degree test
+    #[test]
+    fn test_verify_public_key_shares() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, _) = setup_dealt_dkg();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = aggregate(&pvss_list).unwrap();
+        let public_key_shares = aggregate
+            .domain_points()
+            .iter()
+            .map(|x| aggregate.commitment_at(x).into_affine())
+            .collect::<Vec<_>>();
+        assert!(aggregate.verify_public_key_shares(&public_key_shares, rng));
+
+        // A share off the polynomial
+        let mut tampered = public_key_shares.clone();
+        tampered[1] = (tampered[1] + G1::generator()).into_affine();
+        assert!(!aggregate.verify_public_key_shares(&tampered, rng));
+
+        // Shares of another public key
+        let mut other_key = aggregate.clone();
+        other_key.coeffs[0] =
+            (other_key.coeffs[0] + G1::generator()).into_affine();
+        assert!(!other_key.verify_public_key_shares(&public_key_shares, rng));
+
+        // Shares of a polynomial of higher degree
+        let mut higher_degree = aggregate.clone();
+        higher_degree.coeffs.push(G1::generator());
+        let higher_degree_shares = higher_degree
+            .domain_points()
+            .iter()
+            .map(|x| higher_degree.commitment_at(x).into_affine())
+            .collect::<Vec<_>>();
+        assert!(!aggregate.verify_public_key_shares(&higher_degree_shares, rng));
+
+        // Shares of another polynomial with the same public key
+        let mut other_poly = aggregate.clone();
+        other_poly.coeffs[1] =
+            (other_poly.coeffs[1] + G1::generator()).into_affine();
+        let other_poly_shares = other_poly
+            .domain_points()
+            .iter()
+            .map(|x| other_poly.commitment_at(x).into_affine())
+            .collect::<Vec<_>>();
+        assert!(!aggregate.verify_public_key_shares(&other_poly_shares, rng));
+
+        // Without coefficients
+        let mut empty = aggregate.clone();
+        empty.coeffs.clear();
+        assert!(!empty.verify_public_key_shares(&public_key_shares, rng));
+        assert!(
+            !aggregate.verify_public_key_shares(&public_key_shares[1..], rng)
+        );
     }
 
     /// Check that if the aggregated PVSS transcript has an
@@ -563,6 +2427,36 @@ mod test_pvss {
                 .expect_err("Test failed")
                 .to_string(),
             "Transcript aggregate doesn't match the received PVSS instances"
+        );
+
+        // The aggregator is to blame, not the dealers
+        let diagnosis = aggregated.diagnose_aggregation(&dkg);
+        assert!(diagnosis.offending_dealers.is_empty());
+        assert!(diagnosis.aggregate_mismatch);
+    }
+
+    /// Check that the dealer of an invalid transcript is found when aggregation fails
+    #[test]
+    fn test_diagnose_aggregation_finds_offending_dealer() {
+        let (mut dkg, _) = setup_dealt_dkg();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let valid = aggregate(&pvss_list).unwrap();
+        assert!(valid.diagnose_aggregation(&dkg).is_clean());
+
+        // A dealer sends an inconsistent share
+        let offender = dkg.vss.keys().nth(1).unwrap().clone();
+        let pvss = dkg.vss.get_mut(&offender).unwrap();
+        pvss.shares[0] = ShareEncryptions::new(
+            (pvss.shares[0].into_group() + G2::generator()).into_affine(),
         )
+        .unwrap();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregated = aggregate(&pvss_list).unwrap();
+        assert!(aggregated.verify_aggregation(&dkg).is_err());
+
+        let diagnosis = aggregated.diagnose_aggregation(&dkg);
+        assert_eq!(diagnosis.offending_dealers, vec![offender]);
+        assert!(!diagnosis.aggregate_mismatch);
+        assert!(!diagnosis.is_clean());
     }
 }
@@ -0,0 +1,222 @@
+use std::ops::Mul;
+
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::Field;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Nonce,
+};
+use ferveo_common::Keypair;
+use ferveo_tdec::{
+    protocol::{ProtocolVersion, V1},
+    AadPolicy, CiphertextHeader, DecryptionShareSimple, PrivateKeyShare,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{
+    AggregatedPvss, DkgParams, DomainDescriptor, Error, PubliclyVerifiableDkg,
+    Result, Validator, ValidatorSetHash,
+};
+
+/// The minimal state a validator needs to serve decryptions for a ritual, e.g. to stand up
+/// a disaster recovery environment.
+///
+/// The bundle doesn't contain the decryption key of the validator, only its public key,
+/// so the keypair must be provided separately when creating decryption shares.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct DecryptionContextBundle<E: Pairing> {
+    pub dkg_params: DkgParams,
+    pub validator_set_hash: ValidatorSetHash,
+    pub domain: DomainDescriptor<E>,
+    pub me: Validator<E>,
+    pub aad_policy: AadPolicy,
+    /// Commitments to the coefficients of the aggregate polynomial. Bundles without
+    /// coefficients are rejected when deserialized
+    #[serde_as(as = "ferveo_common::serialization::NonEmptySerdeAs")]
+    pub coeffs: Vec<E::G1Affine>,
+    /// `Y_i`, the (first) share of this validator in the aggregate, blinded by its public key
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub blinded_share: E::G2Affine,
+    /// The base `g` of the PVSS params of the ritual, which decryption shares are bound to
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub g: E::G1Affine,
+}
+
+impl<E: Pairing> DecryptionContextBundle<E> {
+    pub fn new(
+        dkg: &PubliclyVerifiableDkg<E>,
+        aggregate: &AggregatedPvss<E>,
+    ) -> Result<Self> {
+        let share_index = dkg.me.share_index;
+        let blinded_share = aggregate
+            .shares
+            .get(share_index as usize)
+            .ok_or(Error::InvalidShareIndex(share_index))?
+            .into_affine();
+        Ok(Self {
+            dkg_params: dkg.dkg_params,
+            validator_set_hash: aggregate.validator_set_hash,
+            domain: aggregate.domain,
+            me: dkg.me.clone(),
+            aad_policy: dkg.aad_policy,
+            coeffs: aggregate.coeffs.clone(),
+            blinded_share,
+            g: dkg.pvss_params.g.into_affine(),
+        })
+    }
+
+    /// The public key of the ritual, committed to by the first coefficient, which bundles
+    /// always have since bundles without coefficients are rejected when deserialized
+    pub fn public_key(&self) -> E::G1Affine {
+        self.coeffs[0]
+    }
+
+    pub fn domain_point(&self) -> E::ScalarField {
+        self.domain.element(self.me.share_index as usize)
+    }
+
+    pub fn create_decryption_share_simple(
+        &self,
+        ciphertext_header: &CiphertextHeader<E>,
+        aad: &[u8],
+        validator_keypair: &Keypair<E>,
+    ) -> Result<DecryptionShareSimple<E>> {
+        if validator_keypair.public_key() != self.me.public_key {
+            return Err(Error::ValidatorPublicKeyMismatch);
+        }
+        self.aad_policy.check(aad)?;
+        let private_key_share =
+            PrivateKeyShare {
+                private_key_share: self
+                    .blinded_share
+                    .mul(validator_keypair.decryption_key.inverse().expect(
+                        "Validator decryption key must have an inverse",
+                    ))
+                    .into_affine(),
+            };
+        DecryptionShareSimple::create(
+            &validator_keypair.decryption_key,
+            &private_key_share,
+            ciphertext_header,
+            aad,
+            &E::G1Prepared::from(-self.g.into_group()),
+        )
+        .map_err(Error::from)
+    }
+
+    /// Encrypt the bundle with a 32-byte wrapping key, e.g. managed by a KMS.
+    pub fn seal(
+        &self,
+        wrapping_key: &[u8; 32],
+        rng: &mut impl RngCore,
+    ) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; 12];
+        rng.fill_bytes(&mut nonce);
+        let payload = Payload {
+            msg: &bincode::serialize(self)?,
+            aad: V1::RECOVERY_BUNDLE_DOMAIN,
+        };
+        let ciphertext = ChaCha20Poly1305::new(wrapping_key.into())
+            .encrypt(Nonce::from_slice(&nonce), payload)
+            .map_err(|_| Error::RecoveryBundleDecryptionFailed)?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    /// Decrypt a bundle produced by [`DecryptionContextBundle::seal`].
+    pub fn open(sealed: &[u8], wrapping_key: &[u8; 32]) -> Result<Self> {
+        if sealed.len() < 12 {
+            return Err(Error::RecoveryBundleDecryptionFailed);
+        }
+        let (nonce, ciphertext) = sealed.split_at(12);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: V1::RECOVERY_BUNDLE_DOMAIN,
+        };
+        let bytes = ChaCha20Poly1305::new(wrapping_key.into())
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .map_err(|_| Error::RecoveryBundleDecryptionFailed)?;
+        Ok(bincode::deserialize(&bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod test_recovery {
+    use ark_poly::EvaluationDomain;
+    use ark_std::test_rng;
+    use ferveo_tdec::SecretBox;
+
+    use super::*;
+    use crate::test_common::*;
+
+    #[test]
+    fn serve_decryptions_from_bundle() {
+        let rng = &mut test_rng();
+        let (dkg, validator_keypairs) = setup_dealt_dkg();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = crate::pvss::aggregate(&pvss_list).unwrap();
+        let keypair = &validator_keypairs[dkg.me.share_index as usize];
+        let ciphertext = ferveo_tdec::encrypt::<E>(
+            SecretBox::new(MSG.to_vec()),
+            AAD,
+            &dkg.public_key(),
+            rng,
+        )
+        .unwrap();
+        let header = ciphertext.header().unwrap();
+        let g_inv = dkg.pvss_params.g_inv();
+
+        let bundle = DecryptionContextBundle::new(&dkg, &aggregate).unwrap();
+        let wrapping_key = [7u8; 32];
+        let sealed = bundle.seal(&wrapping_key, rng).unwrap();
+        let restored =
+            DecryptionContextBundle::<E>::open(&sealed, &wrapping_key).unwrap();
+        assert_eq!(restored, bundle);
+        assert_eq!(restored.public_key(), dkg.public_key());
+        assert_eq!(
+            restored.domain_point(),
+            dkg.domain.element(dkg.me.share_index as usize)
+        );
+
+        // The restored context serves the same decryption shares
+        let share = restored
+            .create_decryption_share_simple(&header, AAD, keypair)
+            .unwrap();
+        let expected = aggregate
+            .make_decryption_share_simple(
+                &header,
+                AAD,
+                &keypair.decryption_key,
+                dkg.me.share_index as usize,
+                &g_inv,
+            )
+            .unwrap();
+        assert_eq!(share, expected);
+
+        // But only with the keypair of the validator
+        assert!(matches!(
+            restored.create_decryption_share_simple(
+                &header,
+                AAD,
+                &validator_keypairs[(dkg.me.share_index as usize + 1)
+                    % validator_keypairs.len()],
+            ),
+            Err(Error::ValidatorPublicKeyMismatch)
+        ));
+
+        // Opening requires the wrapping key, and an untampered bundle
+        assert!(matches!(
+            DecryptionContextBundle::<E>::open(&sealed, &[8u8; 32]),
+            Err(Error::RecoveryBundleDecryptionFailed)
+        ));
+        let mut tampered = sealed;
+        tampered[20] ^= 1;
+        assert!(matches!(
+            DecryptionContextBundle::<E>::open(&tampered, &wrapping_key),
+            Err(Error::RecoveryBundleDecryptionFailed)
+        ));
+    }
+}
@@ -6,6 +6,13 @@ use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
 use ferveo_tdec::{lagrange_basis_at, PrivateKeyShare};
 use itertools::zip_eq;
 use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{
+    dealing::decrypt_share, evaluate_commitment, pairings_match, Error,
+    PubliclyVerifiableParams, Result,
+};
 
 // SHARE UPDATE FUNCTIONS:
 
@@ -72,6 +79,174 @@ pub fn prepare_share_updates_for_refresh<E: Pairing>(
     )
 }
 
+// LAZY REFRESH:
+
+/// The share updates of a refresh dealt by one validator, with commitments to the update
+/// polynomial, so that validators can check their update before applying it.
+///
+/// Each update is encrypted to the holder of its share, like the shares of a transcript:
+/// whoever learned a share before the refresh must not be able to update it.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareUpdateTranscript<E: Pairing> {
+    /// Commitments `[c_k] G` to the coefficients of the update polynomial `d`, with
+    /// `d(0) = 0`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub coeffs: Vec<E::G1Affine>,
+    /// The encrypted updates `[d(ω_i)] ek_i`, in share index order
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub updates: Vec<E::G2Affine>,
+}
+
+impl<E: Pairing> ShareUpdateTranscript<E> {
+    /// The updates of a refresh of the shares at `domain_points`, encrypted to the
+    /// matching `encryption_keys` of their holders, e.g. of [`crate::share_owners`], and
+    /// committed to with the generators of `pvss_params`
+    pub fn new_refresh(
+        domain_points: &[E::ScalarField],
+        encryption_keys: &[E::G2Affine],
+        threshold: usize,
+        pvss_params: &PubliclyVerifiableParams<E>,
+        rng: &mut impl RngCore,
+    ) -> Result<Self> {
+        if encryption_keys.len() != domain_points.len() {
+            return Err(Error::InsufficientValidators(
+                domain_points.len() as u32,
+                encryption_keys.len() as u32,
+            ));
+        }
+        let d_i = make_random_polynomial_with_root::<E>(
+            threshold - 1,
+            &E::ScalarField::zero(),
+            rng,
+        );
+        let coeffs = d_i
+            .coeffs
+            .iter()
+            .map(|c_k| pvss_params.g.mul(c_k))
+            .collect::<Vec<_>>();
+        let updates = domain_points
+            .iter()
+            .zip(encryption_keys)
+            .map(|(x_i, ek_i)| ek_i.mul(d_i.evaluate(x_i)))
+            .collect::<Vec<_>>();
+        Ok(Self {
+            coeffs: E::G1::normalize_batch(&coeffs),
+            updates: E::G2::normalize_batch(&updates),
+        })
+    }
+
+    /// Check that the transcript refreshes `shares_num` shares with a polynomial of degree
+    /// `threshold - 1`, that the update of share `share_index`, at `domain_point`, is the
+    /// evaluation of the committed polynomial encrypted to `encryption_key`, and that the
+    /// polynomial keeps the shared secret, i.e. e(G, U_i) == e(D(ω_i), ek_i) and `D_0` is
+    /// the identity, for the generator `G` of `pvss_params`
+    pub fn verify_update(
+        &self,
+        share_index: u32,
+        domain_point: &E::ScalarField,
+        encryption_key: &E::G2Affine,
+        threshold: usize,
+        shares_num: usize,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> bool {
+        if self.coeffs.len() != threshold || self.updates.len() != shares_num {
+            return false;
+        }
+        let update = match self.updates.get(share_index as usize) {
+            Some(update) => *update,
+            None => return false,
+        };
+        if self.coeffs.first().map_or(true, |d_0| !d_0.is_zero()) {
+            return false;
+        }
+        pairings_match::<E>(
+            pvss_params.g,
+            update,
+            evaluate_commitment::<E>(&self.coeffs, domain_point),
+            *encryption_key,
+        )
+    }
+}
+
+/// The share update transcripts a validator received while offline, e.g. during the
+/// refresh epochs it missed. They are verified and applied to its private key share the
+/// next time it decrypts it, see
+/// [`crate::PubliclyVerifiableSS::decrypt_private_key_share_with_updates`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct PendingUpdates<E: Pairing> {
+    share_index: u32,
+    transcripts: Vec<ShareUpdateTranscript<E>>,
+}
+
+impl<E: Pairing> PendingUpdates<E> {
+    pub fn new(share_index: u32) -> Self {
+        Self {
+            share_index,
+            transcripts: vec![],
+        }
+    }
+
+    pub fn share_index(&self) -> u32 {
+        self.share_index
+    }
+
+    pub fn push(&mut self, transcript: ShareUpdateTranscript<E>) {
+        self.transcripts.push(transcript);
+    }
+
+    pub fn len(&self) -> usize {
+        self.transcripts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transcripts.is_empty()
+    }
+
+    /// Verify the pending updates of the share at `domain_point`, of a ritual of
+    /// `shares_num` shares and `threshold` dealt with `pvss_params`, decrypt them with
+    /// `validator_decryption_key` and apply them to `private_key_share`
+    pub fn apply(
+        &self,
+        private_key_share: &PrivateKeyShare<E>,
+        domain_point: &E::ScalarField,
+        validator_decryption_key: &E::ScalarField,
+        threshold: usize,
+        shares_num: usize,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> Result<PrivateKeyShare<E>> {
+        let encryption_key =
+            pvss_params.h.mul(validator_decryption_key).into_affine();
+        let updates = self
+            .transcripts
+            .iter()
+            .enumerate()
+            .map(|(position, transcript)| {
+                if transcript.verify_update(
+                    self.share_index,
+                    domain_point,
+                    &encryption_key,
+                    threshold,
+                    shares_num,
+                    pvss_params,
+                ) {
+                    let update = &transcript.updates[self.share_index as usize];
+                    Ok(decrypt_share::<E>(update, validator_decryption_key)
+                        .private_key_share
+                        .into())
+                } else {
+                    Err(Error::InvalidShareUpdate(position))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(apply_updates_to_private_share::<E>(
+            private_key_share,
+            &updates,
+        ))
+    }
+}
+
 // UTILS:
 
 fn prepare_share_updates_with_root<E: Pairing>(
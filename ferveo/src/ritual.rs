@@ -0,0 +1,369 @@
+use std::collections::BTreeSet;
+
+use ark_ec::pairing::Pairing;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{DkgParams, Error, EthereumAddress, PubliclyVerifiableDkg, Result};
+
+/// The lifecycle of a ritual, as run by production deployments:
+///
+/// `Dealing` → `Aggregating` → `Active` ⇄ `Refreshing`, and finally `HandedOff` to a
+/// successor ritual.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub enum RitualPhase<E: Pairing> {
+    /// Waiting for every validator to post their transcript
+    Dealing,
+    /// Waiting for every validator to post their aggregate of the transcripts
+    Aggregating,
+    /// The ritual is finalized and serves decryption requests
+    Active {
+        #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+        public_key: E::G1Affine,
+    },
+    /// The private key shares are being refreshed
+    Refreshing {
+        #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+        public_key: E::G1Affine,
+    },
+    /// The ritual was superseded by the ritual `successor`
+    HandedOff { successor: u32 },
+}
+
+impl<E: Pairing> RitualPhase<E> {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RitualPhase::Dealing => "Dealing",
+            RitualPhase::Aggregating => "Aggregating",
+            RitualPhase::Active { .. } => "Active",
+            RitualPhase::Refreshing { .. } => "Refreshing",
+            RitualPhase::HandedOff { .. } => "HandedOff",
+        }
+    }
+}
+
+/// An event observed on chain that may advance a ritual.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub enum RitualEvent<E: Pairing> {
+    TranscriptPosted(EthereumAddress),
+    AggregationPosted {
+        validator: EthereumAddress,
+        #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+        public_key: E::G1Affine,
+    },
+    RefreshStarted,
+    RefreshCompleted,
+    HandoffCompleted {
+        successor: u32,
+    },
+}
+
+impl<E: Pairing> RitualEvent<E> {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RitualEvent::TranscriptPosted(_) => "TranscriptPosted",
+            RitualEvent::AggregationPosted { .. } => "AggregationPosted",
+            RitualEvent::RefreshStarted => "RefreshStarted",
+            RitualEvent::RefreshCompleted => "RefreshCompleted",
+            RitualEvent::HandoffCompleted { .. } => "HandoffCompleted",
+        }
+    }
+}
+
+/// Tracks the lifecycle of a ritual and rejects events that are invalid in its current phase.
+///
+/// This only tracks who posted what; checking the posted transcripts and aggregates is
+/// left to [`PubliclyVerifiableDkg`].
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct Ritual<E: Pairing> {
+    params: DkgParams,
+    validators: BTreeSet<EthereumAddress>,
+    transcripts: BTreeSet<EthereumAddress>,
+    aggregations: BTreeSet<EthereumAddress>,
+    #[serde_as(as = "Option<ferveo_common::serialization::SerdeAs>")]
+    aggregated_public_key: Option<E::G1Affine>,
+    phase: RitualPhase<E>,
+}
+
+impl<E: Pairing> Ritual<E> {
+    pub fn new(
+        params: DkgParams,
+        validators: impl IntoIterator<Item = EthereumAddress>,
+    ) -> Result<Self> {
+        let validators = validators.into_iter().collect::<BTreeSet<_>>();
+        // Validators with a weight above one hold several shares
+        if validators.is_empty()
+            || validators.len() > params.shares_num() as usize
+        {
+            return Err(Error::InsufficientValidators(
+                params.shares_num(),
+                validators.len() as u32,
+            ));
+        }
+        Ok(Self {
+            params,
+            validators,
+            transcripts: BTreeSet::new(),
+            aggregations: BTreeSet::new(),
+            aggregated_public_key: None,
+            phase: RitualPhase::Dealing,
+        })
+    }
+
+    pub fn from_dkg(dkg: &PubliclyVerifiableDkg<E>) -> Result<Self> {
+        Self::new(
+            dkg.dkg_params,
+            dkg.dealers().map(|validator| validator.address.clone()),
+        )
+    }
+
+    pub fn id(&self) -> u32 {
+        self.params.tau()
+    }
+
+    pub fn params(&self) -> &DkgParams {
+        &self.params
+    }
+
+    pub fn phase(&self) -> &RitualPhase<E> {
+        &self.phase
+    }
+
+    /// The public key of the ritual, once it was finalized
+    pub fn public_key(&self) -> Option<E::G1Affine> {
+        match self.phase {
+            RitualPhase::Active { public_key }
+            | RitualPhase::Refreshing { public_key } => Some(public_key),
+            _ => None,
+        }
+    }
+
+    /// Whether validators should create decryption shares for this ritual.
+    /// Decryption is paused while the key shares are being refreshed.
+    pub fn is_serving_decryption(&self) -> bool {
+        matches!(self.phase, RitualPhase::Active { .. })
+    }
+
+    /// Apply an event, returning the resulting phase.
+    /// On error, the ritual is left unchanged.
+    pub fn apply(&mut self, event: RitualEvent<E>) -> Result<&RitualPhase<E>> {
+        match (&self.phase, event) {
+            (RitualPhase::Dealing, RitualEvent::TranscriptPosted(dealer)) => {
+                self.check_validator(&dealer)?;
+                if !self.transcripts.insert(dealer.clone()) {
+                    return Err(Error::DuplicateDealer(dealer));
+                }
+                if self.transcripts.len() == self.validators.len() {
+                    self.phase = RitualPhase::Aggregating;
+                }
+            }
+            (
+                RitualPhase::Aggregating,
+                RitualEvent::AggregationPosted {
+                    validator,
+                    public_key,
+                },
+            ) => {
+                self.check_validator(&validator)?;
+                if self.aggregations.contains(&validator) {
+                    return Err(Error::DuplicateDealer(validator));
+                }
+                // Every aggregate must result in the same public key
+                if self
+                    .aggregated_public_key
+                    .map_or(false, |key| key != public_key)
+                {
+                    return Err(Error::InvalidDkgPublicKey);
+                }
+                self.aggregated_public_key = Some(public_key);
+                self.aggregations.insert(validator);
+                if self.aggregations.len() == self.validators.len() {
+                    self.phase = RitualPhase::Active { public_key };
+                }
+            }
+            (
+                RitualPhase::Active { public_key },
+                RitualEvent::RefreshStarted,
+            ) => {
+                self.phase = RitualPhase::Refreshing {
+                    public_key: *public_key,
+                };
+            }
+            (
+                RitualPhase::Refreshing { public_key },
+                RitualEvent::RefreshCompleted,
+            ) => {
+                self.phase = RitualPhase::Active {
+                    public_key: *public_key,
+                };
+            }
+            (
+                RitualPhase::Active { .. },
+                RitualEvent::HandoffCompleted { successor },
+            ) => {
+                self.phase = RitualPhase::HandedOff { successor };
+            }
+            (phase, event) => {
+                return Err(Error::InvalidRitualTransition(
+                    phase.name(),
+                    event.name(),
+                ));
+            }
+        }
+        Ok(&self.phase)
+    }
+
+    fn check_validator(&self, address: &EthereumAddress) -> Result<()> {
+        if self.validators.contains(address) {
+            Ok(())
+        } else {
+            Err(Error::UnknownDealer(address.clone()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_ritual {
+    use ark_ec::AffineRepr;
+
+    use super::*;
+    use crate::test_common::*;
+
+    fn setup_ritual() -> Ritual<E> {
+        let (dkg, _) = setup_dkg(0);
+        Ritual::from_dkg(&dkg).unwrap()
+    }
+
+    fn finalize(ritual: &mut Ritual<E>, public_key: G1) {
+        for i in 0..SHARES_NUM as usize {
+            ritual
+                .apply(RitualEvent::TranscriptPosted(gen_address(i)))
+                .unwrap();
+        }
+        for i in 0..SHARES_NUM as usize {
+            ritual
+                .apply(RitualEvent::AggregationPosted {
+                    validator: gen_address(i),
+                    public_key,
+                })
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn ritual_lifecycle() {
+        let mut ritual = setup_ritual();
+        let public_key = G1::generator();
+        assert_eq!(ritual.phase(), &RitualPhase::Dealing);
+
+        for i in 0..SHARES_NUM as usize {
+            assert_eq!(ritual.phase(), &RitualPhase::Dealing);
+            ritual
+                .apply(RitualEvent::TranscriptPosted(gen_address(i)))
+                .unwrap();
+        }
+        assert_eq!(ritual.phase(), &RitualPhase::Aggregating);
+
+        for i in 0..SHARES_NUM as usize {
+            assert!(!ritual.is_serving_decryption());
+            ritual
+                .apply(RitualEvent::AggregationPosted {
+                    validator: gen_address(i),
+                    public_key,
+                })
+                .unwrap();
+        }
+        assert_eq!(ritual.phase(), &RitualPhase::Active { public_key });
+        assert_eq!(ritual.public_key(), Some(public_key));
+        assert!(ritual.is_serving_decryption());
+
+        ritual.apply(RitualEvent::RefreshStarted).unwrap();
+        assert!(!ritual.is_serving_decryption());
+        assert_eq!(ritual.public_key(), Some(public_key));
+        ritual.apply(RitualEvent::RefreshCompleted).unwrap();
+        assert!(ritual.is_serving_decryption());
+
+        ritual
+            .apply(RitualEvent::HandoffCompleted { successor: 1 })
+            .unwrap();
+        assert_eq!(ritual.phase(), &RitualPhase::HandedOff { successor: 1 });
+        assert!(!ritual.is_serving_decryption());
+    }
+
+    #[test]
+    fn invalid_ritual_events_are_rejected() {
+        let mut ritual = setup_ritual();
+        let public_key = G1::generator();
+
+        // Events from the wrong phase
+        assert!(matches!(
+            ritual.apply(RitualEvent::RefreshStarted),
+            Err(Error::InvalidRitualTransition("Dealing", "RefreshStarted"))
+        ));
+        assert!(matches!(
+            ritual.apply(RitualEvent::AggregationPosted {
+                validator: gen_address(0),
+                public_key,
+            }),
+            Err(Error::InvalidRitualTransition(
+                "Dealing",
+                "AggregationPosted"
+            ))
+        ));
+
+        // Unknown and duplicate dealers
+        let unknown = gen_address(SHARES_NUM as usize);
+        assert!(matches!(
+            ritual.apply(RitualEvent::TranscriptPosted(unknown)),
+            Err(Error::UnknownDealer(_))
+        ));
+        ritual
+            .apply(RitualEvent::TranscriptPosted(gen_address(0)))
+            .unwrap();
+        assert!(matches!(
+            ritual.apply(RitualEvent::TranscriptPosted(gen_address(0))),
+            Err(Error::DuplicateDealer(_))
+        ));
+        for i in 1..SHARES_NUM as usize {
+            ritual
+                .apply(RitualEvent::TranscriptPosted(gen_address(i)))
+                .unwrap();
+        }
+
+        // Aggregates must agree on the public key
+        ritual
+            .apply(RitualEvent::AggregationPosted {
+                validator: gen_address(0),
+                public_key,
+            })
+            .unwrap();
+        let before = ritual.clone();
+        assert!(matches!(
+            ritual.apply(RitualEvent::AggregationPosted {
+                validator: gen_address(1),
+                public_key: (public_key + public_key).into(),
+            }),
+            Err(Error::InvalidDkgPublicKey)
+        ));
+        assert_eq!(ritual, before);
+    }
+
+    #[test]
+    fn ritual_serialization() {
+        let mut ritual = setup_ritual();
+        let bytes = bincode::serialize(&ritual).unwrap();
+        assert_eq!(bincode::deserialize::<Ritual<E>>(&bytes).unwrap(), ritual);
+
+        finalize(&mut ritual, G1::generator());
+        let bytes = bincode::serialize(&ritual).unwrap();
+        let deserialized: Ritual<E> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(deserialized, ritual);
+        assert!(deserialized.is_serving_decryption());
+    }
+}
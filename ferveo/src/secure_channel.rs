@@ -0,0 +1,195 @@
+use std::{marker::PhantomData, ops::Mul};
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Nonce,
+};
+use ferveo_common::Keypair;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::{Error, EthereumAddress, Result, Validator};
+
+/// An authenticated and encrypted channel between two validators, e.g. to send share
+/// updates of a recovery or refresh to each of them.
+///
+/// The key of the channel is derived from a static Diffie-Hellman exchange of the
+/// validator keypairs, so only the two validators can seal and open its messages, but a
+/// compromised keypair also exposes past messages. Each message is bound to its direction
+/// and to a caller-provided context, e.g. the ritual and round it belongs to.
+pub struct SecureChannel<E: Pairing> {
+    key: Zeroizing<[u8; 32]>,
+    me: EthereumAddress,
+    peer: EthereumAddress,
+    phantom: PhantomData<E>,
+}
+
+impl<E: Pairing> SecureChannel<E> {
+    /// Open the channel of `me`, whose keypair is `keypair`, with `peer`
+    pub fn new(
+        keypair: &Keypair<E>,
+        me: &Validator<E>,
+        peer: &Validator<E>,
+    ) -> Result<Self> {
+        if keypair.public_key() != me.public_key {
+            return Err(Error::ValidatorPublicKeyMismatch);
+        }
+        // [dk_me dk_peer] H
+        let shared = peer.public_key.encryption_key.mul(keypair.decryption_key);
+        let mut shared_bytes = Zeroizing::new(Vec::new());
+        shared.serialize_compressed(&mut *shared_bytes)?;
+        let mut keys = [vec![], vec![]];
+        me.public_key
+            .encryption_key
+            .serialize_compressed(&mut keys[0])?;
+        peer.public_key
+            .encryption_key
+            .serialize_compressed(&mut keys[1])?;
+        keys.sort();
+
+        let mut hasher = Sha256::new();
+        hasher.update(V1::SECURE_CHANNEL_DOMAIN);
+        hasher.update(&*shared_bytes);
+        hasher.update(&keys[0]);
+        hasher.update(&keys[1]);
+        Ok(Self {
+            key: Zeroizing::new(hasher.finalize().into()),
+            me: me.address.clone(),
+            peer: peer.address.clone(),
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn peer(&self) -> &EthereumAddress {
+        &self.peer
+    }
+
+    /// Encrypt a message to the peer, under a random nonce
+    pub fn seal(
+        &self,
+        plaintext: &[u8],
+        context: &[u8],
+        rng: &mut impl RngCore,
+    ) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; 12];
+        rng.fill_bytes(&mut nonce);
+        let payload = Payload {
+            msg: plaintext,
+            aad: &associated_data(&self.me, &self.peer, context),
+        };
+        let ciphertext = ChaCha20Poly1305::new(self.key.as_ref().into())
+            .encrypt(Nonce::from_slice(&nonce), payload)
+            .map_err(|_| Error::SecureChannelDecryptionFailed)?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    /// Decrypt a message the peer sealed with [`SecureChannel::seal`] in the same `context`
+    pub fn open(&self, sealed: &[u8], context: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < 12 {
+            return Err(Error::SecureChannelDecryptionFailed);
+        }
+        let (nonce, ciphertext) = sealed.split_at(12);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: &associated_data(&self.peer, &self.me, context),
+        };
+        ChaCha20Poly1305::new(self.key.as_ref().into())
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .map_err(|_| Error::SecureChannelDecryptionFailed)
+    }
+}
+
+fn associated_data(
+    sender: &EthereumAddress,
+    recipient: &EthereumAddress,
+    context: &[u8],
+) -> Vec<u8> {
+    [
+        V1::SECURE_CHANNEL_DOMAIN,
+        sender.to_string().as_bytes(),
+        recipient.to_string().as_bytes(),
+        context,
+    ]
+    .concat()
+}
+
+#[cfg(all(test, feature = "refresh"))]
+mod test_secure_channel {
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_serialize::CanonicalDeserialize;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::{prepare_share_updates_for_refresh, test_common::*};
+
+    #[test]
+    fn share_updates_over_pairwise_channels() {
+        let rng = &mut test_rng();
+        let keypairs = gen_keypairs(3);
+        let validators = gen_validators(&keypairs);
+        let alice = SecureChannel::<E>::new(
+            &keypairs[0],
+            &validators[0],
+            &validators[1],
+        )
+        .unwrap();
+        let bob = SecureChannel::<E>::new(
+            &keypairs[1],
+            &validators[1],
+            &validators[0],
+        )
+        .unwrap();
+        assert_eq!(alice.peer(), &validators[1].address);
+
+        // Alice sends Bob his share update
+        let domain_points = [ScalarField::from(1u64), ScalarField::from(2u64)];
+        let update = prepare_share_updates_for_refresh::<E>(
+            &domain_points,
+            &G2::generator(),
+            2,
+            rng,
+        )[1]
+        .into_affine();
+        let mut plaintext = vec![];
+        update.serialize_compressed(&mut plaintext).unwrap();
+        let context = b"ritual 0, refresh 1";
+        let sealed = alice.seal(&plaintext, context, rng).unwrap();
+        let opened = bob.open(&sealed, context).unwrap();
+        assert_eq!(G2::deserialize_compressed(&opened[..]).unwrap(), update);
+
+        // In another context, reflected back to its sender, or tampered with, it doesn't
+        // open
+        assert!(matches!(
+            bob.open(&sealed, b"ritual 1, refresh 1"),
+            Err(Error::SecureChannelDecryptionFailed)
+        ));
+        assert!(alice.open(&sealed, context).is_err());
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(bob.open(&tampered, context).is_err());
+        assert!(bob.open(&sealed[..11], context).is_err());
+
+        // Nor can a third validator open it
+        let eve = SecureChannel::<E>::new(
+            &keypairs[2],
+            &validators[2],
+            &validators[0],
+        )
+        .unwrap();
+        assert!(eve.open(&sealed, context).is_err());
+
+        // The keypair must be the one of the validator opening the channel
+        assert!(matches!(
+            SecureChannel::<E>::new(
+                &keypairs[2],
+                &validators[1],
+                &validators[0]
+            ),
+            Err(Error::ValidatorPublicKeyMismatch)
+        ));
+    }
+}
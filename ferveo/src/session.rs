@@ -0,0 +1,562 @@
+use std::collections::BTreeSet;
+
+use ark_ec::pairing::Pairing;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{
+    DkgState, Error, EthereumAddress, Message, PubliclyVerifiableDkg,
+    PubliclyVerifiableSS, Result, Validator,
+};
+
+/// The rounds of a [`DkgSession`]:
+///
+/// `Announce` → `Deal` → `Complain` → `Aggregate` → `Finalized`, or `Aborted` if a round
+/// times out without enough contributions to go on.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub enum DkgRound<E: Pairing> {
+    /// Waiting for the validators to announce that they take part in the ritual
+    Announce,
+    /// Waiting for the validators to deal their transcript
+    Deal,
+    /// Waiting for [`crate::Complaint`]s about the transcripts, until the deadline
+    Complain,
+    /// Waiting for an aggregate of the transcripts
+    Aggregate,
+    /// An aggregate was verified and applied
+    Finalized {
+        #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+        public_key: E::G1Affine,
+    },
+    /// A round timed out, and the validators in `missing` didn't contribute to it
+    Aborted { missing: Vec<EthereumAddress> },
+}
+
+impl<E: Pairing> DkgRound<E> {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DkgRound::Announce => "Announce",
+            DkgRound::Deal => "Deal",
+            DkgRound::Complain => "Complain",
+            DkgRound::Aggregate => "Aggregate",
+            DkgRound::Finalized { .. } => "Finalized",
+            DkgRound::Aborted { .. } => "Aborted",
+        }
+    }
+
+    /// Whether the session is over, successfully or not
+    pub fn is_final(&self) -> bool {
+        matches!(self, DkgRound::Finalized { .. } | DkgRound::Aborted { .. })
+    }
+}
+
+/// How many blocks each round of a [`DkgSession`] lasts at most, counted from the block
+/// it started at
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundDeadlines {
+    pub announce: u32,
+    pub deal: u32,
+    pub complain: u32,
+    pub aggregate: u32,
+}
+
+/// What [`DkgSession::on_timeout`] observed when a round timed out
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeoutReport {
+    /// The round that timed out
+    pub round: &'static str,
+    /// The validators that didn't contribute to the round
+    pub missing: Vec<EthereumAddress>,
+    /// Whether the session was aborted, or went on to the next round with the
+    /// contributions it had
+    pub aborted: bool,
+}
+
+/// How a validator misbehaved in a [`DkgSession`]
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum FaultKind {
+    /// Didn't announce before the announcement round timed out
+    DidNotAnnounce,
+    /// Didn't deal before the dealing round timed out
+    DidNotDeal,
+    /// Dealt a transcript that failed verification, or that a complaint exposed
+    InvalidTranscript,
+    /// Dealt two different transcripts
+    DoubleDealt,
+}
+
+/// A fault of `validator` observed by a [`DkgSession`]
+#[derive(
+    Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct ValidatorFault {
+    pub validator: EthereumAddress,
+    pub fault: FaultKind,
+}
+
+/// The result of a [`DkgSession`] that is over
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub enum DkgOutcome<E: Pairing> {
+    Finalized {
+        #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+        public_key: E::G1Affine,
+    },
+    /// The session was aborted, and `offenders` are every fault observed during it, by
+    /// validator
+    Aborted { offenders: Vec<ValidatorFault> },
+}
+
+/// Drives a [`PubliclyVerifiableDkg`] through the rounds of a ritual, see [`DkgRound`].
+///
+/// Time is counted in blocks, which the caller passes along with every event. A round
+/// moves on as soon as every validator contributed to it, or enough dealers under
+/// [`crate::DropoutPolicy::TolerateDropouts`], or, once its deadline passed,
+/// with [`DkgSession::on_timeout`] if enough of them did: the security threshold for
+/// announcements and transcripts. Otherwise the session is aborted. The complaint round
+/// always lasts until its deadline, and must leave enough transcripts to aggregate.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct DkgSession<E: Pairing> {
+    dkg: PubliclyVerifiableDkg<E>,
+    deadlines: RoundDeadlines,
+    round: DkgRound<E>,
+    round_started: u32,
+    announced: BTreeSet<EthereumAddress>,
+    faults: BTreeSet<ValidatorFault>,
+}
+
+impl<E: Pairing> DkgSession<E> {
+    /// Start a session at `block`, in the `Announce` round
+    pub fn new(
+        dkg: PubliclyVerifiableDkg<E>,
+        deadlines: RoundDeadlines,
+        block: u32,
+    ) -> Self {
+        Self {
+            dkg,
+            deadlines,
+            round: DkgRound::Announce,
+            round_started: block,
+            announced: BTreeSet::new(),
+            faults: BTreeSet::new(),
+        }
+    }
+
+    pub fn dkg(&self) -> &PubliclyVerifiableDkg<E> {
+        &self.dkg
+    }
+
+    pub fn round(&self) -> &DkgRound<E> {
+        &self.round
+    }
+
+    pub fn deadlines(&self) -> &RoundDeadlines {
+        &self.deadlines
+    }
+
+    /// The faults observed so far, by validator, whether they stopped the session or not
+    pub fn faults(&self) -> impl Iterator<Item = &ValidatorFault> {
+        self.faults.iter()
+    }
+
+    /// The faults observed so far, with the validators at fault, e.g. to report them by
+    /// the label or endpoint in their [`crate::ValidatorMetadata`]
+    pub fn faulty_validators(
+        &self,
+    ) -> impl Iterator<Item = (&Validator<E>, FaultKind)> {
+        self.faults.iter().filter_map(|fault| {
+            self.dkg
+                .validators
+                .get(&fault.validator)
+                .map(|validator| (validator, fault.fault))
+        })
+    }
+
+    /// The outcome of the session, or `None` while it runs
+    pub fn outcome(&self) -> Option<DkgOutcome<E>> {
+        match &self.round {
+            DkgRound::Finalized { public_key } => Some(DkgOutcome::Finalized {
+                public_key: *public_key,
+            }),
+            DkgRound::Aborted { .. } => Some(DkgOutcome::Aborted {
+                offenders: self.faults.iter().cloned().collect(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// The last block of the current round, or `None` once the session is over
+    pub fn deadline(&self) -> Option<u32> {
+        let duration = match self.round {
+            DkgRound::Announce => self.deadlines.announce,
+            DkgRound::Deal => self.deadlines.deal,
+            DkgRound::Complain => self.deadlines.complain,
+            DkgRound::Aggregate => self.deadlines.aggregate,
+            DkgRound::Finalized { .. } | DkgRound::Aborted { .. } => {
+                return None
+            }
+        };
+        Some(self.round_started.saturating_add(duration))
+    }
+
+    /// Record that `validator` announced it takes part in the ritual
+    pub fn announce(
+        &mut self,
+        validator: &EthereumAddress,
+        block: u32,
+    ) -> Result<&DkgRound<E>> {
+        self.check_round(&DkgRound::Announce, "Announcement", block)?;
+        if !self.dkg.validators.contains_key(validator) {
+            return Err(Error::UnknownDealer(validator.clone()));
+        }
+        if !self.announced.insert(validator.clone()) {
+            return Err(Error::DuplicateDealer(validator.clone()));
+        }
+        if self.announced.len() == self.dkg.validators.len() {
+            self.enter(DkgRound::Deal, block);
+        }
+        Ok(&self.round)
+    }
+
+    /// Verify and apply a message of the current round, received at `block`.
+    /// On error, the session is left unchanged, except for the fault of a dealer whose
+    /// transcript fails verification or conflicts with the one it dealt before.
+    pub fn apply_message(
+        &mut self,
+        sender: &Validator<E>,
+        payload: &Message<E>,
+        block: u32,
+    ) -> Result<&DkgRound<E>> {
+        match payload {
+            Message::Deal(_) => {
+                self.check_round(&DkgRound::Deal, "Deal", block)?
+            }
+            Message::Aggregate(_) => {
+                self.check_round(&DkgRound::Aggregate, "Aggregate", block)?
+            }
+            Message::Complaint(_) => {
+                self.check_round(&DkgRound::Complain, "Complaint", block)?
+            }
+        }
+        if let Err(err) = self.dkg.verify_message(sender, payload) {
+            if let Message::Deal(pvss) = payload {
+                self.record_deal_fault(sender, pvss, &err);
+            }
+            return Err(err);
+        }
+        self.dkg.apply_message(sender, payload)?;
+        if let Message::Complaint(complaint) = payload {
+            self.faults.insert(ValidatorFault {
+                validator: complaint.dealer.clone(),
+                fault: FaultKind::InvalidTranscript,
+            });
+        }
+        match (&self.round, &self.dkg.state) {
+            (_, DkgState::Success { public_key }) => {
+                let public_key = *public_key;
+                self.enter(DkgRound::Finalized { public_key }, block)
+            }
+            (DkgRound::Deal, _) if self.dkg.dealing_complete() => {
+                self.enter(DkgRound::Complain, block)
+            }
+            _ => {}
+        }
+        Ok(&self.round)
+    }
+
+    /// End the current round if its deadline passed at `block`, and report the validators
+    /// that didn't contribute to it. Returns `None` if the round is still running or the
+    /// session is over
+    pub fn on_timeout(&mut self, block: u32) -> Option<TimeoutReport> {
+        if block <= self.deadline()? {
+            return None;
+        }
+        let round = self.round.name();
+        let missing = self.missing();
+        let threshold = self.dkg.dkg_params.security_threshold() as usize;
+        let dealt = self.dkg.vss.len() >= threshold;
+        let next = match self.round {
+            DkgRound::Announce if self.announced.len() >= threshold => {
+                DkgRound::Deal
+            }
+            DkgRound::Deal if dealt => DkgRound::Complain,
+            DkgRound::Complain if dealt => DkgRound::Aggregate,
+            _ => DkgRound::Aborted {
+                missing: missing.clone(),
+            },
+        };
+        let aborted = matches!(next, DkgRound::Aborted { .. });
+        let fault = match self.round {
+            DkgRound::Announce => Some(FaultKind::DidNotAnnounce),
+            DkgRound::Deal => Some(FaultKind::DidNotDeal),
+            _ => None,
+        };
+        if let Some(fault) = fault {
+            self.faults.extend(missing.iter().map(|validator| {
+                ValidatorFault {
+                    validator: validator.clone(),
+                    fault,
+                }
+            }));
+        }
+        self.enter(next, block);
+        Some(TimeoutReport {
+            round,
+            missing,
+            aborted,
+        })
+    }
+
+    /// The validators that didn't contribute to the current round yet
+    pub fn missing(&self) -> Vec<EthereumAddress> {
+        self.dkg
+            .validators
+            .keys()
+            .filter(|address| match self.round {
+                DkgRound::Announce => !self.announced.contains(address),
+                DkgRound::Deal => {
+                    !self.dkg.vss.contains_key(address)
+                        && !self.dkg.validators[*address].is_observer()
+                }
+                DkgRound::Aggregate => true,
+                DkgRound::Complain
+                | DkgRound::Finalized { .. }
+                | DkgRound::Aborted { .. } => false,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Blame `sender` for a transcript rejected with `err`, unless it wasn't a dealer of the
+    /// DKG, or the transcript is a copy of the one it dealt
+    fn record_deal_fault(
+        &mut self,
+        sender: &Validator<E>,
+        pvss: &PubliclyVerifiableSS<E>,
+        err: &Error,
+    ) {
+        let fault = match err {
+            Error::UnknownDealer(_) | Error::UnsignedTranscript => return,
+            Error::DuplicateDealer(_)
+                if self.dkg.vss.get(&sender.address) == Some(pvss) =>
+            {
+                return
+            }
+            Error::DuplicateDealer(_) => FaultKind::DoubleDealt,
+            _ => FaultKind::InvalidTranscript,
+        };
+        self.faults.insert(ValidatorFault {
+            validator: sender.address.clone(),
+            fault,
+        });
+    }
+
+    fn check_round(
+        &self,
+        expected: &DkgRound<E>,
+        event: &'static str,
+        block: u32,
+    ) -> Result<()> {
+        if &self.round != expected {
+            return Err(Error::InvalidRitualTransition(
+                self.round.name(),
+                event,
+            ));
+        }
+        if self.deadline().map_or(false, |deadline| block > deadline) {
+            return Err(Error::RoundDeadlinePassed(self.round.name(), block));
+        }
+        Ok(())
+    }
+
+    fn enter(&mut self, round: DkgRound<E>, block: u32) {
+        self.round = round;
+        self.round_started = block;
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_session {
+    use super::*;
+    use crate::test_common::*;
+
+    const DEADLINES: RoundDeadlines = RoundDeadlines {
+        announce: 10,
+        deal: 10,
+        complain: 10,
+        aggregate: 10,
+    };
+
+    fn setup_session() -> (DkgSession<E>, Vec<(Validator<E>, Message<E>)>) {
+        let rng = &mut ark_std::test_rng();
+        let messages = (0..SHARES_NUM as usize)
+            .map(|i| {
+                let (mut dkg, _) = setup_dkg(i);
+                (dkg.me.clone(), dkg.share(rng).unwrap())
+            })
+            .collect();
+        let (dkg, _) = setup_dkg(0);
+        (DkgSession::new(dkg, DEADLINES, 0), messages)
+    }
+
+    #[test]
+    fn test_session_rounds() {
+        let (mut session, messages) = setup_session();
+        assert_eq!(session.deadline(), Some(10));
+        assert!(session.on_timeout(10).is_none());
+        assert!(matches!(
+            session.apply_message(&messages[0].0, &messages[0].1, 1),
+            Err(Error::InvalidRitualTransition("Announce", "Deal"))
+        ));
+        for (validator, _) in &messages {
+            session.announce(&validator.address, 1).unwrap();
+        }
+        assert_eq!(session.round(), &DkgRound::Deal);
+        assert_eq!(session.deadline(), Some(11));
+
+        for (validator, message) in &messages {
+            session.apply_message(validator, message, 2).unwrap();
+        }
+        assert_eq!(session.round(), &DkgRound::Complain);
+        let aggregate = session.dkg().aggregate().unwrap();
+        assert!(matches!(
+            session.apply_message(&messages[1].0, &aggregate, 3),
+            Err(Error::InvalidRitualTransition("Complain", "Aggregate"))
+        ));
+
+        // Nobody complained
+        let report = session.on_timeout(13).unwrap();
+        assert!(report.missing.is_empty() && !report.aborted);
+        assert_eq!(session.round(), &DkgRound::Aggregate);
+        assert!(matches!(
+            session.apply_message(&messages[1].0, &aggregate, 24),
+            Err(Error::RoundDeadlinePassed("Aggregate", 24))
+        ));
+        session
+            .apply_message(&messages[1].0, &aggregate, 14)
+            .unwrap();
+        assert_eq!(
+            session.round(),
+            &DkgRound::Finalized {
+                public_key: session.dkg().public_key()
+            }
+        );
+        assert!(session.round().is_final());
+        assert_eq!(session.deadline(), None);
+        assert!(session.on_timeout(u32::MAX).is_none());
+    }
+
+    #[test]
+    fn test_session_timeouts() {
+        let (mut session, messages) = setup_session();
+        let silent = messages[SHARES_NUM as usize - 1].0.address.clone();
+        for (validator, _) in &messages[..SHARES_NUM as usize - 1] {
+            session.announce(&validator.address, 1).unwrap();
+        }
+        assert!(matches!(
+            session.announce(&messages[0].0.address, 1),
+            Err(Error::DuplicateDealer(_))
+        ));
+
+        // Enough validators announced to go on without the silent one
+        let report = session.on_timeout(11).unwrap();
+        assert_eq!(
+            report,
+            TimeoutReport {
+                round: "Announce",
+                missing: vec![silent.clone()],
+                aborted: false,
+            }
+        );
+        assert_eq!(session.round(), &DkgRound::Deal);
+        assert_eq!(session.deadline(), Some(21));
+
+        // But not enough of them deal
+        for (validator, message) in &messages[..SECURITY_THRESHOLD as usize - 1]
+        {
+            session.apply_message(validator, message, 12).unwrap();
+        }
+        assert!(session.on_timeout(21).is_none());
+        let report = session.on_timeout(22).unwrap();
+        assert_eq!(report.round, "Deal");
+        assert!(report.aborted);
+        assert_eq!(report.missing.len(), 2);
+        assert!(report.missing.contains(&silent));
+        assert_eq!(
+            session.round(),
+            &DkgRound::Aborted {
+                missing: report.missing.clone()
+            }
+        );
+
+        // The outcome blames the silent validator for both rounds
+        let offenders = match session.outcome() {
+            Some(DkgOutcome::Aborted { offenders }) => offenders,
+            _ => panic!("Session wasn't aborted"),
+        };
+        assert_eq!(offenders.len(), 3);
+        for fault in [FaultKind::DidNotAnnounce, FaultKind::DidNotDeal] {
+            assert!(offenders.contains(&ValidatorFault {
+                validator: silent.clone(),
+                fault,
+            }));
+        }
+        assert!(report.missing.iter().all(|validator| offenders.contains(
+            &ValidatorFault {
+                validator: validator.clone(),
+                fault: FaultKind::DidNotDeal,
+            }
+        )));
+    }
+
+    #[test]
+    fn test_session_faults() {
+        let rng = &mut ark_std::test_rng();
+        let (mut session, messages) = setup_session();
+        for (validator, _) in &messages {
+            session.announce(&validator.address, 1).unwrap();
+        }
+        let (dealer, message) = &messages[0];
+        session.apply_message(dealer, message, 2).unwrap();
+        // Copies of a transcript aren't faults, but other transcripts are
+        assert!(session.apply_message(dealer, message, 2).is_err());
+        assert_eq!(session.faults().count(), 0);
+        let (mut dkg, _) = setup_dkg(0);
+        let other = dkg.share(rng).unwrap();
+        assert!(session.apply_message(dealer, &other, 2).is_err());
+
+        // Transcripts sent by another validator than their dealer are invalid
+        let (sender, _) = &messages[1];
+        assert!(session.apply_message(sender, message, 2).is_err());
+        assert_eq!(
+            session.faults().cloned().collect::<Vec<_>>(),
+            [
+                ValidatorFault {
+                    validator: dealer.address.clone(),
+                    fault: FaultKind::DoubleDealt,
+                },
+                ValidatorFault {
+                    validator: sender.address.clone(),
+                    fault: FaultKind::InvalidTranscript,
+                },
+            ]
+        );
+        assert_eq!(session.outcome(), None);
+        assert_eq!(
+            session
+                .faulty_validators()
+                .map(|(validator, fault)| (validator.address.clone(), fault))
+                .collect::<Vec<_>>(),
+            [
+                (dealer.address.clone(), FaultKind::DoubleDealt),
+                (sender.address.clone(), FaultKind::InvalidTranscript),
+            ]
+        );
+    }
+}
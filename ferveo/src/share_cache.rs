@@ -0,0 +1,275 @@
+use ark_ec::pairing::Pairing;
+use ferveo_tdec::{CiphertextHeader, DecryptionShareSimple, PrivateKeyShare};
+
+use crate::{
+    aggregate_hash, dealing::decrypt_share, AggregatedPvss, Error, Result,
+};
+#[cfg(feature = "refresh")]
+use crate::{PendingUpdates, PubliclyVerifiableParams};
+
+/// The private key share of a validator, decrypted from an aggregate once and reused for
+/// every decryption request, instead of being decrypted again for each of them.
+///
+/// The cache is bound to the hash of the aggregate it decrypts from, and serves a single
+/// validator: it must always be given the same decryption key. The decrypted share is
+/// zeroized when it is dropped or invalidated.
+pub struct DecryptedShareCache<E: Pairing> {
+    aggregate_hash: [u8; 32],
+    share_index: usize,
+    encrypted_share: E::G2Affine,
+    #[cfg(feature = "refresh")]
+    domain_point: E::ScalarField,
+    #[cfg(feature = "refresh")]
+    threshold: usize,
+    #[cfg(feature = "refresh")]
+    shares_num: usize,
+    #[cfg(feature = "refresh")]
+    pending: Option<(PendingUpdates<E>, PubliclyVerifiableParams<E>)>,
+    private_key_share: Option<PrivateKeyShare<E>>,
+}
+
+impl<E: Pairing> DecryptedShareCache<E> {
+    /// A cache of the share `share_index` of `aggregate`. Nothing is decrypted until the
+    /// share is first used
+    pub fn new(
+        aggregate: &AggregatedPvss<E>,
+        share_index: usize,
+    ) -> Result<Self> {
+        let encrypted_share = aggregate
+            .shares
+            .get(share_index)
+            .ok_or(Error::InvalidShareIndex(share_index as u32))?
+            .into_affine();
+        Ok(Self {
+            aggregate_hash: aggregate_hash(aggregate)?,
+            share_index,
+            encrypted_share,
+            #[cfg(feature = "refresh")]
+            domain_point: aggregate.domain.element(share_index),
+            #[cfg(feature = "refresh")]
+            threshold: aggregate.coeffs.len(),
+            #[cfg(feature = "refresh")]
+            shares_num: aggregate.shares.len(),
+            #[cfg(feature = "refresh")]
+            pending: None,
+            private_key_share: None,
+        })
+    }
+
+    /// The hash of the aggregate the share is decrypted from, see [`aggregate_hash`]
+    pub fn aggregate_hash(&self) -> &[u8; 32] {
+        &self.aggregate_hash
+    }
+
+    pub fn share_index(&self) -> usize {
+        self.share_index
+    }
+
+    /// Whether the cache decrypts from `aggregate`, e.g. to drop it once a new aggregate
+    /// is published
+    pub fn is_bound_to(&self, aggregate: &AggregatedPvss<E>) -> Result<bool> {
+        Ok(aggregate_hash(aggregate)? == self.aggregate_hash)
+    }
+
+    /// Whether the share is decrypted, i.e. the next request won't decrypt it
+    pub fn is_cached(&self) -> bool {
+        self.private_key_share.is_some()
+    }
+
+    /// Drop, and zeroize, the decrypted share
+    pub fn invalidate(&mut self) {
+        self.private_key_share = None;
+    }
+
+    /// Apply `pending`, dealt with `pvss_params`, to the share from now on, see
+    /// [`crate::PubliclyVerifiableSS::decrypt_private_key_share_with_updates`]. The updates
+    /// replace the ones set before, and are verified when the share is next decrypted.
+    #[cfg(feature = "refresh")]
+    pub fn set_pending_updates(
+        &mut self,
+        pending: PendingUpdates<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> Result<()> {
+        if pending.share_index() as usize != self.share_index {
+            return Err(Error::InvalidShareIndex(pending.share_index()));
+        }
+        self.pending = Some((pending, pvss_params.clone()));
+        self.invalidate();
+        Ok(())
+    }
+
+    /// The private key share, decrypted with `validator_decryption_key` if it isn't cached
+    pub fn private_key_share(
+        &mut self,
+        validator_decryption_key: &E::ScalarField,
+    ) -> Result<&PrivateKeyShare<E>> {
+        if self.private_key_share.is_none() {
+            let private_key_share = self.decrypt(validator_decryption_key)?;
+            self.private_key_share = Some(private_key_share);
+        }
+        Ok(self
+            .private_key_share
+            .as_ref()
+            .expect("The share was just decrypted"))
+    }
+
+    /// Same as [`crate::PubliclyVerifiableSS::make_decryption_share_simple`], with the
+    /// cached private key share
+    pub fn make_decryption_share_simple(
+        &mut self,
+        ciphertext: &CiphertextHeader<E>,
+        aad: &[u8],
+        validator_decryption_key: &E::ScalarField,
+        g_inv: &E::G1Prepared,
+    ) -> Result<DecryptionShareSimple<E>> {
+        let private_key_share =
+            self.private_key_share(validator_decryption_key)?;
+        DecryptionShareSimple::create(
+            validator_decryption_key,
+            private_key_share,
+            ciphertext,
+            aad,
+            g_inv,
+        )
+        .map_err(|e| e.into())
+    }
+
+    fn decrypt(
+        &self,
+        validator_decryption_key: &E::ScalarField,
+    ) -> Result<PrivateKeyShare<E>> {
+        let private_key_share =
+            decrypt_share::<E>(&self.encrypted_share, validator_decryption_key);
+        #[cfg(feature = "refresh")]
+        if let Some((pending, pvss_params)) = &self.pending {
+            return pending.apply(
+                &private_key_share,
+                &self.domain_point,
+                validator_decryption_key,
+                self.threshold,
+                self.shares_num,
+                pvss_params,
+            );
+        }
+        Ok(private_key_share)
+    }
+}
+
+#[cfg(test)]
+mod test_share_cache {
+    use ferveo_tdec::SecretBox;
+
+    use super::*;
+    use crate::{pvss::aggregate, test_common::*};
+
+    #[test]
+    fn test_cached_decryption_shares() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, validator_keypairs) = setup_dealt_dkg();
+        let transcripts = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = aggregate(&transcripts).unwrap();
+        let ciphertext = ferveo_tdec::encrypt::<E>(
+            SecretBox::new(MSG.to_vec()),
+            AAD,
+            &dkg.public_key(),
+            rng,
+        )
+        .unwrap();
+        let header = ciphertext.header().unwrap();
+        let g_inv = dkg.pvss_params.g_inv();
+        let decryption_key = &validator_keypairs[1].decryption_key;
+
+        let mut cache = DecryptedShareCache::new(&aggregate, 1).unwrap();
+        assert!(cache.is_bound_to(&aggregate).unwrap());
+        assert!(!cache.is_cached());
+        let expected = aggregate
+            .make_decryption_share_simple(
+                &header,
+                AAD,
+                decryption_key,
+                1,
+                &g_inv,
+            )
+            .unwrap();
+        for _ in 0..2 {
+            let share = cache
+                .make_decryption_share_simple(
+                    &header,
+                    AAD,
+                    decryption_key,
+                    &g_inv,
+                )
+                .unwrap();
+            assert_eq!(share, expected);
+            assert!(cache.is_cached());
+        }
+        cache.invalidate();
+        assert!(!cache.is_cached());
+        assert_eq!(
+            cache.private_key_share(decryption_key).unwrap(),
+            &aggregate
+                .decrypt_private_key_share(decryption_key, 1)
+                .unwrap()
+        );
+
+        // Bound to its aggregate
+        let other = crate::pvss::aggregate(&transcripts[1..]).unwrap();
+        assert!(!cache.is_bound_to(&other).unwrap());
+        assert!(matches!(
+            DecryptedShareCache::new(&aggregate, SHARES_NUM as usize),
+            Err(Error::InvalidShareIndex(_))
+        ));
+    }
+
+    #[cfg(feature = "refresh")]
+    #[test]
+    fn test_cache_invalidated_by_updates() {
+        use ark_poly::EvaluationDomain;
+
+        use crate::{PendingUpdates, ShareUpdateTranscript};
+
+        let rng = &mut ark_std::test_rng();
+        let (dkg, validator_keypairs) = setup_dealt_dkg();
+        let transcripts = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = aggregate(&transcripts).unwrap();
+        let decryption_key = &validator_keypairs[2].decryption_key;
+        let mut cache = DecryptedShareCache::new(&aggregate, 2).unwrap();
+        cache.private_key_share(decryption_key).unwrap();
+
+        let domain_points = dkg.domain.elements().collect::<Vec<_>>();
+        let encryption_keys = dkg
+            .share_owners()
+            .iter()
+            .map(|validator| validator.public_key.encryption_key)
+            .collect::<Vec<_>>();
+        let mut pending = PendingUpdates::<E>::new(2);
+        pending.push(
+            ShareUpdateTranscript::new_refresh(
+                &domain_points,
+                &encryption_keys,
+                SECURITY_THRESHOLD as usize,
+                &dkg.pvss_params,
+                rng,
+            )
+            .unwrap(),
+        );
+        assert!(matches!(
+            cache.set_pending_updates(PendingUpdates::new(1), &dkg.pvss_params),
+            Err(Error::InvalidShareIndex(1))
+        ));
+        cache
+            .set_pending_updates(pending.clone(), &dkg.pvss_params)
+            .unwrap();
+        assert!(!cache.is_cached());
+        assert_eq!(
+            cache.private_key_share(decryption_key).unwrap(),
+            &aggregate
+                .decrypt_private_key_share_with_updates(
+                    decryption_key,
+                    &pending,
+                    &dkg.pvss_params
+                )
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use std::ops::Mul;
+
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+#[cfg(feature = "dkg")]
+use ark_std::UniformRand;
+#[cfg(feature = "dkg")]
+use ferveo_common::Keypair;
+use ferveo_common::PublicKey;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+#[cfg(feature = "dkg")]
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+#[cfg(feature = "dkg")]
+use zeroize::Zeroizing;
+
+#[cfg(feature = "dkg")]
+use crate::Error;
+use crate::{
+    evaluate_commitment, AggregatedPvss, BlindedShare, DomainDescriptor,
+    PubliclyVerifiableParams, Result,
+};
+
+/// Proof to third parties, e.g. a coordinator contract, that a validator holds the share at
+/// their index in a finalized aggregate, without revealing the share.
+///
+/// The blinded share `Y_i = [f(ω_i) dk_i] h` is checked against the commitment evaluation
+/// `A_i = [f(ω_i)] G` of the aggregate, as in [`BlindedShare::verify`], and the proof is a
+/// Schnorr proof of knowledge of `dk_i`, the key that unblinds it, bound to the ritual, the
+/// share and a `context` chosen by the verifier, e.g. a nonce, so that it can't be replayed.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareOwnershipProof<E: Pairing> {
+    /// The ritual of the aggregate
+    pub tau: u32,
+    pub share: BlindedShare<E>,
+    /// `R = [k] h`, for the `h` of the PVSS params
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    commitment: E::G2Affine,
+    /// `z = k + c dk_i`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    response: E::ScalarField,
+}
+
+impl<E: Pairing> ShareOwnershipProof<E> {
+    /// Check that the share is the share of the holder of `public_key` in the aggregate
+    /// commitment `coeffs` over `domain`, and that the prover knows the key that unblinds
+    /// it, for `context`
+    pub fn verify(
+        &self,
+        coeffs: &[E::G1Affine],
+        domain: &DomainDescriptor<E>,
+        public_key: &PublicKey<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+        context: &[u8],
+    ) -> bool {
+        if !self.share.verify(coeffs, domain, public_key, pvss_params) {
+            return false;
+        }
+        let a_i = evaluate_commitment::<E>(
+            coeffs,
+            &domain.element(self.share.share_index as usize),
+        );
+        challenge(
+            self.tau,
+            &self.share,
+            &a_i.into_affine(),
+            public_key,
+            &self.commitment,
+            context,
+        )
+        .map_or(false, |c| {
+            pvss_params.h.mul(self.response)
+                == self.commitment + public_key.encryption_key.mul(c)
+        })
+    }
+}
+
+fn challenge<E: Pairing>(
+    tau: u32,
+    share: &BlindedShare<E>,
+    a_i: &E::G1Affine,
+    public_key: &PublicKey<E>,
+    commitment: &E::G2Affine,
+    context: &[u8],
+) -> Result<E::ScalarField> {
+    let mut points = Vec::new();
+    a_i.serialize_compressed(&mut points)?;
+    for point in [&share.blinded_share, &public_key.encryption_key, commitment]
+    {
+        point.serialize_compressed(&mut points)?;
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(V1::SHARE_OWNERSHIP_DOMAIN);
+    hasher.update(tau.to_le_bytes());
+    hasher.update(share.share_index.to_le_bytes());
+    hasher.update(points);
+    hasher.update((context.len() as u64).to_le_bytes());
+    hasher.update(context);
+    Ok(E::ScalarField::from_be_bytes_mod_order(&hasher.finalize()))
+}
+
+impl<E: Pairing> AggregatedPvss<E> {
+    /// Prove that the holder of `keypair` holds the share `share_index` of the aggregate,
+    /// for the verifier of `context`. See [`ShareOwnershipProof`]
+    #[cfg(feature = "dkg")]
+    pub fn prove_share_ownership<R: RngCore>(
+        &self,
+        share_index: u32,
+        keypair: &Keypair<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+        context: &[u8],
+        rng: &mut R,
+    ) -> Result<ShareOwnershipProof<E>> {
+        let share = self.blinded_share_for(share_index)?;
+        let public_key = pvss_params.public_key(keypair);
+        if !share.verify(&self.coeffs, &self.domain, &public_key, pvss_params) {
+            return Err(Error::NotShareHolder(share_index));
+        }
+        let a_i = self
+            .commitment_at(&self.domain.element(share_index as usize))
+            .into_affine();
+        let k = Zeroizing::new(E::ScalarField::rand(rng));
+        let commitment = pvss_params.h.mul(*k).into_affine();
+        let c = challenge(
+            self.tau,
+            &share,
+            &a_i,
+            &public_key,
+            &commitment,
+            context,
+        )?;
+        Ok(ShareOwnershipProof {
+            tau: self.tau,
+            share,
+            commitment,
+            response: *k + c * keypair.decryption_key,
+        })
+    }
+
+    /// Check that `proof` proves that the holder of `public_key` holds a share of this
+    /// aggregate, for `context`
+    pub fn verify_share_ownership(
+        &self,
+        proof: &ShareOwnershipProof<E>,
+        public_key: &PublicKey<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+        context: &[u8],
+    ) -> bool {
+        proof.tau == self.tau
+            && self
+                .shares
+                .get(proof.share.share_index as usize)
+                .map_or(false, |share| {
+                    share.into_affine() == proof.share.blinded_share
+                })
+            && proof.verify(
+                &self.coeffs,
+                &self.domain,
+                public_key,
+                pvss_params,
+                context,
+            )
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_share_ownership {
+    use super::*;
+    use crate::{test_common::*, Error};
+
+    #[test]
+    fn validators_prove_share_ownership() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, keypairs) = setup_dealt_dkg();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = crate::pvss::aggregate(&pvss_list).unwrap();
+        let params = &dkg.pvss_params;
+        let context = b"coordinator nonce";
+
+        for validator in dkg.validators.values() {
+            let keypair = keypairs
+                .iter()
+                .find(|keypair| keypair.public_key() == validator.public_key)
+                .unwrap();
+            let proof = aggregate
+                .prove_share_ownership(
+                    validator.share_index,
+                    keypair,
+                    params,
+                    context,
+                    rng,
+                )
+                .unwrap();
+            let bytes = bincode::serialize(&proof).unwrap();
+            let proof: ShareOwnershipProof<E> =
+                bincode::deserialize(&bytes).unwrap();
+            assert!(aggregate.verify_share_ownership(
+                &proof,
+                &validator.public_key,
+                params,
+                context
+            ));
+            assert!(proof.verify(
+                &aggregate.coeffs,
+                &aggregate.domain,
+                &validator.public_key,
+                params,
+                context
+            ));
+
+            // Proofs can't be replayed to another verifier, or claimed by another validator
+            assert!(!aggregate.verify_share_ownership(
+                &proof,
+                &validator.public_key,
+                params,
+                b"another nonce"
+            ));
+            let other = keypairs
+                .iter()
+                .find(|other| other.public_key() != validator.public_key)
+                .unwrap();
+            assert!(!aggregate.verify_share_ownership(
+                &proof,
+                &other.public_key(),
+                params,
+                context
+            ));
+            let mut other_ritual = proof.clone();
+            other_ritual.tau += 1;
+            assert!(!aggregate.verify_share_ownership(
+                &other_ritual,
+                &validator.public_key,
+                params,
+                context
+            ));
+        }
+
+        // Validators can't prove ownership of the shares of others
+        let owner = &dkg.share_owners()[0].public_key;
+        let impostor = keypairs
+            .iter()
+            .find(|keypair| &keypair.public_key() != owner)
+            .unwrap();
+        assert!(matches!(
+            aggregate.prove_share_ownership(0, impostor, params, context, rng),
+            Err(Error::NotShareHolder(0))
+        ));
+        assert!(matches!(
+            aggregate.prove_share_ownership(
+                SHARES_NUM, impostor, params, context, rng
+            ),
+            Err(Error::InvalidShareIndex(SHARES_NUM))
+        ));
+    }
+}
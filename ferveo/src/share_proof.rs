@@ -0,0 +1,274 @@
+use std::ops::Mul;
+
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+#[cfg(feature = "dkg")]
+use ark_std::UniformRand;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+#[cfg(feature = "dkg")]
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    evaluate_commitment, Error, PubliclyVerifiableDkg,
+    PubliclyVerifiableParams, PubliclyVerifiableSS, Result, Validator,
+};
+
+/// Extension type of the [`ShareProof`]s of a transcript, in share index order
+pub const SHARE_PROOFS_EXTENSION_TYPE: u16 = 2;
+
+/// Non-interactive proof that the encrypted share `Y_i = [f(ω_i)] ek_i` encrypts the
+/// evaluation committed to by `A_i = [f(ω_i)] G`, i.e. a Chaum-Pedersen proof that
+/// `log_G(A_i) == log_ek_i(Y_i)`.
+///
+/// Dealers attach one per share with [`crate::DealingContext::with_share_proofs`]. Anyone can
+/// then tell whether a share is faulty without pairings, and without its holder revealing
+/// their decryption key, so that complaints about a dealer are resolved non-interactively.
+#[serde_as]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareProof<E: Pairing> {
+    /// `c = H(tau, i, G, ek_i, A_i, Y_i, [k] G, [k] ek_i)`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub challenge: E::ScalarField,
+    /// `z = k + c f(ω_i)`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub response: E::ScalarField,
+}
+
+impl<E: Pairing> ShareProof<E> {
+    /// Prove that `y_i` encrypts `eval` to `ek_i`, and that `a_i` commits to it with `g`, in
+    /// the ritual `tau`
+    #[cfg(feature = "dkg")]
+    pub(crate) fn prove<R: RngCore>(
+        tau: u32,
+        share_index: u32,
+        eval: &E::ScalarField,
+        g: &E::G1Affine,
+        ek_i: &E::G2Affine,
+        a_i: &E::G1Affine,
+        y_i: &E::G2Affine,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let k = E::ScalarField::rand(rng);
+        let challenge = challenge::<E>(
+            tau,
+            share_index,
+            g,
+            ek_i,
+            a_i,
+            y_i,
+            &g.mul(k).into_affine(),
+            &ek_i.mul(k).into_affine(),
+        )?;
+        Ok(Self {
+            challenge,
+            response: k + challenge * eval,
+        })
+    }
+
+    /// Check that `y_i`, the share `share_index` encrypted to `ek_i` in the ritual `tau`,
+    /// encrypts the evaluation committed to by `a_i`, i.e. that the challenge matches
+    /// `[z] G - [c] A_i` and `[z] ek_i - [c] Y_i`
+    pub fn verify(
+        &self,
+        tau: u32,
+        share_index: u32,
+        g: &E::G1Affine,
+        ek_i: &E::G2Affine,
+        a_i: &E::G1Affine,
+        y_i: &E::G2Affine,
+    ) -> bool {
+        let t_g = g.mul(self.response) - a_i.mul(self.challenge);
+        let t_ek = ek_i.mul(self.response) - y_i.mul(self.challenge);
+        challenge::<E>(
+            tau,
+            share_index,
+            g,
+            ek_i,
+            a_i,
+            y_i,
+            &t_g.into_affine(),
+            &t_ek.into_affine(),
+        )
+        .map_or(false, |challenge| challenge == self.challenge)
+    }
+}
+
+fn challenge<E: Pairing>(
+    tau: u32,
+    share_index: u32,
+    g: &E::G1Affine,
+    ek_i: &E::G2Affine,
+    a_i: &E::G1Affine,
+    y_i: &E::G2Affine,
+    t_g: &E::G1Affine,
+    t_ek: &E::G2Affine,
+) -> Result<E::ScalarField> {
+    let mut points = Vec::new();
+    for point in [g, a_i, t_g] {
+        point.serialize_compressed(&mut points)?;
+    }
+    for point in [ek_i, y_i, t_ek] {
+        point.serialize_compressed(&mut points)?;
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(V1::SHARE_PROOF_DOMAIN);
+    // Proofs can't be lifted to a transcript of another ritual
+    hasher.update(tau.to_le_bytes());
+    hasher.update(share_index.to_le_bytes());
+    hasher.update(points);
+    Ok(E::ScalarField::from_be_bytes_mod_order(&hasher.finalize()))
+}
+
+impl<E: Pairing> PubliclyVerifiableSS<E> {
+    /// The proofs of the shares of this transcript, if it was dealt with them
+    pub fn share_proofs(&self) -> Option<Vec<ShareProof<E>>> {
+        self.extensions
+            .get(SHARE_PROOFS_EXTENSION_TYPE)
+            .and_then(|data| bincode::deserialize(data).ok())
+    }
+
+    /// Whether the share `share_index` is encrypted to `validator`, its holder, and has a
+    /// valid [`ShareProof`]. Unlike [`PubliclyVerifiableSS::verify_my_share`], this needs no
+    /// pairing, nor to be the holder of the share.
+    pub fn verify_share_proof(
+        &self,
+        share_index: u32,
+        validator: &Validator<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> bool {
+        match self.share_proofs() {
+            Some(proofs) => self.verify_share_proof_in(
+                &proofs,
+                share_index,
+                validator,
+                pvss_params,
+            ),
+            None => false,
+        }
+    }
+
+    /// The share indices of the shares of this transcript whose proof is missing or
+    /// invalid, i.e. the shares the dealer is to blame for. Empty if every share is proven.
+    pub fn invalid_share_proofs(
+        &self,
+        dkg: &PubliclyVerifiableDkg<E>,
+    ) -> Result<Vec<u32>> {
+        let proofs = self.share_proofs().ok_or(Error::MissingShareProofs)?;
+        Ok(dkg
+            .share_owners()
+            .into_iter()
+            .enumerate()
+            .map(|(share_index, validator)| (share_index as u32, validator))
+            .filter(|(share_index, validator)| {
+                !self.verify_share_proof_in(
+                    &proofs,
+                    *share_index,
+                    validator,
+                    &dkg.pvss_params,
+                )
+            })
+            .map(|(share_index, _)| share_index)
+            .collect())
+    }
+
+    fn verify_share_proof_in(
+        &self,
+        proofs: &[ShareProof<E>],
+        share_index: u32,
+        validator: &Validator<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> bool {
+        let (proof, y_i) = match (
+            proofs.get(share_index as usize),
+            self.shares.get(share_index as usize),
+        ) {
+            (Some(proof), Some(y_i)) if validator.holds_share(share_index) => {
+                (proof, y_i.into_affine())
+            }
+            _ => return false,
+        };
+        let a_i = evaluate_commitment::<E>(
+            &self.coeffs,
+            &self.domain.element(share_index as usize),
+        )
+        .into_affine();
+        proof.verify(
+            self.tau,
+            share_index,
+            &pvss_params.g.into_affine(),
+            &validator.public_key.encryption_key,
+            &a_i,
+            &y_i,
+        )
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_share_proof {
+    use ark_ec::AffineRepr;
+    use ark_std::UniformRand;
+
+    use super::*;
+    use crate::{test_common::*, DealingContext, Extensions, ShareEncryptions};
+
+    #[test]
+    fn test_share_proofs() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, _) = setup_dkg(0);
+        let s = ScalarField::rand(rng);
+        let pvss = PubliclyVerifiableSS::<E>::new_standalone(
+            &s,
+            &DealingContext::from_dkg(&dkg).with_share_proofs(),
+            rng,
+        )
+        .unwrap();
+        assert!(pvss.verify_optimistic(&dkg.me.address));
+        assert!(pvss.verify_full(&dkg));
+        assert_eq!(pvss.share_proofs().unwrap().len(), SHARES_NUM as usize);
+        assert!(pvss.invalid_share_proofs(&dkg).unwrap().is_empty());
+        for validator in dkg.validators.values() {
+            assert!(pvss.verify_share_proof(
+                validator.share_index,
+                validator,
+                &dkg.pvss_params
+            ));
+            assert!(!pvss.verify_share_proof(
+                (validator.share_index + 1) % SHARES_NUM,
+                validator,
+                &dkg.pvss_params
+            ));
+        }
+
+        // The dealer is blamed for a share that doesn't match its commitment
+        let mut bad_pvss = pvss.clone();
+        bad_pvss.shares[2] = ShareEncryptions::new(G2::generator()).unwrap();
+        assert_eq!(bad_pvss.invalid_share_proofs(&dkg).unwrap(), [2]);
+        let mut bad_pvss = pvss;
+        let mut proofs = bad_pvss.share_proofs().unwrap();
+        proofs[1].response += ScalarField::from(1u64);
+        bad_pvss.extensions = Extensions::default();
+        bad_pvss
+            .extensions
+            .insert(
+                SHARE_PROOFS_EXTENSION_TYPE,
+                bincode::serialize(&proofs).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(bad_pvss.invalid_share_proofs(&dkg).unwrap(), [1]);
+        // Replacing the proofs breaks the proof of knowledge
+        assert!(!bad_pvss.verify_optimistic(&dkg.me.address));
+
+        // Transcripts are dealt without proofs by default
+        let pvss = PubliclyVerifiableSS::<E>::new(&s, &dkg, rng).unwrap();
+        assert!(pvss.share_proofs().is_none());
+        assert!(!pvss.verify_share_proof(0, &dkg.me, &dkg.pvss_params));
+        assert!(matches!(
+            pvss.invalid_share_proofs(&dkg),
+            Err(Error::MissingShareProofs)
+        ));
+    }
+}
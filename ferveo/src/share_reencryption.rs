@@ -0,0 +1,383 @@
+use std::ops::Mul;
+
+use ark_ec::{pairing::Pairing, CurveGroup};
+#[cfg(feature = "dkg")]
+use ark_ff::Field;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+#[cfg(feature = "dkg")]
+use ark_std::UniformRand;
+#[cfg(feature = "dkg")]
+use ferveo_common::Keypair;
+use ferveo_common::PublicKey;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+#[cfg(feature = "dkg")]
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+#[cfg(feature = "dkg")]
+use zeroize::Zeroizing;
+
+use crate::{
+    validator_set_hash, AggregatedPvss, Error, EthereumAddress,
+    PubliclyVerifiableParams, Result, ShareEncryptions, Validator,
+};
+
+/// The shares of a validator in an aggregate, re-encrypted from its current encryption key
+/// to a rotated one, `Y'_i = [dk' / dk] Y_i`.
+///
+/// It carries a Chaum-Pedersen proof that `log_ek(ek') == log_Y_i(Y'_i)` for every share of
+/// the validator, so anyone can patch the aggregate with
+/// [`AggregatedPvss::apply_reencryption`] without learning either decryption key. The same
+/// proof shows knowledge of `dk` and `dk'`: without the former, anyone could rotate the key
+/// of a validator to `[r] ek` for some `r` of their choosing, and without the latter, to a
+/// key nobody can decrypt with.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareReencryption<E: Pairing> {
+    /// The validator whose shares are re-encrypted
+    pub address: EthereumAddress,
+    /// The rotated public key, `ek' = [dk'] h` for the `h` of the PVSS params
+    #[serde(bound(serialize = "", deserialize = ""))]
+    pub public_key: PublicKey<E>,
+    /// The re-encrypted shares, in the order of the share indices of the validator
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub shares: Vec<E::G2Affine>,
+    /// `c = H(address, ek, ek', Y_i.., Y'_i.., [k] ek, [k_dk] H, [k_dk'] H, [k] Y_i..)`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub challenge: E::ScalarField,
+    /// `z = k + c dk' / dk`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub response: E::ScalarField,
+    /// `z_dk = k_dk + c dk`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub key_response: E::ScalarField,
+    /// `z_dk' = k_dk' + c dk'`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub new_key_response: E::ScalarField,
+}
+
+impl<E: Pairing> ShareReencryption<E> {
+    /// Check that the shares are the shares `old_shares` of `validator` re-encrypted from
+    /// its current key to [`ShareReencryption::public_key`], both built on the `h` of
+    /// `pvss_params`
+    pub fn verify(
+        &self,
+        validator: &Validator<E>,
+        old_shares: &[E::G2Affine],
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> bool {
+        if self.address != validator.address
+            || self.shares.len() != old_shares.len()
+            || self.shares.len() != validator.weight as usize
+        {
+            return false;
+        }
+        let h = pvss_params.h.into_affine();
+        let ek = validator.public_key.encryption_key;
+        let new_ek = self.public_key.encryption_key;
+        let t_keys = E::G2::normalize_batch(&[
+            ek.mul(self.response) - new_ek.mul(self.challenge),
+            h.mul(self.key_response) - ek.mul(self.challenge),
+            h.mul(self.new_key_response) - new_ek.mul(self.challenge),
+        ]);
+        let t_shares = old_shares
+            .iter()
+            .zip(&self.shares)
+            .map(|(y_i, new_y_i)| {
+                y_i.mul(self.response) - new_y_i.mul(self.challenge)
+            })
+            .collect::<Vec<_>>();
+        challenge::<E>(
+            &self.address,
+            &ek,
+            &new_ek,
+            old_shares,
+            &self.shares,
+            &t_keys,
+            &E::G2::normalize_batch(&t_shares),
+        )
+        .map_or(false, |challenge| challenge == self.challenge)
+    }
+}
+
+fn challenge<E: Pairing>(
+    address: &EthereumAddress,
+    ek: &E::G2Affine,
+    new_ek: &E::G2Affine,
+    old_shares: &[E::G2Affine],
+    new_shares: &[E::G2Affine],
+    t_keys: &[E::G2Affine],
+    t_shares: &[E::G2Affine],
+) -> Result<E::ScalarField> {
+    let mut points = Vec::new();
+    for point in [ek, new_ek]
+        .into_iter()
+        .chain(old_shares)
+        .chain(new_shares)
+        .chain(t_keys)
+        .chain(t_shares)
+    {
+        point.serialize_compressed(&mut points)?;
+    }
+    let address = address.to_string();
+    let mut hasher = Sha256::new();
+    hasher.update(V1::SHARE_REENCRYPTION_DOMAIN);
+    hasher.update((address.len() as u32).to_le_bytes());
+    hasher.update(address.as_bytes());
+    hasher.update((old_shares.len() as u32).to_le_bytes());
+    hasher.update(points);
+    Ok(E::ScalarField::from_be_bytes_mod_order(&hasher.finalize()))
+}
+
+impl<E: Pairing> AggregatedPvss<E> {
+    /// The shares of `validator` in this aggregate
+    fn shares_of(&self, validator: &Validator<E>) -> Result<Vec<E::G2Affine>> {
+        validator
+            .share_indices()?
+            .map(|share_index| {
+                self.shares
+                    .get(share_index as usize)
+                    .map(|y_i| y_i.into_affine())
+                    .ok_or(Error::InvalidShareIndex(share_index))
+            })
+            .collect()
+    }
+
+    /// Re-encrypt the shares of `validator`, the holder of `keypair`, to `new_keypair`, e.g.
+    /// after rotating its encryption keypair. The keys are built on the `h` of
+    /// `pvss_params`, see [`PubliclyVerifiableParams::public_key`]
+    #[cfg(feature = "dkg")]
+    pub fn reencrypt_shares<R: RngCore>(
+        &self,
+        validator: &Validator<E>,
+        keypair: &Keypair<E>,
+        new_keypair: &Keypair<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+        rng: &mut R,
+    ) -> Result<ShareReencryption<E>> {
+        if pvss_params.public_key(keypair) != validator.public_key {
+            return Err(Error::ValidatorPublicKeyMismatch);
+        }
+        let old_shares = self.shares_of(validator)?;
+        let factor = Zeroizing::new(
+            new_keypair.decryption_key
+                * keypair
+                    .decryption_key
+                    .inverse()
+                    .ok_or(Error::ValidatorPublicKeyMismatch)?,
+        );
+        let shares = E::G2::normalize_batch(
+            &old_shares
+                .iter()
+                .map(|y_i| y_i.mul(*factor))
+                .collect::<Vec<_>>(),
+        );
+
+        let k = Zeroizing::new(E::ScalarField::rand(rng));
+        let k_key = Zeroizing::new(E::ScalarField::rand(rng));
+        let k_new_key = Zeroizing::new(E::ScalarField::rand(rng));
+        let h = pvss_params.h.into_affine();
+        let ek = validator.public_key.encryption_key;
+        let public_key = pvss_params.public_key(new_keypair);
+        let t_shares =
+            old_shares.iter().map(|y_i| y_i.mul(*k)).collect::<Vec<_>>();
+        let challenge = challenge::<E>(
+            &validator.address,
+            &ek,
+            &public_key.encryption_key,
+            &old_shares,
+            &shares,
+            &E::G2::normalize_batch(&[
+                ek.mul(*k),
+                h.mul(*k_key),
+                h.mul(*k_new_key),
+            ]),
+            &E::G2::normalize_batch(&t_shares),
+        )?;
+        Ok(ShareReencryption {
+            address: validator.address.clone(),
+            public_key,
+            shares,
+            challenge,
+            response: *k + challenge * *factor,
+            key_response: *k_key + challenge * keypair.decryption_key,
+            new_key_response: *k_new_key
+                + challenge * new_keypair.decryption_key,
+        })
+    }
+
+    /// Check that `reencryption` re-encrypts the shares of `validator` in this aggregate,
+    /// dealt with `pvss_params`
+    pub fn verify_reencryption(
+        &self,
+        validator: &Validator<E>,
+        reencryption: &ShareReencryption<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> bool {
+        self.shares_of(validator).map_or(false, |old_shares| {
+            reencryption.verify(validator, &old_shares, pvss_params)
+        })
+    }
+
+    /// Patch this aggregate with the re-encrypted shares of a validator of `validators`, the
+    /// validator set it was aggregated for with `pvss_params`.
+    ///
+    /// The patched aggregate is bound to the validator set where the validator has the
+    /// rotated key, so the DKG that verifies or decrypts with it must be set up with that key.
+    pub fn apply_reencryption(
+        &self,
+        validators: &[Validator<E>],
+        reencryption: &ShareReencryption<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> Result<Self> {
+        if pvss_params.bind(validator_set_hash(validators))
+            != self.validator_set_hash
+        {
+            return Err(Error::ValidatorSetMismatch);
+        }
+        let validator = validators
+            .iter()
+            .find(|validator| validator.address == reencryption.address)
+            .ok_or_else(|| {
+                Error::UnknownDealer(reencryption.address.clone())
+            })?;
+        if !self.verify_reencryption(validator, reencryption, pvss_params) {
+            return Err(Error::InvalidShareReencryption(
+                reencryption.address.clone(),
+            ));
+        }
+        let mut patched = self.clone();
+        for (share_index, y_i) in
+            validator.share_indices()?.zip(&reencryption.shares)
+        {
+            patched.shares[share_index as usize] = ShareEncryptions::new(*y_i)?;
+        }
+        let rotated = Validator {
+            public_key: reencryption.public_key,
+            ..validator.clone()
+        };
+        patched.validator_set_hash = pvss_params.bind(validator_set_hash(
+            validators.iter().map(|other| {
+                if other.address == rotated.address {
+                    &rotated
+                } else {
+                    other
+                }
+            }),
+        ));
+        Ok(patched)
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_share_reencryption {
+    use ark_ec::AffineRepr;
+    use ferveo_common::Keypair;
+
+    use super::*;
+    use crate::{test_common::*, DkgParams, PubliclyVerifiableDkg};
+
+    #[test]
+    fn test_reencrypt_shares_to_rotated_key() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, keypairs) = setup_dealt_dkg();
+        let pvss_list = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = crate::pvss::aggregate(&pvss_list).unwrap();
+        let validators = dkg.validators.values().cloned().collect::<Vec<_>>();
+        let validator = &validators[1];
+        let keypair = &keypairs[validator.share_index as usize];
+        let new_keypair = Keypair::<E>::new(rng);
+
+        let params = &dkg.pvss_params;
+
+        let reencryption = aggregate
+            .reencrypt_shares(validator, keypair, &new_keypair, params, rng)
+            .unwrap();
+        assert!(aggregate.verify_reencryption(
+            validator,
+            &reencryption,
+            params
+        ));
+        assert!(!aggregate.verify_reencryption(
+            &validators[0],
+            &reencryption,
+            params
+        ));
+        let patched = aggregate
+            .apply_reencryption(&validators, &reencryption, params)
+            .unwrap();
+
+        // The rotated key decrypts the share the old key decrypted
+        let share_index = validator.share_index as usize;
+        assert!(
+            patched
+                .decrypt_private_key_share(
+                    &new_keypair.decryption_key,
+                    share_index
+                )
+                .unwrap()
+                == aggregate
+                    .decrypt_private_key_share(
+                        &keypair.decryption_key,
+                        share_index
+                    )
+                    .unwrap()
+        );
+
+        // And the patched aggregate verifies in a DKG where the validator has the rotated key
+        let rotated = validators
+            .iter()
+            .map(|other| Validator {
+                public_key: if other.address == validator.address {
+                    new_keypair.public_key()
+                } else {
+                    other.public_key
+                },
+                ..other.clone()
+            })
+            .collect::<Vec<_>>();
+        let rotated_dkg = PubliclyVerifiableDkg::<E>::new(
+            &rotated,
+            &DkgParams::new(TAU, SECURITY_THRESHOLD, SHARES_NUM).unwrap(),
+            &rotated[0],
+        )
+        .unwrap();
+        assert_eq!(
+            patched.validator_set_hash,
+            rotated_dkg.validator_set_hash()
+        );
+        assert!(patched.verify_full(&rotated_dkg));
+        assert!(!aggregate.verify_full(&rotated_dkg));
+
+        // Tampered re-encryptions are rejected
+        let mut tampered = reencryption.clone();
+        tampered.shares[0] = G2::generator();
+        assert!(matches!(
+            aggregate.apply_reencryption(&validators, &tampered, params),
+            Err(Error::InvalidShareReencryption(address)) if address == validator.address
+        ));
+        let mut tampered = reencryption.clone();
+        tampered.public_key = Keypair::<E>::new(rng).public_key();
+        assert!(!aggregate.verify_reencryption(validator, &tampered, params));
+
+        // Without the current and the rotated decryption keys, the proof doesn't verify
+        let mut tampered = reencryption.clone();
+        tampered.key_response = ScalarField::rand(rng);
+        assert!(!aggregate.verify_reencryption(validator, &tampered, params));
+        let mut tampered = reencryption;
+        tampered.new_key_response = ScalarField::rand(rng);
+        assert!(!aggregate.verify_reencryption(validator, &tampered, params));
+        assert!(matches!(
+            aggregate.reencrypt_shares(
+                validator,
+                &new_keypair,
+                keypair,
+                params,
+                rng
+            ),
+            Err(Error::ValidatorPublicKeyMismatch)
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    merkle_proof, merkle_root, Error, MerkleProof, PubliclyVerifiableSS,
+    Result, ShareEncryptions,
+};
+
+/// Proof that `share` is the encrypted share at `proof.leaf_index` of a transcript, given
+/// the root of its shares, see [`PubliclyVerifiableSS::shares_root`].
+///
+/// A contract that records the root of a transcript, instead of the transcript, checks a
+/// single share with a proof, e.g. the share of a validator it handles a complaint about.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct ShareInclusionProof<E: Pairing> {
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub share: ShareEncryptions<E>,
+    pub proof: MerkleProof,
+}
+
+impl<E: Pairing> ShareInclusionProof<E> {
+    pub fn share_index(&self) -> u32 {
+        self.proof.leaf_index
+    }
+
+    /// Whether the share is in the transcript of `num_shares` shares with root
+    /// `shares_root`
+    pub fn verify(&self, shares_root: &[u8; 32], num_shares: u32) -> bool {
+        share_leaf(&self.share).map_or(false, |leaf| {
+            self.proof.verify(leaf, num_shares as usize, shares_root)
+        })
+    }
+}
+
+impl<E: Pairing, T> PubliclyVerifiableSS<E, T> {
+    /// The Merkle root over the encrypted shares of this transcript, in share index order
+    pub fn shares_root(&self) -> Result<[u8; 32]> {
+        Ok(merkle_root(&self.share_leaves()?))
+    }
+
+    /// Proof that the share `share_index` is in the tree of [`Self::shares_root`]
+    pub fn share_inclusion_proof(
+        &self,
+        share_index: u32,
+    ) -> Result<ShareInclusionProof<E>> {
+        let share = *self
+            .shares
+            .get(share_index as usize)
+            .ok_or(Error::InvalidShareIndex(share_index))?;
+        let proof = merkle_proof(&self.share_leaves()?, share_index as usize)
+            .expect("The share is one of the leaves");
+        Ok(ShareInclusionProof { share, proof })
+    }
+
+    fn share_leaves(&self) -> Result<Vec<[u8; 32]>> {
+        self.shares.iter().map(share_leaf).collect()
+    }
+}
+
+/// The leaf of an encrypted share: the hash of the compressed point
+fn share_leaf<E: Pairing>(share: &ShareEncryptions<E>) -> Result<[u8; 32]> {
+    let mut bytes = Vec::new();
+    share.serialize_compressed(&mut bytes)?;
+    let mut hasher = Sha256::new();
+    hasher.update(V1::SHARE_LEAF_DOMAIN);
+    hasher.update(bytes);
+    Ok(hasher.finalize().into())
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_share_tree {
+    use ark_ec::AffineRepr;
+
+    use super::*;
+    use crate::{pvss::aggregate, test_common::*};
+
+    #[test]
+    fn test_share_inclusion_proofs() {
+        let (dkg, _) = setup_dealt_dkg();
+        let transcripts = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = aggregate(&transcripts).unwrap();
+        let root = aggregate.shares_root().unwrap();
+        assert_ne!(root, transcripts[0].shares_root().unwrap());
+
+        for share_index in 0..SHARES_NUM {
+            let proof = aggregate.share_inclusion_proof(share_index).unwrap();
+            assert_eq!(proof.share_index(), share_index);
+            assert_eq!(proof.share, aggregate.shares[share_index as usize]);
+            assert!(proof.verify(&root, SHARES_NUM));
+
+            // Another share, or another transcript
+            let mut bad_proof = proof.clone();
+            bad_proof.share = ShareEncryptions::new(G2::generator()).unwrap();
+            assert!(!bad_proof.verify(&root, SHARES_NUM));
+            let other_root = transcripts[0].shares_root().unwrap();
+            assert!(!proof.verify(&other_root, SHARES_NUM));
+
+            let bytes = bincode::serialize(&proof).unwrap();
+            let proof: ShareInclusionProof<E> =
+                bincode::deserialize(&bytes).unwrap();
+            assert!(proof.verify(&root, SHARES_NUM));
+        }
+        assert!(matches!(
+            aggregate.share_inclusion_proof(SHARES_NUM),
+            Err(Error::InvalidShareIndex(_))
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+#[cfg(feature = "dkg")]
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "dkg")]
+use crate::SigningKeypair;
+use crate::{EthereumAddress, Message, Result, SigningPublicKey};
+
+/// A DKG message signed by its sender for a ritual, so that a DKG can authenticate the
+/// messages it receives itself, instead of relying on the transport, e.g. a blockchain, to
+/// authenticate them. See [`crate::PubliclyVerifiableDkg::apply_signed_message`].
+///
+/// The signature is a Schnorr signature over the sender, the ritual and the payload,
+/// verified against the signing key of the sender, as for [`crate::SignedTranscript`].
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct SignedMessage<E: Pairing> {
+    pub sender: EthereumAddress,
+    pub ritual_id: u32,
+    pub payload: Message<E>,
+    /// `R = [k] H`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    commitment: E::G2Affine,
+    /// `z = k + c s`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    response: E::ScalarField,
+}
+
+impl<E: Pairing> SignedMessage<E> {
+    #[cfg(feature = "dkg")]
+    pub fn sign<R: RngCore>(
+        payload: Message<E>,
+        sender: EthereumAddress,
+        ritual_id: u32,
+        signing_key: &SigningKeypair<E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let public_key = signing_key.public_key();
+        let (commitment, response) = signing_key.sign(rng, |commitment| {
+            challenge(commitment, &public_key, &sender, ritual_id, &payload)
+        })?;
+        Ok(Self {
+            sender,
+            ritual_id,
+            payload,
+            commitment,
+            response,
+        })
+    }
+
+    /// Check that the message was signed by the holder of `signing_key`
+    pub fn verify(&self, signing_key: &SigningPublicKey<E>) -> Result<bool> {
+        let c = challenge(
+            &self.commitment,
+            signing_key,
+            &self.sender,
+            self.ritual_id,
+            &self.payload,
+        )?;
+        Ok(signing_key.verify(&self.commitment, &self.response, &c))
+    }
+}
+
+fn challenge<E: Pairing>(
+    commitment: &E::G2Affine,
+    signing_key: &SigningPublicKey<E>,
+    sender: &EthereumAddress,
+    ritual_id: u32,
+    payload: &Message<E>,
+) -> Result<E::ScalarField> {
+    let mut points = Vec::new();
+    commitment.serialize_compressed(&mut points)?;
+    signing_key.key.serialize_compressed(&mut points)?;
+    let mut hasher = Sha256::new();
+    hasher.update(V1::MESSAGE_SIGNATURE_DOMAIN);
+    hasher.update(points);
+    // The sender is length-prefixed, since the ritual id and payload follow it
+    let sender = sender.to_string();
+    hasher.update((sender.len() as u32).to_le_bytes());
+    hasher.update(sender.as_bytes());
+    hasher.update(ritual_id.to_le_bytes());
+    hasher.update(bincode::serialize(payload)?);
+    Ok(E::ScalarField::from_be_bytes_mod_order(&hasher.finalize()))
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_signed_message {
+    use super::*;
+    use crate::{test_common::*, Error};
+
+    #[test]
+    fn signed_messages_are_authenticated() {
+        let rng = &mut ark_std::test_rng();
+        let (mut dkg, _) = setup_dkg(0);
+        dkg.security.require_signed_transcripts = true;
+        let (mut dealer_dkg, _) = setup_dkg(1);
+        let dealer = dealer_dkg.me.clone();
+        let deal = dealer_dkg.share(rng).unwrap();
+        let signed = SignedMessage::sign(
+            deal,
+            dealer.address.clone(),
+            TAU,
+            &gen_signing_keypair(1),
+            rng,
+        )
+        .unwrap();
+        let bytes = bincode::serialize(&signed).unwrap();
+        let signed: SignedMessage<E> = bincode::deserialize(&bytes).unwrap();
+        assert!(signed.verify(&dealer.signing_key.unwrap()).unwrap());
+
+        // The envelope can't be attributed to another sender, or moved to another ritual
+        let mut misattributed = signed.clone();
+        misattributed.sender = gen_address(2);
+        assert!(matches!(
+            dkg.apply_signed_message(&misattributed),
+            Err(Error::InvalidMessageSignature(address)) if address == gen_address(2)
+        ));
+        let mut other_ritual = signed.clone();
+        other_ritual.ritual_id = TAU + 1;
+        assert!(matches!(
+            dkg.apply_signed_message(&other_ritual),
+            Err(Error::RitualMismatch(..))
+        ));
+        let mut unknown = signed.clone();
+        unknown.sender = gen_address(SHARES_NUM as usize + 1);
+        assert!(matches!(
+            dkg.apply_signed_message(&unknown),
+            Err(Error::UnknownDealer(_))
+        ));
+        let forged = SignedMessage::sign(
+            signed.payload.clone(),
+            dealer.address.clone(),
+            TAU,
+            &gen_signing_keypair(2),
+            rng,
+        )
+        .unwrap();
+        assert!(matches!(
+            dkg.apply_signed_message(&forged),
+            Err(Error::InvalidMessageSignature(_))
+        ));
+        assert!(dkg.vss.is_empty());
+
+        // An authenticated transcript counts as signed by its dealer
+        dkg.apply_signed_message(&signed).unwrap();
+        assert!(dkg.vss.contains_key(&dealer.address));
+        assert!(matches!(
+            dkg.apply_signed_message(&signed),
+            Err(Error::DuplicateDealer(_))
+        ));
+    }
+}
@@ -0,0 +1,152 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
+#[cfg(feature = "dkg")]
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "dkg")]
+use crate::SigningKeypair;
+use crate::{EthereumAddress, PubliclyVerifiableSS, Result, SigningPublicKey};
+
+/// A transcript signed by its dealer with their [`SigningKeypair`], so that relays of the
+/// transcript can't attribute it to another dealer.
+///
+/// The signature is a Schnorr signature over the canonical serialization of the transcript
+/// and the address of the dealer, verified against the signing key of the dealer.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct SignedTranscript<E: Pairing> {
+    pub transcript: PubliclyVerifiableSS<E>,
+    pub dealer: EthereumAddress,
+    /// `R = [k] H`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    commitment: E::G2Affine,
+    /// `z = k + c s`
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    response: E::ScalarField,
+}
+
+impl<E: Pairing> SignedTranscript<E> {
+    #[cfg(feature = "dkg")]
+    pub fn sign<R: RngCore>(
+        transcript: PubliclyVerifiableSS<E>,
+        dealer: EthereumAddress,
+        signing_key: &SigningKeypair<E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let public_key = signing_key.public_key();
+        let (commitment, response) = signing_key.sign(rng, |commitment| {
+            challenge(commitment, &public_key, &dealer, &transcript)
+        })?;
+        Ok(Self {
+            transcript,
+            dealer,
+            commitment,
+            response,
+        })
+    }
+
+    /// Check that the transcript was signed by the holder of `signing_key`
+    pub fn verify(&self, signing_key: &SigningPublicKey<E>) -> Result<bool> {
+        let c = challenge(
+            &self.commitment,
+            signing_key,
+            &self.dealer,
+            &self.transcript,
+        )?;
+        Ok(signing_key.verify(&self.commitment, &self.response, &c))
+    }
+}
+
+fn challenge<E: Pairing>(
+    commitment: &E::G2Affine,
+    signing_key: &SigningPublicKey<E>,
+    dealer: &EthereumAddress,
+    transcript: &PubliclyVerifiableSS<E>,
+) -> Result<E::ScalarField> {
+    let mut points = Vec::new();
+    commitment.serialize_compressed(&mut points)?;
+    signing_key.key.serialize_compressed(&mut points)?;
+    let mut hasher = Sha256::new();
+    hasher.update(V1::TRANSCRIPT_SIGNATURE_DOMAIN);
+    hasher.update(points);
+    hasher.update(dealer.to_string().as_bytes());
+    hasher.update(bincode::serialize(transcript)?);
+    Ok(E::ScalarField::from_be_bytes_mod_order(&hasher.finalize()))
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_signed_transcript {
+    use ark_std::UniformRand;
+
+    use super::*;
+    use crate::{test_common::*, DkgState, Error, Message};
+
+    #[test]
+    fn signed_transcripts_are_bound_to_dealer() {
+        let rng = &mut ark_std::test_rng();
+        let (mut dkg, _) = setup_dkg(0);
+        let dealer = dkg.me.clone();
+        let signing_key = dealer.signing_key.unwrap();
+        let pvss =
+            PubliclyVerifiableSS::<E>::new(&ScalarField::rand(rng), &dkg, rng)
+                .unwrap();
+        let signed = SignedTranscript::sign(
+            pvss.clone(),
+            dealer.address.clone(),
+            &gen_signing_keypair(0),
+            rng,
+        )
+        .unwrap();
+        assert!(signed.verify(&signing_key).unwrap());
+        let bytes = bincode::serialize(&signed).unwrap();
+        let signed: SignedTranscript<E> = bincode::deserialize(&bytes).unwrap();
+
+        // Relays can't attribute the transcript to another dealer, or swap it
+        let other = dkg.validators[&gen_address(1)].clone();
+        let mut misattributed = signed.clone();
+        misattributed.dealer = other.address.clone();
+        assert!(matches!(
+            dkg.verify_signed_transcript(&misattributed),
+            Err(Error::InvalidTranscriptSignature(address))
+                if address == other.address
+        ));
+        let mut swapped = signed.clone();
+        swapped.transcript.sigma = G2::default();
+        assert!(!swapped.verify(&signing_key).unwrap());
+
+        // The validator keypair doesn't sign transcripts
+        let mut unannounced = dkg.clone();
+        unannounced
+            .validators
+            .get_mut(&dealer.address)
+            .unwrap()
+            .signing_key = None;
+        assert!(matches!(
+            unannounced.verify_signed_transcript(&signed),
+            Err(Error::InvalidTranscriptSignature(_))
+        ));
+
+        // Unsigned transcripts are rejected once signatures are required
+        let message = Message::Deal(pvss);
+        dkg.verify_message(&dealer, &message).unwrap();
+        dkg.security.require_signed_transcripts = true;
+        assert!(matches!(
+            dkg.verify_message(&dealer, &message),
+            Err(Error::UnsignedTranscript)
+        ));
+        assert!(matches!(
+            dkg.apply_message(&dealer, &message),
+            Err(Error::UnsignedTranscript)
+        ));
+        assert!(dkg.vss.is_empty());
+        dkg.apply_signed_transcript(&signed).unwrap();
+        assert!(dkg.vss.contains_key(&dealer.address));
+        assert!(matches!(dkg.state, DkgState::Sharing { .. }));
+    }
+}
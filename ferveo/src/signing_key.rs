@@ -0,0 +1,104 @@
+use std::{fmt::Formatter, ops::Mul};
+
+use ark_ec::{pairing::Pairing, AffineRepr};
+#[cfg(feature = "dkg")]
+use ark_std::UniformRand;
+#[cfg(feature = "dkg")]
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+#[cfg(feature = "dkg")]
+use zeroize::Zeroizing;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[cfg(feature = "dkg")]
+use crate::Result;
+
+/// The key a validator signs its DKG messages with, see [`crate::SignedTranscript`] and
+/// [`crate::SignedMessage`].
+///
+/// It is kept apart from the validator [`ferveo_common::Keypair`], whose decryption key is
+/// only ever used to decrypt the shares dealt to the validator
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct SigningKeypair<E: Pairing> {
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    secret: E::ScalarField,
+}
+
+/// The public half of a [`SigningKeypair`], `[s] H` for the generator `H` of G2, announced
+/// with the [`crate::Validator`] it belongs to
+#[serde_as]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct SigningPublicKey<E: Pairing> {
+    #[serde_as(as = "ferveo_common::serialization::SerdeAs")]
+    pub key: E::G2Affine,
+}
+
+impl<E: Pairing> SigningKeypair<E> {
+    #[cfg(feature = "dkg")]
+    pub fn new<R: RngCore>(rng: &mut R) -> Self {
+        Self {
+            secret: E::ScalarField::rand(rng),
+        }
+    }
+
+    pub fn public_key(&self) -> SigningPublicKey<E> {
+        SigningPublicKey {
+            key: E::G2Affine::generator().mul(self.secret).into(),
+        }
+    }
+
+    /// A Schnorr signature `(R, z)` with `R = [k] H` and `z = k + c s`, where the challenge
+    /// `c` is derived from `R` by `challenge`
+    #[cfg(feature = "dkg")]
+    pub(crate) fn sign<R: RngCore>(
+        &self,
+        rng: &mut R,
+        challenge: impl FnOnce(&E::G2Affine) -> Result<E::ScalarField>,
+    ) -> Result<(E::G2Affine, E::ScalarField)> {
+        let k = Zeroizing::new(E::ScalarField::rand(rng));
+        let commitment: E::G2Affine = E::G2Affine::generator().mul(*k).into();
+        let c = challenge(&commitment)?;
+        Ok((commitment, *k + c * self.secret))
+    }
+}
+
+impl<E: Pairing> SigningPublicKey<E> {
+    /// Check a signature of [`SigningKeypair::sign`], i.e. `[z] H == R + [c] pk`
+    pub(crate) fn verify(
+        &self,
+        commitment: &E::G2Affine,
+        response: &E::ScalarField,
+        challenge: &E::ScalarField,
+    ) -> bool {
+        E::G2Affine::generator().mul(response)
+            == *commitment + self.key.mul(challenge)
+    }
+}
+
+// Keep the signing key out of logs
+impl<E: Pairing> std::fmt::Debug for SigningKeypair<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SigningKeypair(<redacted>)")
+    }
+}
+
+impl<E: Pairing> Zeroize for SigningKeypair<E> {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+// `ZeroizeOnDrop` derivation fails because of missing trait bounds, so we manually introduce
+// required traits
+
+impl<E: Pairing> Drop for SigningKeypair<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<E: Pairing> ZeroizeOnDrop for SigningKeypair<E> {}
@@ -0,0 +1,202 @@
+use std::collections::BTreeSet;
+
+use ark_ec::pairing::Pairing;
+use async_trait::async_trait;
+
+use crate::{
+    AggregatedPvss, DkgParams, EthereumAddress, PubliclyVerifiableDkg,
+    PubliclyVerifiableSS, PvssAggregator, Result, Validator, ValidatorSetHash,
+    ValidatorsMap,
+};
+
+/// A PVSS transcript as posted on chain by its dealer
+pub type TranscriptMessage<E> = (Validator<E>, PubliclyVerifiableSS<E>);
+
+/// A page of transcripts, as returned by a [`TranscriptSource`]
+#[derive(Clone, Debug)]
+pub struct TranscriptPage<E: Pairing> {
+    pub messages: Vec<TranscriptMessage<E>>,
+    /// Cursor of the next page, or `None` if this is the last page
+    pub next_cursor: Option<u64>,
+}
+
+/// A paginated source of transcripts, e.g. a chain RPC or an indexer
+#[async_trait]
+pub trait TranscriptSource<E: Pairing>: Send + Sync {
+    /// Fetch up to `limit` transcripts, starting at `cursor`. The first page is at cursor `0`.
+    async fn fetch_page(
+        &self,
+        cursor: u64,
+        limit: usize,
+    ) -> Result<TranscriptPage<E>>;
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncProgress {
+    /// Number of transcripts fetched from the source so far
+    pub fetched: usize,
+    /// Number of transcripts fed to the aggregator so far
+    pub accepted: usize,
+    /// Dealers of the transcripts that were skipped as malformed, unknown or duplicate
+    pub rejected: Vec<EthereumAddress>,
+    /// Whether enough transcripts were accepted to aggregate
+    pub has_quorum: bool,
+    /// Whether the source has been exhausted
+    pub is_complete: bool,
+}
+
+/// Syncs the transcripts of a ritual from a [`TranscriptSource`], aggregating them as they
+/// arrive.
+///
+/// Pages are only fetched when [`TranscriptStream::next`] is awaited, so a slow consumer
+/// naturally applies backpressure on the source.
+pub struct TranscriptStream<E: Pairing, S: TranscriptSource<E>> {
+    source: S,
+    page_size: usize,
+    next_cursor: Option<u64>,
+    dkg_params: DkgParams,
+    validators: ValidatorsMap<E>,
+    validator_set_hash: ValidatorSetHash,
+    dealers: BTreeSet<EthereumAddress>,
+    aggregator: PvssAggregator<E>,
+    progress: SyncProgress,
+}
+
+impl<E: Pairing, S: TranscriptSource<E>> TranscriptStream<E, S> {
+    pub fn new(
+        source: S,
+        dkg: &PubliclyVerifiableDkg<E>,
+        page_size: usize,
+    ) -> Self {
+        Self {
+            source,
+            page_size,
+            next_cursor: Some(0),
+            dkg_params: dkg.dkg_params,
+            validators: dkg.validators.clone(),
+            validator_set_hash: dkg.validator_set_hash(),
+            dealers: BTreeSet::new(),
+            aggregator: PvssAggregator::default(),
+            progress: SyncProgress::default(),
+        }
+    }
+
+    /// Fetch and ingest the next page of transcripts.
+    /// Returns `None` once the source is exhausted.
+    pub async fn next(&mut self) -> Option<Result<SyncProgress>> {
+        let cursor = self.next_cursor?;
+        let page = match self.source.fetch_page(cursor, self.page_size).await {
+            Ok(page) => page,
+            Err(err) => return Some(Err(err)),
+        };
+        for (dealer, pvss) in page.messages.iter() {
+            self.progress.fetched += 1;
+            if self.is_well_formed(dealer, pvss) {
+                self.dealers.insert(dealer.address.clone());
+                self.aggregator.add(pvss);
+                self.progress.accepted += 1;
+            } else {
+                self.progress.rejected.push(dealer.address.clone());
+            }
+        }
+        self.next_cursor = page.next_cursor;
+        self.progress.is_complete = self.next_cursor.is_none();
+        self.progress.has_quorum = self.progress.accepted
+            >= self.dkg_params.security_threshold() as usize;
+        Some(Ok(self.progress.clone()))
+    }
+
+    /// Cheap checks that don't require the full transcript verification
+    fn is_well_formed(
+        &self,
+        dealer: &Validator<E>,
+        pvss: &PubliclyVerifiableSS<E>,
+    ) -> bool {
+        self.validators.get(&dealer.address) == Some(dealer)
+            && !self.dealers.contains(&dealer.address)
+            && pvss.validator_set_hash == self.validator_set_hash
+            && pvss.tau == self.dkg_params.tau()
+            && pvss.coeffs.len()
+                == self.dkg_params.security_threshold() as usize
+            && pvss.shares.len() == self.dkg_params.shares_num() as usize
+            && pvss.verify_optimistic(&dealer.address)
+    }
+
+    pub fn progress(&self) -> &SyncProgress {
+        &self.progress
+    }
+
+    /// The aggregate of all transcripts accepted so far.
+    /// Note that it still has to be verified, e.g. with `verify_aggregation`.
+    pub fn aggregate(&self) -> Result<AggregatedPvss<E>> {
+        self.aggregator.finish()
+    }
+}
+
+#[cfg(test)]
+mod test_stream {
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::test_common::*;
+
+    struct InMemorySource(Vec<TranscriptMessage<E>>);
+
+    #[async_trait]
+    impl TranscriptSource<E> for InMemorySource {
+        async fn fetch_page(
+            &self,
+            cursor: u64,
+            limit: usize,
+        ) -> Result<TranscriptPage<E>> {
+            let start = cursor as usize;
+            let end = (start + limit).min(self.0.len());
+            Ok(TranscriptPage {
+                messages: self.0[start..end].to_vec(),
+                next_cursor: (end < self.0.len()).then_some(end as u64),
+            })
+        }
+    }
+
+    #[test]
+    fn sync_ritual_from_source() {
+        let (dkg, _) = setup_dealt_dkg();
+        let mut messages: Vec<_> = dkg
+            .vss
+            .iter()
+            .map(|(address, pvss)| {
+                (dkg.validators[address].clone(), pvss.clone())
+            })
+            .collect();
+        // A duplicate, and a transcript with the wrong shape
+        messages.push(messages[0].clone());
+        let mut malformed = messages[1].clone();
+        malformed.1.shares.pop();
+        messages.insert(1, malformed);
+
+        let mut stream =
+            TranscriptStream::new(InMemorySource(messages), &dkg, 2);
+        let mut updates = vec![];
+        pollster::block_on(async {
+            while let Some(progress) = stream.next().await {
+                updates.push(progress.unwrap());
+            }
+        });
+
+        assert_eq!(updates.len(), 3);
+        assert!(!updates[0].is_complete);
+        let last = updates.last().unwrap();
+        assert!(last.is_complete && last.has_quorum);
+        assert_eq!(last.fetched, 6);
+        assert_eq!(last.accepted, 4);
+        assert_eq!(last.rejected.len(), 2);
+
+        let aggregate = stream.aggregate().unwrap();
+        let expected = crate::pvss::aggregate(
+            &dkg.vss.values().cloned().collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert_eq!(aggregate, expected);
+        assert!(aggregate.verify_aggregation(&dkg).unwrap());
+    }
+}
@@ -4,13 +4,18 @@ use std::str::FromStr;
 pub use ark_bls12_381::Bls12_381 as E;
 use ark_ec::pairing::Pairing;
 use ferveo_common::Keypair;
-use rand::seq::SliceRandom;
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 
-use crate::{DkgParams, EthereumAddress, PubliclyVerifiableDkg, Validator};
+use crate::{
+    DkgParams, EthereumAddress, PubliclyVerifiableDkg, SigningKeypair,
+    Validator,
+};
 
 pub type ScalarField = <E as Pairing>::ScalarField;
 pub type G1 = <E as Pairing>::G1Affine;
 pub type G2 = <E as Pairing>::G2Affine;
+#[cfg(feature = "refresh")]
 pub type TargetField = <E as Pairing>::TargetField;
 
 pub const TAU: u32 = 0;
@@ -24,6 +29,11 @@ pub fn gen_keypairs(n: u32) -> Vec<Keypair<E>> {
     (0..n).map(|_| Keypair::<E>::new(rng)).collect()
 }
 
+/// The signing keypair of the `i`-th validator of [`gen_validators`]
+pub fn gen_signing_keypair(i: usize) -> SigningKeypair<E> {
+    SigningKeypair::new(&mut ChaCha20Rng::seed_from_u64(i as u64))
+}
+
 pub fn gen_address(i: usize) -> EthereumAddress {
     EthereumAddress::from_str(&format!("0x{i:040}")).unwrap()
 }
@@ -36,6 +46,9 @@ pub fn gen_validators(keypairs: &[Keypair<E>]) -> Vec<Validator<E>> {
             address: gen_address(i),
             public_key: keypair.public_key(),
             share_index: i as u32,
+            weight: 1,
+            metadata: Default::default(),
+            signing_key: Some(gen_signing_keypair(i).public_key()),
         })
         .collect()
 }
@@ -0,0 +1,203 @@
+//! Bootstrapping a ritual with a single trusted dealer instead of a DKG, e.g. for testnets,
+//! or to migrate a key generated elsewhere to a cohort of validators.
+//!
+//! **The dealer knows the secret key of the ritual.** Nothing encrypted to the ritual is
+//! safe from the dealer, or from anyone who compromises the dealer, so none of the
+//! guarantees of the DKG hold. Don't use this for production rituals.
+//!
+//! The dealer deals a single transcript to the cohort, which stands in for the aggregate of
+//! a DKG: validators decrypt their shares from it and create decryption shares as they
+//! would from an aggregate, so threshold decryption works unchanged. Since it doesn't
+//! aggregate transcripts of the validators, it doesn't pass
+//! [`PubliclyVerifiableSS::verify_aggregation`]; validators check it with
+//! [`TrustedDealing::verify`] instead.
+
+use ark_ec::pairing::Pairing;
+use ark_std::UniformRand;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::{
+    aggregate, share_owners, validator_set_hash, AggregatedPvss, BlindedShare,
+    DealingContext, PubliclyVerifiableParams, PubliclyVerifiableSS, Result,
+    Validator,
+};
+
+/// The output of a trusted dealer: a transcript in the form of the aggregate of a DKG, see
+/// [`crate::trusted_dealer`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct TrustedDealing<E: Pairing> {
+    pub aggregate: AggregatedPvss<E>,
+}
+
+impl<E: Pairing> TrustedDealing<E> {
+    /// Deal `secret` to the validators of `context`, e.g. an existing key to migrate
+    pub fn deal<R: RngCore>(
+        secret: &E::ScalarField,
+        context: &DealingContext<'_, E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let transcript =
+            PubliclyVerifiableSS::<E>::new_standalone(secret, context, rng)?;
+        Ok(Self {
+            aggregate: aggregate(&[transcript])?,
+        })
+    }
+
+    /// Deal a fresh secret to the validators of `context`
+    pub fn random<R: RngCore>(
+        context: &DealingContext<'_, E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let secret = Zeroizing::new(E::ScalarField::rand(rng));
+        Self::deal(&secret, context, rng)
+    }
+
+    /// The public key of the ritual
+    pub fn public_key(&self) -> E::G1Affine {
+        self.aggregate.public_key().0
+    }
+
+    /// The share of every validator, encrypted to its key, in share index order, e.g. to
+    /// hand each validator their share on its own
+    pub fn blinded_shares(&self) -> Vec<BlindedShare<E>> {
+        (0..self.aggregate.shares.len() as u32)
+            .filter_map(|share_index| {
+                self.aggregate.blinded_share_for(share_index).ok()
+            })
+            .collect()
+    }
+
+    /// Check that the dealing was dealt to `validators` with `security_threshold`, and that
+    /// every share is the share of its holder in the commitment of the dealing, so that
+    /// every set of `security_threshold` shares decrypts under its public key
+    pub fn verify(
+        &self,
+        validators: &[Validator<E>],
+        security_threshold: u32,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> bool {
+        let owners = share_owners(validators);
+        self.aggregate.validator_set_hash
+            == pvss_params.bind(validator_set_hash(validators))
+            && self.aggregate.coeffs.len() == security_threshold as usize
+            && self.aggregate.shares.len() == owners.len()
+            && self.blinded_shares().iter().zip(&owners).all(
+                |(share, owner)| {
+                    share.verify(
+                        &self.aggregate.coeffs,
+                        &self.aggregate.domain,
+                        &owner.public_key,
+                        pvss_params,
+                    )
+                },
+            )
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_trusted_dealer {
+    use ark_ec::CurveGroup;
+    use ark_poly::EvaluationDomain;
+    use ferveo_tdec::{
+        decrypt_with_shared_secret, encrypt, prepare_combine_simple,
+        share_combine_simple, SecretBox,
+    };
+
+    use super::*;
+    use crate::test_common::*;
+
+    #[test]
+    fn trusted_dealer_outputs_decrypt_as_dkg_outputs() {
+        let rng = &mut ark_std::test_rng();
+        let (dkg, keypairs) = setup_dkg(0);
+        let validators = dkg.validators.values().cloned().collect::<Vec<_>>();
+        let dealer = gen_address(SHARES_NUM as usize);
+        let context = DealingContext::new(
+            &validators,
+            dkg.domain,
+            &dkg.pvss_params,
+            SECURITY_THRESHOLD,
+            TAU,
+            &dealer,
+        )
+        .unwrap();
+
+        // Migrate a known key
+        let secret = ScalarField::rand(rng);
+        let dealing =
+            TrustedDealing::<E>::deal(&secret, &context, rng).unwrap();
+        assert_eq!(
+            dealing.public_key(),
+            (dkg.pvss_params.g * secret).into_affine()
+        );
+        assert!(dealing.verify(
+            &validators,
+            SECURITY_THRESHOLD,
+            &dkg.pvss_params
+        ));
+        assert!(!dealing.verify(&validators, SHARES_NUM, &dkg.pvss_params));
+        let mut tampered = dealing.clone();
+        tampered.aggregate.shares.swap(0, 1);
+        assert!(!tampered.verify(
+            &validators,
+            SECURITY_THRESHOLD,
+            &dkg.pvss_params
+        ));
+        let bytes = bincode::serialize(&dealing).unwrap();
+        let dealing: TrustedDealing<E> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(dealing.blinded_shares().len(), SHARES_NUM as usize);
+
+        // A threshold of validators decrypts with the shares of the dealing
+        let ciphertext = encrypt::<E>(
+            SecretBox::new(MSG.to_vec()),
+            AAD,
+            &dealing.public_key(),
+            rng,
+        )
+        .unwrap();
+        let header = ciphertext.header().unwrap();
+        let g_inv = dkg.pvss_params.g_inv();
+        let owners = dkg.share_owners();
+        let selected = &owners[..SECURITY_THRESHOLD as usize];
+        let decryption_shares = selected
+            .iter()
+            .map(|validator| {
+                let keypair = keypairs
+                    .iter()
+                    .find(|keypair| {
+                        keypair.public_key() == validator.public_key
+                    })
+                    .unwrap();
+                dealing
+                    .aggregate
+                    .make_decryption_share_simple(
+                        &header,
+                        AAD,
+                        &keypair.decryption_key,
+                        validator.share_index as usize,
+                        &g_inv,
+                    )
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let domain_points = selected
+            .iter()
+            .map(|validator| dkg.domain.element(validator.share_index as usize))
+            .collect::<Vec<_>>();
+        let shared_secret = share_combine_simple::<E>(
+            &decryption_shares,
+            &prepare_combine_simple::<E>(&domain_points),
+        );
+        let plaintext = decrypt_with_shared_secret(
+            &ciphertext,
+            AAD,
+            &shared_secret,
+            &g_inv,
+        )
+        .unwrap();
+        assert_eq!(plaintext.as_bytes(), MSG);
+    }
+}
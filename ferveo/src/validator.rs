@@ -1,11 +1,20 @@
-use std::{collections::HashSet, fmt::Display, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    iter,
+    ops::Range,
+    str::FromStr,
+};
 
 use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
 use ferveo_common::PublicKey;
+use ferveo_tdec::protocol::{ProtocolVersion, V1};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
-use crate::Error;
+use crate::{Error, SigningPublicKey};
 
 #[derive(
     Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize, Hash,
@@ -41,7 +50,15 @@ impl Display for EthereumAddress {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    bound(serialize = "", deserialize = ""),
+    into = "VersionedValidator<E>",
+    from = "VersionedValidator<E>"
+)]
 /// Represents an external validator
+///
+/// Validators are encoded with a version tag, see [`Validator::from_legacy_bytes`] for the
+/// encoding without one.
 pub struct Validator<E: Pairing> {
     /// The established address of the validator
     pub address: EthereumAddress,
@@ -49,6 +66,96 @@ pub struct Validator<E: Pairing> {
     pub public_key: PublicKey<E>,
     /// The index of the validator in the given ritual
     pub share_index: u32,
+    /// The number of shares of the validator, e.g. proportional to its stake. The validator
+    /// holds the shares at the `weight` consecutive indices starting at `share_index`.
+    /// Validators of weight zero are observers, see [`Validator::is_observer`].
+    pub weight: u32,
+    /// What applications know of the validator besides its identity, set with
+    /// [`Validator::with_metadata`]
+    pub(crate) metadata: ValidatorMetadata,
+    /// The key the validator signs its DKG messages with, if it signs them, see
+    /// [`crate::SigningKeypair`]
+    pub signing_key: Option<SigningPublicKey<E>>,
+}
+
+/// The encoding of a [`Validator`]. Every version is a variant, so that fields can be added
+/// to validators without changing how the validators encoded before decode
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+enum VersionedValidator<E: Pairing> {
+    V1 {
+        address: EthereumAddress,
+        public_key: PublicKey<E>,
+        share_index: u32,
+        weight: u32,
+        metadata: ValidatorMetadata,
+        signing_key: Option<SigningPublicKey<E>>,
+    },
+}
+
+impl<E: Pairing> From<Validator<E>> for VersionedValidator<E> {
+    fn from(validator: Validator<E>) -> Self {
+        VersionedValidator::V1 {
+            address: validator.address,
+            public_key: validator.public_key,
+            share_index: validator.share_index,
+            weight: validator.weight,
+            metadata: validator.metadata,
+            signing_key: validator.signing_key,
+        }
+    }
+}
+
+impl<E: Pairing> From<VersionedValidator<E>> for Validator<E> {
+    fn from(validator: VersionedValidator<E>) -> Self {
+        match validator {
+            VersionedValidator::V1 {
+                address,
+                public_key,
+                share_index,
+                weight,
+                metadata,
+                signing_key,
+            } => Self {
+                address,
+                public_key,
+                share_index,
+                weight,
+                metadata,
+                signing_key,
+            },
+        }
+    }
+}
+
+/// The encoding of a [`Validator`] before weights and metadata, without a version tag
+#[derive(Deserialize)]
+#[serde(bound(deserialize = ""))]
+struct LegacyValidator<E: Pairing> {
+    address: EthereumAddress,
+    public_key: PublicKey<E>,
+    share_index: u32,
+}
+
+/// Optional information about a [`Validator`], carried along with it, e.g. to report the
+/// validators at fault by name, or to reach them, without a lookup table of their own.
+///
+/// Metadata is informative only: it isn't part of the validator set hash, so validators
+/// don't need to agree on it
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub struct ValidatorMetadata {
+    /// The operator running the validator, if it isn't the validator address itself
+    pub operator: Option<EthereumAddress>,
+    /// Where the validator can be reached, e.g. a URL or a multiaddr
+    pub endpoint: Option<String>,
+    /// A human-readable name for the validator
+    pub label: Option<String>,
+}
+
+fn default_weight() -> u32 {
+    1
 }
 
 impl<E: Pairing> Validator<E> {
@@ -61,6 +168,250 @@ impl<E: Pairing> Validator<E> {
             address: EthereumAddress::from_str(&address)?,
             public_key,
             share_index,
+            weight: default_weight(),
+            metadata: ValidatorMetadata::default(),
+            signing_key: None,
+        })
+    }
+
+    /// Decode a validator from its bincode encoding before weights and metadata, which had
+    /// no version tag. The validator holds a single share, and has no metadata or signing key
+    pub fn from_legacy_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let legacy: LegacyValidator<E> = bincode::deserialize(bytes)?;
+        Ok(Self {
+            address: legacy.address,
+            public_key: legacy.public_key,
+            share_index: legacy.share_index,
+            weight: default_weight(),
+            metadata: ValidatorMetadata::default(),
+            signing_key: None,
+        })
+    }
+
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: ValidatorMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// What applications know of the validator besides its identity, see
+    /// [`ValidatorMetadata`]
+    pub fn metadata(&self) -> &ValidatorMetadata {
+        &self.metadata
+    }
+
+    pub fn with_signing_key(
+        mut self,
+        signing_key: SigningPublicKey<E>,
+    ) -> Self {
+        self.signing_key = Some(signing_key);
+        self
+    }
+
+    /// Whether the validator is an observer, e.g. an auditor or a standby node: it verifies
+    /// the transcripts and aggregates of the DKG, but holds no shares and doesn't deal
+    pub fn is_observer(&self) -> bool {
+        self.weight == 0
+    }
+
+    /// The share indices held by the validator. Fails if they don't all fit in a `u32`
+    pub fn share_indices(&self) -> Result<Range<u32>, Error> {
+        let end = self
+            .share_index
+            .checked_add(self.weight)
+            .ok_or(Error::InvalidShareIndex(self.share_index))?;
+        Ok(self.share_index..end)
+    }
+
+    /// Whether the validator holds the share `share_index`
+    pub fn holds_share(&self, share_index: u32) -> bool {
+        share_index >= self.share_index
+            && share_index - self.share_index < self.weight
+    }
+}
+
+/// The label of the validator followed by its address, or only its address if it has no
+/// label, e.g. for logs and error reports
+impl<E: Pairing> Display for Validator<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.metadata.label {
+            Some(label) => write!(f, "{label} ({})", self.address),
+            None => write!(f, "{}", self.address),
+        }
+    }
+}
+
+/// The total number of shares held by the validators
+pub fn total_weight<'a, E: Pairing>(
+    validators: impl IntoIterator<Item = &'a Validator<E>>,
+) -> u32 {
+    validators
+        .into_iter()
+        .map(|validator| validator.weight)
+        .sum()
+}
+
+/// The holder of each share, by share index.
+///
+/// Assumes the share indices of the validators are contiguous from zero, e.g. as assigned by
+/// [`assign_share_indices`].
+pub fn share_owners<'a, E: Pairing>(
+    validators: impl IntoIterator<Item = &'a Validator<E>>,
+) -> Vec<&'a Validator<E>> {
+    let mut validators = validators.into_iter().collect::<Vec<_>>();
+    validators.sort_by_key(|validator| validator.share_index);
+    validators
+        .into_iter()
+        .flat_map(|validator| {
+            iter::repeat(validator).take(validator.weight as usize)
+        })
+        .collect()
+}
+
+/// Commitment to a validator set, i.e. to the cohort a PVSS transcript is dealt for
+pub type ValidatorSetHash = [u8; 32];
+
+/// Hash the validators, ordered by share index, together with their weights, encryption
+/// keys and signing keys.
+pub fn validator_set_hash<'a, E: Pairing>(
+    validators: impl IntoIterator<Item = &'a Validator<E>>,
+) -> ValidatorSetHash {
+    let mut validators = validators.into_iter().collect::<Vec<_>>();
+    validators.sort_by_key(|validator| validator.share_index);
+
+    let mut hasher = Sha256::new();
+    hasher.update(V1::VALIDATOR_SET_DOMAIN);
+    hasher.update((validators.len() as u32).to_le_bytes());
+    for validator in validators {
+        update_with_validator(&mut hasher, validator);
+    }
+    hasher.finalize().into()
+}
+
+/// Feed the share index, weight, address and encryption key of `validator` to `hasher`,
+/// followed by its signing key if it has one, so that the hashes of validators without one
+/// don't change
+pub(crate) fn update_with_validator<E: Pairing>(
+    hasher: &mut Sha256,
+    validator: &Validator<E>,
+) {
+    let address = validator.address.to_string();
+    hasher.update(validator.share_index.to_le_bytes());
+    hasher.update(validator.weight.to_le_bytes());
+    hasher.update((address.len() as u32).to_le_bytes());
+    hasher.update(address.as_bytes());
+    let mut encryption_key = Vec::new();
+    validator
+        .public_key
+        .encryption_key
+        .serialize_compressed(&mut encryption_key)
+        .expect("Public key is serializable");
+    hasher.update(encryption_key);
+    if let Some(signing_key) = &validator.signing_key {
+        let mut key = Vec::new();
+        signing_key
+            .key
+            .serialize_compressed(&mut key)
+            .expect("Signing key is serializable");
+        hasher.update(key);
+    }
+}
+
+fn share_index_hash(address: &EthereumAddress) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(V1::SHARE_INDEX_DOMAIN);
+    // Addresses are case-insensitive
+    hasher.update(address.0.to_lowercase().as_bytes());
+    hasher.finalize().into()
+}
+
+/// Assign share indices canonically, from the addresses of the validators alone.
+///
+/// Validators are ordered by the hashes of their addresses, so that nodes derive the same
+/// indices from the same validator set without coordinating, whatever its order. Each
+/// validator gets `weight` consecutive indices. Any share indices the validators already
+/// have are ignored.
+pub fn assign_share_indices<E: Pairing>(
+    validators: &[Validator<E>],
+) -> Vec<Validator<E>> {
+    let mut validators = validators.to_vec();
+    validators.sort_by_cached_key(|validator| {
+        (
+            share_index_hash(&validator.address),
+            validator.address.clone(),
+        )
+    });
+    let mut share_index = 0;
+    for validator in validators.iter_mut() {
+        validator.share_index = share_index;
+        // Overflowing indices are rejected by `Validator::share_indices`
+        share_index = share_index.saturating_add(validator.weight);
+    }
+    validators
+}
+
+/// Check that the share indices of the validators are the ones assigned by
+/// [`assign_share_indices`].
+pub fn check_share_indices<E: Pairing>(
+    validators: &[Validator<E>],
+) -> Result<(), Error> {
+    let canonical = assign_share_indices(validators)
+        .into_iter()
+        .map(|validator| (validator.address, validator.share_index))
+        .collect::<BTreeMap<_, _>>();
+    for validator in validators {
+        let expected = canonical[&validator.address];
+        if validator.share_index != expected {
+            return Err(Error::NonCanonicalShareIndex(
+                validator.address.clone(),
+                validator.share_index,
+                expected,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Prepared (Miller loop ready) encryption keys of a validator set.
+///
+/// Preparing a key is costly, so the keys are prepared once per DKG session and reused by
+/// every transcript and decryption share verification in that session.
+#[derive(Clone, Debug)]
+pub struct PreparedEncryptionKeys<E: Pairing>(
+    BTreeMap<EthereumAddress, E::G2Prepared>,
+);
+
+impl<E: Pairing> PreparedEncryptionKeys<E> {
+    pub fn new<'a>(
+        validators: impl IntoIterator<Item = &'a Validator<E>>,
+    ) -> Self {
+        Self(
+            validators
+                .into_iter()
+                .map(|validator| {
+                    (
+                        validator.address.clone(),
+                        E::G2Prepared::from(
+                            validator.public_key.encryption_key,
+                        ),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, address: &EthereumAddress) -> Option<&E::G2Prepared> {
+        self.0.get(address)
+    }
+
+    /// The prepared encryption key of `validator`, prepared on the spot if it's not cached
+    pub fn get_or_prepare(&self, validator: &Validator<E>) -> E::G2Prepared {
+        self.get(&validator.address).cloned().unwrap_or_else(|| {
+            E::G2Prepared::from(validator.public_key.encryption_key)
         })
     }
 }
@@ -71,10 +422,12 @@ pub fn assert_no_share_duplicates<E: Pairing>(
     let mut set = HashSet::new();
 
     for validator in validators {
-        if set.contains(&validator.share_index) {
-            return Err(Error::DuplicatedShareIndex(validator.share_index));
-        } else {
-            set.insert(validator.share_index);
+        for share_index in validator.share_indices()? {
+            if set.contains(&share_index) {
+                return Err(Error::DuplicatedShareIndex(share_index));
+            } else {
+                set.insert(share_index);
+            }
         }
     }
 
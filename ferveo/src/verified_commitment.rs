@@ -0,0 +1,214 @@
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ferveo_tdec::VerificationReport;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{
+    check_share_commitments, coeffs_hash, pairings_match, share_commitments,
+    DomainDescriptor, Error, PubliclyVerifiableDkg, PubliclyVerifiableParams,
+    PubliclyVerifiableSS, Result, Validator,
+};
+
+/// The commitments `A_i = [f(ω_i)] G` to every share of a transcript or aggregate,
+/// evaluated once with an FFT over its domain, so that its shares are checked afterwards
+/// without another FFT, e.g. for per-share audits, or to verify it again after a restart.
+///
+/// The cache is bound to the coefficients and domain it was evaluated from, and can be
+/// serialized. [`VerifiedCommitment::verify_full`] checks the cached commitments against
+/// the transcript with [`PubliclyVerifiableSS::verify_public_key_shares`], which is cheaper
+/// than evaluating them again, but [`VerifiedCommitment::verify_share`] trusts them, so a
+/// cache that wasn't fully verified must only be loaded from local storage.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct VerifiedCommitment<E: Pairing> {
+    coeffs_hash: [u8; 32],
+    domain: DomainDescriptor<E>,
+    #[serde_as(as = "Vec<ferveo_common::serialization::SerdeAs>")]
+    share_commitments: Vec<E::G1Affine>,
+}
+
+impl<E: Pairing> VerifiedCommitment<E> {
+    /// Evaluate the commitment of `pvss` over its domain
+    pub fn new<T>(pvss: &PubliclyVerifiableSS<E, T>) -> Result<Self> {
+        let commitment =
+            share_commitments::<E>(&pvss.coeffs, &pvss.domain.domain());
+        Ok(Self {
+            coeffs_hash: coeffs_hash::<E>(&pvss.coeffs)?,
+            domain: pvss.domain,
+            share_commitments: E::G1::normalize_batch(&commitment),
+        })
+    }
+
+    /// Check that the cache was evaluated from the commitment of `pvss`
+    pub fn check<T>(&self, pvss: &PubliclyVerifiableSS<E, T>) -> Result<()> {
+        if self.domain == pvss.domain
+            && self.coeffs_hash == coeffs_hash::<E>(&pvss.coeffs)?
+        {
+            Ok(())
+        } else {
+            Err(Error::CommitmentCacheMismatch)
+        }
+    }
+
+    /// The commitment `A_i` to the share `share_index`, i.e. its public key share
+    pub fn share_commitment(&self, share_index: u32) -> Result<&E::G1Affine> {
+        self.share_commitments
+            .get(share_index as usize)
+            .ok_or(Error::InvalidShareIndex(share_index))
+    }
+
+    /// Same as [`crate::PubliclyVerifiableSS::verify_my_share`], with a single pairing check
+    /// and no evaluation of the commitment
+    pub fn verify_share<T>(
+        &self,
+        pvss: &PubliclyVerifiableSS<E, T>,
+        share_index: u32,
+        validator: &Validator<E>,
+        pvss_params: &PubliclyVerifiableParams<E>,
+    ) -> Result<bool> {
+        self.check(pvss)?;
+        let y_i = match pvss.shares.get(share_index as usize) {
+            Some(share) if validator.holds_share(share_index) => {
+                share.into_affine()
+            }
+            _ => return Ok(false),
+        };
+        Ok(pairings_match::<E>(
+            pvss_params.g,
+            y_i,
+            *self.share_commitment(share_index)?,
+            validator.public_key.encryption_key,
+        ))
+    }
+
+    /// Same as [`crate::PubliclyVerifiableSS::verify_full_report`], with the cached
+    /// commitment
+    pub fn verify_full_report<T>(
+        &self,
+        pvss: &PubliclyVerifiableSS<E, T>,
+        dkg: &PubliclyVerifiableDkg<E>,
+    ) -> Result<VerificationReport> {
+        self.check(pvss)?;
+        let mut report = pvss.shape_report(dkg);
+        if !report.is_valid() {
+            return Ok(report);
+        }
+        let low_degree = report.check("cached_commitment", || {
+            self.share_commitments.get(..pvss.shares.len()).map_or(
+                false,
+                |public_key_shares| {
+                    pvss.verify_public_key_shares(
+                        public_key_shares,
+                        &mut rand::thread_rng(),
+                    )
+                },
+            )
+        });
+        if !low_degree {
+            return Ok(report);
+        }
+        let validators = dkg.validators.values().cloned().collect::<Vec<_>>();
+        report.extend(check_share_commitments(
+            &self.share_commitments,
+            &pvss.shares,
+            &dkg.pvss_params,
+            &validators,
+            &dkg.prepared_keys,
+        ));
+        Ok(report)
+    }
+
+    /// Same as [`crate::PubliclyVerifiableSS::verify_full`], with the cached commitment
+    pub fn verify_full<T>(
+        &self,
+        pvss: &PubliclyVerifiableSS<E, T>,
+        dkg: &PubliclyVerifiableDkg<E>,
+    ) -> Result<bool> {
+        Ok(self.verify_full_report(pvss, dkg)?.is_valid())
+    }
+}
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_verified_commitment {
+    use ark_ec::AffineRepr;
+
+    use super::*;
+    use crate::{pvss::aggregate, test_common::*, ShareEncryptions};
+
+    #[test]
+    fn test_verified_commitment() {
+        let (dkg, _) = setup_dealt_dkg();
+        let transcripts = dkg.vss.values().cloned().collect::<Vec<_>>();
+        let aggregate = aggregate(&transcripts).unwrap();
+        let cache = VerifiedCommitment::new(&aggregate).unwrap();
+        assert!(cache.verify_full(&aggregate, &dkg).unwrap());
+        for validator in dkg.validators.values() {
+            let share_index = validator.share_index;
+            assert_eq!(
+                cache.share_commitment(share_index).unwrap(),
+                &aggregate
+                    .public_key_share(share_index)
+                    .unwrap()
+                    .public_key_share
+            );
+            assert!(cache
+                .verify_share(
+                    &aggregate,
+                    share_index,
+                    validator,
+                    &dkg.pvss_params
+                )
+                .unwrap());
+            assert!(!cache
+                .verify_share(
+                    &aggregate,
+                    share_index + 1,
+                    validator,
+                    &dkg.pvss_params
+                )
+                .unwrap());
+        }
+
+        // Reloaded after a restart
+        let bytes = bincode::serialize(&cache).unwrap();
+        let cache: VerifiedCommitment<E> =
+            bincode::deserialize(&bytes).unwrap();
+        assert!(cache.verify_full(&aggregate, &dkg).unwrap());
+
+        // A tampered cache is caught by full verification
+        let mut tampered_cache = cache.clone();
+        tampered_cache.share_commitments[1] = G1::generator();
+        let report =
+            tampered_cache.verify_full_report(&aggregate, &dkg).unwrap();
+        assert!(!report.get("cached_commitment").unwrap().passed);
+        assert!(report.get("share_commitments").is_none());
+
+        // Tampered shares are found without evaluating the commitment again
+        let mut tampered = aggregate;
+        tampered.shares[1] = ShareEncryptions::new(G2::generator()).unwrap();
+        let report = cache.verify_full_report(&tampered, &dkg).unwrap();
+        assert_eq!(
+            report.get("share_commitments").unwrap().failed_indices,
+            [1]
+        );
+        assert_eq!(
+            report.get("share_commitments").unwrap().failed_indices,
+            tampered
+                .verify_full_report(&dkg)
+                .get("share_commitments")
+                .unwrap()
+                .failed_indices
+        );
+
+        // Bound to the commitment it was evaluated from
+        assert!(matches!(
+            cache.verify_full(&transcripts[0], &dkg),
+            Err(Error::CommitmentCacheMismatch)
+        ));
+        assert!(matches!(
+            cache.share_commitment(cache.share_commitments.len() as u32),
+            Err(Error::InvalidShareIndex(_))
+        ));
+    }
+}
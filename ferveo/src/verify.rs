@@ -0,0 +1,90 @@
+//! Verification of public DKG and threshold decryption artifacts, for auditors and light
+//! clients.
+//!
+//! Build with `--no-default-features --features verify` to get a crate that checks
+//! transcripts, aggregates and ciphertexts, but doesn't contain the code that deals
+//! transcripts or decrypts private key shares. Enable `tdec-simple` or `tdec-fast` to verify
+//! the decryption shares of that variant, and combined secrets with a
+//! [`ShareAggregationProof`].
+
+#[cfg(feature = "tdec-fast")]
+pub use ferveo_tdec::{
+    batch_verify_decryption_shares, verify_decryption_shares_fast,
+    DecryptionShareFast, PublicDecryptionContextFast,
+};
+#[cfg(feature = "tdec-simple")]
+pub use ferveo_tdec::{
+    verify_decryption_shares_simple, CohortCommitment, CohortMember,
+    DecryptionShareSimple, PublicDecryptionContextSimple,
+    ShareAggregationProof, ValidatorShareChecksum,
+};
+pub use ferveo_tdec::{
+    CheckOutcome, Ciphertext, CiphertextHeader, VerificationReport,
+};
+
+pub use crate::{
+    aggregate_hash, coeffs_hash, do_verify_aggregation, do_verify_full,
+    do_verify_full_report, find_invalid_transcripts, pok_base,
+    validator_set_hash, verify_full_batch, Aggregated, AggregatedPvss,
+    AggregationDiagnosis, BlindedShare, DealerCommitment, DealtPublicKey,
+    DkgParams, DomainDescriptor, EpochEntry, EpochRegistry, Message,
+    PreparedEncryptionKeys, PubliclyVerifiableDkg, PubliclyVerifiableParams,
+    PubliclyVerifiableSS, PvssAggregator, Unaggregated, Validator,
+    ValidatorSetHash,
+};
+
+#[cfg(all(test, feature = "dkg"))]
+mod test_verify {
+    use ark_std::test_rng;
+    use ferveo_tdec::{encrypt, SecretBox};
+
+    use super::*;
+    use crate::test_common::*;
+
+    #[test]
+    fn verify_public_artifacts() {
+        let (dkg, _) = setup_dealt_dkg();
+
+        // A light client only sees the transcripts
+        let mut aggregator = PvssAggregator::default();
+        for (dealer, pvss) in &dkg.vss {
+            assert!(pvss.verify_optimistic(dealer));
+            assert!(pvss.verify_full_report(&dkg).is_valid());
+            aggregator.add(pvss);
+        }
+        let transcripts = dkg.vss.values().cloned().collect::<Vec<_>>();
+        assert!(verify_full_batch(&transcripts, &dkg));
+        let aggregate = aggregator.finish().unwrap();
+        assert!(aggregate.verify_aggregation(&dkg).unwrap());
+        assert!(aggregate.verify_full(&dkg));
+
+        let commitments = dkg
+            .vss
+            .iter()
+            .map(|(dealer, pvss)| {
+                let signing_key = gen_signing_keypair(
+                    dkg.validators[dealer].share_index as usize,
+                );
+                DealerCommitment::sign(
+                    dealer.clone(),
+                    pvss,
+                    &signing_key,
+                    &mut test_rng(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert!(aggregate
+            .verify_with_commitments(&dkg, &commitments)
+            .unwrap());
+
+        let ciphertext = encrypt::<E>(
+            SecretBox::new(MSG.to_vec()),
+            AAD,
+            &aggregate.coeffs[0],
+            &mut test_rng(),
+        )
+        .unwrap();
+        assert!(ciphertext.check(AAD, &dkg.pvss_params.g_inv()).unwrap());
+    }
+}